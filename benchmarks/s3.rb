@@ -15,6 +15,8 @@
 #   BENCH_S3_BUCKET  – S3 bucket name (default: "test-bucket-alexwoo-2")
 #   BENCH_S3_REGION  – AWS region     (default: "us-west-2")
 #   BENCH_THREADS    – TM executor thread pool size (default: 8)
+#   BENCH_S3_ALPN    – comma-separated ALPN protocols for the CRT client,
+#                      e.g. "h2,http/1.1" (default: none)
 #
 # Usage:
 #   bundle exec rake benchmark:s3
@@ -29,6 +31,7 @@ require "fileutils"
 BUCKET  = ENV.fetch("BENCH_S3_BUCKET", "test-bucket-alexwoo-2")
 REGION  = ENV.fetch("BENCH_S3_REGION", "us-west-1")
 THREADS = Integer(ENV.fetch("BENCH_THREADS", "8"))
+ALPN    = ENV.fetch("BENCH_S3_ALPN", "").split(",")
 
 MB = 1024 * 1024
 
@@ -111,7 +114,8 @@ credentials = sdk_client.config.credentials
 
 crt_client = AwsCrt::S3::Client.new(
   region: REGION,
-  credentials: credentials
+  credentials: credentials,
+  alpn: ALPN
 )
 
 tm_executor = Concurrent::FixedThreadPool.new(THREADS)
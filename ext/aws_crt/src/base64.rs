@@ -0,0 +1,97 @@
+//! Base64 (RFC 4648) encoding and decoding.
+//!
+//! Used for checksum header values (standard alphabet) and for CBOR carried
+//! as text in JSON fields, where either alphabet may appear. Encoding always
+//! pads. Decoding is strict for the standard alphabet, like Ruby's
+//! `Base64.strict_decode64`; the URL-safe alphabet may omit padding, like
+//! `Base64.urlsafe_decode64`.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alphabet {
+    /// `A-Z a-z 0-9 + /`
+    Standard,
+    /// `A-Z a-z 0-9 - _`
+    UrlSafe,
+}
+
+impl Alphabet {
+    fn table(self) -> &'static [u8; 64] {
+        match self {
+            Self::Standard => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+            Self::UrlSafe => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+        }
+    }
+
+    /// The 6-bit value of an encoded character, if it belongs to this
+    /// alphabet.
+    fn value(self, c: u8) -> Option<u32> {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' if self == Self::Standard => 62,
+            b'/' if self == Self::Standard => 63,
+            b'-' if self == Self::UrlSafe => 62,
+            b'_' if self == Self::UrlSafe => 63,
+            _ => return None,
+        };
+        Some(v as u32)
+    }
+}
+
+/// Encode `input` with padding.
+pub fn encode(input: &[u8], alphabet: Alphabet) -> String {
+    let table = alphabet.table();
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for group in input.chunks(3) {
+        let b = [
+            group[0],
+            group.get(1).copied().unwrap_or(0),
+            group.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        out.push(table[(n >> 18) as usize & 63] as char);
+        out.push(table[(n >> 12) as usize & 63] as char);
+        out.push(if group.len() > 1 { table[(n >> 6) as usize & 63] as char } else { '=' });
+        out.push(if group.len() > 2 { table[n as usize & 63] as char } else { '=' });
+    }
+    out
+}
+
+/// Decode `input`. The error names the first problem found.
+pub fn decode(input: &[u8], alphabet: Alphabet) -> Result<Vec<u8>, String> {
+    let end = input.iter().rposition(|&c| c != b'=').map_or(0, |i| i + 1);
+    let padding = input.len() - end;
+    let rem = input.len() % 4;
+    let valid_length = match alphabet {
+        Alphabet::Standard => rem == 0,
+        Alphabet::UrlSafe => rem == 0 || padding == 0,
+    };
+    if !valid_length || padding > 2 || end % 4 == 1 {
+        return Err(format!(
+            "invalid base64: bad length or padding ({} bytes)",
+            input.len()
+        ));
+    }
+
+    let mut out = Vec::with_capacity(end / 4 * 3 + 2);
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    for (i, &c) in input[..end].iter().enumerate() {
+        let v = alphabet.value(c).ok_or_else(|| {
+            format!("invalid base64: unexpected character {:?} at offset {}", c as char, i)
+        })?;
+        acc = (acc << 6) | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    // Leftover bits of the last character must be zero (canonical form).
+    if acc != 0 {
+        return Err("invalid base64: non-zero trailing bits".to_string());
+    }
+    Ok(out)
+}
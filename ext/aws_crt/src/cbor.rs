@@ -1,9 +1,12 @@
 use magnus::{
+    error::ErrorType,
     exception, function, method,
     prelude::*,
+    scan_args::scan_args,
     rb_sys::{AsRawValue, FromRawValue},
-    typed_data, Class, Error, ExceptionClass, Module, RArray, RString, Ruby, Symbol, TryConvert,
-    Value,
+    gc::Marker,
+    typed_data, Class, DataTypeFunctions, Error, ExceptionClass, Module, RArray, RHash, RString, Ruby,
+    Symbol, TryConvert, Value,
 };
 use rb_sys::{
     rb_ary_push, rb_enc_get_index, rb_enc_str_new, rb_float_value,
@@ -18,12 +21,19 @@ use std::cell::{Cell, RefCell};
 use std::ffi::c_int;
 use std::os::raw::c_long;
 
+use crate::base64::{self, Alphabet};
+
 // ---------------------------------------------------------------------------
 // Cached class references – set once during init(), read on every encode/decode
 // ---------------------------------------------------------------------------
 
 static mut TAGGED_CLASS: VALUE = 0;
+static mut EXACT_FLOAT_CLASS: VALUE = 0;
+static mut BREAK_SENTINEL: VALUE = 0;
+static mut NEED_MORE_SENTINEL: VALUE = 0;
 static mut TIME_CLASS: VALUE = 0;
+static mut RANGE_CLASS: VALUE = 0;
+static mut SET_CLASS: VALUE = 0;
 static mut BIGDECIMAL_CLASS: VALUE = 0;
 static mut BIGDECIMAL_LOADED: bool = false;
 static mut UTF8_ENCINDEX: c_int = 0;
@@ -33,6 +43,9 @@ static mut BINARY_ENCINDEX: c_int = 0;
 unsafe fn cache_classes(ruby: &Ruby) {
     let time_val: Value = ruby.eval("Time").unwrap();
     TIME_CLASS = time_val.as_raw();
+    let range_val: Value = ruby.eval("Range").unwrap();
+    RANGE_CLASS = range_val.as_raw();
+    set_class(ruby);
 
     let bd_val: Value = ruby
         .eval("defined?(BigDecimal) ? BigDecimal : nil")
@@ -46,6 +59,41 @@ unsafe fn cache_classes(ruby: &Ruby) {
     BINARY_ENCINDEX = rb_sys::rb_ascii8bit_encindex();
 }
 
+/// The cached `BigDecimal` class, looking it up again if it wasn't loaded at
+/// init. With `require`, loads the bigdecimal library first if needed;
+/// otherwise returns 0 when it isn't loaded.
+unsafe fn bigdecimal_class(ruby: &Ruby, require: bool) -> Result<VALUE, Error> {
+    if !BIGDECIMAL_LOADED {
+        if require {
+            ruby.eval::<Value>("require 'bigdecimal'")?;
+        }
+        let bd_val: Value = ruby
+            .eval("defined?(BigDecimal) ? BigDecimal : nil")
+            .unwrap_or_else(|_| ruby.qnil().as_value());
+        if bd_val.is_nil() {
+            return Ok(0);
+        }
+        BIGDECIMAL_CLASS = bd_val.as_raw();
+        BIGDECIMAL_LOADED = true;
+    }
+    Ok(BIGDECIMAL_CLASS)
+}
+
+/// The cached `Set` class, looking it up again if it wasn't loaded at init
+/// (before Ruby 3.2 it needs `require "set"`). Returns 0 while it isn't
+/// loaded.
+unsafe fn set_class(ruby: &Ruby) -> VALUE {
+    if SET_CLASS == 0 {
+        let set_val: Value = ruby
+            .eval("defined?(Set) ? Set : nil")
+            .unwrap_or_else(|_| ruby.qnil().as_value());
+        if !set_val.is_nil() {
+            SET_CLASS = set_val.as_raw();
+        }
+    }
+    SET_CLASS
+}
+
 // ---------------------------------------------------------------------------
 // Error helpers
 // ---------------------------------------------------------------------------
@@ -58,7 +106,7 @@ fn get_cbor_module(ruby: &Ruby) -> magnus::RModule {
         .unwrap()
 }
 
-fn cbor_error(ruby: &Ruby) -> ExceptionClass {
+pub(crate) fn cbor_error(ruby: &Ruby) -> ExceptionClass {
     get_cbor_module(ruby)
         .const_get::<_, ExceptionClass>("Error")
         .unwrap()
@@ -94,6 +142,37 @@ fn unexpected_break_code_error(ruby: &Ruby) -> ExceptionClass {
         .unwrap()
 }
 
+fn max_depth_error(ruby: &Ruby) -> ExceptionClass {
+    get_cbor_module(ruby)
+        .const_get::<_, ExceptionClass>("MaxDepthError")
+        .unwrap()
+}
+
+/// Point a decode error at the byte `offset` where decoding stopped: the
+/// message gains "(at byte offset N)", or "(at byte offset N of LEN)" when
+/// the whole buffer's `total` length is given, and the exception's
+/// `#offset` returns it. Errors that aren't `Cbor::Error`s raised by the
+/// decoder itself (a TypeError, or an exception from a block) are returned
+/// unchanged.
+fn with_offset(ruby: &Ruby, err: Error, offset: usize, total: Option<usize>) -> Error {
+    let (class, message) = match err.error_type() {
+        ErrorType::Error(class, message) if err.is_kind_of(cbor_error(ruby)) => (*class, message),
+        _ => return err,
+    };
+    let message = match total {
+        Some(total) => format!("{} (at byte offset {} of {})", message, offset, total),
+        None => format!("{} (at byte offset {})", message, offset),
+    };
+    let exception = match class.new_instance((message,)) {
+        Ok(exception) => exception,
+        Err(e) => return e,
+    };
+    match exception.funcall::<_, _, Value>("instance_variable_set", ("@offset", offset)) {
+        Ok(_) => exception.into(),
+        Err(e) => e,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Raw Ruby helpers
 // ---------------------------------------------------------------------------
@@ -153,6 +232,7 @@ const MAJOR_MAP: u8 = 0xa0;
 const MAJOR_TAG: u8 = 0xc0;
 const MAJOR_SIMPLE: u8 = 0xe0;
 
+const HALF_MARKER: u8 = 0xf9;
 const FLOAT_MARKER: u8 = 0xfa;
 const DOUBLE_MARKER: u8 = 0xfb;
 
@@ -160,6 +240,9 @@ const TAG_EPOCH: u64 = 1;
 const TAG_BIGNUM: u64 = 2;
 const TAG_NEG_BIGNUM: u64 = 3;
 const TAG_BIGDEC: u64 = 4;
+/// Finite set (IANA registry), written around a Set's array with
+/// `tag_sets: true`.
+const TAG_SET: u64 = 258;
 
 // ---------------------------------------------------------------------------
 // Hash iteration context
@@ -167,6 +250,7 @@ const TAG_BIGDEC: u64 = 4;
 
 struct HashIterCtx {
     buf: *mut Vec<u8>,
+    opts: *const EncodeOptions,
     error: Option<Error>,
 }
 
@@ -174,21 +258,124 @@ unsafe extern "C" fn hash_foreach_cb(key: VALUE, val: VALUE, ctx_ptr: VALUE) ->
     let ctx = &mut *(ctx_ptr as *mut HashIterCtx);
     let ruby = Ruby::get_unchecked();
     let buf = &mut *ctx.buf;
-    if let Err(e) = encode_value(&ruby, buf, key) {
+    let opts = &*ctx.opts;
+    if let Err(e) = encode_value(&ruby, buf, key, opts) {
         ctx.error = Some(e);
         return 1;
     }
-    if let Err(e) = encode_value(&ruby, buf, val) {
+    if let Err(e) = encode_value(&ruby, buf, val, opts) {
         ctx.error = Some(e);
         return 1;
     }
     0
 }
 
+/// Collects a Hash's entries for `encode_canonical_map`.
+unsafe extern "C" fn collect_pair_cb(key: VALUE, val: VALUE, pairs_ptr: VALUE) -> c_int {
+    let pairs = &mut *(pairs_ptr as *mut Vec<(VALUE, VALUE)>);
+    pairs.push((key, val));
+    0
+}
+
 // ---------------------------------------------------------------------------
 // Core CBOR encoding (free functions — no struct overhead)
 // ---------------------------------------------------------------------------
 
+/// Width of a float written by the encoder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FloatWidth {
+    Half,
+    Single,
+    Double,
+}
+
+/// Options accepted by `Encoder.new`, `Cbor.encode` and `Cbor.encode_base64`.
+#[derive(Clone, Copy, Default)]
+struct EncodeOptions {
+    /// Width for NaN and ±Infinity, for decoders that only accept one.
+    /// NaN is then written as the canonical quiet NaN at that width. `None`
+    /// keeps the usual shortest-exact choice.
+    special_float_width: Option<FloatWidth>,
+    /// Deterministic encoding (RFC 8949 §4.2.1): map entries sorted by
+    /// their encoded keys and floats in the shortest form that keeps their
+    /// value, half precision included.
+    canonical: bool,
+    /// Write every finite Float as a double (`float_format: :double`), for
+    /// peers that mishandle half or single precision.
+    double_floats: bool,
+    /// Wrap the array a `Set` is written as in tag 258, so peers that know
+    /// the tag can tell it from an Array.
+    tag_sets: bool,
+    /// Raise rather than write a float whose bytes would not decode back to
+    /// the same bits, and write a `Rational` as an exact decimal fraction.
+    exact_floats: bool,
+}
+
+impl EncodeOptions {
+    fn from_hash(opts: Option<RHash>) -> Result<Self, Error> {
+        let mut options = Self::default();
+        if let Some(opts) = opts {
+            let width: Option<Value> = opts.lookup(Symbol::new("special_float_width"))?;
+            if let Some(v) = width.filter(|v| !v.is_nil()) {
+                let name = Symbol::from_value(v).map(|sym| sym.name()).transpose()?;
+                options.special_float_width = Some(match name.as_deref() {
+                    Some("half") => FloatWidth::Half,
+                    Some("single") => FloatWidth::Single,
+                    Some("double") => FloatWidth::Double,
+                    _ => {
+                        return Err(Error::new(
+                            exception::arg_error(),
+                            format!(
+                                "invalid special_float_width {}: must be :half, :single or :double",
+                                v.inspect()
+                            ),
+                        ))
+                    }
+                });
+            }
+            if let Some(v) = opts.get(Symbol::new("canonical")) {
+                options.canonical = v.to_bool();
+            }
+            if let Some(v) = opts.get(Symbol::new("tag_sets")) {
+                options.tag_sets = v.to_bool();
+            }
+            if let Some(v) = opts.get(Symbol::new("exact_floats")) {
+                options.exact_floats = v.to_bool();
+            }
+            let format: Option<Value> = opts.lookup(Symbol::new("float_format"))?;
+            if let Some(v) = format.filter(|v| !v.is_nil()) {
+                let name = Symbol::from_value(v).map(|sym| sym.name()).transpose()?;
+                options.double_floats = match name.as_deref() {
+                    Some("auto") => false,
+                    Some("double") => true,
+                    _ => {
+                        return Err(Error::new(
+                            exception::arg_error(),
+                            format!(
+                                "invalid float_format {}: must be :auto or :double",
+                                v.inspect()
+                            ),
+                        ))
+                    }
+                };
+            }
+        }
+        if options.canonical && options.special_float_width.is_some() {
+            return Err(Error::new(
+                exception::arg_error(),
+                "canonical and special_float_width cannot be combined",
+            ));
+        }
+        if options.canonical && options.double_floats {
+            return Err(Error::new(
+                exception::arg_error(),
+                "canonical and float_format: :double cannot be combined",
+            ));
+        }
+        Ok(options)
+    }
+}
+
 #[inline(always)]
 fn write_head(buf: &mut Vec<u8>, major: u8, value: u64) {
     match value {
@@ -233,19 +420,188 @@ fn encode_double(buf: &mut Vec<u8>, val: f64) {
     buf.extend_from_slice(&val.to_be_bytes());
 }
 
+/// Write the shortest of single or double precision that holds `val`
+/// bit-for-bit. The sign of `-0.0` and NaN sign/payload bits are kept: a
+/// NaN is written as a single only when its payload survives the narrowing.
 #[inline(always)]
 fn encode_auto_float(buf: &mut Vec<u8>, val: f64) {
+    match f64_to_f32_exact(val) {
+        Some(single) => {
+            buf.push(FLOAT_MARKER);
+            buf.extend_from_slice(&single.to_be_bytes());
+        }
+        None => encode_double(buf, val),
+    }
+}
+
+/// Write a Float, honouring `special_float_width` for NaN and ±Infinity.
+#[inline(always)]
+fn encode_float(buf: &mut Vec<u8>, val: f64, opts: &EncodeOptions) {
+    match opts.special_float_width {
+        Some(width) if !val.is_finite() => encode_special_float(buf, val, width),
+        _ if opts.double_floats => encode_double(buf, val),
+        _ if opts.canonical => encode_shortest_float(buf, val),
+        _ => encode_auto_float(buf, val),
+    }
+}
+
+/// Write a Float as `encode_float` does. With `exact_floats`, raise
+/// instead if the form the options pick would not decode back to `val`'s
+/// bits (a NaN payload under `canonical` or `special_float_width`).
+fn encode_float_exact(
+    ruby: &Ruby,
+    buf: &mut Vec<u8>,
+    val: f64,
+    opts: &EncodeOptions,
+) -> Result<(), Error> {
+    let start = buf.len();
+    encode_float(buf, val, opts);
+    if opts.exact_floats && written_float_bits(&buf[start..]) != val.to_bits() {
+        buf.truncate(start);
+        return Err(Error::new(
+            cbor_error(ruby),
+            format!(
+                "cannot write {:?} (bits 0x{:016x}) exactly with these options",
+                val,
+                val.to_bits()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// The bits of a float written by `write_exact_float`, widened to a double
+/// as the decoder widens them.
+fn written_float_bits(bytes: &[u8]) -> u64 {
+    match bytes[0] {
+        HALF_MARKER => half_to_f64(u16::from_be_bytes([bytes[1], bytes[2]])).to_bits(),
+        FLOAT_MARKER => {
+            let single = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+            f32_to_f64_exact(f32::from_bits(single)).to_bits()
+        }
+        _ => u64::from_be_bytes(bytes[1..9].try_into().unwrap()),
+    }
+}
+
+/// Write the shortest of half, single or double precision that holds
+/// `val`'s value. Every NaN becomes the half-precision quiet NaN, as
+/// deterministic encoding requires.
+fn encode_shortest_float(buf: &mut Vec<u8>, val: f64) {
+    if val.is_nan() {
+        return encode_special_float(buf, val, FloatWidth::Half);
+    }
+    match f64_to_f16_exact(val) {
+        Some(half) => write_exact_float(buf, 16, half as u64),
+        None => encode_auto_float(buf, val),
+    }
+}
+
+/// Write NaN (as the canonical quiet NaN) or ±Infinity at `width`.
+fn encode_special_float(buf: &mut Vec<u8>, val: f64, width: FloatWidth) {
+    let (bits, nan, infinity) = match width {
+        FloatWidth::Half => (16, 0x7e00, 0x7c00),
+        FloatWidth::Single => (32, 0x7fc0_0000, 0x7f80_0000),
+        FloatWidth::Double => (64, 0x7ff8_0000_0000_0000, 0x7ff0_0000_0000_0000),
+    };
+    let encoded = if val.is_nan() {
+        nan
+    } else if val.is_sign_negative() {
+        infinity | 1 << (bits - 1)
+    } else {
+        infinity
+    };
+    write_exact_float(buf, bits, encoded);
+}
+
+/// Narrow `val` to single precision if that loses no bits.
+#[inline(always)]
+fn f64_to_f32_exact(val: f64) -> Option<f32> {
+    let bits = val.to_bits();
+    if val.is_nan() {
+        // Casting would quiet signaling NaNs; move the payload by hand.
+        if bits & ((1 << 29) - 1) != 0 {
+            return None;
+        }
+        let sign = ((bits >> 63) as u32) << 31;
+        let mant = ((bits >> 29) & 0x7f_ffff) as u32;
+        return Some(f32::from_bits(sign | 0x7f80_0000 | mant));
+    }
+    let single = val as f32;
+    ((single as f64).to_bits() == bits).then_some(single)
+}
+
+/// Narrow a non-NaN `val` to half precision bits if that loses nothing.
+fn f64_to_f16_exact(val: f64) -> Option<u16> {
+    let bits = f64_to_f32_exact(val)?.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mant = bits & 0x7f_ffff;
+    match exp {
+        0xff => Some(sign | 0x7c00),
+        0 if mant == 0 => Some(sign),
+        // Single subnormals are far below the smallest half.
+        0 => None,
+        _ => {
+            let e = exp - 127;
+            if (-14..=15).contains(&e) {
+                // Normal half: the low 13 mantissa bits must be zero.
+                (mant & 0x1fff == 0).then(|| sign | ((e + 15) as u16) << 10 | (mant >> 13) as u16)
+            } else if (-24..-14).contains(&e) {
+                // Subnormal half: the significand shifted down to units of
+                // 2^-24 must not drop any set bits.
+                let significand = mant | 0x80_0000;
+                let shift = (-(e + 1)) as u32;
+                (significand & ((1 << shift) - 1) == 0).then(|| sign | (significand >> shift) as u16)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Widen a single to double precision, keeping NaN payload bits.
+#[inline(always)]
+fn f32_to_f64_exact(val: f32) -> f64 {
     if val.is_nan() {
-        buf.push(FLOAT_MARKER);
-        buf.extend_from_slice(&(val as f32).to_be_bytes());
+        let bits = val.to_bits();
+        let sign = ((bits >> 31) as u64) << 63;
+        let mant = ((bits & 0x7f_ffff) as u64) << 29;
+        f64::from_bits(sign | 0x7ff0_0000_0000_0000 | mant)
     } else {
-        let single = val as f32;
-        if single as f64 == val {
+        val as f64
+    }
+}
+
+/// Convert IEEE 754 half precision bits to a double, keeping NaN payload bits.
+fn half_to_f64(b16: u16) -> f64 {
+    let sign = (b16 >> 15) as u64;
+    let exp = ((b16 >> 10) & 0x1f) as i32;
+    let mant = (b16 & 0x3ff) as u64;
+    if exp == 31 && mant != 0 {
+        return f64::from_bits(sign << 63 | 0x7ff0_0000_0000_0000 | mant << 42);
+    }
+    let val = match exp {
+        0 => mant as f64 * 2.0f64.powi(-24),
+        31 => f64::INFINITY,
+        _ => (1024.0 + mant as f64) * 2.0f64.powi(exp - 25),
+    };
+    if sign == 0 { val } else { -val }
+}
+
+/// Write a float of the given width (16, 32 or 64) from its raw bits.
+fn write_exact_float(buf: &mut Vec<u8>, width: u64, bits: u64) {
+    match width {
+        16 => {
+            buf.push(HALF_MARKER);
+            buf.extend_from_slice(&(bits as u16).to_be_bytes());
+        }
+        32 => {
             buf.push(FLOAT_MARKER);
-            buf.extend_from_slice(&single.to_be_bytes());
-        } else {
+            buf.extend_from_slice(&(bits as u32).to_be_bytes());
+        }
+        _ => {
             buf.push(DOUBLE_MARKER);
-            buf.extend_from_slice(&val.to_be_bytes());
+            buf.extend_from_slice(&bits.to_be_bytes());
         }
     }
 }
@@ -282,36 +638,156 @@ fn encode_ruby_bignum(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(),
     Ok(())
 }
 
-fn encode_big_decimal(_ruby: &Ruby, buf: &mut Vec<u8>, value: Value) -> Result<(), Error> {
+fn encode_big_decimal(
+    ruby: &Ruby,
+    buf: &mut Vec<u8>,
+    value: Value,
+    opts: &EncodeOptions,
+) -> Result<(), Error> {
     let infinite: Value = value.funcall("infinite?", ())?;
     if !infinite.is_nil() {
         let inf_val: i64 = TryConvert::try_convert(infinite)?;
-        encode_auto_float(
+        encode_float(
             buf,
             if inf_val >= 0 { f64::INFINITY } else { f64::NEG_INFINITY },
+            opts,
         );
         return Ok(());
     }
     let nan: bool = value.funcall("nan?", ())?;
     if nan {
-        encode_auto_float(buf, f64::NAN);
+        encode_float(buf, f64::NAN, opts);
         return Ok(());
     }
-    write_head(buf, MAJOR_TAG, TAG_BIGDEC);
     let parts: RArray = value.funcall("split", ())?;
     let sign: i64 = TryConvert::try_convert(parts.entry(0)?)?;
     let digits_str: String = TryConvert::try_convert(parts.entry(1)?)?;
     let exp: i64 = TryConvert::try_convert(parts.entry(3)?)?;
     let cbor_exp = exp - digits_str.len() as i64;
-    let mantissa: i128 = digits_str.parse::<i128>().unwrap_or(0) * sign as i128;
+    let mantissa = match digits_str.parse::<i128>() {
+        Ok(digits) => digits * sign as i128,
+        Err(_) if opts.exact_floats => {
+            return Err(Error::new(
+                cbor_error(ruby),
+                format!(
+                    "cannot write a BigDecimal of {} digits exactly",
+                    digits_str.len()
+                ),
+            ))
+        }
+        Err(_) => 0,
+    };
+    write_head(buf, MAJOR_TAG, TAG_BIGDEC);
     write_head(buf, MAJOR_ARRAY, 2);
     encode_integer(buf, cbor_exp as i128);
     encode_integer(buf, mantissa);
     Ok(())
 }
 
+/// A `Rational` as a decimal fraction (tag 4), with `exact_floats`. Only a
+/// rational whose denominator divides a power of ten has one; any other
+/// raises rather than being rounded.
+fn encode_rational(
+    ruby: &Ruby,
+    buf: &mut Vec<u8>,
+    value: Value,
+    opts: &EncodeOptions,
+) -> Result<(), Error> {
+    let numerator: Value = value.funcall("numerator", ())?;
+    let denominator: Value = value.funcall("denominator", ())?;
+    // Strip the 2s and 5s from the denominator; what remains must be 1.
+    let mut rest = denominator;
+    let mut digits = 0;
+    for factor in [2i64, 5] {
+        let mut count = 0;
+        loop {
+            let qr: RArray = rest.funcall("divmod", (factor,))?;
+            let remainder: i64 = qr.entry(1)?;
+            if remainder != 0 {
+                break;
+            }
+            rest = qr.entry(0)?;
+            count += 1;
+        }
+        digits = std::cmp::max(digits, count);
+    }
+    if !rest.funcall::<_, _, bool>("==", (1i64,))? {
+        let inspect: String = value.funcall("inspect", ())?;
+        return Err(Error::new(
+            cbor_error(ruby),
+            format!("{} has no exact decimal form", inspect),
+        ));
+    }
+    let scale: Value = ruby.integer_from_i64(10).funcall("**", (digits,))?;
+    let scaled: Value = numerator.funcall("*", (scale,))?;
+    let mantissa: Value = scaled.funcall("/", (denominator,))?;
+    write_head(buf, MAJOR_TAG, TAG_BIGDEC);
+    write_head(buf, MAJOR_ARRAY, 2);
+    encode_integer(buf, -(digits as i128));
+    encode_value(ruby, buf, mantissa.as_raw(), opts)
+}
+
+/// Write a Hash as a map in deterministic order (RFC 8949 §4.2.1):
+/// entries sorted by the bytewise order of their encoded keys, which are
+/// compared as bytes rather than as Ruby values. Two keys that encode the
+/// same (`"a"` and `:a`) would make the map ambiguous, so they raise.
+fn encode_canonical_map(
+    ruby: &Ruby,
+    buf: &mut Vec<u8>,
+    raw: VALUE,
+    opts: &EncodeOptions,
+) -> Result<(), Error> {
+    let mut pairs: Vec<(VALUE, VALUE)> = Vec::new();
+    unsafe {
+        rb_hash_foreach(
+            raw,
+            Some(collect_pair_cb),
+            &mut pairs as *mut Vec<(VALUE, VALUE)> as VALUE,
+        );
+    }
+
+    // Each entry is encoded into `scratch` as (start, key end, value end).
+    let mut scratch = Vec::new();
+    let mut entries = Vec::with_capacity(pairs.len());
+    for (key, val) in pairs {
+        let start = scratch.len();
+        encode_value(ruby, &mut scratch, key, opts)?;
+        let key_end = scratch.len();
+        encode_value(ruby, &mut scratch, val, opts)?;
+        entries.push((start, key_end, scratch.len()));
+    }
+
+    let key_bytes = |entry: &(usize, usize, usize)| &scratch[entry.0..entry.1];
+    entries.sort_unstable_by(|a, b| key_bytes(a).cmp(key_bytes(b)));
+    if let Some(pair) = entries
+        .windows(2)
+        .find(|pair| key_bytes(&pair[0]) == key_bytes(&pair[1]))
+    {
+        let key =
+            unsafe { Value::from_raw(new_encoded_string(key_bytes(&pair[0]), BINARY_ENCINDEX)) };
+        return Err(Error::new(
+            cbor_error(ruby),
+            format!(
+                "Duplicate map key in canonical encoding: two keys encode to {}",
+                key.inspect()
+            ),
+        ));
+    }
+
+    write_head(buf, MAJOR_MAP, entries.len() as u64);
+    for (start, _, end) in entries {
+        buf.extend_from_slice(&scratch[start..end]);
+    }
+    Ok(())
+}
+
 /// Main recursive encoder — operates on raw VALUEs, writes to a Vec<u8>.
-fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error> {
+fn encode_value(
+    ruby: &Ruby,
+    buf: &mut Vec<u8>,
+    raw: VALUE,
+    opts: &EncodeOptions,
+) -> Result<(), Error> {
     // Immediate values — no C API call needed
     if raw == rb_sys::Qnil as VALUE {
         write_head(buf, MAJOR_SIMPLE, 22);
@@ -332,8 +808,7 @@ fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error>
     }
     if rb_sys::FLONUM_P(raw) {
         let f = unsafe { float_to_f64(raw) };
-        encode_auto_float(buf, f);
-        return Ok(());
+        return encode_float_exact(ruby, buf, f, opts);
     }
 
     let typ = raw_type(raw);
@@ -359,11 +834,15 @@ fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error>
             let ptr = unsafe { rb_sys::RARRAY_CONST_PTR(raw) };
             for i in 0..len {
                 let elem = unsafe { *ptr.add(i) };
-                encode_value(ruby, buf, elem)?;
+                encode_value(ruby, buf, elem, opts)?;
             }
             Ok(())
         }
 
+        ruby_value_type::RUBY_T_HASH if opts.canonical => {
+            encode_canonical_map(ruby, buf, raw, opts)
+        }
+
         ruby_value_type::RUBY_T_HASH => {
             let size_val = unsafe { rb_hash_size(raw) };
             let size = unsafe { rb_sys::FIX2LONG(size_val) } as u64;
@@ -371,6 +850,7 @@ fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error>
 
             let mut ctx = HashIterCtx {
                 buf: buf as *mut Vec<u8>,
+                opts: opts as *const EncodeOptions,
                 error: None,
             };
             unsafe {
@@ -388,14 +868,30 @@ fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error>
 
         ruby_value_type::RUBY_T_FLOAT => {
             let f = unsafe { float_to_f64(raw) };
-            encode_auto_float(buf, f);
-            Ok(())
+            encode_float_exact(ruby, buf, f, opts)
         }
 
         ruby_value_type::RUBY_T_SYMBOL => {
             let str_val = unsafe { rb_sym2str(raw) };
             let (ptr, len) = unsafe { rstring_ptr_len(str_val) };
             let bytes = unsafe { std::slice::from_raw_parts(ptr, len) };
+            // Symbols built from binary Strings keep ASCII-8BIT; send those
+            // as byte strings like the Strings they came from.
+            if unsafe { string_enc_index(str_val) == BINARY_ENCINDEX } {
+                write_head(buf, MAJOR_BYTES, len as u64);
+                buf.extend_from_slice(bytes);
+                return Ok(());
+            }
+            if let Err(e) = std::str::from_utf8(bytes) {
+                return Err(Error::new(
+                    cbor_error(ruby),
+                    format!(
+                        "Symbol :{} is not valid UTF-8 (invalid byte at offset {})",
+                        String::from_utf8_lossy(bytes),
+                        e.valid_up_to()
+                    ),
+                ));
+            }
             encode_text(buf, bytes);
             Ok(())
         }
@@ -403,6 +899,11 @@ fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error>
         ruby_value_type::RUBY_T_BIGNUM => encode_ruby_bignum(ruby, buf, raw),
 
         ruby_value_type::RUBY_T_STRUCT => {
+            let range_class = unsafe { RANGE_CLASS };
+            if range_class != 0 && raw_is_kind_of(raw, range_class) {
+                return encode_range(ruby, buf, raw, opts);
+            }
+
             let value = unsafe { Value::from_raw(raw) };
             let class_name: String =
                 value.funcall("class", ()).and_then(|c: Value| c.funcall("to_s", ()))?;
@@ -420,7 +921,33 @@ fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error>
                 let tag: u64 = value.funcall("tag", ())?;
                 let inner: Value = value.funcall("value", ())?;
                 write_head(buf, MAJOR_TAG, tag);
-                return encode_value(ruby, buf, inner.as_raw());
+                return encode_value(ruby, buf, inner.as_raw(), opts);
+            }
+
+            let exact_float_class = unsafe { EXACT_FLOAT_CLASS };
+            if exact_float_class != 0 && raw_is_kind_of(raw, exact_float_class) {
+                let value = unsafe { Value::from_raw(raw) };
+                let width: u64 = value.funcall("width", ())?;
+                let bits: u64 = value.funcall("bits", ())?;
+                if opts.canonical {
+                    // Deterministic encoding has one form per value, so the
+                    // original width can't be kept.
+                    let start = buf.len();
+                    encode_float(buf, value.funcall("to_f", ())?, opts);
+                    let mut exact = Vec::with_capacity(9);
+                    write_exact_float(&mut exact, width, bits);
+                    if opts.exact_floats && buf[start..] != exact[..] {
+                        buf.truncate(start);
+                        let inspect: String = value.funcall("inspect", ())?;
+                        return Err(Error::new(
+                            cbor_error(ruby),
+                            format!("cannot write {} exactly in canonical CBOR", inspect),
+                        ));
+                    }
+                    return Ok(());
+                }
+                write_exact_float(buf, width, bits);
+                return Ok(());
             }
 
             let time_class = unsafe { TIME_CLASS };
@@ -428,29 +955,24 @@ fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error>
                 let value = unsafe { Value::from_raw(raw) };
                 write_head(buf, MAJOR_TAG, TAG_EPOCH);
                 let epoch: f64 = value.funcall("to_f", ())?;
-                encode_double(buf, epoch);
+                if opts.canonical {
+                    encode_float(buf, epoch, opts);
+                } else {
+                    encode_double(buf, epoch);
+                }
                 return Ok(());
             }
 
-            let bd_class = unsafe {
-                if BIGDECIMAL_LOADED {
-                    BIGDECIMAL_CLASS
-                } else {
-                    let bd_val: Value = ruby
-                        .eval("defined?(BigDecimal) ? BigDecimal : nil")
-                        .unwrap_or_else(|_| ruby.qnil().as_value());
-                    if !bd_val.is_nil() {
-                        BIGDECIMAL_CLASS = bd_val.as_raw();
-                        BIGDECIMAL_LOADED = true;
-                        BIGDECIMAL_CLASS
-                    } else {
-                        0
-                    }
-                }
-            };
+            let bd_class = unsafe { bigdecimal_class(ruby, false)? };
             if bd_class != 0 && raw_is_kind_of(raw, bd_class) {
                 let value = unsafe { Value::from_raw(raw) };
-                return encode_big_decimal(ruby, buf, value);
+                return encode_big_decimal(ruby, buf, value, opts);
+            }
+
+            // Set is T_DATA once it is implemented in C (Ruby 3.5)
+            let set_class = unsafe { set_class(ruby) };
+            if set_class != 0 && raw_is_kind_of(raw, set_class) {
+                return encode_set(ruby, buf, raw, opts);
             }
 
             let value = unsafe { Value::from_raw(raw) };
@@ -462,7 +984,16 @@ fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error>
             ))
         }
 
+        ruby_value_type::RUBY_T_RATIONAL if opts.exact_floats => {
+            encode_rational(ruby, buf, unsafe { Value::from_raw(raw) }, opts)
+        }
+
         _ => {
+            let set_class = unsafe { set_class(ruby) };
+            if set_class != 0 && raw_is_kind_of(raw, set_class) {
+                return encode_set(ruby, buf, raw, opts);
+            }
+
             let value = unsafe { Value::from_raw(raw) };
             let class_name: String =
                 value.funcall("class", ()).and_then(|c: Value| c.funcall("to_s", ()))?;
@@ -474,10 +1005,151 @@ fn encode_value(ruby: &Ruby, buf: &mut Vec<u8>, raw: VALUE) -> Result<(), Error>
     }
 }
 
+/// A `Set` as an array of its members in iteration order, inside tag 258
+/// with `tag_sets`.
+fn encode_set(
+    ruby: &Ruby,
+    buf: &mut Vec<u8>,
+    raw: VALUE,
+    opts: &EncodeOptions,
+) -> Result<(), Error> {
+    let value = unsafe { Value::from_raw(raw) };
+    let members: RArray = value.funcall("to_a", ())?;
+    if opts.tag_sets {
+        write_head(buf, MAJOR_TAG, TAG_SET);
+    }
+    encode_value(ruby, buf, members.as_raw(), opts)
+}
+
+/// A `Range` as the two-element array `[begin, end]`; a beginless or
+/// endless range has nil at that end. Whether the end is excluded isn't
+/// recorded.
+fn encode_range(
+    ruby: &Ruby,
+    buf: &mut Vec<u8>,
+    raw: VALUE,
+    opts: &EncodeOptions,
+) -> Result<(), Error> {
+    let value = unsafe { Value::from_raw(raw) };
+    let first: Value = value.funcall("begin", ())?;
+    let last: Value = value.funcall("end", ())?;
+    write_head(buf, MAJOR_ARRAY, 2);
+    encode_value(ruby, buf, first.as_raw(), opts)?;
+    encode_value(ruby, buf, last.as_raw(), opts)
+}
+
 // ---------------------------------------------------------------------------
 // Core CBOR decoding (free functions — no struct overhead)
 // ---------------------------------------------------------------------------
 
+/// Default for `max_depth`: deep enough for any real document, shallow
+/// enough that the recursive decoder stays well inside a thread's stack.
+const DEFAULT_MAX_DEPTH: usize = 1000;
+
+/// Options accepted by `Decoder.new` and `Cbor.decode`.
+#[derive(Clone, Copy)]
+struct DecodeOptions {
+    /// Return floats that would not re-encode to the same bytes (half
+    /// precision, doubles that fit in a single, NaNs with payloads that do
+    /// not narrow) as `ExactFloat` instead of `Float`.
+    exact_floats: bool,
+    /// Return every float as a `BigDecimal` built from its shortest
+    /// round-trip decimal string, for data where floats stand in for
+    /// decimals.
+    floats_as_decimal: bool,
+    /// Largest byte length accepted for a text, byte or bignum string
+    /// (for an indefinite-length string, the total of its chunks). Checked
+    /// against the declared length before any bytes are read, so a hostile
+    /// header can't make the decoder allocate or copy.
+    max_string_length: Option<usize>,
+    /// Return text map keys as Symbols instead of Strings.
+    symbolize_keys: bool,
+    /// Most arrays, maps and tags that may enclose one another. Decoding
+    /// recurses once per level, so this bounds native stack use on
+    /// hostile input.
+    max_depth: usize,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            exact_floats: false,
+            floats_as_decimal: false,
+            max_string_length: None,
+            symbolize_keys: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+impl DecodeOptions {
+    fn from_hash(ruby: &Ruby, opts: Option<RHash>) -> Result<Self, Error> {
+        let mut options = Self::default();
+        if let Some(opts) = opts {
+            if let Some(v) = opts.get(Symbol::new("exact_floats")) {
+                options.exact_floats = v.to_bool();
+            }
+            if let Some(v) = opts.get(Symbol::new("floats_as_decimal")) {
+                options.floats_as_decimal = v.to_bool();
+            }
+            if let Some(v) = opts.get(Symbol::new("symbolize_keys")) {
+                options.symbolize_keys = v.to_bool();
+            }
+            if let Some(v) = opts.lookup::<_, Option<usize>>(Symbol::new("max_string_length"))? {
+                options.max_string_length = Some(v);
+            }
+            if let Some(v) = opts.lookup::<_, Option<usize>>(Symbol::new("max_depth"))? {
+                options.max_depth = v;
+            }
+        }
+        if options.exact_floats && options.floats_as_decimal {
+            return Err(Error::new(
+                exception::arg_error(),
+                "exact_floats and floats_as_decimal cannot be combined",
+            ));
+        }
+        if options.floats_as_decimal {
+            unsafe { bigdecimal_class(ruby, true)? };
+        }
+        Ok(options)
+    }
+
+    #[inline(always)]
+    fn string_length_ok(&self, len: u64) -> bool {
+        self.max_string_length.is_none_or(|max| len <= max as u64)
+    }
+
+    /// Raise `Cbor::Error` if a string's declared `len` is over
+    /// `max_string_length`.
+    #[inline]
+    fn check_string_length(&self, ruby: &Ruby, len: u64) -> Result<(), Error> {
+        if self.string_length_ok(len) {
+            return Ok(());
+        }
+        Err(Error::new(
+            cbor_error(ruby),
+            format!(
+                "String length {} exceeds max_string_length {}",
+                len,
+                self.max_string_length.unwrap_or(0)
+            ),
+        ))
+    }
+
+    /// Raise `MaxDepthError` if an array, map or tag at nesting `depth`
+    /// (0 for a top-level item) would go past `max_depth`.
+    #[inline(always)]
+    fn check_depth(&self, ruby: &Ruby, depth: usize) -> Result<(), Error> {
+        if depth < self.max_depth {
+            return Ok(());
+        }
+        Err(Error::new(
+            max_depth_error(ruby),
+            format!("Maximum nesting depth of {} exceeded", self.max_depth),
+        ))
+    }
+}
+
 #[inline(always)]
 fn dec_peek(ruby: &Ruby, data: &[u8], pos: usize) -> Result<u8, Error> {
     if pos >= data.len() {
@@ -547,7 +1219,13 @@ fn dec_read_count(ruby: &Ruby, data: &[u8], pos: &mut usize, ai: u8) -> Result<u
     }
 }
 
-fn decode_value(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
+fn decode_value(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+    depth: usize,
+) -> Result<VALUE, Error> {
     let p = *pos;
     if p >= data.len() {
         return Err(Error::new(
@@ -574,15 +1252,15 @@ fn decode_value(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Erro
             Ok(fixnum_val(-1 - add_info as i64))
         }
         0 | 1 => decode_integer_raw(ruby, data, pos),
-        2 if add_info == 31 => decode_indef_binary(ruby, data, pos),
-        2 => decode_binary_raw(ruby, data, pos),
-        3 if add_info == 31 => decode_indef_text(ruby, data, pos),
-        3 => decode_text_raw(ruby, data, pos),
-        4 if add_info == 31 => decode_indef_array(ruby, data, pos),
-        4 => decode_array_raw(ruby, data, pos),
-        5 if add_info == 31 => decode_indef_map(ruby, data, pos),
-        5 => decode_map_raw(ruby, data, pos),
-        6 => decode_tag_raw(ruby, data, pos),
+        2 if add_info == 31 => decode_indef_binary(ruby, data, pos, opts),
+        2 => decode_binary_raw(ruby, data, pos, opts),
+        3 if add_info == 31 => decode_indef_text(ruby, data, pos, opts),
+        3 => decode_text_raw(ruby, data, pos, opts),
+        4 if add_info == 31 => decode_indef_array(ruby, data, pos, opts, depth),
+        4 => decode_array_raw(ruby, data, pos, opts, depth),
+        5 if add_info == 31 => decode_indef_map(ruby, data, pos, opts, depth),
+        5 => decode_map_raw(ruby, data, pos, opts, depth),
+        6 => decode_tag_raw(ruby, data, pos, opts, depth),
         7 => match add_info {
             20 => {
                 *pos = p + 1;
@@ -600,7 +1278,7 @@ fn decode_value(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Erro
                 *pos = p + 1;
                 Ok(Symbol::new("undefined").as_value().as_raw())
             }
-            25 => decode_half_raw(ruby, data, pos),
+            25 => decode_half_raw(ruby, data, pos, opts),
             26 => {
                 let start = p + 1;
                 let end = start + 4;
@@ -615,7 +1293,7 @@ fn decode_value(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Erro
                 }
                 let f = f32::from_be_bytes([data[start], data[start+1], data[start+2], data[start+3]]);
                 *pos = end;
-                Ok(unsafe { rb_float_new(f as f64) })
+                decode_float(ruby, &data[p..end], f32_to_f64_exact(f), opts)
             }
             27 => {
                 let start = p + 1;
@@ -634,7 +1312,7 @@ fn decode_value(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Erro
                     data[start+4], data[start+5], data[start+6], data[start+7],
                 ]);
                 *pos = end;
-                Ok(unsafe { rb_float_new(f) })
+                decode_float(ruby, &data[p..end], f, opts)
             }
             31 => Err(Error::new(
                 unexpected_break_code_error(ruby),
@@ -692,20 +1370,32 @@ fn decode_integer_raw(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE
 }
 
 #[inline]
-fn decode_binary_raw(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
+fn decode_binary_raw(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+) -> Result<VALUE, Error> {
     let (_mt, ai) = dec_read_info(ruby, data, pos)?;
-    let len = dec_read_count(ruby, data, pos, ai)? as usize;
+    let len = dec_read_count(ruby, data, pos, ai)?;
+    opts.check_string_length(ruby, len)?;
+    let len = len as usize;
     let bytes = dec_take(ruby, data, pos, len)?;
     Ok(unsafe { new_encoded_string(bytes, BINARY_ENCINDEX) })
 }
 
 #[inline]
-fn decode_text_raw(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
+fn decode_text_raw(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+) -> Result<VALUE, Error> {
     // Fast path for short strings (ai < 24)
     let p = *pos;
     if p < data.len() {
         let ai = data[p] & 0x1f;
-        if ai < 24 {
+        if ai < 24 && opts.string_length_ok(ai as u64) {
             let len = ai as usize;
             let start = p + 1;
             let end = start + len;
@@ -716,58 +1406,151 @@ fn decode_text_raw(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, E
         }
     }
     let (_mt, ai) = dec_read_info(ruby, data, pos)?;
-    let len = dec_read_count(ruby, data, pos, ai)? as usize;
+    let len = dec_read_count(ruby, data, pos, ai)?;
+    opts.check_string_length(ruby, len)?;
+    let len = len as usize;
     let bytes = dec_take(ruby, data, pos, len)?;
     Ok(unsafe { new_encoded_string(bytes, UTF8_ENCINDEX) })
 }
 
-fn decode_array_raw(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
-    let (_mt, ai) = dec_read_info(ruby, data, pos)?;
-    let len = dec_read_count(ruby, data, pos, ai)? as usize;
-    let arr = unsafe { rb_sys::rb_ary_new_capa(len as c_long) };
-    for _ in 0..len {
-        let item = decode_value(ruby, data, pos)?;
-        unsafe { rb_ary_push(arr, item) };
+/// Number of leading elements peeked to decide whether an array looks like
+/// a homogeneous run of small integers.
+const SMALL_INT_PROBE: usize = 8;
+
+/// True if `ib` is a complete one-byte integer: major type 0 or 1 with
+/// `ai < 24` (i.e. -24..=23).
+#[inline(always)]
+fn is_small_int(ib: u8) -> bool {
+    ib < 0x18 || (0x20..0x38).contains(&ib)
+}
+
+/// Decode a one-byte integer previously checked with `is_small_int`.
+#[inline(always)]
+fn small_int_val(ib: u8) -> VALUE {
+    if ib < 0x18 {
+        fixnum_val(ib as i64)
+    } else {
+        fixnum_val(-1 - (ib & 0x1f) as i64)
     }
-    Ok(arr)
 }
 
-fn decode_map_raw(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
+fn decode_array_raw(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+    depth: usize,
+) -> Result<VALUE, Error> {
+    opts.check_depth(ruby, depth)?;
     let (_mt, ai) = dec_read_info(ruby, data, pos)?;
     let len = dec_read_count(ruby, data, pos, ai)? as usize;
-    let hash = unsafe { rb_sys::rb_hash_new_capa(len as c_long) };
-    for _ in 0..len {
-        // Inline key decode: most keys are short text (major 3, ai < 24)
-        let key;
-        let p = *pos;
-        if p < data.len() {
-            let ib = data[p];
-            let kai = ib & 0x1f;
-            if (ib >> 5) == 3 && kai < 24 {
-                let slen = kai as usize;
-                let start = p + 1;
-                let end = start + slen;
-                if end <= data.len() {
-                    *pos = end;
-                    key = unsafe {
-                        new_encoded_string(&data[start..end], UTF8_ENCINDEX)
-                    };
-                } else {
-                    key = decode_text_raw(ruby, data, pos)?;
-                }
-            } else {
-                key = decode_text_raw(ruby, data, pos)?;
-            }
-        } else {
-            key = decode_text_raw(ruby, data, pos)?;
+    // Every item takes at least a byte, so a count beyond the rest of the
+    // input is only reserved up to what could be there; decoding the items
+    // then runs out of bytes.
+    let capa = len.min(data.len() - *pos);
+    let arr = unsafe { rb_sys::rb_ary_new_capa(capa as c_long) };
+
+    // Fast path for arrays of small integers (e.g. samples, byte-ish data):
+    // if the first few elements are one-byte ints, consume the run in a
+    // tight loop. Stops at the first other element and falls through to
+    // the general path for the rest, as does a count too large to add to
+    // the position.
+    let mut decoded = 0;
+    let p = *pos;
+    let small_ints = len >= SMALL_INT_PROBE
+        && data.len() - p >= SMALL_INT_PROBE
+        && data[p..p + SMALL_INT_PROBE].iter().all(|&b| is_small_int(b));
+    if let Some(end) = p.checked_add(len).filter(|_| small_ints) {
+        let end = end.min(data.len());
+        let mut q = p;
+        while q < end && is_small_int(data[q]) {
+            unsafe { rb_ary_push(arr, small_int_val(data[q])) };
+            q += 1;
         }
-        let val = decode_value(ruby, data, pos)?;
-        unsafe { rb_hash_aset(hash, key, val) };
+        decoded = q - p;
+        *pos = q;
+    }
+
+    for _ in decoded..len {
+        let item = decode_value(ruby, data, pos, opts, depth + 1)?;
+        unsafe { rb_ary_push(arr, item) };
+    }
+    Ok(arr)
+}
+
+/// Decode a map key, which is expected to be text.
+///
+/// Most keys are short text (major 3, ai < 24), so those are read inline.
+/// With `symbolize_keys` they are interned straight from the input bytes,
+/// without building a String first.
+#[inline(always)]
+fn decode_map_key(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+) -> Result<VALUE, Error> {
+    let p = *pos;
+    if p < data.len() {
+        let ib = data[p];
+        let kai = ib & 0x1f;
+        if (ib >> 5) == 3 && kai < 24 && opts.string_length_ok(kai as u64) {
+            let start = p + 1;
+            let end = start + kai as usize;
+            if end <= data.len() {
+                *pos = end;
+                let bytes = &data[start..end];
+                return Ok(unsafe {
+                    if opts.symbolize_keys {
+                        rb_sys::rb_id2sym(rb_sys::rb_intern3(
+                            bytes.as_ptr() as *const _,
+                            bytes.len() as c_long,
+                            rb_sys::rb_enc_from_index(UTF8_ENCINDEX),
+                        ))
+                    } else {
+                        new_encoded_string(bytes, UTF8_ENCINDEX)
+                    }
+                });
+            }
+        }
+    }
+    let key = decode_text_raw(ruby, data, pos, opts)?;
+    if opts.symbolize_keys {
+        return Ok(unsafe { rb_sys::rb_str_intern(key) });
+    }
+    Ok(key)
+}
+
+fn decode_map_raw(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+    depth: usize,
+) -> Result<VALUE, Error> {
+    opts.check_depth(ruby, depth)?;
+    let (_mt, ai) = dec_read_info(ruby, data, pos)?;
+    let len = dec_read_count(ruby, data, pos, ai)? as usize;
+    // Each entry needs at least two bytes, so cap a hostile count before
+    // allocating.
+    let capa = len.min((data.len() - *pos) / 2);
+    let hash = unsafe { rb_sys::rb_hash_new_capa(capa as c_long) };
+    for _ in 0..len {
+        let key = decode_map_key(ruby, data, pos, opts)?;
+        let val = decode_value(ruby, data, pos, opts, depth + 1)?;
+        unsafe { rb_hash_aset(hash, key, val) };
     }
     Ok(hash)
 }
 
-fn decode_indef_array(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
+fn decode_indef_array(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+    depth: usize,
+) -> Result<VALUE, Error> {
+    opts.check_depth(ruby, depth)?;
     *pos += 1; // skip initial byte (0x9f)
     let arr = unsafe { rb_sys::rb_ary_new() };
     loop {
@@ -776,13 +1559,20 @@ fn decode_indef_array(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE
             *pos += 1;
             break;
         }
-        let item = decode_value(ruby, data, pos)?;
+        let item = decode_value(ruby, data, pos, opts, depth + 1)?;
         unsafe { rb_ary_push(arr, item) };
     }
     Ok(arr)
 }
 
-fn decode_indef_map(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
+fn decode_indef_map(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+    depth: usize,
+) -> Result<VALUE, Error> {
+    opts.check_depth(ruby, depth)?;
     *pos += 1;
     let hash = unsafe { rb_sys::rb_hash_new() };
     loop {
@@ -791,80 +1581,102 @@ fn decode_indef_map(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE,
             *pos += 1;
             break;
         }
-        // Inline short text key decode (major 3, ai < 24)
-        let key;
-        let p = *pos;
-        if p < data.len() {
-            let b = data[p];
-            let kai = b & 0x1f;
-            if (b >> 5) == 3 && kai < 24 {
-                let slen = kai as usize;
-                let start = p + 1;
-                let end = start + slen;
-                if end <= data.len() {
-                    *pos = end;
-                    key = unsafe { new_encoded_string(&data[start..end], UTF8_ENCINDEX) };
-                } else {
-                    key = decode_text_raw(ruby, data, pos)?;
-                }
-            } else {
-                key = decode_text_raw(ruby, data, pos)?;
-            }
-        } else {
-            key = decode_text_raw(ruby, data, pos)?;
-        }
-        let val = decode_value(ruby, data, pos)?;
+        let key = decode_map_key(ruby, data, pos, opts)?;
+        let val = decode_value(ruby, data, pos, opts, depth + 1)?;
         unsafe { rb_hash_aset(hash, key, val) };
     }
     Ok(hash)
 }
 
-fn decode_indef_binary(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
-    *pos += 1;
-    let mut result = Vec::new();
-    loop {
-        let ib = dec_peek(ruby, data, *pos)?;
-        if ib == 0xff {
-            *pos += 1;
-            break;
-        }
-        let (_mt, ai) = dec_read_info(ruby, data, pos)?;
-        let len = dec_read_count(ruby, data, pos, ai)? as usize;
-        result.extend_from_slice(dec_take(ruby, data, pos, len)?);
-    }
-    Ok(unsafe { new_encoded_string(&result, BINARY_ENCINDEX) })
+/// Scratch buffers larger than this are released after use rather than
+/// kept for the next indefinite-length string.
+const INDEF_SCRATCH_MAX_RETAINED: usize = 1024 * 1024;
+
+thread_local! {
+    /// Reused to join the chunks of indefinite-length strings, so decoding
+    /// many small messages doesn't allocate a Vec per string.
+    static INDEF_SCRATCH: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
 }
 
-fn decode_indef_text(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
-    *pos += 1;
-    let mut result = Vec::new();
-    loop {
-        let ib = dec_peek(ruby, data, *pos)?;
-        if ib == 0xff {
-            *pos += 1;
-            break;
+fn decode_indef_binary(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+) -> Result<VALUE, Error> {
+    decode_indef_string(ruby, data, pos, unsafe { BINARY_ENCINDEX }, opts)
+}
+
+fn decode_indef_text(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+) -> Result<VALUE, Error> {
+    decode_indef_string(ruby, data, pos, unsafe { UTF8_ENCINDEX }, opts)
+}
+
+fn decode_indef_string(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    enc_index: c_int,
+    opts: &DecodeOptions,
+) -> Result<VALUE, Error> {
+    INDEF_SCRATCH.with(|scratch| {
+        // Chunks can't nest, so the buffer is only busy if something
+        // re-entered the decoder; fall back to a fresh Vec then.
+        let mut fresh = Vec::new();
+        let mut borrowed = scratch.try_borrow_mut();
+        let result = match borrowed {
+            Ok(ref mut buf) => &mut **buf,
+            Err(_) => &mut fresh,
+        };
+        result.clear();
+
+        *pos += 1;
+        let mut join_chunks = || loop {
+            let ib = dec_peek(ruby, data, *pos)?;
+            if ib == 0xff {
+                *pos += 1;
+                return Ok(unsafe { new_encoded_string(result, enc_index) });
+            }
+            let (_mt, ai) = dec_read_info(ruby, data, pos)?;
+            let len = dec_read_count(ruby, data, pos, ai)?;
+            opts.check_string_length(ruby, (result.len() as u64).saturating_add(len))?;
+            let len = len as usize;
+            result.extend_from_slice(dec_take(ruby, data, pos, len)?);
+        };
+        let decoded = join_chunks();
+
+        if result.capacity() > INDEF_SCRATCH_MAX_RETAINED {
+            *result = Vec::new();
         }
-        let (_mt, ai) = dec_read_info(ruby, data, pos)?;
-        let len = dec_read_count(ruby, data, pos, ai)? as usize;
-        result.extend_from_slice(dec_take(ruby, data, pos, len)?);
-    }
-    Ok(unsafe { new_encoded_string(&result, UTF8_ENCINDEX) })
+        decoded
+    })
 }
 
-fn decode_tag_raw(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
+fn decode_tag_raw(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+    depth: usize,
+) -> Result<VALUE, Error> {
+    opts.check_depth(ruby, depth)?;
     let (_mt, ai) = dec_read_info(ruby, data, pos)?;
     let tag = dec_read_count(ruby, data, pos, ai)?;
     match tag {
         TAG_EPOCH => {
-            let item = decode_value(ruby, data, pos)?;
+            let item = decode_value(ruby, data, pos, opts, depth + 1)?;
             let item_val = unsafe { Value::from_raw(item) };
             let time_class = unsafe { Value::from_raw(TIME_CLASS) };
             Ok(time_class.funcall::<_, _, Value>("at", (item_val,))?.as_raw())
         }
-        TAG_BIGNUM | TAG_NEG_BIGNUM => decode_bignum_raw(ruby, data, pos, tag),
+        TAG_BIGNUM | TAG_NEG_BIGNUM => decode_bignum_raw(ruby, data, pos, tag, opts),
         TAG_BIGDEC => decode_bigdec_raw(ruby, data, pos),
         _ => {
-            let inner = decode_value(ruby, data, pos)?;
+            let inner = decode_value(ruby, data, pos, opts, depth + 1)?;
             let inner_val = unsafe { Value::from_raw(inner) };
             let tagged_class = unsafe { Value::from_raw(TAGGED_CLASS) };
             Ok(tagged_class
@@ -874,47 +1686,142 @@ fn decode_tag_raw(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Er
     }
 }
 
-fn decode_half_raw(ruby: &Ruby, data: &[u8], pos: &mut usize) -> Result<VALUE, Error> {
+fn decode_half_raw(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+) -> Result<VALUE, Error> {
+    let p = *pos;
     *pos += 1; // skip initial byte
     let b = dec_take(ruby, data, pos, 2)?;
     let b16 = u16::from_be_bytes([b[0], b[1]]);
-    let exp = ((b16 >> 10) & 0x1f) as i32;
-    let mant = (b16 & 0x3ff) as f64;
-    let val = match exp {
-        0 => mant * 2.0f64.powi(-24),
-        31 => {
-            if mant == 0.0 { f64::INFINITY } else { f64::NAN }
+    decode_float(ruby, &data[p..*pos], half_to_f64(b16), opts)
+}
+
+/// Build the Ruby value for a decoded float whose encoding is `encoded`
+/// (initial byte included). With `exact_floats`, a float that
+/// `encode_auto_float` would not write back byte-for-byte is returned as an
+/// `AwsCrt::Cbor::ExactFloat` carrying its original width and bits. With
+/// `floats_as_decimal` it is returned as a `BigDecimal`.
+fn decode_float(
+    ruby: &Ruby,
+    encoded: &[u8],
+    val: f64,
+    opts: &DecodeOptions,
+) -> Result<VALUE, Error> {
+    if opts.floats_as_decimal {
+        return float_to_big_decimal(ruby, encoded, val);
+    }
+    if opts.exact_floats {
+        let mut reencoded = Vec::with_capacity(9);
+        encode_auto_float(&mut reencoded, val);
+        if reencoded != encoded {
+            let mut bits = 0u64;
+            for &b in &encoded[1..] {
+                bits = bits << 8 | b as u64;
+            }
+            let width = (encoded.len() as u64 - 1) * 8;
+            let exact_class = unsafe { Value::from_raw(EXACT_FLOAT_CLASS) };
+            return Ok(exact_class
+                .funcall::<_, _, Value>("new", (width, bits))?
+                .as_raw());
         }
-        _ => (1024.0 + mant) * 2.0f64.powi(exp - 25),
-    };
-    let val = if (b16 >> 15) == 0 { val } else { -val };
+    }
     Ok(unsafe { rb_float_new(val) })
 }
 
+/// `BigDecimal(str)` where `str` is the shortest decimal that round-trips to
+/// the float at its encoded width, so a single `0.1` becomes `0.1` rather
+/// than `0.100000001490116...`. NaN and the infinities map to BigDecimal's
+/// own special values.
+fn float_to_big_decimal(ruby: &Ruby, encoded: &[u8], val: f64) -> Result<VALUE, Error> {
+    let text = if val.is_nan() {
+        "NaN".to_string()
+    } else if val.is_infinite() {
+        if val > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else if encoded.len() <= 5 {
+        // Half and single precision are both exact as f32.
+        format!("{:e}", val as f32)
+    } else {
+        format!("{:e}", val)
+    };
+    let decimal: Value = ruby.module_kernel().funcall("BigDecimal", (text,))?;
+    Ok(decimal.as_raw())
+}
+
 fn decode_bignum_raw(
     ruby: &Ruby,
     data: &[u8],
     pos: &mut usize,
     tag: u64,
+    opts: &DecodeOptions,
 ) -> Result<VALUE, Error> {
     let (_mt, ai) = dec_read_info(ruby, data, pos)?;
-    let len = dec_read_count(ruby, data, pos, ai)? as usize;
+    let len = dec_read_count(ruby, data, pos, ai)?;
+    opts.check_string_length(ruby, len)?;
+    let len = len as usize;
     let bytes = dec_take(ruby, data, pos, len)?;
-    let mut val: Value = ruby.into_value(0i64);
-    for &b in bytes {
-        val = val.funcall("<<", (8i64,))?;
-        val = val.funcall("+", (b as i64,))?;
-    }
-    match tag {
-        TAG_BIGNUM => Ok(val.as_raw()),
-        TAG_NEG_BIGNUM => {
-            let neg_one: Value = ruby.into_value(-1i64);
-            Ok(neg_one.funcall::<_, _, Value>("-", (val,))?.as_raw())
+    let negative = match tag {
+        TAG_BIGNUM => false,
+        TAG_NEG_BIGNUM => true,
+        _ => {
+            return Err(Error::new(
+                cbor_error(ruby),
+                format!("Invalid Tag value for BigNum, expected 2 or 3, got: {}", tag),
+            ))
         }
-        _ => Err(Error::new(
-            cbor_error(ruby),
-            format!("Invalid Tag value for BigNum, expected 2 or 3, got: {}", tag),
-        )),
+    };
+
+    // Leading zero bytes don't change the value.
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    let magnitude = &bytes[start..];
+    if magnitude.len() <= 8 {
+        let n = magnitude.iter().fold(0u64, |acc, &b| acc << 8 | b as u64);
+        if !negative {
+            return Ok(ruby.into_value(n).as_raw());
+        }
+        if n <= i64::MAX as u64 {
+            return Ok(ruby.into_value(-1 - n as i64).as_raw());
+        }
+    }
+    if !negative {
+        return Ok(unpack_big_endian(magnitude, false));
+    }
+
+    // Tag 3 holds n for the value -1 - n, which is -(n + 1): add the one
+    // here so Ruby only has to negate.
+    let mut plus_one = magnitude.to_vec();
+    let carried = plus_one.iter_mut().rev().all(|b| {
+        *b = b.wrapping_add(1);
+        *b == 0
+    });
+    if carried {
+        plus_one.insert(0, 1);
+    }
+    Ok(unpack_big_endian(&plus_one, true))
+}
+
+/// `rb_integer_unpack` flags: most significant word and byte first.
+const INTEGER_PACK_BIG_ENDIAN: c_int = 0x01 | 0x10;
+const INTEGER_PACK_NEGATIVE: c_int = 0x200;
+
+/// Build an Integer from big-endian magnitude bytes in one call, negated
+/// if `negative`.
+fn unpack_big_endian(magnitude: &[u8], negative: bool) -> VALUE {
+    let flags = if negative {
+        INTEGER_PACK_BIG_ENDIAN | INTEGER_PACK_NEGATIVE
+    } else {
+        INTEGER_PACK_BIG_ENDIAN
+    };
+    unsafe {
+        rb_sys::rb_integer_unpack(
+            magnitude.as_ptr() as *const std::ffi::c_void,
+            magnitude.len(),
+            1,
+            0,
+            flags,
+        )
     }
 }
 
@@ -965,35 +1872,219 @@ impl AwsCrtTagged {
     }
 }
 
+// ---------------------------------------------------------------------------
+// ExactFloat — a float kept at its encoded width and bits
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+#[magnus::wrap(class = "AwsCrt::Cbor::ExactFloat", free_immediately, size)]
+struct ExactFloat {
+    width: Cell<u64>,
+    bits: Cell<u64>,
+}
+
+impl ExactFloat {
+    fn rb_initialize(ruby: &Ruby, rb_self: &Self, width: u64, bits: u64) -> Result<(), Error> {
+        let max = match width {
+            16 => u16::MAX as u64,
+            32 => u32::MAX as u64,
+            64 => u64::MAX,
+            _ => {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    format!("width must be 16, 32 or 64, got: {}", width),
+                ))
+            }
+        };
+        if bits > max {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                format!("bits do not fit in {} bits", width),
+            ));
+        }
+        rb_self.width.set(width);
+        rb_self.bits.set(bits);
+        Ok(())
+    }
+
+    fn rb_width(&self) -> u64 {
+        self.width.get()
+    }
+
+    fn rb_bits(&self) -> u64 {
+        self.bits.get()
+    }
+
+    fn rb_to_f(&self) -> f64 {
+        let bits = self.bits.get();
+        match self.width.get() {
+            16 => half_to_f64(bits as u16),
+            32 => f32_to_f64_exact(f32::from_bits(bits as u32)),
+            _ => f64::from_bits(bits),
+        }
+    }
+
+    fn rb_eq(&self, other: Value) -> bool {
+        match <&ExactFloat>::try_convert(other) {
+            Ok(other) => {
+                self.width.get() == other.width.get() && self.bits.get() == other.bits.get()
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn rb_inspect(&self) -> String {
+        let digits = self.width.get() as usize / 4;
+        format!(
+            "#<AwsCrt::Cbor::ExactFloat width={} bits=0x{:0digits$x} value={:?}>",
+            self.width.get(),
+            self.bits.get(),
+            self.rb_to_f(),
+        )
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Encoder struct wrapper — delegates to encode_value free function
 // ---------------------------------------------------------------------------
 
+/// Default number of buffered bytes after which an IO-backed encoder
+/// writes to its IO.
+const DEFAULT_FLUSH_THRESHOLD: usize = 64 * 1024;
+
 #[derive(Default)]
-#[magnus::wrap(class = "AwsCrt::Cbor::Encoder", free_immediately, size)]
+#[magnus::wrap(class = "AwsCrt::Cbor::Encoder", mark, free_immediately, size)]
 struct Encoder {
     buf: RefCell<Vec<u8>>,
+    /// Destination IO for `Encoder.to_io`; marked so it outlives us.
+    io: Cell<Option<VALUE>>,
+    flush_threshold: Cell<usize>,
+    /// Bytes written to the IO so far.
+    written: Cell<usize>,
+    opts: Cell<EncodeOptions>,
+}
+
+impl DataTypeFunctions for Encoder {
+    fn mark(&self, marker: &Marker) {
+        if let Some(io) = self.io.get() {
+            marker.mark(unsafe { Value::from_raw(io) });
+        }
+    }
 }
 
 impl Encoder {
-    fn rb_initialize(rb_self: &Self) {
+    fn rb_initialize(rb_self: &Self, args: &[Value]) -> Result<(), Error> {
+        let args = scan_args::<(), (Option<RHash>,), (), (), (), ()>(args)?;
+        let (opts,) = args.optional;
+        rb_self.opts.set(EncodeOptions::from_hash(opts)?);
         // Pre-allocate buffer; Default gives us an empty Vec, reserve here
         rb_self.buf.borrow_mut().reserve(256);
+        Ok(())
+    }
+
+    /// `Encoder.to_io(io, flush_threshold = 64 KiB, options = {})` — an
+    /// encoder that writes to `io` whenever at least `flush_threshold`
+    /// bytes are buffered. Takes the same options as `Encoder.new`; with no
+    /// threshold they can come second, as `Encoder.to_io(io, options)`.
+    fn rb_to_io(args: &[Value]) -> Result<typed_data::Obj<Self>, Error> {
+        let args = scan_args::<(Value,), (Option<Value>, Option<RHash>), (), (), (), ()>(args)?;
+        let (io,) = args.required;
+        let (flush_threshold, opts) = match args.optional {
+            (Some(threshold), None) => match RHash::from_value(threshold) {
+                Some(opts) => (None, Some(opts)),
+                None => (Some(threshold), None),
+            },
+            optional => optional,
+        };
+        let flush_threshold: Option<usize> = flush_threshold
+            .filter(|v| !v.is_nil())
+            .map(TryConvert::try_convert)
+            .transpose()?;
+        let opts = EncodeOptions::from_hash(opts)?;
+        if !io.respond_to("write", false)? {
+            return Err(Error::new(
+                exception::type_error(),
+                "expected an IO (an object responding to #write)",
+            ));
+        }
+
+        let flush_threshold = flush_threshold.unwrap_or(DEFAULT_FLUSH_THRESHOLD);
+        Ok(Self::for_io(io, flush_threshold, opts))
+    }
+
+    /// An encoder writing to `io`; the caller has checked it responds to
+    /// `#write`.
+    fn for_io(io: Value, flush_threshold: usize, opts: EncodeOptions) -> typed_data::Obj<Self> {
+        let capacity = flush_threshold.min(DEFAULT_FLUSH_THRESHOLD);
+        typed_data::Obj::wrap(Encoder {
+            buf: RefCell::new(Vec::with_capacity(capacity)),
+            io: Cell::new(Some(io.as_raw())),
+            flush_threshold: Cell::new(flush_threshold),
+            written: Cell::new(0),
+            opts: Cell::new(opts),
+        })
     }
 
     fn rb_add(ruby: &Ruby, rb_self: typed_data::Obj<Self>, value: Value) -> Result<Value, Error> {
-        {
+        let flush = {
             let mut buf = rb_self.buf.borrow_mut();
-            encode_value(ruby, &mut buf, value.as_raw())?;
+            encode_value(ruby, &mut buf, value.as_raw(), &rb_self.opts.get())?;
+            // Only checked here, between top-level items, so a flush never
+            // splits an item's head from its content.
+            rb_self.io.get().is_some() && buf.len() >= rb_self.flush_threshold.get()
+        };
+        if flush {
+            rb_self.write_buffered()?;
         }
         // Return self for chaining
         Ok(rb_self.as_value())
     }
 
+    /// `encoder.flush` — write any buffered bytes to the IO (IO-backed
+    /// encoders only; a no-op otherwise).
+    fn rb_flush(rb_self: typed_data::Obj<Self>) -> Result<Value, Error> {
+        if rb_self.io.get().is_some() {
+            rb_self.write_buffered()?;
+        }
+        Ok(rb_self.as_value())
+    }
+
+    /// Write the buffer to the IO and clear it. The buffer is kept if the
+    /// write raises, so a retry doesn't lose data.
+    fn write_buffered(&self) -> Result<(), Error> {
+        let Some(io) = self.io.get() else { return Ok(()) };
+        let chunk = {
+            let buf = self.buf.borrow();
+            if buf.is_empty() {
+                return Ok(());
+            }
+            unsafe { Value::from_raw(new_encoded_string(&buf, BINARY_ENCINDEX)) }
+        };
+        let _: Value = unsafe { Value::from_raw(io) }.funcall("write", (chunk,))?;
+        let mut buf = self.buf.borrow_mut();
+        self.written.set(self.written.get() + buf.len());
+        buf.clear();
+        Ok(())
+    }
+
+    /// For an IO-backed encoder, only the bytes not yet written to the IO.
     fn rb_bytes(rb_self: &Self) -> Result<Value, Error> {
         let buf = rb_self.buf.borrow();
         Ok(unsafe { Value::from_raw(new_encoded_string(&buf, BINARY_ENCINDEX)) })
     }
+
+    /// `encoder.reset` — discard the encoded bytes (for an IO-backed
+    /// encoder, those not yet written) so the encoder can start a new
+    /// message. The buffer keeps its capacity.
+    fn rb_reset(rb_self: typed_data::Obj<Self>) -> Value {
+        rb_self.buf.borrow_mut().clear();
+        rb_self.as_value()
+    }
+
+    /// `encoder.size` — the number of bytes `#bytes` would return.
+    fn rb_size(&self) -> usize {
+        self.buf.borrow().len()
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1005,46 +2096,188 @@ impl Encoder {
 struct Decoder {
     data: RefCell<Vec<u8>>,
     pos: Cell<usize>,
+    /// Decoded bytes `#feed` has dropped from the front of `data`, so
+    /// `#position` and error offsets still count from the first byte.
+    consumed: Cell<usize>,
+    options: Cell<DecodeOptions>,
+    /// Make `#decode` raise `ExtraBytesError` when bytes follow the item
+    /// (`strict: true`).
+    strict: Cell<bool>,
 }
 
 impl Decoder {
-    fn rb_initialize(rb_self: &Self, bytes: RString) {
+    fn rb_initialize(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<(), Error> {
+        let args = scan_args::<(RString,), (Option<RHash>,), (), (), (), ()>(args)?;
+        let (bytes,) = args.required;
+        let (opts,) = args.optional;
         let data = unsafe { bytes.as_slice().to_vec() };
         *rb_self.data.borrow_mut() = data;
         rb_self.pos.set(0);
+        rb_self.consumed.set(0);
+        let strict = opts.and_then(|o| o.get(Symbol::new("strict")));
+        rb_self.strict.set(strict.is_some_and(|v| v.to_bool()));
+        rb_self.options.set(DecodeOptions::from_hash(ruby, opts)?);
+        Ok(())
     }
 
+    /// Decode the next item. Bytes left after it are kept for the next
+    /// call, unless the decoder is strict, when they raise ExtraBytesError.
     fn rb_decode(ruby: &Ruby, rb_self: &Self) -> Result<Value, Error> {
         let data = rb_self.data.borrow();
         let mut pos = rb_self.pos.get();
-        let result = decode_value(ruby, &data, &mut pos)?;
+        let opts = rb_self.options.get();
+        let result = decode_value(ruby, &data, &mut pos, &opts, 0)
+            .map_err(|e| with_offset(ruby, e, rb_self.consumed.get() + pos, None))?;
         rb_self.pos.set(pos);
 
-        if pos < data.len() {
-            return Err(Error::new(
+        if rb_self.strict.get() && pos < data.len() {
+            let err = Error::new(
                 extra_bytes_error(ruby),
                 format!(
                     "Extra bytes: {} bytes remaining after decode",
                     data.len() - pos
                 ),
-            ));
+            );
+            return Err(with_offset(ruby, err, rb_self.consumed.get() + pos, None));
+        }
+
+        Ok(unsafe { Value::from_raw(result) })
+    }
+
+    /// Decode the next item without requiring it to be the last one, so a
+    /// buffer holding several items can be read one at a time. A break code
+    /// (0xff) where an item would start is consumed and returned as
+    /// `AwsCrt::Cbor::BREAK`, letting the caller track the end of an
+    /// indefinite-length container it is walking itself.
+    ///
+    /// If the buffer ends before the item does, returns
+    /// `AwsCrt::Cbor::NEED_MORE` and consumes nothing, so the call can be
+    /// repeated once `#feed` has added more. (Not nil: that is a decoded
+    /// CBOR null.)
+    fn rb_decode_next(ruby: &Ruby, rb_self: &Self) -> Result<Value, Error> {
+        let data = rb_self.data.borrow();
+        let mut pos = rb_self.pos.get();
+        if data.get(pos) == Some(&0xff) {
+            rb_self.pos.set(pos + 1);
+            return Ok(unsafe { Value::from_raw(BREAK_SENTINEL) });
         }
 
+        let opts = rb_self.options.get();
+        let result = match decode_value(ruby, &data, &mut pos, &opts, 0) {
+            Ok(result) => result,
+            Err(e) if e.is_kind_of(out_of_bytes_error(ruby)) => {
+                return Ok(unsafe { Value::from_raw(NEED_MORE_SENTINEL) });
+            }
+            Err(e) => return Err(with_offset(ruby, e, rb_self.consumed.get() + pos, None)),
+        };
+        rb_self.pos.set(pos);
         Ok(unsafe { Value::from_raw(result) })
     }
+
+    /// `decoder.feed(bytes)` — append bytes that have arrived since (say,
+    /// from a socket) for `#decode_next` to read. Bytes already decoded are
+    /// dropped first, so a long-lived decoder only holds what is left.
+    fn rb_feed(rb_self: typed_data::Obj<Self>, bytes: RString) -> Value {
+        {
+            let mut data = rb_self.data.borrow_mut();
+            let pos = rb_self.pos.get();
+            data.drain(..pos);
+            rb_self.consumed.set(rb_self.consumed.get() + pos);
+            rb_self.pos.set(0);
+            data.extend_from_slice(unsafe { bytes.as_slice() });
+        }
+        rb_self.as_value()
+    }
+
+    /// Byte offset of the next item to decode.
+    fn rb_position(rb_self: &Self) -> usize {
+        rb_self.consumed.get() + rb_self.pos.get()
+    }
+
+    /// Number of bytes not yet decoded.
+    fn rb_remaining(rb_self: &Self) -> usize {
+        rb_self.data.borrow().len() - rb_self.pos.get()
+    }
+
+    /// The bytes not yet decoded, as a binary String.
+    fn rb_rest(rb_self: &Self) -> Value {
+        let data = rb_self.data.borrow();
+        let rest = &data[rb_self.pos.get()..];
+        unsafe { Value::from_raw(new_encoded_string(rest, BINARY_ENCINDEX)) }
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Module-level encode/decode functions (JSON.dump / JSON.parse style)
 // ---------------------------------------------------------------------------
 
-fn rb_encode(ruby: &Ruby, value: Value) -> Result<Value, Error> {
+fn rb_encode(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    let args = scan_args::<(Value,), (Option<RHash>,), (), (), (), ()>(args)?;
+    let (value,) = args.required;
+    let (opts,) = args.optional;
+    let opts = EncodeOptions::from_hash(opts)?;
     let mut buf = Vec::with_capacity(256);
-    encode_value(ruby, &mut buf, value.as_raw())?;
+    encode_value(ruby, &mut buf, value.as_raw(), &opts)?;
     Ok(unsafe { Value::from_raw(new_encoded_string(&buf, BINARY_ENCINDEX)) })
 }
 
-fn rb_decode(ruby: &Ruby, bytes: Value) -> Result<Value, Error> {
+/// `Cbor.encode_stream(enumerable, io, options = {})` — write what
+/// `enumerable.each` yields to `io` as one indefinite-length array,
+/// encoding items as they arrive and writing whenever 64 KiB is buffered,
+/// so the sequence is never held in memory. Returns the bytes written.
+fn rb_encode_stream(ruby: &Ruby, args: &[Value]) -> Result<usize, Error> {
+    let args = scan_args::<(Value, Value), (Option<RHash>,), (), (), (), ()>(args)?;
+    let (enumerable, io) = args.required;
+    let (opts,) = args.optional;
+    let opts = EncodeOptions::from_hash(opts)?;
+    if opts.canonical {
+        // Deterministic encoding only allows definite lengths.
+        return Err(Error::new(
+            exception::arg_error(),
+            "encode_stream cannot write canonical CBOR",
+        ));
+    }
+    if !enumerable.respond_to("each", false)? {
+        return Err(Error::new(
+            exception::type_error(),
+            "expected an Enumerable (an object responding to #each)",
+        ));
+    }
+    if !io.respond_to("write", false)? {
+        return Err(Error::new(
+            exception::type_error(),
+            "expected an IO (an object responding to #write)",
+        ));
+    }
+
+    let encoder = Encoder::for_io(io, DEFAULT_FLUSH_THRESHOLD, opts);
+    encoder.buf.borrow_mut().push(0x9f);
+    // Items arrive through a block rather than an external enumerator, so
+    // there is no Fiber switch per item. The Proc only holds the encoder's
+    // VALUE, which stays on this stack (and so alive) until `each` returns.
+    let raw = encoder.as_raw();
+    let block = ruby.proc_from_fn(move |args, _block| {
+        // Blocks only run on Ruby threads.
+        let ruby = unsafe { Ruby::get_unchecked() };
+        let encoder = typed_data::Obj::<Encoder>::try_convert(unsafe { Value::from_raw(raw) })?;
+        // Several yielded values become one Array, as `Enumerator#next` gives.
+        let item = match args {
+            [item] => *item,
+            _ => ruby.ary_new_from_values(args).as_value(),
+        };
+        Encoder::rb_add(&ruby, encoder, item)
+    });
+    let _: Value = enumerable.funcall_with_block("each", (), block)?;
+    encoder.buf.borrow_mut().push(0xff);
+    encoder.write_buffered()?;
+    Ok(encoder.written.get())
+}
+
+fn rb_decode(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    let args = scan_args::<(Value,), (Option<RHash>,), (), (), (), ()>(args)?;
+    let (bytes,) = args.required;
+    let (opts,) = args.optional;
+    let opts = DecodeOptions::from_hash(ruby, opts)?;
     let rstr = RString::from_value(bytes).ok_or_else(|| {
         Error::new(
             exception::type_error(),
@@ -1053,22 +2286,368 @@ fn rb_decode(ruby: &Ruby, bytes: Value) -> Result<Value, Error> {
     })?;
     let (ptr, len) = unsafe { rstring_ptr_len(rstr.as_raw()) };
     let data = unsafe { std::slice::from_raw_parts(ptr, len) };
-    let mut pos = 0usize;
-    let result = decode_value(ruby, data, &mut pos)?;
+    decode_document(ruby, data, &opts)
+}
+
+fn rb_decode_seq(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    let args = scan_args::<(Value,), (Option<RHash>,), (), (), (), ()>(args)?;
+    let (bytes,) = args.required;
+    let (opts,) = args.optional;
+    let opts = DecodeOptions::from_hash(ruby, opts)?;
+    let rstr = RString::from_value(bytes).ok_or_else(|| {
+        Error::new(
+            exception::type_error(),
+            "expected a String argument for decode_seq",
+        )
+    })?;
+    let (ptr, len) = unsafe { rstring_ptr_len(rstr.as_raw()) };
+    let data = unsafe { std::slice::from_raw_parts(ptr, len) };
+    decode_sequence(ruby, data, &opts)
+}
+
+/// `Cbor.decode_each(bytes, options)`: like `decode_seq`, but with a block
+/// each item is yielded as soon as it is decoded (and nil returned) rather
+/// than collected into an Array.
+fn rb_decode_each(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    if !ruby.block_given() {
+        return rb_decode_seq(ruby, args);
+    }
+    let args = scan_args::<(Value,), (Option<RHash>,), (), (), (), ()>(args)?;
+    let (bytes,) = args.required;
+    let (opts,) = args.optional;
+    let opts = DecodeOptions::from_hash(ruby, opts)?;
+    let rstr = RString::from_value(bytes).ok_or_else(|| {
+        Error::new(
+            exception::type_error(),
+            "expected a String argument for decode_each",
+        )
+    })?;
+    // The block runs between items and could mutate the String, so walk a
+    // copy of its bytes.
+    let data = unsafe { rstr.as_slice() }.to_vec();
+    each_sequence_item(ruby, &data, &opts, |item| {
+        ruby.yield_value::<Value, Value>(item)?;
+        Ok(())
+    })?;
+    Ok(ruby.qnil().as_value())
+}
+
+/// The base64 alphabet selected by a `url_safe:` option.
+fn base64_alphabet(opts: Option<RHash>) -> Alphabet {
+    match opts.and_then(|o| o.get(Symbol::new("url_safe"))) {
+        Some(v) if v.to_bool() => Alphabet::UrlSafe,
+        _ => Alphabet::Standard,
+    }
+}
 
-    if pos < len {
+fn rb_encode_base64(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    let args = scan_args::<(Value,), (Option<RHash>,), (), (), (), ()>(args)?;
+    let (value,) = args.required;
+    let (opts,) = args.optional;
+    let encode_opts = EncodeOptions::from_hash(opts)?;
+    let mut buf = Vec::with_capacity(256);
+    encode_value(ruby, &mut buf, value.as_raw(), &encode_opts)?;
+    Ok(ruby.str_new(&base64::encode(&buf, base64_alphabet(opts))).as_value())
+}
+
+fn rb_decode_base64(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    let args = scan_args::<(Value,), (Option<RHash>,), (), (), (), ()>(args)?;
+    let (text,) = args.required;
+    let (opts,) = args.optional;
+    let alphabet = base64_alphabet(opts);
+    let opts = DecodeOptions::from_hash(ruby, opts)?;
+    let rstr = RString::from_value(text).ok_or_else(|| {
+        Error::new(
+            exception::type_error(),
+            "expected a String argument for decode_base64",
+        )
+    })?;
+    let data = base64::decode(unsafe { rstr.as_slice() }, alphabet)
+        .map_err(|msg| Error::new(exception::arg_error(), msg))?;
+    decode_document(ruby, &data, &opts)
+}
+
+/// `Cbor.decode_as(bytes, struct_class, options)`: decode a CBOR map into
+/// a new `struct_class` (a `Struct` subclass), built without calling its
+/// `initialize`, so `keyword_init` structs work too. Each member is read
+/// from the map's text key of the same name (or a Symbol key). A member the
+/// map lacks takes its value from the `defaults:` Hash (keyed by Symbol,
+/// value used as is), and raises `Cbor::Error` if that has none. Map keys
+/// that aren't members are ignored, so data with newer fields still decodes.
+fn rb_decode_as(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    let args = scan_args::<(Value, magnus::RClass), (Option<RHash>,), (), (), (), ()>(args)?;
+    let (bytes, struct_class) = args.required;
+    let (opts,) = args.optional;
+    let defaults = match opts {
+        Some(opts) => opts.lookup::<_, Option<RHash>>(Symbol::new("defaults"))?,
+        None => None,
+    };
+    let opts = DecodeOptions::from_hash(ruby, opts)?;
+
+    let struct_base: Value = ruby.eval("Struct")?;
+    let is_struct: Value = struct_class.funcall("<", (struct_base,))?;
+    if !is_struct.to_bool() {
         return Err(Error::new(
+            exception::type_error(),
+            format!(
+                "expected a Struct subclass for decode_as, got {}",
+                struct_class.inspect()
+            ),
+        ));
+    }
+    let rstr = RString::from_value(bytes).ok_or_else(|| {
+        Error::new(
+            exception::type_error(),
+            "expected a String argument for decode_as",
+        )
+    })?;
+    let (ptr, len) = unsafe { rstring_ptr_len(rstr.as_raw()) };
+    let data = unsafe { std::slice::from_raw_parts(ptr, len) };
+    let decoded = decode_document(ruby, data, &opts)?;
+    let map = RHash::from_value(decoded).ok_or_else(|| {
+        Error::new(
+            cbor_error(ruby),
+            format!(
+                "expected a CBOR map to decode as {}, got {}",
+                struct_class.inspect(),
+                decoded.class().inspect()
+            ),
+        )
+    })?;
+
+    let members: Vec<Symbol> = struct_class.funcall("members", ())?;
+    let instance: Value = struct_class.funcall("allocate", ())?;
+    for member in members {
+        let name = member.name()?;
+        let value = match map.get(&*name).or_else(|| map.get(member)) {
+            Some(value) => value,
+            None => defaults.and_then(|d| d.get(member)).ok_or_else(|| {
+                Error::new(
+                    cbor_error(ruby),
+                    format!(
+                        "missing member :{} for {}, and no default given",
+                        name,
+                        struct_class.inspect()
+                    ),
+                )
+            })?,
+        };
+        let _: Value = instance.funcall("[]=", (member, value))?;
+    }
+    Ok(instance)
+}
+
+/// Decode exactly one CBOR data item from `data`, rejecting trailing bytes.
+fn decode_document(ruby: &Ruby, data: &[u8], opts: &DecodeOptions) -> Result<Value, Error> {
+    let mut pos = 0usize;
+    let result = decode_value(ruby, data, &mut pos, opts, 0)
+        .map_err(|e| with_offset(ruby, e, pos, Some(data.len())))?;
+
+    if pos < data.len() {
+        let err = Error::new(
             extra_bytes_error(ruby),
             format!(
                 "Extra bytes: {} bytes remaining after decode",
-                len - pos
+                data.len() - pos
             ),
-        ));
+        );
+        return Err(with_offset(ruby, err, pos, Some(data.len())));
     }
 
     Ok(unsafe { Value::from_raw(result) })
 }
 
+/// Decode a CBOR sequence (RFC 8742): every top-level item until `data` is
+/// exhausted, as a Ruby Array. An item cut short by the end of the buffer
+/// raises OutOfBytesError naming the byte offset where that item started.
+fn decode_sequence(ruby: &Ruby, data: &[u8], opts: &DecodeOptions) -> Result<Value, Error> {
+    let items = ruby.ary_new();
+    each_sequence_item(ruby, data, opts, |item| items.push(item))?;
+    Ok(items.as_value())
+}
+
+/// Call `f` with each top-level item of the CBOR sequence in `data`, as
+/// `decode_sequence` collects them.
+fn each_sequence_item(
+    ruby: &Ruby,
+    data: &[u8],
+    opts: &DecodeOptions,
+    mut f: impl FnMut(Value) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut pos = 0usize;
+    while pos < data.len() {
+        let start = pos;
+        match decode_value(ruby, data, &mut pos, opts, 0) {
+            Ok(item) => f(unsafe { Value::from_raw(item) })?,
+            Err(e) if e.is_kind_of(out_of_bytes_error(ruby)) => {
+                let err = Error::new(
+                    out_of_bytes_error(ruby),
+                    format!(
+                        "Out of bytes. Truncated item at offset {} ({} bytes remaining in buffer)",
+                        start,
+                        data.len() - start
+                    ),
+                );
+                return Err(with_offset(ruby, err, start, Some(data.len())));
+            }
+            Err(e) => return Err(with_offset(ruby, e, pos, Some(data.len()))),
+        }
+    }
+    Ok(())
+}
+
+/// Decode a CBOR document held in Rust memory with the default options.
+/// Used by `ConnectionPool#request(decode: :cbor)` so response bodies are
+/// decoded without first being copied into a Ruby String.
+pub(crate) fn decode_slice(ruby: &Ruby, data: &[u8]) -> Result<Value, Error> {
+    decode_document(ruby, data, &DecodeOptions::default())
+}
+
+// ---------------------------------------------------------------------------
+// Event parsing — Cbor.parse yields events instead of building containers
+// ---------------------------------------------------------------------------
+
+/// Yield `[name]` or `[name, value]` to the block given to `Cbor.parse`.
+fn emit_event(ruby: &Ruby, name: &str, value: Option<Value>) -> Result<(), Error> {
+    let event = match value {
+        Some(v) => RArray::from_slice(&[Symbol::new(name).as_value(), v]),
+        None => RArray::from_slice(&[Symbol::new(name).as_value()]),
+    };
+    ruby.yield_value::<RArray, Value>(event)?;
+    Ok(())
+}
+
+/// Item count of a definite-length container, `None` for an indefinite one.
+fn container_size(ruby: &Ruby, data: &[u8], pos: &mut usize, ai: u8) -> Result<Option<u64>, Error> {
+    if ai == 31 {
+        return Ok(None);
+    }
+    dec_read_count(ruby, data, pos, ai).map(Some)
+}
+
+/// Walk one data item, emitting events for arrays, maps and unrecognised
+/// tags, and a `:value` event for everything else. Scalars (including
+/// indefinite-length strings and the tags `decode` maps to Time, Integer
+/// and BigDecimal) are decoded with the same helpers as `decode_value`.
+fn parse_value(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+    depth: usize,
+) -> Result<(), Error> {
+    let ib = dec_peek(ruby, data, *pos)?;
+    let ai = ib & 0x1f;
+    if (4..=6).contains(&(ib >> 5)) {
+        opts.check_depth(ruby, depth)?;
+    }
+    match ib >> 5 {
+        4 => {
+            *pos += 1;
+            let size = container_size(ruby, data, pos, ai)?;
+            emit_event(ruby, "array_start", Some(ruby.into_value(size)))?;
+            match size {
+                Some(n) => {
+                    for _ in 0..n {
+                        parse_value(ruby, data, pos, opts, depth + 1)?;
+                    }
+                }
+                None => {
+                    while dec_peek(ruby, data, *pos)? != 0xff {
+                        parse_value(ruby, data, pos, opts, depth + 1)?;
+                    }
+                    *pos += 1;
+                }
+            }
+            emit_event(ruby, "array_end", None)
+        }
+        5 => {
+            *pos += 1;
+            let size = container_size(ruby, data, pos, ai)?;
+            emit_event(ruby, "map_start", Some(ruby.into_value(size)))?;
+            match size {
+                Some(n) => {
+                    for _ in 0..n {
+                        parse_pair(ruby, data, pos, opts, depth + 1)?;
+                    }
+                }
+                None => {
+                    while dec_peek(ruby, data, *pos)? != 0xff {
+                        parse_pair(ruby, data, pos, opts, depth + 1)?;
+                    }
+                    *pos += 1;
+                }
+            }
+            emit_event(ruby, "map_end", None)
+        }
+        6 => {
+            let mut p = *pos;
+            let (_mt, tai) = dec_read_info(ruby, data, &mut p)?;
+            let tag = dec_read_count(ruby, data, &mut p, tai)?;
+            if matches!(tag, TAG_EPOCH | TAG_BIGNUM | TAG_NEG_BIGNUM | TAG_BIGDEC) {
+                let value = decode_tag_raw(ruby, data, pos, opts, depth)?;
+                return emit_event(ruby, "value", Some(unsafe { Value::from_raw(value) }));
+            }
+            *pos = p;
+            emit_event(ruby, "tag", Some(ruby.into_value(tag)))?;
+            parse_value(ruby, data, pos, opts, depth + 1)
+        }
+        _ => {
+            let value = decode_value(ruby, data, pos, opts, depth)?;
+            emit_event(ruby, "value", Some(unsafe { Value::from_raw(value) }))
+        }
+    }
+}
+
+/// A map entry: the key is decoded whole and emitted as `[:key, k]`, then
+/// the value is walked.
+fn parse_pair(
+    ruby: &Ruby,
+    data: &[u8],
+    pos: &mut usize,
+    opts: &DecodeOptions,
+    depth: usize,
+) -> Result<(), Error> {
+    let key = decode_value(ruby, data, pos, opts, depth)?;
+    emit_event(ruby, "key", Some(unsafe { Value::from_raw(key) }))?;
+    parse_value(ruby, data, pos, opts, depth)
+}
+
+fn rb_parse(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    if !ruby.block_given() {
+        let cbor = get_cbor_module(ruby);
+        return Ok(cbor.enumeratorize("parse", args).as_value());
+    }
+    let parsed = scan_args::<(Value,), (Option<RHash>,), (), (), (), ()>(args)?;
+    let (bytes,) = parsed.required;
+    let (opts,) = parsed.optional;
+    let opts = DecodeOptions::from_hash(ruby, opts)?;
+    let rstr = RString::from_value(bytes).ok_or_else(|| {
+        Error::new(
+            exception::type_error(),
+            "expected a String argument for parse",
+        )
+    })?;
+    // The block runs between reads and could mutate the String, so walk a
+    // copy of its bytes.
+    let data = unsafe { rstr.as_slice() }.to_vec();
+
+    let mut pos = 0usize;
+    parse_value(ruby, &data, &mut pos, &opts, 0)
+        .map_err(|e| with_offset(ruby, e, pos, Some(data.len())))?;
+    if pos < data.len() {
+        let err = Error::new(
+            extra_bytes_error(ruby),
+            format!(
+                "Extra bytes: {} bytes remaining after decode",
+                data.len() - pos
+            ),
+        );
+        return Err(with_offset(ruby, err, pos, Some(data.len())));
+    }
+    Ok(ruby.qnil().as_value())
+}
+
 // ---------------------------------------------------------------------------
 // Init — register classes and module functions
 // ---------------------------------------------------------------------------
@@ -1079,11 +2658,14 @@ pub fn init(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
     // Error classes — use eval to get StandardError as RClass
     let std_error: magnus::RClass = ruby.eval("StandardError")?;
     let error_class = cbor.define_class("Error", std_error)?;
+    // Byte offset a decode error was raised at; nil for encode errors.
+    error_class.define_attr("offset", magnus::Attr::Read)?;
     cbor.define_class("OutOfBytesError", error_class)?;
     cbor.define_class("ExtraBytesError", error_class)?;
     cbor.define_class("UnknownTypeError", error_class)?;
     cbor.define_class("UnexpectedAdditionalInformationError", error_class)?;
     cbor.define_class("UnexpectedBreakCodeError", error_class)?;
+    cbor.define_class("MaxDepthError", error_class)?;
 
     // Tagged struct
     let tagged = cbor.define_class("Tagged", ruby.class_object())?;
@@ -1092,28 +2674,69 @@ pub fn init(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
     tagged.define_method("tag", method!(AwsCrtTagged::rb_tag, 0))?;
     tagged.define_method("value", method!(AwsCrtTagged::rb_value, 0))?;
 
+    // ExactFloat struct
+    let exact_float = cbor.define_class("ExactFloat", ruby.class_object())?;
+    exact_float.define_alloc_func::<ExactFloat>();
+    exact_float.define_method("initialize", method!(ExactFloat::rb_initialize, 2))?;
+    exact_float.define_method("width", method!(ExactFloat::rb_width, 0))?;
+    exact_float.define_method("bits", method!(ExactFloat::rb_bits, 0))?;
+    exact_float.define_method("to_f", method!(ExactFloat::rb_to_f, 0))?;
+    exact_float.define_method("==", method!(ExactFloat::rb_eq, 1))?;
+    exact_float.define_method("inspect", method!(ExactFloat::rb_inspect, 0))?;
+
+    // Sentinel returned by Decoder#decode_next for a break code
+    let break_sentinel: Value = ruby.eval(
+        "Object.new.tap { |o| def o.inspect; 'AwsCrt::Cbor::BREAK'; end }.freeze",
+    )?;
+    cbor.const_set("BREAK", break_sentinel)?;
+
+    // Sentinel returned by Decoder#decode_next for an incomplete item
+    let need_more_sentinel: Value = ruby.eval(
+        "Object.new.tap { |o| def o.inspect; 'AwsCrt::Cbor::NEED_MORE'; end }.freeze",
+    )?;
+    cbor.const_set("NEED_MORE", need_more_sentinel)?;
+
     // Cache class references
     unsafe {
+        BREAK_SENTINEL = break_sentinel.as_raw();
+        NEED_MORE_SENTINEL = need_more_sentinel.as_raw();
         TAGGED_CLASS = tagged.as_raw();
+        EXACT_FLOAT_CLASS = exact_float.as_raw();
         cache_classes(ruby);
     }
 
     // Encoder class
     let encoder_class = cbor.define_class("Encoder", ruby.class_object())?;
     encoder_class.define_alloc_func::<Encoder>();
-    encoder_class.define_method("initialize", method!(Encoder::rb_initialize, 0))?;
+    encoder_class.define_method("initialize", method!(Encoder::rb_initialize, -1))?;
     encoder_class.define_method("add", method!(Encoder::rb_add, 1))?;
     encoder_class.define_method("bytes", method!(Encoder::rb_bytes, 0))?;
+    encoder_class.define_method("flush", method!(Encoder::rb_flush, 0))?;
+    encoder_class.define_method("reset", method!(Encoder::rb_reset, 0))?;
+    encoder_class.define_method("size", method!(Encoder::rb_size, 0))?;
+    encoder_class.define_singleton_method("to_io", function!(Encoder::rb_to_io, -1))?;
 
     // Decoder class
     let decoder_class = cbor.define_class("Decoder", ruby.class_object())?;
     decoder_class.define_alloc_func::<Decoder>();
-    decoder_class.define_method("initialize", method!(Decoder::rb_initialize, 1))?;
+    decoder_class.define_method("initialize", method!(Decoder::rb_initialize, -1))?;
     decoder_class.define_method("decode", method!(Decoder::rb_decode, 0))?;
+    decoder_class.define_method("decode_next", method!(Decoder::rb_decode_next, 0))?;
+    decoder_class.define_method("feed", method!(Decoder::rb_feed, 1))?;
+    decoder_class.define_method("position", method!(Decoder::rb_position, 0))?;
+    decoder_class.define_method("remaining", method!(Decoder::rb_remaining, 0))?;
+    decoder_class.define_method("rest", method!(Decoder::rb_rest, 0))?;
 
     // Module-level encode/decode (fast path — no object allocation)
-    cbor.define_module_function("encode", function!(rb_encode, 1))?;
-    cbor.define_module_function("decode", function!(rb_decode, 1))?;
+    cbor.define_module_function("encode", function!(rb_encode, -1))?;
+    cbor.define_module_function("encode_stream", function!(rb_encode_stream, -1))?;
+    cbor.define_module_function("decode", function!(rb_decode, -1))?;
+    cbor.define_module_function("decode_seq", function!(rb_decode_seq, -1))?;
+    cbor.define_module_function("decode_each", function!(rb_decode_each, -1))?;
+    cbor.define_module_function("decode_as", function!(rb_decode_as, -1))?;
+    cbor.define_module_function("parse", function!(rb_parse, -1))?;
+    cbor.define_module_function("encode_base64", function!(rb_encode_base64, -1))?;
+    cbor.define_module_function("decode_base64", function!(rb_decode_base64, -1))?;
 
     Ok(())
 }
@@ -5,6 +5,7 @@
 //! keep-alive, idle cleanup, and queuing internally.
 
 use std::ffi::CString;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::error::CrtError;
 use crate::proxy::{ProxyAuthType, ProxyOptions};
@@ -158,6 +159,34 @@ extern "C" {
 // ConnectionManager — wraps aws_http_connection_manager
 // ---------------------------------------------------------------------------
 
+/// Address family for the pool's sockets (`enum aws_socket_domain`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SocketDomain {
+    Ipv4,
+    Ipv6,
+}
+
+impl SocketDomain {
+    fn as_crt(self) -> u32 {
+        match self {
+            SocketDomain::Ipv4 => 0, // AWS_SOCKET_IPV4
+            SocketDomain::Ipv6 => 1, // AWS_SOCKET_IPV6
+        }
+    }
+}
+
+/// TCP keep-alive probing for the pool's sockets. A zero leaves that
+/// setting to the OS.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TcpKeepAlive {
+    /// Seconds a connection sits idle before the first probe (TCP_KEEPIDLE).
+    pub idle_sec: u16,
+    /// Seconds between unanswered probes (TCP_KEEPINTVL).
+    pub interval_sec: u16,
+    /// Unanswered probes before the connection is dropped (TCP_KEEPCNT).
+    pub max_failed_probes: u16,
+}
+
 /// Configuration for creating a connection manager.
 pub struct ConnectionManagerOptions {
     pub host: String,
@@ -168,10 +197,18 @@ pub struct ConnectionManagerOptions {
     pub max_connection_idle_ms: u64,
     /// Connection timeout in milliseconds (default: 60_000).
     pub connect_timeout_ms: u32,
+    /// Force IPv4 or IPv6 sockets (None = leave it to the CRT).
+    pub socket_domain: Option<SocketDomain>,
+    /// Enable TCP keep-alive on every connection (None = off).
+    pub tcp_keepalive: Option<TcpKeepAlive>,
     /// TLS options (None for plaintext HTTP).
     pub tls_options: Option<TlsOptions>,
     /// Proxy configuration (None for direct connections).
     pub proxy_options: Option<ProxyOptions>,
+    /// Initial per-stream read window in bytes. `Some` enables read
+    /// backpressure: the server can only send this much before the stream
+    /// window is incremented again (see `http.rs`). None = no flow control.
+    pub initial_read_window: Option<usize>,
 }
 
 impl Default for ConnectionManagerOptions {
@@ -182,18 +219,27 @@ impl Default for ConnectionManagerOptions {
             max_connections: 25,
             max_connection_idle_ms: 60_000,
             connect_timeout_ms: 60_000,
+            socket_domain: None,
+            tcp_keepalive: None,
             tls_options: None,
             proxy_options: None,
+            initial_read_window: None,
         }
     }
 }
 
+/// `AWS_ERROR_HTTP_CONNECTION_MANAGER_SHUTTING_DOWN` — the error pending
+/// acquisitions fail with once the manager is released.
+const AWS_ERROR_HTTP_CONNECTION_MANAGER_SHUTTING_DOWN: i32 = 0x0800 + 24;
+
 /// A CRT HTTP connection pool for a single endpoint.
 ///
 /// Wraps `aws_http_connection_manager`. The CRT handles connection creation,
 /// reuse, keep-alive, idle cleanup, and queuing internally. Thread-safe.
 pub struct ConnectionManager {
     manager: *mut AwsHttpConnectionManager,
+    /// Set once `shutdown` has released our reference to the manager.
+    shut_down: AtomicBool,
     // Hold ownership of TLS context so it outlives the connection manager
     _tls_ctx: Option<TlsContext>,
 }
@@ -211,16 +257,20 @@ impl ConnectionManager {
         let host_bytes = opts.host.as_bytes();
         let host_cursor = AwsByteCursor::from_slice(host_bytes);
 
-        // Socket options — TCP stream with configured connect timeout
+        // Socket options — TCP stream with configured connect timeout,
+        // address family (the CRT's default domain is IPv4) and keep-alive.
+        // aws-c-io applies keep_alive_interval_sec as TCP_KEEPIDLE and
+        // keep_alive_timeout_sec as TCP_KEEPINTVL, and only when both are set.
+        let keepalive = opts.tcp_keepalive.unwrap_or_default();
         let socket_options = AwsSocketOptions {
             socket_type: 0,  // AWS_SOCKET_STREAM
-            domain: 0,       // AWS_SOCKET_IPV4
+            domain: opts.socket_domain.map_or(0, SocketDomain::as_crt),
             impl_type: 0,    // AWS_SOCKET_IMPL_PLATFORM_DEFAULT
             connect_timeout_ms: opts.connect_timeout_ms,
-            keep_alive_interval_sec: 0,
-            keep_alive_timeout_sec: 0,
-            keep_alive_max_failed_probes: 0,
-            keepalive: false,
+            keep_alive_interval_sec: keepalive.idle_sec,
+            keep_alive_timeout_sec: keepalive.interval_sec,
+            keep_alive_max_failed_probes: keepalive.max_failed_probes,
+            keepalive: opts.tcp_keepalive.is_some(),
             network_interface_name: [0u8; 16],
         };
 
@@ -340,7 +390,7 @@ impl ConnectionManager {
         // Build the connection manager options
         let cm_options = AwsHttpConnectionManagerOptions {
             bootstrap: rt.client_bootstrap(),
-            initial_window_size: usize::MAX, // no flow control
+            initial_window_size: opts.initial_read_window.unwrap_or(usize::MAX),
             socket_options: &socket_options,
             response_first_byte_timeout_ms: 0,
             tls_connection_options: tls_conn_ptr,
@@ -360,7 +410,7 @@ impl ConnectionManager {
             max_connections: opts.max_connections,
             shutdown_complete_user_data: std::ptr::null_mut(),
             shutdown_complete_callback: std::ptr::null(),
-            enable_read_back_pressure: false,
+            enable_read_back_pressure: opts.initial_read_window.is_some(),
             _pad3: [0; 7],
             max_connection_idle_in_milliseconds: opts.max_connection_idle_ms,
             connection_acquisition_timeout_ms: opts.connect_timeout_ms as u64,
@@ -387,6 +437,7 @@ impl ConnectionManager {
 
         Ok(ConnectionManager {
             manager,
+            shut_down: AtomicBool::new(false),
             _tls_ctx: tls_ctx,
         })
     }
@@ -395,10 +446,37 @@ impl ConnectionManager {
     pub fn as_ptr(&self) -> *mut AwsHttpConnectionManager {
         self.manager
     }
+
+    /// Release the manager without waiting for it to be dropped.
+    ///
+    /// The CRT fails every queued acquisition with
+    /// `AWS_ERROR_HTTP_CONNECTION_MANAGER_SHUTTING_DOWN` and closes idle
+    /// connections. Connections already vended keep the manager alive
+    /// internally, so in-flight requests finish and release normally.
+    /// Idempotent.
+    pub fn shutdown(&self) {
+        if !self.shut_down.swap(true, Ordering::AcqRel) {
+            unsafe { aws_http_connection_manager_release(self.manager) };
+        }
+    }
+
+    /// Whether `shutdown` has been called.
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down.load(Ordering::Acquire)
+    }
+
+    /// Fail with the CRT's shutting-down error if `shutdown` has been
+    /// called, since the manager must not be used for new acquisitions.
+    pub fn ensure_open(&self) -> Result<(), CrtError> {
+        if self.is_shut_down() {
+            return Err(CrtError::from_code(AWS_ERROR_HTTP_CONNECTION_MANAGER_SHUTTING_DOWN));
+        }
+        Ok(())
+    }
 }
 
 impl Drop for ConnectionManager {
     fn drop(&mut self) {
-        unsafe { aws_http_connection_manager_release(self.manager) };
+        self.shutdown();
     }
 }
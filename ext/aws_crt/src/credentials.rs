@@ -4,6 +4,10 @@
 //! Currently supports static credentials (access key, secret key, optional
 //! session token). The provider is ref-counted by the CRT; `Drop` releases
 //! our reference.
+//!
+//! Owned copies of credential strings on the Rust side are held in
+//! `SecretString`, which zeroes its buffer on drop. The CRT keeps its own
+//! copies internally, so ours only need to live until the provider is built.
 
 use crate::error::CrtError;
 use crate::runtime::{AwsAllocator, CrtRuntime};
@@ -77,6 +81,52 @@ struct AwsCredentialsProviderStaticOptions {
     account_id: AwsByteCursor,
 }
 
+// ---------------------------------------------------------------------------
+// SecretString — owned credential string zeroed on drop
+// ---------------------------------------------------------------------------
+
+/// An owned credential string whose buffer is zeroed when dropped.
+///
+/// Used for any Rust-side copy of an access key, secret key, or session
+/// token (e.g. values extracted from a Ruby Hash). Deliberately does not
+/// implement `Debug`, `Display`, or `Clone` so the secret cannot leak into
+/// logs or be duplicated by accident.
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Take ownership of `s`. The caller should not keep other copies.
+    pub fn new(s: String) -> Self {
+        Self(s)
+    }
+
+    /// Borrow the secret for the duration of a CRT call.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(s: String) -> Self {
+        Self::new(s)
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        // Zero the full allocation (not just `len`) with volatile writes so
+        // the compiler cannot elide them as dead stores.
+        unsafe {
+            let vec = self.0.as_mut_vec();
+            let ptr = vec.as_mut_ptr();
+            for i in 0..vec.capacity() {
+                std::ptr::write_volatile(ptr.add(i), 0);
+            }
+            vec.set_len(0);
+        }
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // FFI declarations
 // ---------------------------------------------------------------------------
@@ -113,7 +163,9 @@ impl CredentialsProvider {
     /// and optional session token.
     ///
     /// The CRT copies the credential strings internally, so the input
-    /// slices do not need to outlive this call.
+    /// slices do not need to outlive this call. Callers holding owned
+    /// copies should keep them in a `SecretString` so they are zeroed
+    /// as soon as the provider has been built.
     pub fn new_static(
         access_key_id: &str,
         secret_access_key: &str,
@@ -2,7 +2,7 @@ use std::ffi::CStr;
 
 use magnus::exception::ExceptionClass;
 use magnus::rb_sys::{AsRawValue, FromRawValue};
-use magnus::{Error, Module, Ruby, Value};
+use magnus::{Class, Error, Module, Object, Ruby, Value};
 use rb_sys::VALUE;
 
 // ---------------------------------------------------------------------------
@@ -23,6 +23,8 @@ static mut HTTP_CONNECTION_ERROR: VALUE = 0;
 static mut HTTP_TIMEOUT_ERROR: VALUE = 0;
 static mut HTTP_TLS_ERROR: VALUE = 0;
 static mut HTTP_PROXY_ERROR: VALUE = 0;
+static mut HTTP_QUEUE_FULL_ERROR: VALUE = 0;
+static mut HTTP_CHECKSUM_MISMATCH_ERROR: VALUE = 0;
 
 /// Register the HTTP error hierarchy under `AwsCrt::Http` and cache the
 /// exception classes for later use by `CrtError`.
@@ -43,6 +45,8 @@ pub fn define_http_errors(
     let timeout_error = http_module.define_error("TimeoutError", error)?;
     let tls_error = http_module.define_error("TlsError", error)?;
     let proxy_error = http_module.define_error("ProxyError", error)?;
+    let queue_full_error = http_module.define_error("QueueFullError", error)?;
+    let checksum_mismatch_error = http_module.define_error("ChecksumMismatchError", error)?;
 
     unsafe {
         HTTP_ERROR = error.as_raw();
@@ -50,6 +54,8 @@ pub fn define_http_errors(
         HTTP_TIMEOUT_ERROR = timeout_error.as_raw();
         HTTP_TLS_ERROR = tls_error.as_raw();
         HTTP_PROXY_ERROR = proxy_error.as_raw();
+        HTTP_QUEUE_FULL_ERROR = queue_full_error.as_raw();
+        HTTP_CHECKSUM_MISMATCH_ERROR = checksum_mismatch_error.as_raw();
     }
 
     Ok(())
@@ -114,10 +120,32 @@ impl CrtError {
         }
     }
 
+    /// The numeric CRT error code.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
     /// The CRT error name, e.g. `AWS_IO_DNS_QUERY_FAILED`.
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Whether a request that failed with this error can be sent again:
+    /// connection failures (DNS, refused or reset sockets, connect timeouts)
+    /// and connections the server closed before responding. A closed pool
+    /// is not retryable.
+    pub fn is_retryable(&self) -> bool {
+        self.name.starts_with("AWS_IO_DNS_")
+            || self.name.starts_with("AWS_IO_SOCKET_")
+            || self.name == "AWS_ERROR_HTTP_CONNECTION_CLOSED"
+            || self.name == "AWS_ERROR_HTTP_SERVER_CLOSED"
+    }
+
+    /// Whether this is a failed DNS query, as opposed to a name that can't
+    /// be resolved at all. Often transient on a cold or overloaded resolver.
+    pub fn is_dns_query_failure(&self) -> bool {
+        self.name == "AWS_IO_DNS_QUERY_FAILED"
+    }
 }
 
 impl std::fmt::Display for CrtError {
@@ -134,6 +162,8 @@ impl From<CrtError> for Error {
     /// - `AWS_IO_DNS_*`           → `AwsCrt::Http::ConnectionError`
     /// - `AWS_IO_SOCKET_TIMEOUT`  → `AwsCrt::Http::TimeoutError`
     /// - `AWS_IO_SOCKET_*`        → `AwsCrt::Http::ConnectionError`
+    /// - `AWS_ERROR_HTTP_CONNECTION_MANAGER_SHUTTING_DOWN` (pool closed)
+    ///   → `AwsCrt::Http::ConnectionError`
     /// - `AWS_ERROR_HTTP_PROXY_*` → `AwsCrt::Http::ProxyError`
     /// - Everything else          → `AwsCrt::Http::Error`
     fn from(e: CrtError) -> Error {
@@ -153,7 +183,9 @@ unsafe fn classify_error(name: &str) -> ExceptionClass {
         exception_class(HTTP_CONNECTION_ERROR)
     } else if name == "AWS_IO_SOCKET_TIMEOUT" {
         exception_class(HTTP_TIMEOUT_ERROR)
-    } else if name.starts_with("AWS_IO_SOCKET_") {
+    } else if name.starts_with("AWS_IO_SOCKET_")
+        || name == "AWS_ERROR_HTTP_CONNECTION_MANAGER_SHUTTING_DOWN"
+    {
         exception_class(HTTP_CONNECTION_ERROR)
     } else if name.starts_with("AWS_ERROR_HTTP_PROXY_") {
         exception_class(HTTP_PROXY_ERROR)
@@ -161,3 +193,51 @@ unsafe fn classify_error(name: &str) -> ExceptionClass {
         exception_class(HTTP_ERROR)
     }
 }
+
+/// `AwsCrt::Http::Error` for a failure detected outside the CRT.
+///
+/// Must be called while the GVL is held.
+pub fn http_error(message: String) -> Error {
+    Error::new(unsafe { exception_class(HTTP_ERROR) }, message)
+}
+
+/// `AwsCrt::Http::ConnectionError` for a failure detected outside the CRT.
+///
+/// Must be called while the GVL is held.
+pub fn connection_error(message: String) -> Error {
+    Error::new(unsafe { exception_class(HTTP_CONNECTION_ERROR) }, message)
+}
+
+/// `AwsCrt::Http::QueueFullError`: a fair-queued pool already had its
+/// maximum number of requests waiting.
+///
+/// Must be called while the GVL is held.
+pub fn queue_full_error(message: String) -> Error {
+    Error::new(unsafe { exception_class(HTTP_QUEUE_FULL_ERROR) }, message)
+}
+
+/// `AwsCrt::Http::ChecksumMismatchError`: a response body's checksum
+/// differed from the expected one (`verify_checksum:`). The checksums are
+/// base64, and are exposed as `#expected_checksum` / `#computed_checksum`
+/// along with `#algorithm`.
+///
+/// Must be called while the GVL is held.
+pub fn checksum_mismatch_error(algorithm: &str, expected: &str, computed: &str) -> Error {
+    let class = unsafe { exception_class(HTTP_CHECKSUM_MISMATCH_ERROR) };
+    let message = format!(
+        "response checksum mismatch ({}): expected {}, computed {}",
+        algorithm, expected, computed
+    );
+    let exception = match class.new_instance((message,)) {
+        Ok(exception) => exception,
+        Err(e) => return e,
+    };
+    let set = exception
+        .ivar_set("@algorithm", algorithm)
+        .and_then(|_| exception.ivar_set("@expected_checksum", expected))
+        .and_then(|_| exception.ivar_set("@computed_checksum", computed));
+    match set {
+        Ok(()) => exception.into(),
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,175 @@
+//! FIFO admission for `ConnectionPool#request` (`fair_queue: true`).
+//!
+//! Without it, every thread calling `request` on a saturated pool parks
+//! inside the CRT connection manager, and whichever is woken first takes
+//! the next free connection — under sustained load some threads can lose
+//! that race again and again. The queue hands out tickets in arrival order
+//! and admits at most `slots` requests (the pool's `max_connections`) at a
+//! time, so a request only ever waits behind requests that arrived before
+//! it.
+//!
+//! Waiting happens with the GVL released, like the waits in http.rs.
+
+use std::sync::{Condvar, Mutex};
+
+use magnus::Error;
+
+use crate::error::{connection_error, queue_full_error};
+
+extern "C" {
+    fn rb_thread_call_without_gvl(
+        func: unsafe extern "C" fn(data: *mut std::ffi::c_void) -> *mut std::ffi::c_void,
+        data1: *mut std::ffi::c_void,
+        ubf: *const std::ffi::c_void,
+        ubf_data: *const std::ffi::c_void,
+    ) -> *mut std::ffi::c_void;
+}
+
+/// A ticket-ordered admission gate.
+pub struct FairQueue {
+    state: Mutex<QueueState>,
+    turn: Condvar,
+    /// Requests admitted at once.
+    slots: usize,
+    /// Requests allowed to wait at once; `None` for no limit.
+    max_waiting: Option<usize>,
+}
+
+struct QueueState {
+    /// Ticket handed to the next arrival.
+    next_ticket: u64,
+    /// Oldest ticket not yet admitted.
+    now_serving: u64,
+    /// Admitted requests that haven't finished.
+    active: usize,
+    closed: bool,
+}
+
+/// Why a request was not admitted.
+#[derive(Debug)]
+pub enum AdmitError {
+    /// `max_waiting` requests were already queued; carries that limit.
+    Full(usize),
+    /// The queue was closed before the request's turn came.
+    Closed,
+}
+
+impl From<AdmitError> for Error {
+    fn from(e: AdmitError) -> Error {
+        match e {
+            AdmitError::Full(max) => queue_full_error(format!(
+                "Connection pool queue is full ({} requests already waiting)",
+                max
+            )),
+            AdmitError::Closed => connection_error("Connection pool is closed".to_string()),
+        }
+    }
+}
+
+/// An admitted request. Dropping it frees the slot for the next ticket.
+pub struct Admission<'a> {
+    queue: &'a FairQueue,
+}
+
+impl Drop for Admission<'_> {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().active -= 1;
+        self.queue.turn.notify_all();
+    }
+}
+
+struct WaitData<'a> {
+    queue: &'a FairQueue,
+    ticket: u64,
+    admitted: bool,
+}
+
+/// Called without the GVL — blocks until `ticket` is admitted or the
+/// queue is closed.
+unsafe extern "C" fn wait_for_turn(data: *mut std::ffi::c_void) -> *mut std::ffi::c_void {
+    let wait = &mut *(data as *mut WaitData);
+    let queue = wait.queue;
+
+    let mut state = queue.state.lock().unwrap();
+    while !state.closed && (state.now_serving != wait.ticket || state.active >= queue.slots) {
+        state = queue.turn.wait(state).unwrap();
+    }
+    if !state.closed {
+        state.now_serving += 1;
+        state.active += 1;
+        wait.admitted = true;
+    }
+    drop(state);
+    // The next ticket may fit in a slot that is still free.
+    queue.turn.notify_all();
+
+    std::ptr::null_mut()
+}
+
+impl FairQueue {
+    pub fn new(slots: usize, max_waiting: Option<usize>) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                next_ticket: 0,
+                now_serving: 0,
+                active: 0,
+                closed: false,
+            }),
+            turn: Condvar::new(),
+            slots: slots.max(1),
+            max_waiting,
+        }
+    }
+
+    /// Wait (without the GVL) until every earlier arrival has been admitted
+    /// and a slot is free. Must be called with the GVL held.
+    pub fn admit(&self) -> Result<Admission<'_>, AdmitError> {
+        let ticket = {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return Err(AdmitError::Closed);
+            }
+            let waiting = (state.next_ticket - state.now_serving) as usize;
+            if waiting == 0 && state.active < self.slots {
+                // Nobody ahead and a slot free: no need to release the GVL.
+                state.next_ticket += 1;
+                state.now_serving += 1;
+                state.active += 1;
+                return Ok(Admission { queue: self });
+            }
+            if let Some(max) = self.max_waiting {
+                if waiting >= max {
+                    return Err(AdmitError::Full(max));
+                }
+            }
+            state.next_ticket += 1;
+            state.next_ticket - 1
+        };
+
+        let mut wait = WaitData {
+            queue: self,
+            ticket,
+            admitted: false,
+        };
+        unsafe {
+            rb_thread_call_without_gvl(
+                wait_for_turn,
+                &mut wait as *mut WaitData as *mut std::ffi::c_void,
+                std::ptr::null(),
+                std::ptr::null(),
+            );
+        }
+        if wait.admitted {
+            Ok(Admission { queue: self })
+        } else {
+            Err(AdmitError::Closed)
+        }
+    }
+
+    /// Fail every waiting and future request with `AdmitError::Closed`.
+    /// Admitted requests are unaffected.
+    pub fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.turn.notify_all();
+    }
+}
@@ -3,17 +3,24 @@
 //! Provides `make_request()` (buffered) and `make_streaming_request()` (chunked)
 //! which acquire a connection from a connection manager, send an HTTP request,
 //! collect the response via CRT callbacks, and return the result to Ruby.
+//! `start_request()` sends a buffered request without waiting for it and
+//! returns a `PendingRequest` to poll or wait on later.
 //!
 //! The GVL is released during blocking waits so other Ruby threads can run.
+//! When the pool enables read backpressure, the stream's read window is
+//! reopened only as body data is consumed: immediately in buffered mode,
+//! and after each chunk has been yielded in streaming mode, so a slow
+//! Ruby consumer paces the server.
 //! Body data is copied into Rust-owned memory before the GVL is released to
 //! prevent use-after-free if Ruby's GC moves the original string.
 
 use std::collections::VecDeque;
 use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crate::connection_manager::{AwsHttpConnection, AwsHttpConnectionManager};
+use crate::connection_manager::{AwsHttpConnection, AwsHttpConnectionManager, ConnectionManager};
 use crate::error::CrtError;
-use crate::runtime::AwsAllocator;
+use crate::runtime::{AwsAllocator, CrtRuntime};
 
 // ---------------------------------------------------------------------------
 // Opaque CRT types
@@ -64,6 +71,20 @@ struct AwsHttpHeader {
     _pad: u32,
 }
 
+/// Mirrors `struct aws_http_stream_metrics`. Timestamps and durations are
+/// in nanoseconds, or -1 when the CRT didn't record them.
+#[repr(C)]
+struct AwsHttpStreamMetrics {
+    send_start_timestamp_ns: i64,
+    send_end_timestamp_ns: i64,
+    sending_duration_ns: i64,
+    receive_start_timestamp_ns: i64,
+    receive_end_timestamp_ns: i64,
+    receiving_duration_ns: i64,
+    stream_id: u32,
+    _pad: u32,
+}
+
 /// Mirrors `struct aws_http_make_request_options`.
 #[repr(C)]
 struct AwsHttpMakeRequestOptions {
@@ -93,7 +114,13 @@ struct AwsHttpMakeRequestOptions {
             user_data: *mut std::ffi::c_void,
         ) -> i32,
     >,
-    on_metrics: *const std::ffi::c_void,
+    on_metrics: Option<
+        unsafe extern "C" fn(
+            stream: *mut AwsHttpStream,
+            metrics: *const AwsHttpStreamMetrics,
+            user_data: *mut std::ffi::c_void,
+        ),
+    >,
     on_complete: Option<
         unsafe extern "C" fn(
             stream: *mut AwsHttpStream,
@@ -159,6 +186,7 @@ extern "C" {
         manager: *mut AwsHttpConnectionManager,
         connection: *mut AwsHttpConnection,
     ) -> i32;
+    fn aws_http_connection_close(connection: *mut AwsHttpConnection);
 
     // HTTP stream (request/response)
     fn aws_http_connection_make_request(
@@ -171,6 +199,7 @@ extern "C" {
         stream: *const AwsHttpStream,
         out_status: *mut i32,
     ) -> i32;
+    fn aws_http_stream_update_window(stream: *mut AwsHttpStream, increment_size: usize);
 
     // CRT error
     fn aws_last_error() -> i32;
@@ -186,6 +215,86 @@ extern "C" {
     ) -> *mut std::ffi::c_void;
 }
 
+/// `AWS_IO_SOCKET_TIMEOUT` — reported for a streaming response that stalls
+/// past `inactivity_timeout_ms`, so it surfaces as `TimeoutError`.
+const AWS_IO_SOCKET_TIMEOUT: i32 = 0x0400 + 24;
+
+// ---------------------------------------------------------------------------
+// Request timing
+// ---------------------------------------------------------------------------
+
+/// When each stage of a request happened, recorded by the CRT callbacks.
+struct TimingMarks {
+    /// Just before the connection was requested from the manager.
+    started: Instant,
+    connection_acquired: Option<Instant>,
+    first_header: Option<Instant>,
+    first_body_byte: Option<Instant>,
+    completed: Option<Instant>,
+    /// CRT-measured send and receive durations (`on_metrics`).
+    sending_duration_ns: Option<i64>,
+    receiving_duration_ns: Option<i64>,
+    /// From the response's `Date` header, as it arrived.
+    clock_skew_ms: Option<i64>,
+}
+
+impl TimingMarks {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            connection_acquired: None,
+            first_header: None,
+            first_body_byte: None,
+            completed: None,
+            sending_duration_ns: None,
+            receiving_duration_ns: None,
+            clock_skew_ms: None,
+        }
+    }
+
+    fn since_start_ms(&self, mark: Option<Instant>) -> Option<f64> {
+        mark.map(|t| t.duration_since(self.started).as_secs_f64() * 1000.0)
+    }
+
+    fn timing(&self) -> RequestTiming {
+        let ns_to_ms = |ns: Option<i64>| ns.map(|ns| ns as f64 / 1_000_000.0);
+        RequestTiming {
+            connect_ms: self.since_start_ms(self.connection_acquired),
+            ttfb_ms: self.since_start_ms(self.first_header),
+            first_body_byte_ms: self.since_start_ms(self.first_body_byte),
+            total_ms: self
+                .since_start_ms(self.completed.or_else(|| Some(Instant::now())))
+                .unwrap_or(0.0),
+            send_ms: ns_to_ms(self.sending_duration_ns),
+            receive_ms: ns_to_ms(self.receiving_duration_ns),
+            clock_skew_ms: self.clock_skew_ms,
+        }
+    }
+}
+
+/// Latency breakdown of one request attempt, in milliseconds from when the
+/// connection was requested. Stages that never happened are `None`. Also
+/// carries the server clock skew seen by the attempt.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RequestTiming {
+    /// Connection acquired from the pool (includes waiting for a free
+    /// connection and, for a new one, DNS, TCP and TLS).
+    pub connect_ms: Option<f64>,
+    /// First response header received.
+    pub ttfb_ms: Option<f64>,
+    /// First response body byte received.
+    pub first_body_byte_ms: Option<f64>,
+    /// Request complete.
+    pub total_ms: f64,
+    /// Time the CRT spent sending the request.
+    pub send_ms: Option<f64>,
+    /// Time the CRT spent receiving the response.
+    pub receive_ms: Option<f64>,
+    /// How far the server's clock was ahead of ours, from the response's
+    /// `Date` header (see `clock_skew_ms`); `None` without a valid one.
+    pub clock_skew_ms: Option<i64>,
+}
+
 // ---------------------------------------------------------------------------
 // Shared callback state
 // ---------------------------------------------------------------------------
@@ -205,6 +314,19 @@ struct RequestState {
     chunks: VecDeque<Vec<u8>>,
     /// Whether this request uses streaming mode.
     streaming: bool,
+    /// Whether the connection uses manual read-window management, so the
+    /// window must be incremented as body data is consumed.
+    read_backpressure: bool,
+    /// Upper bound on the body pre-allocation from `Content-Length`.
+    max_prealloc_bytes: usize,
+    /// Whether to pre-allocate from `Content-Length` at all.
+    trust_content_length: bool,
+    /// Whether the request was sent with `Connection: close`, so the
+    /// connection must be closed rather than returned for reuse.
+    connection_close: bool,
+    /// The active stream, or null once it has completed. Only dereferenced
+    /// under the lock; `on_stream_complete` clears it before releasing.
+    stream: *mut AwsHttpStream,
     /// CRT error code from on_complete (0 = success).
     error_code: i32,
     /// Set to true when on_complete fires.
@@ -213,6 +335,11 @@ struct RequestState {
     connection: *mut AwsHttpConnection,
     /// The connection manager (needed for releasing the connection).
     manager: *mut AwsHttpConnectionManager,
+    /// When each stage of the request happened.
+    timing: TimingMarks,
+    /// Set when a `PendingRequest` is dropped before the request completes.
+    /// Nobody will wait for the result, so completion frees the context.
+    detached: bool,
 }
 
 // SAFETY: RequestState is only accessed under the Mutex lock, and the raw
@@ -237,6 +364,9 @@ unsafe extern "C" fn on_response_headers(
     let state = &ctx.state;
 
     let mut guard = state.0.lock().unwrap();
+    if guard.timing.first_header.is_none() {
+        guard.timing.first_header = Some(Instant::now());
+    }
 
     // Get the status code on first header callback
     if guard.status_code == 0 {
@@ -255,16 +385,26 @@ unsafe extern "C" fn on_response_headers(
 
         // Pre-allocate body buffer from Content-Length (buffered mode only).
         // This avoids repeated Vec reallocations during on_response_body.
-        if !guard.streaming && h.name.len == 14 {
+        // Capped, since the header is server-controlled: past the cap the
+        // Vec grows as data actually arrives. Skipped entirely for servers
+        // known to lie about it.
+        if !guard.streaming && guard.trust_content_length && h.name.len == 14 {
             if name_bytes.eq_ignore_ascii_case(b"content-length") {
                 if let Ok(s) = std::str::from_utf8(value_bytes) {
                     if let Ok(len) = s.parse::<usize>() {
+                        let len = len.min(guard.max_prealloc_bytes);
                         guard.body.reserve(len);
                     }
                 }
             }
         }
 
+        if h.name.len == 4 && name_bytes.eq_ignore_ascii_case(b"date") {
+            if let Ok(date) = std::str::from_utf8(value_bytes) {
+                guard.timing.clock_skew_ms = clock_skew_ms(date, SystemTime::now());
+            }
+        }
+
         let name =
             std::str::from_utf8_unchecked(name_bytes).to_string();
         let value =
@@ -277,7 +417,7 @@ unsafe extern "C" fn on_response_headers(
 
 /// Called as response body chunks arrive.
 unsafe extern "C" fn on_response_body(
-    _stream: *mut AwsHttpStream,
+    stream: *mut AwsHttpStream,
     data: *const AwsByteCursor,
     user_data: *mut std::ffi::c_void,
 ) -> i32 {
@@ -288,18 +428,40 @@ unsafe extern "C" fn on_response_body(
     let bytes = std::slice::from_raw_parts(cursor.ptr, cursor.len);
 
     let mut guard = state.0.lock().unwrap();
+    if guard.timing.first_body_byte.is_none() && !bytes.is_empty() {
+        guard.timing.first_body_byte = Some(Instant::now());
+    }
     if guard.streaming {
-        // Streaming mode: push chunk and notify the waiting Ruby thread
+        // Streaming mode: push chunk and notify the waiting Ruby thread.
+        // The window is reopened once the Ruby block has consumed it.
         guard.chunks.push_back(bytes.to_vec());
         state.1.notify_one();
     } else {
         // Buffered mode: accumulate into a single body buffer
         guard.body.extend_from_slice(bytes);
+        if guard.read_backpressure {
+            aws_http_stream_update_window(stream, bytes.len());
+        }
     }
 
     0 // AWS_OP_SUCCESS
 }
 
+/// Called with the CRT's own measurements just before `on_stream_complete`.
+unsafe extern "C" fn on_stream_metrics(
+    _stream: *mut AwsHttpStream,
+    metrics: *const AwsHttpStreamMetrics,
+    user_data: *mut std::ffi::c_void,
+) {
+    let ctx = &*(user_data as *const RequestContext);
+    let metrics = &*metrics;
+    let recorded = |ns: i64| (ns >= 0).then_some(ns);
+
+    let mut guard = ctx.state.0.lock().unwrap();
+    guard.timing.sending_duration_ns = recorded(metrics.sending_duration_ns);
+    guard.timing.receiving_duration_ns = recorded(metrics.receiving_duration_ns);
+}
+
 /// Called when the request/response exchange is complete.
 unsafe extern "C" fn on_stream_complete(
     stream: *mut AwsHttpStream,
@@ -309,24 +471,55 @@ unsafe extern "C" fn on_stream_complete(
     let ctx = &*(user_data as *const RequestContext);
     let state = &ctx.state;
 
-    // Release the stream
-    aws_http_stream_release(stream);
-
-    // Release the connection back to the pool
-    let guard = state.0.lock().unwrap();
+    // Clear the stream under the lock so the Ruby thread can no longer
+    // update its window, then release it
+    let mut guard = state.0.lock().unwrap();
+    guard.stream = std::ptr::null_mut();
     let connection = guard.connection;
     let manager = guard.manager;
+    let close = guard.connection_close;
     drop(guard);
 
+    aws_http_stream_release(stream);
+
     if !connection.is_null() {
-        aws_http_connection_manager_release_connection(manager, connection);
+        release_connection(manager, connection, close);
     }
 
-    // Signal completion
-    let mut guard = state.0.lock().unwrap();
-    guard.error_code = error_code;
-    guard.complete = true;
-    state.1.notify_one();
+    complete_request(user_data, error_code);
+}
+
+/// Record the outcome and wake the waiting thread. This is the last use of
+/// the context by the CRT callbacks; for a detached `PendingRequest` it is
+/// freed here.
+unsafe fn complete_request(user_data: *mut std::ffi::c_void, error_code: i32) {
+    let ctx_ptr = user_data as *mut RequestContext;
+    let detached = {
+        let state = &(*ctx_ptr).state;
+        let mut guard = state.0.lock().unwrap();
+        guard.error_code = error_code;
+        guard.complete = true;
+        guard.timing.completed = Some(Instant::now());
+        state.1.notify_all();
+        guard.detached
+    };
+    if detached {
+        cleanup_request_context(ctx_ptr);
+    }
+}
+
+/// Hand a connection back to the manager. With `close`, the connection is
+/// shut down first; the manager sees it no longer accepts requests and
+/// discards it instead of adding it to the idle set.
+unsafe fn release_connection(
+    manager: *mut AwsHttpConnectionManager,
+    connection: *mut AwsHttpConnection,
+    close: bool,
+) {
+    if close {
+        aws_http_connection_close(connection);
+    }
+    aws_http_connection_manager_release_connection(manager, connection);
 }
 
 // ---------------------------------------------------------------------------
@@ -370,10 +563,7 @@ unsafe extern "C" fn on_connection_acquired_with_ctx(
     let state = &ctx.state;
 
     if error_code != 0 || connection.is_null() {
-        let mut guard = state.0.lock().unwrap();
-        guard.error_code = if error_code != 0 { error_code } else { -1 };
-        guard.complete = true;
-        state.1.notify_one();
+        complete_request(user_data, if error_code != 0 { error_code } else { -1 });
         return;
     }
 
@@ -381,6 +571,7 @@ unsafe extern "C" fn on_connection_acquired_with_ctx(
     {
         let mut guard = state.0.lock().unwrap();
         guard.connection = connection;
+        guard.timing.connection_acquired = Some(Instant::now());
     }
 
     // Set up the make_request options
@@ -391,7 +582,7 @@ unsafe extern "C" fn on_connection_acquired_with_ctx(
         on_response_headers: Some(on_response_headers),
         on_response_header_block_done: None,
         on_response_body: Some(on_response_body),
-        on_metrics: std::ptr::null(),
+        on_metrics: Some(on_stream_metrics),
         on_complete: Some(on_stream_complete),
         on_destroy: std::ptr::null(),
         http2_use_manual_data_writes: false,
@@ -408,26 +599,23 @@ unsafe extern "C" fn on_connection_acquired_with_ctx(
             state.0.lock().unwrap().manager,
             connection,
         );
-        let mut guard = state.0.lock().unwrap();
-        guard.error_code = if err != 0 { err } else { -1 };
-        guard.complete = true;
-        state.1.notify_one();
+        complete_request(user_data, if err != 0 { err } else { -1 });
         return;
     }
 
+    state.0.lock().unwrap().stream = stream;
+
     // Activate the stream to start sending
     let rc = aws_http_stream_activate(stream);
     if rc != 0 {
         let err = aws_last_error();
+        state.0.lock().unwrap().stream = std::ptr::null_mut();
         aws_http_stream_release(stream);
         aws_http_connection_manager_release_connection(
             state.0.lock().unwrap().manager,
             connection,
         );
-        let mut guard = state.0.lock().unwrap();
-        guard.error_code = if err != 0 { err } else { -1 };
-        guard.complete = true;
-        state.1.notify_one();
+        complete_request(user_data, if err != 0 { err } else { -1 });
     }
 }
 
@@ -456,27 +644,338 @@ unsafe extern "C" fn wait_for_completion(
     std::ptr::null_mut()
 }
 
+/// Data passed to the without-GVL function for streaming requests.
+struct ChunkWaitData {
+    state: SharedState,
+    /// Longest wait for the next chunk once the response has started
+    /// (`inactivity_timeout_ms`), or `None` to wait indefinitely.
+    inactivity_timeout: Option<Duration>,
+    /// Set when `inactivity_timeout` passed with nothing arriving.
+    timed_out: bool,
+}
+
 /// Called without the GVL — blocks until either a body chunk arrives or the
 /// request completes. Used for streaming requests. Returns as soon as there
-/// is something for the Ruby thread to process.
+/// is something for the Ruby thread to process, or once the response has
+/// gone quiet for longer than the inactivity timeout.
 unsafe extern "C" fn wait_for_chunk_or_completion(
     data: *mut std::ffi::c_void,
 ) -> *mut std::ffi::c_void {
-    let wait_data = &*(data as *const WaitData);
+    let wait_data = &mut *(data as *mut ChunkWaitData);
     let (lock, cvar) = &*wait_data.state;
 
     let mut guard = lock.lock().unwrap();
+    let Some(timeout) = wait_data.inactivity_timeout else {
+        while !guard.complete && guard.chunks.is_empty() {
+            guard = cvar.wait(guard).unwrap();
+        }
+        return std::ptr::null_mut();
+    };
+
+    let mut deadline = Instant::now() + timeout;
     while !guard.complete && guard.chunks.is_empty() {
-        guard = cvar.wait(guard).unwrap();
+        let now = Instant::now();
+        if now < deadline {
+            guard = cvar.wait_timeout(guard, deadline - now).unwrap().0;
+        } else if guard.status_code == 0 {
+            // Still connecting or waiting for the first byte, which
+            // connect_timeout_ms and read_timeout_ms cover.
+            deadline = now + timeout;
+        } else {
+            wait_data.timed_out = true;
+            break;
+        }
     }
 
     std::ptr::null_mut()
 }
 
+/// Give up on a streaming request whose response stalled: close its
+/// connection, which makes the CRT fail the stream, then wait (without the
+/// GVL) for that completion before freeing the request context.
+///
+/// SAFETY: Must be called with the GVL held, once, in place of the usual
+/// `cleanup_request_context`.
+unsafe fn cancel_stalled_stream(state: &SharedState, ctx_ptr: *mut RequestContext) {
+    {
+        let guard = state.0.lock().unwrap();
+        // The connection is released only after the stream is cleared, so
+        // it is still ours while the stream is set.
+        if !guard.stream.is_null() {
+            aws_http_connection_close(guard.connection);
+        }
+    }
+    let wait_data = WaitData {
+        state: Arc::clone(state),
+    };
+    rb_thread_call_without_gvl(
+        wait_for_completion,
+        &wait_data as *const WaitData as *mut std::ffi::c_void,
+        std::ptr::null(),
+        std::ptr::null(),
+    );
+    cleanup_request_context(ctx_ptr);
+}
+
+/// Called without the GVL — sleeps for the given `Duration`.
+unsafe extern "C" fn sleep_without_gvl(data: *mut std::ffi::c_void) -> *mut std::ffi::c_void {
+    std::thread::sleep(*(data as *const std::time::Duration));
+    std::ptr::null_mut()
+}
+
+/// Sleep for `delay` with the GVL released, so other Ruby threads run
+/// meanwhile. Must be called with the GVL held.
+pub fn sleep(delay: std::time::Duration) {
+    unsafe {
+        rb_thread_call_without_gvl(
+            sleep_without_gvl,
+            &delay as *const std::time::Duration as *mut std::ffi::c_void,
+            std::ptr::null(),
+            std::ptr::null(),
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Connection acquisition
+// ---------------------------------------------------------------------------
+
+/// Ask the connection manager for a connection; the request is sent from
+/// `on_connection_acquired_with_ctx`.
+fn acquire_connection(manager: *mut AwsHttpConnectionManager, ctx_ptr: *mut RequestContext) {
+    unsafe {
+        aws_http_connection_manager_acquire_connection(
+            manager,
+            on_connection_acquired_with_ctx,
+            ctx_ptr as *mut std::ffi::c_void,
+        );
+    }
+}
+
+/// Called once a request has completed. If it failed because the DNS query
+/// for the host failed — so no connection was acquired and nothing was
+/// sent — and `AwsCrt.configure(dns_failure_retries:)` allows another try,
+/// back off (without the GVL) and ask for a connection again with the same
+/// request context. Returns whether a new attempt was started.
+///
+/// The pool may be closed during the backoff, releasing the manager, so it
+/// is checked again once the GVL is back; a closed pool fails the request
+/// with the manager's shutting-down error instead.
+fn retry_dns_failure(
+    cm: &ConnectionManager,
+    ctx_ptr: *mut RequestContext,
+    state: &SharedState,
+    dns_retries: &mut u32,
+) -> bool {
+    let config = CrtRuntime::get().config();
+    {
+        let mut guard = state.0.lock().unwrap();
+        if *dns_retries >= config.dns_failure_retries
+            || guard.error_code == 0
+            || guard.timing.connection_acquired.is_some()
+            || !CrtError::from_code(guard.error_code).is_dns_query_failure()
+        {
+            return false;
+        }
+        guard.error_code = 0;
+        guard.complete = false;
+    }
+
+    *dns_retries += 1;
+    sleep(config.dns_retry_delay(*dns_retries));
+    // No GVL release between this check and the acquisition, so a
+    // concurrent #close can't slip in.
+    if let Err(err) = cm.ensure_open() {
+        let mut guard = state.0.lock().unwrap();
+        guard.error_code = err.code();
+        guard.complete = true;
+        return false;
+    }
+    acquire_connection(cm.as_ptr(), ctx_ptr);
+    true
+}
+
 // ---------------------------------------------------------------------------
 // Request building helper
 // ---------------------------------------------------------------------------
 
+/// Default cap on the buffered body pre-allocation.
+pub const DEFAULT_MAX_PREALLOC_BYTES: usize = 4 * 1024 * 1024;
+
+/// Default cap on the wait a `Retry-After` header can ask for.
+pub const DEFAULT_MAX_RETRY_AFTER_MS: u64 = 20_000;
+
+/// Settings applied to a request: the pool's configuration plus any
+/// per-request overrides.
+#[derive(Clone, Copy, Debug)]
+pub struct RequestSettings {
+    /// Read timeout in milliseconds. If non-zero, the CRT will fail the
+    /// request with `AWS_ERROR_HTTP_RESPONSE_FIRST_BYTE_TIMEOUT` if the
+    /// server does not begin responding within this duration after the
+    /// request is fully sent.
+    pub read_timeout_ms: u64,
+    /// Whether the connection manager was created with read backpressure.
+    pub read_backpressure: bool,
+    /// Maximum bytes reserved up front for a buffered body based on the
+    /// response's `Content-Length`.
+    pub max_prealloc_bytes: usize,
+    /// Pre-allocate a buffered body from `Content-Length` (up to
+    /// `max_prealloc_bytes`). When false the body grows as data arrives.
+    pub trust_content_length: bool,
+    /// Send `Connection: close` and close the connection once the response
+    /// completes instead of returning it to the pool.
+    pub connection_close: bool,
+    /// How many times pool.rs resends an idempotent request that failed
+    /// with a retryable connection error (see `CrtError::is_retryable`) or
+    /// was throttled with a `Retry-After` (see `retry_after`).
+    pub max_retries: u32,
+    /// Also retry non-idempotent methods (POST, PATCH).
+    pub retry_non_idempotent: bool,
+    /// Longest wait, in milliseconds, honoured from the `Retry-After` of a
+    /// 429 or 503 response before retrying it (see `retry_after`).
+    pub max_retry_after_ms: u64,
+    /// Send `Content-Length: 0` for a POST, PUT or PATCH whose body was
+    /// given but is empty (see `build_request`).
+    pub empty_body_content_length: bool,
+    /// Inactivity timeout in milliseconds for streaming requests. If
+    /// non-zero, a response that has started but then delivers no body data
+    /// for this long has its connection closed and fails with
+    /// `AWS_IO_SOCKET_TIMEOUT` (0 = no timeout).
+    pub inactivity_timeout_ms: u64,
+}
+
+impl Default for RequestSettings {
+    fn default() -> Self {
+        Self {
+            read_timeout_ms: 0,
+            read_backpressure: false,
+            max_prealloc_bytes: DEFAULT_MAX_PREALLOC_BYTES,
+            trust_content_length: true,
+            connection_close: false,
+            max_retries: 0,
+            retry_non_idempotent: false,
+            max_retry_after_ms: DEFAULT_MAX_RETRY_AFTER_MS,
+            empty_body_content_length: true,
+            inactivity_timeout_ms: 0,
+        }
+    }
+}
+
+impl RequestSettings {
+    /// Retries allowed for a request with this method. Methods RFC 9110
+    /// defines as idempotent may be resent safely; anything else only with
+    /// `retry_non_idempotent`, since the server may already have acted on it.
+    pub fn retries_for(&self, method: &str) -> u32 {
+        const IDEMPOTENT: [&str; 6] = ["GET", "HEAD", "PUT", "DELETE", "OPTIONS", "TRACE"];
+        if self.retry_non_idempotent || IDEMPOTENT.iter().any(|m| m.eq_ignore_ascii_case(method)) {
+            self.max_retries
+        } else {
+            0
+        }
+    }
+
+    /// How long to wait before retrying a response with this status and
+    /// these headers, or `None` if it shouldn't be retried. Only a 429 or
+    /// 503 carrying a `Retry-After` the server meant as an invitation to
+    /// come back is retried; the wait is capped at `max_retry_after_ms`.
+    pub fn retry_after(&self, status_code: i32, headers: &[(String, String)]) -> Option<Duration> {
+        if status_code != 429 && status_code != 503 {
+            return None;
+        }
+        let value = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))?
+            .1
+            .trim();
+        let delay = parse_retry_after(value, SystemTime::now())?;
+        Some(delay.min(Duration::from_millis(self.max_retry_after_ms)))
+    }
+}
+
+/// Parse a `Retry-After` value: either delay-seconds or an HTTP date,
+/// which is turned into the time left until then (zero if it has passed).
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+        return Some(Duration::from_secs(value.parse().unwrap_or(u64::MAX)));
+    }
+    let at = parse_http_date(value)?;
+    let now = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+    Some(Duration::from_secs(at.saturating_sub(now).max(0) as u64))
+}
+
+/// How far the server's clock is ahead of ours (negative when behind), in
+/// milliseconds, from a `Date` header value and the time it was received.
+/// The header has one-second resolution, so this is only good to about a
+/// second either way. `None` if the date doesn't parse.
+pub fn clock_skew_ms(date: &str, received: SystemTime) -> Option<i64> {
+    let server_ms = parse_http_date(date)?.checked_mul(1000)?;
+    let local_ms = received.duration_since(UNIX_EPOCH).ok()?.as_millis() as i64;
+    Some(server_ms - local_ms)
+}
+
+/// Parse an RFC 1123 HTTP date (`Wed, 21 Oct 2015 07:28:00 GMT`) into
+/// seconds since the Unix epoch. Returns `None` for any other format and
+/// for fields out of range (hour 24, a minute of 60, 31 Apr, 29 Feb in a
+/// common year, ...). A leap second (`:60`) is accepted.
+pub fn parse_http_date(s: &str) -> Option<i64> {
+    // Unsigned decimal digits only, so no sign slips through `parse`.
+    fn field(s: &str) -> Option<i64> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok()
+    }
+
+    let mut parts = s.split_whitespace();
+    let _weekday = parts.next()?;
+    let day = field(parts.next()?)?;
+    let month: i64 = match parts.next()? {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year = field(parts.next()?)?;
+    let mut hms = parts.next()?.split(':');
+    let hour = field(hms.next()?)?;
+    let minute = field(hms.next()?)?;
+    let second = field(hms.next()?)?;
+    if hms.next().is_some() || parts.next()? != "GMT" || parts.next().is_some() {
+        return None;
+    }
+    let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let month_days = match month {
+        2 if leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if !(1..=month_days).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Days since epoch for a proleptic Gregorian date (Howard Hinnant's
+    // days_from_civil).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
 /// Options for building and executing an HTTP request.
 pub struct RequestOptions<'a> {
     pub manager: *mut AwsHttpConnectionManager,
@@ -488,11 +987,7 @@ pub struct RequestOptions<'a> {
     /// must remain alive for the CRT input stream's cursor.
     pub body: Option<Vec<u8>>,
     pub streaming: bool,
-    /// Read timeout in milliseconds. If non-zero, the CRT will fail the
-    /// request with `AWS_ERROR_HTTP_RESPONSE_FIRST_BYTE_TIMEOUT` if the
-    /// server does not begin responding within this duration after the
-    /// request is fully sent.
-    pub read_timeout_ms: u64,
+    pub settings: RequestSettings,
 }
 
 /// Build a CRT request message and set up the shared state for async
@@ -528,8 +1023,28 @@ fn build_request(
         }
     }
 
-    // Add headers
-    for (name, value) in opts.headers {
+    // Add headers. A forced close replaces any caller-supplied Connection
+    // header so the server sees exactly one.
+    let connection_close = opts.settings.connection_close;
+    let close_header = [("Connection".to_string(), "close".to_string())];
+    // No body stream is attached for an empty body, so nothing would tell
+    // the server the body is empty; some reject a POST/PUT/PATCH without a
+    // length (411). GET and HEAD without a body are left alone.
+    let empty_length = opts.settings.empty_body_content_length
+        && opts.body.as_ref().is_some_and(|b| b.is_empty())
+        && ["POST", "PUT", "PATCH"].iter().any(|m| m.eq_ignore_ascii_case(opts.method))
+        && !opts.headers.iter().any(|(name, _)| {
+            name.eq_ignore_ascii_case("content-length")
+                || name.eq_ignore_ascii_case("transfer-encoding")
+        });
+    let length_header = [("Content-Length".to_string(), "0".to_string())];
+    let headers = opts
+        .headers
+        .iter()
+        .filter(|(name, _)| !(connection_close && name.eq_ignore_ascii_case("connection")))
+        .chain(close_header.iter().filter(|_| connection_close))
+        .chain(length_header.iter().filter(|_| empty_length));
+    for (name, value) in headers {
         let header = AwsHttpHeader {
             name: AwsByteCursor::from_slice(name.as_bytes()),
             value: AwsByteCursor::from_slice(value.as_bytes()),
@@ -577,10 +1092,17 @@ fn build_request(
             body: Vec::new(),
             chunks: VecDeque::new(),
             streaming: opts.streaming,
+            read_backpressure: opts.settings.read_backpressure,
+            max_prealloc_bytes: opts.settings.max_prealloc_bytes,
+            trust_content_length: opts.settings.trust_content_length,
+            connection_close,
+            stream: std::ptr::null_mut(),
             error_code: 0,
             complete: false,
             connection: std::ptr::null_mut(),
             manager: opts.manager,
+            timing: TimingMarks::new(),
+            detached: false,
         }),
         Condvar::new(),
     ));
@@ -590,7 +1112,7 @@ fn build_request(
         request,
         body_stream,
         _body_data: body_data,
-        response_first_byte_timeout_ms: opts.read_timeout_ms,
+        response_first_byte_timeout_ms: opts.settings.read_timeout_ms,
     });
     let ctx_ptr = Box::into_raw(ctx);
 
@@ -620,6 +1142,7 @@ pub struct HttpResponse {
     pub status_code: i32,
     pub headers: Vec<(String, String)>,
     pub body: Vec<u8>,
+    pub timing: RequestTiming,
 }
 
 /// Execute a buffered HTTP request on the given connection manager.
@@ -628,52 +1151,53 @@ pub struct HttpResponse {
 /// can execute concurrently. The response is fully buffered in memory.
 ///
 /// # Arguments
-/// * `manager` - Raw pointer to the CRT connection manager
+/// * `cm` - The pool's connection manager
 /// * `method` - HTTP method (GET, POST, etc.)
 /// * `path` - Request path (e.g. "/index.html")
 /// * `headers` - Request headers as (name, value) pairs
 /// * `body` - Optional request body bytes
-/// * `read_timeout_ms` - Read timeout in milliseconds (0 = no timeout)
+/// * `settings` - Request settings (timeouts, read windows, buffering,
+///   forced connection close)
 pub fn make_request(
-    manager: *mut AwsHttpConnectionManager,
+    cm: &ConnectionManager,
     method: &str,
     path: &str,
     headers: &[(String, String)],
     body: Option<Vec<u8>>,
-    read_timeout_ms: u64,
+    settings: &RequestSettings,
 ) -> Result<HttpResponse, CrtError> {
     let opts = RequestOptions {
-        manager,
+        manager: cm.as_ptr(),
         method,
         path,
         headers,
         body,
         streaming: false,
-        read_timeout_ms,
+        settings: *settings,
     };
 
     let (ctx_ptr, state) = build_request(opts)?;
 
     // Acquire a connection — this is async, the callback fires the request
-    unsafe {
-        aws_http_connection_manager_acquire_connection(
-            manager,
-            on_connection_acquired_with_ctx,
-            ctx_ptr as *mut std::ffi::c_void,
-        );
-    }
+    acquire_connection(cm.as_ptr(), ctx_ptr);
 
     // Release the GVL and wait for the request to complete
     let wait_data = WaitData {
         state: Arc::clone(&state),
     };
-    unsafe {
-        rb_thread_call_without_gvl(
-            wait_for_completion,
-            &wait_data as *const WaitData as *mut std::ffi::c_void,
-            std::ptr::null(),
-            std::ptr::null(),
-        );
+    let mut dns_retries = 0;
+    loop {
+        unsafe {
+            rb_thread_call_without_gvl(
+                wait_for_completion,
+                &wait_data as *const WaitData as *mut std::ffi::c_void,
+                std::ptr::null(),
+                std::ptr::null(),
+            );
+        }
+        if !retry_dns_failure(cm, ctx_ptr, &state, &mut dns_retries) {
+            break;
+        }
     }
 
     // Clean up the request context
@@ -692,9 +1216,122 @@ pub fn make_request(
         status_code: guard.status_code,
         headers: std::mem::take(&mut guard.headers),
         body: std::mem::take(&mut guard.body),
+        timing: guard.timing.timing(),
     })
 }
 
+/// A buffered request sent by `start_request` that may still be in flight.
+///
+/// Dropping it before the request completes doesn't cancel the request; the
+/// context is freed when the CRT reports completion.
+pub struct PendingRequest {
+    /// Null once `finish` has freed the context.
+    ctx_ptr: *mut RequestContext,
+    state: SharedState,
+}
+
+// SAFETY: the context is only freed by whichever of `finish`, `Drop` and
+// the completion callback comes last, decided under the state lock.
+unsafe impl Send for PendingRequest {}
+
+/// Blocks (without the GVL) until a `PendingRequest` completes. Holds only
+/// the shared state, so callers needn't keep the request borrowed while
+/// other Ruby threads run.
+pub struct CompletionWaiter {
+    state: SharedState,
+}
+
+impl CompletionWaiter {
+    pub fn wait(&self) {
+        let wait_data = WaitData {
+            state: Arc::clone(&self.state),
+        };
+        unsafe {
+            rb_thread_call_without_gvl(
+                wait_for_completion,
+                &wait_data as *const WaitData as *mut std::ffi::c_void,
+                std::ptr::null(),
+                std::ptr::null(),
+            );
+        }
+    }
+}
+
+impl PendingRequest {
+    pub fn is_complete(&self) -> bool {
+        self.state.0.lock().unwrap().complete
+    }
+
+    pub fn waiter(&self) -> CompletionWaiter {
+        CompletionWaiter {
+            state: Arc::clone(&self.state),
+        }
+    }
+
+    /// Free the context and take the result. Waits first if the request is
+    /// still in flight.
+    pub fn finish(mut self) -> Result<HttpResponse, CrtError> {
+        if !self.is_complete() {
+            self.waiter().wait();
+        }
+        unsafe { cleanup_request_context(self.ctx_ptr) };
+        self.ctx_ptr = std::ptr::null_mut();
+
+        let mut guard = self.state.0.lock().unwrap();
+        if guard.error_code != 0 {
+            return Err(CrtError::from_code(guard.error_code));
+        }
+        Ok(HttpResponse {
+            status_code: guard.status_code,
+            headers: std::mem::take(&mut guard.headers),
+            body: std::mem::take(&mut guard.body),
+            timing: guard.timing.timing(),
+        })
+    }
+}
+
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        if self.ctx_ptr.is_null() {
+            return;
+        }
+        let mut guard = self.state.0.lock().unwrap();
+        if guard.complete {
+            drop(guard);
+            unsafe { cleanup_request_context(self.ctx_ptr) };
+        } else {
+            guard.detached = true;
+        }
+    }
+}
+
+/// Send a buffered request without waiting for the response.
+///
+/// Unlike `make_request`, a DNS failure is not retried
+/// (`dns_failure_retries`), since that needs a thread to back off on.
+pub fn start_request(
+    manager: *mut AwsHttpConnectionManager,
+    method: &str,
+    path: &str,
+    headers: &[(String, String)],
+    body: Option<Vec<u8>>,
+    settings: &RequestSettings,
+) -> Result<PendingRequest, CrtError> {
+    let opts = RequestOptions {
+        manager,
+        method,
+        path,
+        headers,
+        body,
+        streaming: false,
+        settings: *settings,
+    };
+
+    let (ctx_ptr, state) = build_request(opts)?;
+    acquire_connection(manager, ctx_ptr);
+    Ok(PendingRequest { ctx_ptr, state })
+}
+
 /// Execute a streaming HTTP request on the given connection manager.
 ///
 /// Instead of buffering the entire response body, this function yields each
@@ -702,7 +1339,7 @@ pub fn make_request(
 /// is released while waiting for chunks and re-acquired before each yield.
 ///
 /// # Arguments
-/// * `manager` - Raw pointer to the CRT connection manager
+/// * `cm` - The pool's connection manager
 /// * `method` - HTTP method (GET, POST, etc.)
 /// * `path` - Request path (e.g. "/index.html")
 /// * `headers` - Request headers as (name, value) pairs
@@ -724,73 +1361,77 @@ pub fn make_request(
 /// code and headers before processing body data.
 ///
 /// # Arguments
-/// * `manager` - Raw pointer to the CRT connection manager
+/// * `cm` - The pool's connection manager
 /// * `method` - HTTP method (GET, POST, etc.)
 /// * `path` - Request path (e.g. "/index.html")
 /// * `headers` - Request headers as (name, value) pairs
 /// * `body` - Optional request body bytes
-/// * `read_timeout_ms` - Read timeout in milliseconds (0 = no timeout)
+/// * `settings` - Pool-wide settings; with read backpressure the window is
+///   incremented by each chunk's size after `on_chunk` returns
 /// * `on_headers` - Called once with (status_code, headers) before body chunks
 /// * `on_chunk` - Called with each body chunk (while GVL is held)
 ///
 /// # Returns
-/// Ok(()) on success, or a CrtError on failure.
+/// The request's timing on success, or a CrtError on failure.
 pub fn make_streaming_request<H, F>(
-    manager: *mut AwsHttpConnectionManager,
+    cm: &ConnectionManager,
     method: &str,
     path: &str,
     headers: &[(String, String)],
     body: Option<Vec<u8>>,
-    read_timeout_ms: u64,
+    settings: &RequestSettings,
     mut on_headers: H,
     mut on_chunk: F,
-) -> Result<(), CrtError>
+) -> Result<RequestTiming, CrtError>
 where
     H: FnMut(i32, &[(String, String)]),
     F: FnMut(&[u8]),
 {
     let opts = RequestOptions {
-        manager,
+        manager: cm.as_ptr(),
         method,
         path,
         headers,
         body,
         streaming: true,
-        read_timeout_ms,
+        settings: *settings,
     };
 
     let (ctx_ptr, state) = build_request(opts)?;
 
     // Acquire a connection
-    unsafe {
-        aws_http_connection_manager_acquire_connection(
-            manager,
-            on_connection_acquired_with_ctx,
-            ctx_ptr as *mut std::ffi::c_void,
-        );
-    }
+    acquire_connection(cm.as_ptr(), ctx_ptr);
 
     // Streaming loop: release GVL → wait for chunk or completion →
     // re-acquire GVL → yield headers/chunks → repeat
-    let wait_data = WaitData {
+    let mut wait_data = ChunkWaitData {
         state: Arc::clone(&state),
+        inactivity_timeout: (settings.inactivity_timeout_ms > 0)
+            .then(|| Duration::from_millis(settings.inactivity_timeout_ms)),
+        timed_out: false,
     };
 
     let mut headers_delivered = false;
+    let mut dns_retries = 0;
 
     loop {
         // Release GVL and wait for data
         unsafe {
             rb_thread_call_without_gvl(
                 wait_for_chunk_or_completion,
-                &wait_data as *const WaitData as *mut std::ffi::c_void,
+                &mut wait_data as *mut ChunkWaitData as *mut std::ffi::c_void,
                 std::ptr::null(),
                 std::ptr::null(),
             );
         }
 
+        if wait_data.timed_out {
+            unsafe { cancel_stalled_stream(&state, ctx_ptr) };
+            return Err(CrtError::from_code(AWS_IO_SOCKET_TIMEOUT));
+        }
+
         // GVL is re-acquired here — drain available chunks
-        let (status_code, resp_headers, chunks, complete, error_code) = {
+        let (status_code, resp_headers, chunks, complete, timing) = {
             let mut guard = state.0.lock().unwrap();
             let chunks: Vec<Vec<u8>> = guard.chunks.drain(..).collect();
             (
@@ -798,7 +1439,7 @@ where
                 guard.headers.clone(),
                 chunks,
                 guard.complete,
-                guard.error_code,
+                guard.timing.timing(),
             )
         };
 
@@ -813,7 +1454,23 @@ where
             on_chunk(chunk);
         }
 
+        // Reopen the read window by what the consumer just processed
+        if settings.read_backpressure && !chunks.is_empty() {
+            let consumed: usize = chunks.iter().map(Vec::len).sum();
+            let guard = state.0.lock().unwrap();
+            if !guard.stream.is_null() {
+                unsafe { aws_http_stream_update_window(guard.stream, consumed) };
+            }
+        }
+
         if complete {
+            if retry_dns_failure(cm, ctx_ptr, &state, &mut dns_retries) {
+                continue;
+            }
+            // Read after the retry check: one that found the pool closed
+            // replaces the error.
+            let error_code = state.0.lock().unwrap().error_code;
+
             // Clean up and return
             unsafe { cleanup_request_context(ctx_ptr) };
 
@@ -826,7 +1483,7 @@ where
                 on_headers(status_code, &resp_headers);
             }
 
-            return Ok(());
+            return Ok(timing);
         }
     }
 }
@@ -1,14 +1,22 @@
+use std::cell::{Cell, RefCell};
+
 use magnus::{
-    function, prelude::*, scan_args::scan_args, Error, RString, Ruby, TryConvert, Value,
+    exception::ExceptionClass, function, method, prelude::*, scan_args::scan_args, typed_data,
+    Error, Exception, RClass, RModule, RString, Ruby, Symbol, TryConvert, Value,
 };
 
+pub mod aws_chunked;
+mod base64;
 mod cbor;
 pub mod connection_manager;
 pub mod credentials;
 pub mod error;
+pub mod fair_queue;
 pub mod http;
+pub mod pending_response;
 pub mod pool;
 pub mod proxy;
+pub mod response;
 pub mod runtime;
 pub mod s3_client;
 pub mod s3_request;
@@ -16,9 +24,9 @@ pub mod s3_ruby;
 pub mod signing;
 pub mod tls;
 
-// FFI bindings to the AWS CRT checksum C functions.
+// FFI bindings to the AWS CRT checksum and hash C functions.
 // These are provided by the pre-built static libraries
-// (aws-checksums and aws-c-common).
+// (aws-checksums, aws-c-cal and aws-c-common).
 mod crt {
     /// Opaque allocator type from aws-c-common.
     #[repr(C)]
@@ -26,9 +34,43 @@ mod crt {
         _private: [u8; 0],
     }
 
+    /// Opaque `struct aws_hash` from aws-c-cal.
+    #[repr(C)]
+    pub struct AwsHash {
+        _private: [u8; 0],
+    }
+
+    /// Mirrors `struct aws_byte_cursor` from aws-c-common.
+    #[repr(C)]
+    pub struct AwsByteCursor {
+        pub len: usize,
+        pub ptr: *const u8,
+    }
+
+    /// Mirrors `struct aws_byte_buf` from aws-c-common. Used with a null
+    /// allocator over a fixed array, so the CRT never grows or frees it.
+    #[repr(C)]
+    pub struct AwsByteBuf {
+        pub len: usize,
+        pub buffer: *mut u8,
+        pub capacity: usize,
+        pub allocator: *mut AwsAllocator,
+    }
+
     extern "C" {
         pub fn aws_default_allocator() -> *mut AwsAllocator;
         pub fn aws_checksums_library_init(allocator: *mut AwsAllocator);
+        pub fn aws_cal_library_init(allocator: *mut AwsAllocator);
+
+        pub fn aws_sha1_new(allocator: *mut AwsAllocator) -> *mut AwsHash;
+        pub fn aws_sha256_new(allocator: *mut AwsAllocator) -> *mut AwsHash;
+        pub fn aws_hash_update(hash: *mut AwsHash, to_hash: *const AwsByteCursor) -> i32;
+        pub fn aws_hash_finalize(
+            hash: *mut AwsHash,
+            output: *mut AwsByteBuf,
+            truncate_to: usize,
+        ) -> i32;
+        pub fn aws_hash_destroy(hash: *mut AwsHash);
 
         pub fn aws_checksums_crc32_ex(
             input: *const u8,
@@ -47,14 +89,32 @@ mod crt {
             length: usize,
             previous_crc64: u64,
         ) -> u64;
+
+        pub fn aws_checksums_crc32_combine(crc1: u32, crc2: u32, len2: u64) -> u32;
+        pub fn aws_checksums_crc32c_combine(crc1: u32, crc2: u32, len2: u64) -> u32;
+        pub fn aws_checksums_crc64nvme_combine(crc1: u64, crc2: u64, len2: u64) -> u64;
+
+        pub fn aws_cpu_has_feature(feature_name: u32) -> bool;
     }
+
+    /// `enum aws_cpu_feature_name` values from aws-c-common.
+    pub const AWS_CPU_FEATURE_CLMUL: u32 = 0;
+    pub const AWS_CPU_FEATURE_SSE_4_1: u32 = 1;
+    pub const AWS_CPU_FEATURE_SSE_4_2: u32 = 2;
+    pub const AWS_CPU_FEATURE_AVX2: u32 = 3;
+    pub const AWS_CPU_FEATURE_ARM_CRC: u32 = 5;
+    pub const AWS_CPU_FEATURE_ARM_PMULL: u32 = 8;
+    pub const AWS_CPU_FEATURE_ARM_CRYPTO: u32 = 9;
 }
 
-/// Initialize the CRT checksums library. Must be called once before use.
+/// Initialize the CRT checksums and crypto (hash) libraries. Must be called
+/// once before use. Neither starts the runtime's event loops, so
+/// `AwsCrt.configure` still applies afterwards.
 fn init_crt() {
     unsafe {
         let allocator = crt::aws_default_allocator();
         crt::aws_checksums_library_init(allocator);
+        crt::aws_cal_library_init(allocator);
     }
 }
 
@@ -119,16 +179,630 @@ fn crc64nvme(args: &[Value]) -> Result<u64, Error> {
     }
 }
 
+/// Combine the CRC32 of two adjacent regions, `crc_a` over the first and
+/// `crc_b` over the second (`len_b` bytes), into the CRC32 of both — as if
+/// `crc32(b, crc32(a))` had been computed, without rereading either region.
+fn crc32_combine(crc_a: u32, crc_b: u32, len_b: u64) -> u32 {
+    unsafe { crt::aws_checksums_crc32_combine(crc_a, crc_b, len_b) }
+}
+
+/// `crc32_combine` for CRC32C.
+fn crc32c_combine(crc_a: u32, crc_b: u32, len_b: u64) -> u32 {
+    unsafe { crt::aws_checksums_crc32c_combine(crc_a, crc_b, len_b) }
+}
+
+/// `crc32_combine` for CRC64-NVME.
+fn crc64nvme_combine(crc_a: u64, crc_b: u64, len_b: u64) -> u64 {
+    unsafe { crt::aws_checksums_crc64nvme_combine(crc_a, crc_b, len_b) }
+}
+
+/// A running CRC behind an `AwsCrt::Checksums` CRC class, so Ruby can feed
+/// a large IO chunk by chunk without threading the previous value through
+/// `crc32(chunk, previous)` itself.
+trait CrcContext: Default + magnus::TypedData {
+    type Crc: Copy + Default + magnus::IntoValue;
+
+    fn crc(&self) -> &Cell<Self::Crc>;
+    /// The CRC of `data` continuing from `previous`.
+    fn compute(data: &[u8], previous: Self::Crc) -> Self::Crc;
+    fn to_be_bytes(crc: Self::Crc) -> Vec<u8>;
+}
+
+/// Ruby class `AwsCrt::Checksums::Crc32`.
+#[magnus::wrap(class = "AwsCrt::Checksums::Crc32", free_immediately, size)]
+#[derive(Default)]
+struct Crc32Context {
+    crc: Cell<u32>,
+}
+
+impl CrcContext for Crc32Context {
+    type Crc = u32;
+
+    fn crc(&self) -> &Cell<u32> {
+        &self.crc
+    }
+
+    fn compute(data: &[u8], previous: u32) -> u32 {
+        unsafe { crt::aws_checksums_crc32_ex(data.as_ptr(), data.len(), previous) }
+    }
+
+    fn to_be_bytes(crc: u32) -> Vec<u8> {
+        crc.to_be_bytes().to_vec()
+    }
+}
+
+/// Ruby class `AwsCrt::Checksums::Crc32c`.
+#[magnus::wrap(class = "AwsCrt::Checksums::Crc32c", free_immediately, size)]
+#[derive(Default)]
+struct Crc32cContext {
+    crc: Cell<u32>,
+}
+
+impl CrcContext for Crc32cContext {
+    type Crc = u32;
+
+    fn crc(&self) -> &Cell<u32> {
+        &self.crc
+    }
+
+    fn compute(data: &[u8], previous: u32) -> u32 {
+        unsafe { crt::aws_checksums_crc32c_ex(data.as_ptr(), data.len(), previous) }
+    }
+
+    fn to_be_bytes(crc: u32) -> Vec<u8> {
+        crc.to_be_bytes().to_vec()
+    }
+}
+
+/// Ruby class `AwsCrt::Checksums::Crc64Nvme`.
+#[magnus::wrap(class = "AwsCrt::Checksums::Crc64Nvme", free_immediately, size)]
+#[derive(Default)]
+struct Crc64NvmeContext {
+    crc: Cell<u64>,
+}
+
+impl CrcContext for Crc64NvmeContext {
+    type Crc = u64;
+
+    fn crc(&self) -> &Cell<u64> {
+        &self.crc
+    }
+
+    fn compute(data: &[u8], previous: u64) -> u64 {
+        unsafe { crt::aws_checksums_crc64nvme_ex(data.as_ptr(), data.len(), previous) }
+    }
+
+    fn to_be_bytes(crc: u64) -> Vec<u8> {
+        crc.to_be_bytes().to_vec()
+    }
+}
+
+/// Ruby: `crc.update(data)` — continue the CRC over `data` (any encoding,
+/// taken as bytes); returns the object.
+fn crc_update<T: CrcContext>(rb_self: typed_data::Obj<T>, data: RString) -> typed_data::Obj<T> {
+    let crc = rb_self.crc();
+    crc.set(T::compute(unsafe { data.as_slice() }, crc.get()));
+    rb_self
+}
+
+/// Ruby: `crc.value` — the CRC so far as an Integer (0 before any update).
+fn crc_value<T: CrcContext>(rb_self: &T) -> T::Crc {
+    rb_self.crc().get()
+}
+
+/// Ruby: `crc.digest` — the CRC so far as big-endian bytes in a binary
+/// String, the form S3 base64-encodes into `x-amz-checksum-*` headers.
+fn crc_digest<T: CrcContext>(ruby: &Ruby, rb_self: &T) -> RString {
+    ruby.str_from_slice(&T::to_be_bytes(rb_self.crc().get()))
+}
+
+/// Ruby: `Checksums.crc32_base64(data)` and friends — the CRC of `data` as
+/// base64 of its big-endian bytes (4 for the CRC32s, 8 for CRC64-NVME), the
+/// exact value of an `x-amz-checksum-*` header.
+fn crc_base64<T: CrcContext>(data: RString) -> String {
+    let crc = T::compute(unsafe { data.as_slice() }, T::Crc::default());
+    base64::encode(&T::to_be_bytes(crc), base64::Alphabet::Standard)
+}
+
+extern "C" {
+    fn rb_thread_call_without_gvl(
+        func: unsafe extern "C" fn(data: *mut std::ffi::c_void) -> *mut std::ffi::c_void,
+        data1: *mut std::ffi::c_void,
+        ubf: *const std::ffi::c_void,
+        ubf_data: *const std::ffi::c_void,
+    ) -> *mut std::ffi::c_void;
+}
+
+/// Chunk size for `crc_file` reads.
+const CRC_FILE_CHUNK: usize = 1024 * 1024;
+
+/// A `crc_file` read, run without the GVL.
+struct CrcFileJob<T: CrcContext> {
+    path: String,
+    result: Option<std::io::Result<T::Crc>>,
+}
+
+/// The CRC of the file at `path`, read in `CRC_FILE_CHUNK` pieces so memory
+/// stays flat however large the file is.
+fn read_file_crc<T: CrcContext>(path: &str) -> std::io::Result<T::Crc> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = vec![0u8; CRC_FILE_CHUNK];
+    let mut crc = T::Crc::default();
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => return Ok(crc),
+            Ok(n) => crc = T::compute(&buf[..n], crc),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+unsafe extern "C" fn crc_file_without_gvl<T: CrcContext>(
+    data: *mut std::ffi::c_void,
+) -> *mut std::ffi::c_void {
+    let job = &mut *(data as *mut CrcFileJob<T>);
+    job.result = Some(read_file_crc::<T>(&job.path));
+    std::ptr::null_mut()
+}
+
+/// Ruby: `Checksums.crc32_file(path)` and friends — the CRC of a file's
+/// contents, as `crc32(File.binread(path))` would return, without loading
+/// the file into a Ruby String. The GVL is released while the file is read,
+/// so other threads keep running. `path` may be a String or respond to
+/// `to_path` (a File or Pathname). Raises the matching `Errno` error if the
+/// file can't be opened or read.
+fn crc_file<T: CrcContext>(ruby: &Ruby, path: Value) -> Result<T::Crc, Error> {
+    let path: String = match RString::from_value(path) {
+        Some(s) => s.to_string()?,
+        None => path.funcall("to_path", ())?,
+    };
+    let mut job = CrcFileJob::<T> { path, result: None };
+    unsafe {
+        rb_thread_call_without_gvl(
+            crc_file_without_gvl::<T>,
+            &mut job as *mut CrcFileJob<T> as *mut std::ffi::c_void,
+            std::ptr::null(),
+            std::ptr::null(),
+        );
+    }
+    match job.result.expect("crc_file job ran") {
+        Ok(crc) => Ok(crc),
+        Err(e) => Err(io_error(ruby, &job.path, &e)),
+    }
+}
+
+/// A Ruby exception for a failed file operation on `path`: the `Errno`
+/// subclass for the OS error (e.g. `Errno::ENOENT`), else `IOError`.
+fn io_error(ruby: &Ruby, path: &str, e: &std::io::Error) -> Error {
+    let Some(errno) = e.raw_os_error() else {
+        return Error::new(ruby.exception_io_error(), format!("{}: {}", path, e));
+    };
+    // SystemCallError.new picks the Errno subclass for the error number.
+    let exception = ruby
+        .class_object()
+        .const_get::<_, RClass>("SystemCallError")
+        .and_then(|class| class.funcall::<_, _, Exception>("new", (path, errno)));
+    match exception {
+        Ok(exception) => exception.into(),
+        Err(err) => err,
+    }
+}
+
+/// Define a CRC class under `AwsCrt::Checksums`.
+fn define_crc_class<T: CrcContext>(
+    ruby: &Ruby,
+    checksums: &RModule,
+    name: &str,
+) -> Result<(), Error> {
+    let class = checksums.define_class(name, ruby.class_object())?;
+    class.define_alloc_func::<T>();
+    class.define_method("update", method!(crc_update::<T>, 1))?;
+    class.define_method("value", method!(crc_value::<T>, 0))?;
+    class.define_method("digest", method!(crc_digest::<T>, 0))?;
+    Ok(())
+}
+
+/// A hash algorithm provided by aws-c-cal (AWS-LC on Linux, CommonCrypto
+/// on macOS).
+#[derive(Clone, Copy)]
+enum HashAlgorithm {
+    Sha1,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sha1 => "SHA1",
+            Self::Sha256 => "SHA256",
+        }
+    }
+
+    /// Digest length in bytes.
+    fn digest_len(self) -> usize {
+        match self {
+            Self::Sha1 => 20,
+            Self::Sha256 => 32,
+        }
+    }
+}
+
+/// A running aws-c-cal hash. Destroyed on drop.
+struct CalHash(*mut crt::AwsHash);
+
+impl CalHash {
+    fn new(ruby: &Ruby, algorithm: HashAlgorithm) -> Result<Self, Error> {
+        let hash = unsafe {
+            let allocator = crt::aws_default_allocator();
+            match algorithm {
+                HashAlgorithm::Sha1 => crt::aws_sha1_new(allocator),
+                HashAlgorithm::Sha256 => crt::aws_sha256_new(allocator),
+            }
+        };
+        if hash.is_null() {
+            Err(hash_error(ruby, algorithm))
+        } else {
+            Ok(Self(hash))
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) -> bool {
+        let cursor = crt::AwsByteCursor {
+            len: data.len(),
+            ptr: data.as_ptr(),
+        };
+        unsafe { crt::aws_hash_update(self.0, &cursor) == 0 }
+    }
+
+    /// Finish the hash, writing the digest into `out`.
+    fn finalize(self, out: &mut [u8]) -> bool {
+        let mut output = crt::AwsByteBuf {
+            len: 0,
+            buffer: out.as_mut_ptr(),
+            capacity: out.len(),
+            allocator: std::ptr::null_mut(),
+        };
+        unsafe { crt::aws_hash_finalize(self.0, &mut output, 0) == 0 }
+    }
+}
+
+impl Drop for CalHash {
+    fn drop(&mut self) {
+        unsafe { crt::aws_hash_destroy(self.0) };
+    }
+}
+
+// The hash is only touched with the GVL held.
+unsafe impl Send for CalHash {}
+
+/// `AwsCrt::Error` for a hash the CRT failed to create or update.
+fn hash_error(ruby: &Ruby, algorithm: HashAlgorithm) -> Error {
+    match ruby
+        .class_object()
+        .const_get::<_, RModule>("AwsCrt")
+        .and_then(|m| m.const_get::<_, ExceptionClass>("Error"))
+    {
+        Ok(class) => Error::new(class, format!("{} computation failed", algorithm.name())),
+        Err(e) => e,
+    }
+}
+
+/// One-shot digest of `data`.
+fn digest_of(ruby: &Ruby, algorithm: HashAlgorithm, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut hash = CalHash::new(ruby, algorithm)?;
+    let mut digest = vec![0u8; algorithm.digest_len()];
+    if !hash.update(data) || !hash.finalize(&mut digest) {
+        return Err(hash_error(ruby, algorithm));
+    }
+    Ok(digest)
+}
+
+/// A streaming hash behind an `AwsCrt::Checksums` context class. The hash
+/// is created on the first update, so allocation failures raise from there
+/// rather than from `new`.
+#[derive(Default)]
+enum DigestState {
+    #[default]
+    Empty,
+    Running(CalHash),
+    Finished(Vec<u8>),
+}
+
+/// A Ruby context class for `Checksums.sha1` / `Checksums.sha256`.
+trait DigestContext: Default + magnus::TypedData {
+    const ALGORITHM: HashAlgorithm;
+    /// The Ruby class name, for error messages.
+    const CLASS: &'static str;
+
+    fn state(&self) -> &RefCell<DigestState>;
+
+    fn add(&self, ruby: &Ruby, data: &[u8]) -> Result<(), Error> {
+        let mut state = self.state().borrow_mut();
+        if let DigestState::Empty = *state {
+            *state = DigestState::Running(CalHash::new(ruby, Self::ALGORITHM)?);
+        }
+        match &mut *state {
+            DigestState::Running(hash) => {
+                if hash.update(data) {
+                    Ok(())
+                } else {
+                    Err(hash_error(ruby, Self::ALGORITHM))
+                }
+            }
+            _ => Err(Error::new(
+                ruby.exception_runtime_error(),
+                format!(
+                    "{} context already finished by #digest",
+                    Self::ALGORITHM.name()
+                ),
+            )),
+        }
+    }
+}
+
+/// Ruby class `AwsCrt::Checksums::Sha1`: a streaming SHA-1 context for
+/// `Checksums.sha1(data, context)`.
+#[magnus::wrap(class = "AwsCrt::Checksums::Sha1", free_immediately, size)]
+#[derive(Default)]
+struct Sha1Context {
+    state: RefCell<DigestState>,
+}
+
+impl DigestContext for Sha1Context {
+    const ALGORITHM: HashAlgorithm = HashAlgorithm::Sha1;
+    const CLASS: &'static str = "AwsCrt::Checksums::Sha1";
+
+    fn state(&self) -> &RefCell<DigestState> {
+        &self.state
+    }
+}
+
+/// Ruby class `AwsCrt::Checksums::Sha256`: a streaming SHA-256 context for
+/// `Checksums.sha256(data, context)`.
+#[magnus::wrap(class = "AwsCrt::Checksums::Sha256", free_immediately, size)]
+#[derive(Default)]
+struct Sha256Context {
+    state: RefCell<DigestState>,
+}
+
+impl DigestContext for Sha256Context {
+    const ALGORITHM: HashAlgorithm = HashAlgorithm::Sha256;
+    const CLASS: &'static str = "AwsCrt::Checksums::Sha256";
+
+    fn state(&self) -> &RefCell<DigestState> {
+        &self.state
+    }
+}
+
+/// Ruby: `context.update(data)` — add `data`; returns the context.
+fn context_update<T: DigestContext>(
+    ruby: &Ruby,
+    rb_self: typed_data::Obj<T>,
+    data: RString,
+) -> Result<typed_data::Obj<T>, Error> {
+    rb_self.add(ruby, unsafe { data.as_slice() })?;
+    Ok(rb_self)
+}
+
+/// Ruby: `context.digest` — the digest of everything added, as a binary
+/// String. Finishes the context; later calls return the same digest and
+/// `update` raises.
+fn context_digest<T: DigestContext>(ruby: &Ruby, rb_self: &T) -> Result<RString, Error> {
+    let mut state = rb_self.state().borrow_mut();
+    let digest = match std::mem::take(&mut *state) {
+        DigestState::Finished(digest) => digest,
+        DigestState::Empty => digest_of(ruby, T::ALGORITHM, &[])?,
+        DigestState::Running(hash) => {
+            let mut digest = vec![0u8; T::ALGORITHM.digest_len()];
+            if !hash.finalize(&mut digest) {
+                return Err(hash_error(ruby, T::ALGORITHM));
+            }
+            digest
+        }
+    };
+    let result = ruby.str_from_slice(&digest);
+    *state = DigestState::Finished(digest);
+    Ok(result)
+}
+
+/// `Checksums.sha1` / `Checksums.sha256`: without a context, the digest of
+/// `data` as a binary String. With a context of the matching class, adds
+/// `data` to it and returns the context, whose `#digest` covers every
+/// call. Anything else raises TypeError.
+fn hash_with_context<T: DigestContext>(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    let args = scan_args::<(RString,), (Option<Value>,), (), (), (), ()>(args)?;
+    let data = args.required.0;
+    match args.optional.0.filter(|v| !v.is_nil()) {
+        None => {
+            let digest = digest_of(ruby, T::ALGORITHM, unsafe { data.as_slice() })?;
+            Ok(ruby.str_from_slice(&digest).as_value())
+        }
+        Some(previous) => {
+            let context = typed_data::Obj::<T>::try_convert(previous).map_err(|_| {
+                Error::new(
+                    magnus::exception::type_error(),
+                    format!(
+                        "expected an {} context, got {}",
+                        T::CLASS,
+                        previous.class().inspect()
+                    ),
+                )
+            })?;
+            context.add(ruby, unsafe { data.as_slice() })?;
+            Ok(context.as_value())
+        }
+    }
+}
+
+/// Compute a SHA-1 digest (20 bytes), e.g. for comparing legacy ETags.
+fn sha1(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    hash_with_context::<Sha1Context>(ruby, args)
+}
+
+/// Compute a SHA-256 digest (32 bytes).
+fn sha256(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    hash_with_context::<Sha256Context>(ruby, args)
+}
+
+/// Algorithm names `Checksums.compute` and `Checksums.hardware_accelerated?`
+/// accept, for their error message.
+const COMPUTE_ALGORITHMS: &str = "crc32, crc32c, crc64nvme, sha1, sha256";
+
+/// `Checksums.compute(algorithm, data, previous = nil)`: dispatch to the
+/// function named by `algorithm`, a Symbol or String in any case (so a
+/// configured `"CRC32C"` works as is). `previous` means what it does for
+/// that function: a running CRC, or a SHA context.
+fn compute(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    let parsed = scan_args::<(Value, RString), (Option<Value>,), (), (), (), ()>(args)?;
+    let name = algorithm_name(parsed.required.0)?;
+    let rest = &args[1..];
+    match name.as_str() {
+        "crc32" => Ok(ruby.into_value(crc32(rest)?)),
+        "crc32c" => Ok(ruby.into_value(crc32c(rest)?)),
+        "crc64nvme" => Ok(ruby.into_value(crc64nvme(rest)?)),
+        "sha1" => sha1(ruby, rest),
+        "sha256" => sha256(ruby, rest),
+        _ => Err(unknown_algorithm(&name)),
+    }
+}
+
+/// An algorithm name given as a Symbol or String, lowercased.
+fn algorithm_name(algorithm: Value) -> Result<String, Error> {
+    let name: String = match Symbol::from_value(algorithm) {
+        Some(sym) => sym.name()?.into_owned(),
+        None => TryConvert::try_convert(algorithm)?,
+    };
+    Ok(name.to_ascii_lowercase())
+}
+
+fn unknown_algorithm(name: &str) -> Error {
+    Error::new(
+        magnus::exception::arg_error(),
+        format!(
+            "unknown checksum algorithm {:?}: expected one of {}",
+            name, COMPUTE_ALGORITHMS
+        ),
+    )
+}
+
+/// `Checksums.hardware_accelerated?(algorithm)`: whether aws-checksums
+/// picked a CPU-accelerated implementation of `algorithm` on this machine,
+/// using the same feature checks it makes at runtime. False on other
+/// architectures, and for SHA-1/SHA-256, which the CRT hands to the
+/// platform crypto library without knowing how that library computes them.
+fn hardware_accelerated(algorithm: Value) -> Result<bool, Error> {
+    let name = algorithm_name(algorithm)?;
+    let has = |feature| unsafe { crt::aws_cpu_has_feature(feature) };
+    let x86 = cfg!(target_arch = "x86_64");
+    let arm = cfg!(target_arch = "aarch64");
+    match name.as_str() {
+        "crc32" => {
+            let clmul = has(crt::AWS_CPU_FEATURE_SSE_4_1) && has(crt::AWS_CPU_FEATURE_CLMUL);
+            Ok((x86 && clmul) || (arm && has(crt::AWS_CPU_FEATURE_ARM_CRC)))
+        }
+        "crc32c" => Ok((x86 && has(crt::AWS_CPU_FEATURE_SSE_4_2))
+            || (arm && has(crt::AWS_CPU_FEATURE_ARM_CRC))),
+        "crc64nvme" => {
+            let clmul = has(crt::AWS_CPU_FEATURE_CLMUL) && has(crt::AWS_CPU_FEATURE_AVX2);
+            let pmull = has(crt::AWS_CPU_FEATURE_ARM_CRYPTO) && has(crt::AWS_CPU_FEATURE_ARM_PMULL);
+            Ok((x86 && clmul) || (arm && pmull))
+        }
+        "sha1" | "sha256" => Ok(false),
+        _ => Err(unknown_algorithm(&name)),
+    }
+}
+
+/// Apply process-wide CRT runtime settings (`AwsCrt.configure`).
+///
+/// Raises `AwsCrt::Error` if the runtime has already been initialized by a
+/// connection pool or S3 client, since the settings could no longer apply.
+fn configure_runtime(
+    ruby: &Ruby,
+    dns_ttl_secs: Option<usize>,
+    dns_failure_retries: Option<u32>,
+    dns_retry_backoff_ms: Option<u64>,
+) -> Result<(), Error> {
+    // Keywords left nil keep whatever an earlier call set.
+    let applied = runtime::CrtRuntime::configure(|config| {
+        if dns_ttl_secs.is_some() {
+            config.dns_ttl_secs = dns_ttl_secs;
+        }
+        if let Some(retries) = dns_failure_retries {
+            config.dns_failure_retries = retries;
+        }
+        if let Some(backoff_ms) = dns_retry_backoff_ms {
+            config.dns_retry_backoff_ms = backoff_ms;
+        }
+    });
+    if applied {
+        return Ok(());
+    }
+
+    let base_error: ExceptionClass = ruby
+        .class_object()
+        .const_get::<_, RModule>("AwsCrt")?
+        .const_get("Error")?;
+    Err(Error::new(
+        base_error,
+        "AwsCrt.configure must be called before the first connection pool or S3 client is created",
+    ))
+}
+
+/// The platform TLS implementation in use (`AwsCrt.tls_backend`):
+/// `:securetransport`, `:schannel`, or `:s2n`.
+fn tls_backend() -> Symbol {
+    Symbol::new(tls::backend_name())
+}
+
 #[magnus::init]
 fn init(ruby: &Ruby) -> Result<(), Error> {
     init_crt();
 
     let module = ruby.define_module("AwsCrt")?;
+    module.define_module_function("_native_configure", function!(configure_runtime, 3))?;
+    module.define_module_function("tls_backend", function!(tls_backend, 0))?;
+
     let checksums = module.define_module("Checksums")?;
 
     checksums.define_module_function("crc32", function!(crc32, -1))?;
     checksums.define_module_function("crc32c", function!(crc32c, -1))?;
     checksums.define_module_function("crc64nvme", function!(crc64nvme, -1))?;
+    checksums.define_module_function("crc32_combine", function!(crc32_combine, 3))?;
+    checksums.define_module_function("crc32c_combine", function!(crc32c_combine, 3))?;
+    checksums.define_module_function("crc64nvme_combine", function!(crc64nvme_combine, 3))?;
+    checksums.define_module_function("crc32_base64", function!(crc_base64::<Crc32Context>, 1))?;
+    checksums.define_module_function("crc32c_base64", function!(crc_base64::<Crc32cContext>, 1))?;
+    checksums.define_module_function(
+        "crc64nvme_base64",
+        function!(crc_base64::<Crc64NvmeContext>, 1),
+    )?;
+
+    checksums.define_module_function("crc32_file", function!(crc_file::<Crc32Context>, 1))?;
+    checksums.define_module_function("crc32c_file", function!(crc_file::<Crc32cContext>, 1))?;
+    checksums.define_module_function("crc64nvme_file", function!(crc_file::<Crc64NvmeContext>, 1))?;
+
+    define_crc_class::<Crc32Context>(ruby, &checksums, "Crc32")?;
+    define_crc_class::<Crc32cContext>(ruby, &checksums, "Crc32c")?;
+    define_crc_class::<Crc64NvmeContext>(ruby, &checksums, "Crc64Nvme")?;
+
+    checksums.define_module_function("sha1", function!(sha1, -1))?;
+    checksums.define_module_function("sha256", function!(sha256, -1))?;
+    checksums.define_module_function("compute", function!(compute, -1))?;
+    checksums
+        .define_module_function("hardware_accelerated?", function!(hardware_accelerated, 1))?;
+
+    let sha1_class = checksums.define_class("Sha1", ruby.class_object())?;
+    sha1_class.define_alloc_func::<Sha1Context>();
+    sha1_class.define_method("update", method!(context_update::<Sha1Context>, 1))?;
+    sha1_class.define_method("digest", method!(context_digest::<Sha1Context>, 0))?;
+
+    let sha256_class = checksums.define_class("Sha256", ruby.class_object())?;
+    sha256_class.define_alloc_func::<Sha256Context>();
+    sha256_class.define_method("update", method!(context_update::<Sha256Context>, 1))?;
+    sha256_class.define_method("digest", method!(context_digest::<Sha256Context>, 0))?;
 
     cbor::init(ruby, &module)?;
 
@@ -136,6 +810,8 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     let http = module.define_module("Http")?;
     error::define_http_errors(ruby, &http)?;
     pool::define_connection_pool(ruby, &http)?;
+    response::define_response(ruby, &http)?;
+    pending_response::define_pending_response(ruby, &http)?;
 
     // S3 module
     let s3 = module.define_module("S3")?;
@@ -0,0 +1,156 @@
+//! Ruby-facing `AwsCrt::Http::PendingResponse` class.
+//!
+//! Returned by `ConnectionPool#request_async`. The request runs on the CRT
+//! event loop while the calling thread carries on; `#done?` polls without
+//! blocking and `#value` waits (without the GVL) for the response.
+//!
+//! The outcome is kept, so `#value` returns the same `Response` — or raises
+//! the same error — every time it is called.
+
+use std::cell::RefCell;
+
+use magnus::gc::Marker;
+use magnus::prelude::*;
+use magnus::rb_sys::{AsRawValue, FromRawValue};
+use magnus::typed_data;
+use magnus::{method, DataTypeFunctions, Error, Ruby, Value};
+use rb_sys::VALUE;
+
+use crate::error::CrtError;
+use crate::http::{HttpResponse, PendingRequest};
+use crate::pool::{self, ChecksumCheck};
+use crate::response::Response;
+
+/// Where a pending request has got to.
+enum Outcome {
+    /// Sent; the response may not have arrived yet.
+    Running(PendingRequest),
+    /// Received but not yet turned into a `Response` — only left here when
+    /// checksum verification, decompressing or `decode: :cbor` failed, so
+    /// each `#value` raises the same error.
+    Received(HttpResponse),
+    /// The request failed with this CRT error code.
+    Failed(i32),
+    /// The `AwsCrt::Http::Response`.
+    Done(VALUE),
+}
+
+/// Ruby class `AwsCrt::Http::PendingResponse`.
+#[magnus::wrap(class = "AwsCrt::Http::PendingResponse", mark, free_immediately, size)]
+pub struct PendingResponse {
+    outcome: RefCell<Outcome>,
+    /// Decode the body as CBOR (`decode: :cbor`).
+    decode_cbor: bool,
+    /// Undo a gzip or deflate Content-Encoding (`decompress: true`).
+    decompress: bool,
+    /// Check the body's checksum (`verify_checksum:`).
+    verify_checksum: Option<ChecksumCheck>,
+    /// The `ConnectionPool`, kept alive while the request may still need it.
+    pool: VALUE,
+}
+
+impl DataTypeFunctions for PendingResponse {
+    fn mark(&self, marker: &Marker) {
+        unsafe { marker.mark(Value::from_raw(self.pool)) };
+        if let Ok(outcome) = self.outcome.try_borrow() {
+            if let Outcome::Done(response) = *outcome {
+                unsafe { marker.mark(Value::from_raw(response)) };
+            }
+        }
+    }
+}
+
+impl PendingResponse {
+    pub fn new(
+        request: PendingRequest,
+        decode_cbor: bool,
+        decompress: bool,
+        verify_checksum: Option<ChecksumCheck>,
+        pool: Value,
+    ) -> Self {
+        Self {
+            outcome: RefCell::new(Outcome::Running(request)),
+            decode_cbor,
+            decompress,
+            verify_checksum,
+            pool: pool.as_raw(),
+        }
+    }
+
+    fn rb_done_p(&self) -> bool {
+        match &*self.outcome.borrow() {
+            Outcome::Running(request) => request.is_complete(),
+            _ => true,
+        }
+    }
+
+    /// Wait for the response and return it, raising if the request failed.
+    fn rb_value(ruby: &Ruby, rb_self: typed_data::Obj<Self>) -> Result<Value, Error> {
+        // Wait without holding the borrow: another Ruby thread may call
+        // #value or #done? while this one is blocked.
+        let waiter = match &*rb_self.outcome.borrow() {
+            Outcome::Running(request) if !request.is_complete() => Some(request.waiter()),
+            _ => None,
+        };
+        if let Some(waiter) = waiter {
+            waiter.wait();
+        }
+
+        let mut outcome = rb_self.outcome.borrow_mut();
+        *outcome = match std::mem::replace(&mut *outcome, Outcome::Failed(0)) {
+            Outcome::Running(request) => match request.finish() {
+                Ok(response) => Outcome::Received(response),
+                Err(e) => Outcome::Failed(e.code()),
+            },
+            settled => settled,
+        };
+
+        match &mut *outcome {
+            Outcome::Running(_) => unreachable!(),
+            Outcome::Done(response) => Ok(unsafe { Value::from_raw(*response) }),
+            Outcome::Failed(code) => Err(CrtError::from_code(*code).into()),
+            Outcome::Received(response) => {
+                if let Some(check) = &rb_self.verify_checksum {
+                    check.verify_body(response)?;
+                }
+                if rb_self.decompress {
+                    pool::decompress_body(ruby, response)?;
+                }
+                let built = if rb_self.decode_cbor {
+                    let data = pool::decode_cbor_body(ruby, response)?;
+                    Response::decoded(ruby, response.status_code, response.headers.clone(), data)
+                } else {
+                    Response::new(
+                        ruby,
+                        response.status_code,
+                        response.headers.clone(),
+                        Some(&response.body),
+                    )
+                };
+                let built = typed_data::Obj::wrap(built.with_timing(response.timing)).as_value();
+                *outcome = Outcome::Done(built.as_raw());
+                Ok(built)
+            }
+        }
+    }
+
+    fn rb_inspect(&self) -> String {
+        let state = match &*self.outcome.borrow() {
+            Outcome::Running(request) if !request.is_complete() => "pending",
+            Outcome::Failed(_) => "failed",
+            _ => "done",
+        };
+        format!("#<AwsCrt::Http::PendingResponse {}>", state)
+    }
+}
+
+/// Register the `AwsCrt::Http::PendingResponse` class with magnus.
+pub fn define_pending_response(ruby: &Ruby, http_module: &magnus::RModule) -> Result<(), Error> {
+    let class = http_module.define_class("PendingResponse", ruby.class_object())?;
+    class.undef_default_alloc_func();
+    class.define_method("done?", method!(PendingResponse::rb_done_p, 0))?;
+    class.define_method("value", method!(PendingResponse::rb_value, 0))?;
+    class.define_method("inspect", method!(PendingResponse::rb_inspect, 0))?;
+
+    Ok(())
+}
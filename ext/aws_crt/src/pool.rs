@@ -1,21 +1,32 @@
 //! Ruby-facing `AwsCrt::Http::ConnectionPool` class.
 //!
 //! Wraps the Rust `ConnectionManager` and `http::make_request` /
-//! `http::make_streaming_request` functions, exposing them to Ruby via magnus.
+//! `http::make_streaming_request` / `http::start_request` functions, exposing
+//! them to Ruby via magnus.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
 
 use magnus::prelude::*;
 use magnus::rb_sys::AsRawValue;
 use magnus::scan_args::scan_args;
 use magnus::typed_data;
-use magnus::{method, Error, RArray, RHash, RString, Ruby, Symbol, Value};
+use magnus::{method, Error, RArray, RClass, RHash, RModule, RString, Ruby, Symbol, Value};
 use rb_sys::VALUE;
 
-use crate::connection_manager::{ConnectionManager, ConnectionManagerOptions};
-use crate::http;
+use crate::aws_chunked::{self, RunningChecksum, TrailerChecksum};
+use crate::cbor;
+use crate::error;
+use crate::connection_manager::{
+    ConnectionManager, ConnectionManagerOptions, SocketDomain, TcpKeepAlive,
+};
+use crate::fair_queue::FairQueue;
+use crate::http::{self, RequestSettings};
+use crate::pending_response::PendingResponse;
 use crate::proxy::{ProxyAuthType, ProxyOptions};
-use crate::tls::TlsOptions;
+use crate::response::Response;
+use crate::tls::{self, TlsCipherPreference, TlsOptions};
 
 /// Ruby class `AwsCrt::Http::ConnectionPool`.
 ///
@@ -25,18 +36,111 @@ use crate::tls::TlsOptions;
 #[magnus::wrap(class = "AwsCrt::Http::ConnectionPool", free_immediately, size)]
 pub struct ConnectionPool {
     inner: RefCell<Option<ConnectionManager>>,
-    read_timeout_ms: RefCell<u64>,
+    settings: RefCell<RequestSettings>,
+    /// FIFO admission gate, with `fair_queue: true`.
+    queue: RefCell<Option<FairQueue>>,
+    /// Where the pool connects, as parsed from the endpoint argument.
+    endpoint: RefCell<Option<Endpoint>>,
 }
 
 impl Default for ConnectionPool {
     fn default() -> Self {
         Self {
             inner: RefCell::new(None),
-            read_timeout_ms: RefCell::new(0),
+            settings: RefCell::new(RequestSettings::default()),
+            queue: RefCell::new(None),
+            endpoint: RefCell::new(None),
         }
     }
 }
 
+/// A pool's scheme, host and port. The port is the scheme's default when
+/// the endpoint didn't give one.
+#[derive(Clone, Debug)]
+struct Endpoint {
+    scheme: String,
+    host: String,
+    port: u32,
+}
+
+/// Default initial read window when read backpressure is enabled.
+const DEFAULT_INITIAL_READ_WINDOW: usize = 64 * 1024;
+
+/// A request's arguments, copied out of Ruby before the GVL is released.
+struct PreparedRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Option<Vec<u8>>,
+    settings: RequestSettings,
+    decode_cbor: bool,
+    decompress: bool,
+    verify_checksum: Option<ChecksumCheck>,
+    opts: Option<RHash>,
+}
+
+/// The `:verify_checksum` request option: check a successful response's
+/// body against `expected`, or, without it, against the response's own
+/// `x-amz-checksum-*` header.
+#[derive(Clone, Debug)]
+pub struct ChecksumCheck {
+    algorithm: TrailerChecksum,
+    expected: Option<String>,
+}
+
+impl ChecksumCheck {
+    /// The checksum a response must match, if it can be verified: only 2xx
+    /// responses are, and a header value is skipped when it is a composite
+    /// (`<checksum>-<parts>`) multipart checksum.
+    fn expected(&self, status: i32, headers: &[(String, String)]) -> Option<String> {
+        if !(200..300).contains(&status) {
+            return None;
+        }
+        if let Some(expected) = &self.expected {
+            return Some(expected.clone());
+        }
+        headers
+            .iter()
+            .find(|(name, value)| {
+                name.eq_ignore_ascii_case(self.algorithm.header_name()) && !value.contains('-')
+            })
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Compare the checksum of a body streamed through `computed`, raising
+    /// `AwsCrt::Http::ChecksumMismatchError` if it differs.
+    fn verify(
+        &self,
+        status: i32,
+        headers: &[(String, String)],
+        computed: &RunningChecksum,
+    ) -> Result<(), Error> {
+        let Some(expected) = self.expected(status, headers) else {
+            return Ok(());
+        };
+        let computed = computed.finish_base64();
+        if computed == expected {
+            Ok(())
+        } else {
+            Err(error::checksum_mismatch_error(
+                self.algorithm.name(),
+                &expected,
+                &computed,
+            ))
+        }
+    }
+
+    /// `verify` for a buffered response.
+    pub(crate) fn verify_body(&self, response: &http::HttpResponse) -> Result<(), Error> {
+        let mut computed = RunningChecksum::new(self.algorithm);
+        computed.update(&response.body);
+        self.verify(response.status_code, &response.headers, &computed)
+    }
+}
+
+/// Codings advertised by `decompress: true`, and the ones it can undo.
+const ACCEPT_ENCODING: &str = "gzip, deflate";
+
 /// Extract a String option from a Ruby Hash by symbol key.
 fn hash_get_string(hash: &RHash, key: &str) -> Result<Option<String>, Error> {
     let sym = Symbol::new(key);
@@ -93,20 +197,153 @@ fn hash_get_usize(hash: &RHash, key: &str, default: usize) -> Result<usize, Erro
     }
 }
 
-/// Extract a bool option from a Ruby Hash by symbol key.
+/// Extract a boolean option from a Ruby Hash by symbol key (Ruby truthiness).
 fn hash_get_bool(hash: &RHash, key: &str, default: bool) -> Result<bool, Error> {
     let sym = Symbol::new(key);
     let val: Option<Value> = hash.lookup(sym)?;
     match val {
         Some(v) if v.is_nil() => Ok(default),
-        Some(v) => {
-            let b: bool = magnus::TryConvert::try_convert(v)?;
-            Ok(b)
-        }
+        Some(v) => Ok(v.to_bool()),
         None => Ok(default),
     }
 }
 
+/// Parse `:ssl_verify_peer`, accepting only `true`, `false`, or nil/absent
+/// (which means `true`).
+///
+/// Unlike the other boolean options this does not fall back to Ruby
+/// truthiness, so disabling verification always requires an explicit
+/// `false` rather than e.g. `0` or `"false"` slipping through.
+fn parse_ssl_verify_peer(opts: &RHash) -> Result<bool, Error> {
+    let sym = Symbol::new("ssl_verify_peer");
+    let val: Option<Value> = opts.lookup(sym)?;
+    match val {
+        None => Ok(true),
+        Some(v) if v.is_nil() => Ok(true),
+        Some(v) if v.as_raw() == rb_sys::Qtrue as VALUE => Ok(true),
+        Some(v) if v.as_raw() == rb_sys::Qfalse as VALUE => Ok(false),
+        Some(_) => Err(Error::new(
+            magnus::exception::type_error(),
+            ":ssl_verify_peer must be true or false",
+        )),
+    }
+}
+
+/// Parse `:socket_domain` (`:ipv4` or `:ipv6`); nil or absent leaves the
+/// choice to the CRT.
+fn parse_socket_domain(opts: &RHash) -> Result<Option<SocketDomain>, Error> {
+    let val: Option<Value> = opts.lookup(Symbol::new("socket_domain"))?;
+    let name = match val {
+        Some(v) if !v.is_nil() => match Symbol::from_value(v) {
+            Some(sym) => sym.name()?.into_owned(),
+            None => String::new(),
+        },
+        _ => return Ok(None),
+    };
+    match name.as_str() {
+        "ipv4" => Ok(Some(SocketDomain::Ipv4)),
+        "ipv6" => Ok(Some(SocketDomain::Ipv6)),
+        _ => Err(Error::new(
+            magnus::exception::arg_error(),
+            ":socket_domain must be :ipv4 or :ipv6",
+        )),
+    }
+}
+
+/// Parse `:tcp_keepalive`: `true` for the OS's probe timings, or a Hash of
+/// `:idle_sec`, `:interval_sec` and `:max_failed_probes` (the first two
+/// only together). nil, false or absent leaves keep-alive off.
+fn parse_tcp_keepalive(opts: &RHash) -> Result<Option<TcpKeepAlive>, Error> {
+    let val: Option<Value> = opts.lookup(Symbol::new("tcp_keepalive"))?;
+    let v = match val {
+        Some(v) if v.to_bool() => v,
+        _ => return Ok(None),
+    };
+    if v.as_raw() == rb_sys::Qtrue as VALUE {
+        return Ok(Some(TcpKeepAlive::default()));
+    }
+    let hash = RHash::from_value(v).ok_or_else(|| {
+        Error::new(
+            magnus::exception::type_error(),
+            ":tcp_keepalive must be true or a Hash with :idle_sec, :interval_sec, :max_failed_probes",
+        )
+    })?;
+    let get = |key: &str| -> Result<u16, Error> {
+        Ok(hash
+            .lookup::<_, Option<u16>>(Symbol::new(key))?
+            .unwrap_or(0))
+    };
+    let keepalive = TcpKeepAlive {
+        idle_sec: get("idle_sec")?,
+        interval_sec: get("interval_sec")?,
+        max_failed_probes: get("max_failed_probes")?,
+    };
+    if (keepalive.idle_sec == 0) != (keepalive.interval_sec == 0) {
+        return Err(Error::new(
+            magnus::exception::arg_error(),
+            ":tcp_keepalive needs both :idle_sec and :interval_sec, or neither",
+        ));
+    }
+    Ok(Some(keepalive))
+}
+
+/// Parse `:tls_cipher_preference`, rejecting policies the TLS backend this
+/// build uses doesn't implement (everything but `:system_default` outside
+/// s2n-tls).
+fn parse_tls_cipher_preference(opts: &RHash) -> Result<Option<TlsCipherPreference>, Error> {
+    let val: Option<Value> = opts.lookup(Symbol::new("tls_cipher_preference"))?;
+    let name = match val {
+        Some(v) if !v.is_nil() => match Symbol::from_value(v) {
+            Some(sym) => sym.name()?.into_owned(),
+            None => String::new(),
+        },
+        _ => return Ok(None),
+    };
+    let pref = TlsCipherPreference::from_name(&name).ok_or_else(|| {
+        Error::new(
+            magnus::exception::arg_error(),
+            ":tls_cipher_preference must be :system_default, :pq_tls_v1, :pq_tls_v1_2 or :pq_default",
+        )
+    })?;
+    if !pref.is_supported() {
+        return Err(Error::new(
+            magnus::exception::arg_error(),
+            format!(
+                "TLS cipher preference :{} is not supported by the {} TLS backend",
+                name,
+                tls::backend_name()
+            ),
+        ));
+    }
+    Ok(Some(pref))
+}
+
+/// Endpoints we have already warned about for disabled TLS verification.
+static INSECURE_TLS_WARNED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+/// Log a warning that TLS peer verification is disabled for `endpoint`.
+///
+/// Warns at most once per endpoint per process, so a pool manager that
+/// recreates pools does not flood the log. Uses `logger.warn` when a
+/// `:logger` is configured, otherwise `Kernel#warn`.
+fn warn_insecure_tls(ruby: &Ruby, logger: Option<Value>, endpoint: &str) -> Result<(), Error> {
+    let warned = INSECURE_TLS_WARNED.get_or_init(|| Mutex::new(HashSet::new()));
+    if !warned.lock().unwrap().insert(endpoint.to_string()) {
+        return Ok(());
+    }
+
+    let message = format!(
+        "[AwsCrt::Http] TLS peer verification is disabled for {} \
+         (ssl_verify_peer: false); connections are vulnerable to interception",
+        endpoint
+    );
+    match logger {
+        Some(logger) => logger.funcall::<_, _, Value>("warn", (message,))?,
+        None => ruby.module_kernel().funcall::<_, _, Value>("warn", (message,))?,
+    };
+    Ok(())
+}
+
 impl ConnectionPool {
     /// Ruby: `ConnectionPool.new(endpoint, options = {})`
     ///
@@ -115,11 +352,42 @@ impl ConnectionPool {
     ///   :max_connections      - Integer (default 25)
     ///   :max_connection_idle_ms - Integer (default 60_000)
     ///   :connect_timeout_ms   - Integer (default 60_000)
+    ///   :socket_domain        - :ipv4 or :ipv6 (default nil, CRT chooses)
+    ///   :tcp_keepalive        - true, or Hash with :idle_sec, :interval_sec,
+    ///                           :max_failed_probes (default nil, off)
     ///   :read_timeout_ms      - Integer (default 0, meaning no timeout)
-    ///   :ssl_verify_peer      - Boolean (default true)
+    ///   :inactivity_timeout_ms - Integer (default 0, meaning no timeout);
+    ///                           longest gap between body chunks of a
+    ///                           streamed response before TimeoutError
+    ///   :ssl_verify_peer      - true or false (default true); false logs a warning
     ///   :ssl_ca_bundle        - String path (default nil)
+    ///   :ssl_ca_path          - String directory of hashed CA certificates
+    ///                           (default nil); may be combined with
+    ///                           :ssl_ca_bundle
     ///   :proxy                - Hash with :host, :port, :username, :password (default nil)
-    fn rb_initialize(rb_self: &Self, args: &[Value]) -> Result<(), Error> {
+    ///   :enable_read_backpressure - Boolean (default false); pace the server
+    ///                           to the rate the response body is consumed
+    ///   :initial_read_window  - Integer bytes (default 64 KiB); per-stream
+    ///                           window when read backpressure is enabled
+    ///   :max_prealloc_bytes   - Integer bytes (default 4 MiB); cap on the
+    ///                           buffered body reserved from Content-Length
+    ///   :trust_content_length - Boolean (default true); false never
+    ///                           reserves from Content-Length at all
+    ///   :max_retries          - Integer (default 0); times to resend an
+    ///                           idempotent request after a connection error,
+    ///                           or a 429/503 response with `Retry-After`
+    ///   :max_retry_after_ms   - Integer (default 20,000); longest wait
+    ///                           honoured from a `Retry-After` header
+    ///   :tls_cipher_preference - Symbol (default nil); cipher suite policy,
+    ///                           s2n-tls (Linux) only — see tls.rs
+    ///   :fair_queue           - Boolean (default false); admit requests in
+    ///                           arrival order, at most max_connections at
+    ///                           once — see fair_queue.rs
+    ///   :max_queue            - Integer (default nil, unbounded); with
+    ///                           :fair_queue, requests allowed to wait before
+    ///                           further ones raise QueueFullError
+    ///   :logger               - Logger for the insecure-TLS warning (default Kernel#warn)
+    fn rb_initialize(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<(), Error> {
         let args = scan_args::<(String,), (Option<RHash>,), (), (), (), ()>(args)?;
         let endpoint = args.required.0;
         let options = args.optional.0;
@@ -138,17 +406,53 @@ impl ConnectionPool {
             hash_get_u32(&opts, "connect_timeout_ms", 60_000)?;
         let read_timeout_ms =
             hash_get_u64(&opts, "read_timeout_ms", 0)?;
-        let ssl_verify_peer =
-            hash_get_bool(&opts, "ssl_verify_peer", true)?;
+        let inactivity_timeout_ms = hash_get_u64(&opts, "inactivity_timeout_ms", 0)?;
+        let socket_domain = parse_socket_domain(&opts)?;
+        let tcp_keepalive = parse_tcp_keepalive(&opts)?;
+        let ssl_verify_peer = parse_ssl_verify_peer(&opts)?;
         let ssl_ca_bundle =
             hash_get_string(&opts, "ssl_ca_bundle")?;
+        let ssl_ca_path = hash_get_string(&opts, "ssl_ca_path")?;
+        let tls_cipher_preference = parse_tls_cipher_preference(&opts)?;
+        let read_backpressure = hash_get_bool(&opts, "enable_read_backpressure", false)?;
+        let initial_read_window =
+            hash_get_usize(&opts, "initial_read_window", DEFAULT_INITIAL_READ_WINDOW)?;
+        if read_backpressure && initial_read_window == 0 {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                ":initial_read_window must be positive",
+            ));
+        }
+        let max_prealloc_bytes =
+            hash_get_usize(&opts, "max_prealloc_bytes", http::DEFAULT_MAX_PREALLOC_BYTES)?;
+        let trust_content_length = hash_get_bool(&opts, "trust_content_length", true)?;
+        let max_retries = hash_get_u32(&opts, "max_retries", 0)?;
+        let max_retry_after_ms =
+            hash_get_u64(&opts, "max_retry_after_ms", http::DEFAULT_MAX_RETRY_AFTER_MS)?;
+        let fair_queue = hash_get_bool(&opts, "fair_queue", false)?;
+        let max_queue: Option<usize> = opts.lookup(Symbol::new("max_queue"))?;
+        if max_queue.is_some() && !fair_queue {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                ":max_queue requires fair_queue: true",
+            ));
+        }
+        let logger: Option<Value> = opts
+            .lookup::<_, Option<Value>>(Symbol::new("logger"))?
+            .filter(|v| !v.is_nil());
+
+        if use_tls && !ssl_verify_peer {
+            warn_insecure_tls(ruby, logger, &format!("{}:{}", host, port))?;
+        }
 
         // TLS options (only for HTTPS)
         let tls_options = if use_tls {
             Some(TlsOptions {
                 verify_peer: ssl_verify_peer,
                 ca_filepath: ssl_ca_bundle,
+                ca_path: ssl_ca_path,
                 alpn_list: None,
+                cipher_preference: tls_cipher_preference,
             })
         } else {
             None
@@ -157,48 +461,71 @@ impl ConnectionPool {
         // Proxy options
         let proxy_options = parse_proxy_options(&opts)?;
 
+        let endpoint = Endpoint {
+            scheme,
+            host: host.clone(),
+            port,
+        };
+
         let cm_opts = ConnectionManagerOptions {
             host,
             port,
             max_connections,
             max_connection_idle_ms,
             connect_timeout_ms,
+            socket_domain,
+            tcp_keepalive,
             tls_options,
             proxy_options,
+            initial_read_window: read_backpressure.then_some(initial_read_window),
         };
 
         let cm = ConnectionManager::new(&cm_opts)
             .map_err(|e| -> Error { e.into() })?;
 
         *rb_self.inner.borrow_mut() = Some(cm);
-        *rb_self.read_timeout_ms.borrow_mut() = read_timeout_ms;
+        *rb_self.endpoint.borrow_mut() = Some(endpoint);
+        *rb_self.queue.borrow_mut() =
+            fair_queue.then(|| FairQueue::new(max_connections, max_queue));
+        *rb_self.settings.borrow_mut() = RequestSettings {
+            read_timeout_ms,
+            read_backpressure,
+            max_prealloc_bytes,
+            trust_content_length,
+            max_retries,
+            max_retry_after_ms,
+            inactivity_timeout_ms,
+            ..RequestSettings::default()
+        };
 
         Ok(())
     }
 
-    /// Ruby: `pool.request(method, path, headers, body = nil, &block)`
-    ///
-    /// Returns an Array: [status_code, headers_array, body_string]
-    /// If a block is given, streams the body and returns [status_code, headers_array]
-    fn rb_request(
-        ruby: &Ruby,
-        rb_self: typed_data::Obj<Self>,
-        args: &[Value],
-    ) -> Result<Value, Error> {
-        let args = scan_args::<(String, String, RArray), (Option<RString>,), (), (), (), ()>(args)?;
+    /// Convert `request`'s arguments — `(method, path, headers, body = nil,
+    /// options = {})` — to Rust, applying the per-request options shared by
+    /// `request` and `request_async`.
+    fn prepare_request(&self, args: &[Value]) -> Result<PreparedRequest, Error> {
+        let args = scan_args::<
+            (String, String, RArray),
+            (Option<RString>, Option<RHash>),
+            (),
+            (),
+            (),
+            (),
+        >(args)?;
         let method = args.required.0;
         let path = args.required.1;
         let headers = args.required.2;
         let body = args.optional.0;
-        let inner = rb_self.inner.borrow();
-        let cm = inner.as_ref().ok_or_else(|| {
-            Error::new(
-                ruby.exception_runtime_error(),
-                "ConnectionPool not initialized",
-            )
-        })?;
+        let request_opts = args.optional.1;
 
-        let read_timeout_ms = *rb_self.read_timeout_ms.borrow();
+        let mut settings = *self.settings.borrow();
+        if let Some(opts) = &request_opts {
+            settings.connection_close = hash_get_bool(opts, "connection_close", false)?;
+            settings.retry_non_idempotent = hash_get_bool(opts, "retry_non_idempotent", false)?;
+            settings.empty_body_content_length =
+                hash_get_bool(opts, "empty_body_content_length", true)?;
+        }
 
         // Convert Ruby headers array [[name, value], ...] to Vec<(String, String)>
         // Uses raw rb_sys APIs to avoid per-element magnus type-checking
@@ -242,66 +569,418 @@ impl ConnectionPool {
             _ => None,
         };
 
+        // aws-chunked trailer mode: replace the body with its framed
+        // encoding and swap the caller's Content-Length for ours.
+        let trailer_checksum = match &request_opts {
+            Some(opts) => parse_trailer_checksum(opts)?,
+            None => None,
+        };
+        let body_bytes = match trailer_checksum {
+            Some(algorithm) => {
+                let encoded = aws_chunked::encode(
+                    body_bytes.as_deref().unwrap_or(&[]),
+                    algorithm,
+                    aws_chunked::DEFAULT_CHUNK_SIZE,
+                );
+                header_vec.retain(|(name, _)| {
+                    !name.eq_ignore_ascii_case("content-length")
+                        && !name.eq_ignore_ascii_case("content-encoding")
+                });
+                header_vec.extend(encoded.headers);
+                Some(encoded.body)
+            }
+            None => body_bytes,
+        };
+
+        let decode_cbor = match &request_opts {
+            Some(opts) => parse_decode(opts)?,
+            None => false,
+        };
+
+        // Ask for a compressed response unless the caller already chose
+        // what to accept.
+        let decompress = match &request_opts {
+            Some(opts) => hash_get_bool(opts, "decompress", false)?,
+            None => false,
+        };
+        if decompress
+            && !header_vec
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("accept-encoding"))
+        {
+            header_vec.push(("Accept-Encoding".to_string(), ACCEPT_ENCODING.to_string()));
+        }
+
+        // A HEAD response carries the object's checksum header but no body
+        // to check it against.
+        let verify_checksum = match &request_opts {
+            Some(opts) => parse_verify_checksum(opts)?,
+            None => None,
+        }
+        .filter(|_| !method.eq_ignore_ascii_case("HEAD"));
+
+        Ok(PreparedRequest {
+            method,
+            path,
+            headers: header_vec,
+            body: body_bytes,
+            settings,
+            decode_cbor,
+            decompress,
+            verify_checksum,
+            opts: request_opts,
+        })
+    }
+
+    /// Ruby: `pool.request(method, path, headers, body = nil, options = {}, &block)`
+    ///
+    /// options:
+    ///   :trailer_checksum - "CRC32", "CRC32C", or "CRC64NVME". Sends the body
+    ///                       `aws-chunked` encoded with that checksum as a
+    ///                       trailer (see aws_chunked.rs).
+    ///   :connection_close - when true, sends `Connection: close` and closes
+    ///                       the connection after the response instead of
+    ///                       returning it to the pool.
+    ///   :decode           - `:cbor` to decode the buffered body in native
+    ///                       code; the result is the response's `data` and
+    ///                       `body` is nil. Not allowed with a block.
+    ///   :retry_non_idempotent - when true, POST and PATCH are retried like
+    ///                       idempotent methods (up to the pool's
+    ///                       `:max_retries`).
+    ///   :empty_body_content_length - default true. An empty (not nil) body
+    ///                       on a POST, PUT or PATCH is sent with
+    ///                       `Content-Length: 0` unless the headers already
+    ///                       give a length or transfer encoding. Pass false
+    ///                       to send the headers exactly as given.
+    ///   :decompress       - when true, sends `Accept-Encoding: gzip, deflate`
+    ///                       (unless the headers already have an
+    ///                       Accept-Encoding) and decodes a gzip or deflate
+    ///                       body, dropping its Content-Encoding. Buffered
+    ///                       requests only.
+    ///   :verify_checksum  - `{crc32c: "base64..."}` (or `crc32:` /
+    ///                       `crc64nvme:`). Checksums the body as it
+    ///                       arrives and raises
+    ///                       `AwsCrt::Http::ChecksumMismatchError` at
+    ///                       completion if a 2xx response doesn't match. A
+    ///                       nil value takes the expected checksum from the
+    ///                       response's `x-amz-checksum-*` header; without
+    ///                       one, the body is not verified. Covers the bytes
+    ///                       as received, before `:decompress`.
+    ///   :response_targets - Array of sinks, each responding to `<<` or
+    ///                       `update`. Every body chunk is handed to each
+    ///                       sink in order (then to the block, if any).
+    ///                       Not allowed with `:decode`.
+    ///
+    /// Returns an `AwsCrt::Http::Response`. If a block or
+    /// `:response_targets` is given, the body is streamed to them and the
+    /// response's `body` is nil. A request is only
+    /// retried if the failure happened before any body chunk was yielded;
+    /// `Response#attempts` tells which attempt succeeded. A 429 or 503
+    /// response with a `Retry-After` header is retried too, after the wait
+    /// it asks for (at most the pool's `:max_retry_after_ms`); its body is
+    /// never yielded.
+    fn rb_request(
+        ruby: &Ruby,
+        rb_self: typed_data::Obj<Self>,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let inner = rb_self.inner.borrow();
+        let cm = inner.as_ref().ok_or_else(|| not_initialized(ruby))?;
+        let PreparedRequest {
+            method,
+            path,
+            headers: header_vec,
+            body: body_bytes,
+            settings,
+            decode_cbor,
+            decompress,
+            verify_checksum,
+            opts: request_opts,
+        } = rb_self.prepare_request(args)?;
+
+        let retries = settings.retries_for(&method);
+        let mut body_bytes = body_bytes;
+
+        // With fair queueing, wait for this request's turn before competing
+        // for a connection. The slot is held across retries and released
+        // when the request returns.
+        let queue = rb_self.queue.borrow();
+        let _admission = match queue.as_ref() {
+            Some(queue) => Some(queue.admit()?),
+            None => None,
+        };
+
+        let sinks = match &request_opts {
+            Some(opts) => parse_response_targets(opts)?,
+            None => Vec::new(),
+        };
+
         // Check if a block was given
         let block = ruby.block_given();
+        if block && decode_cbor {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                "decode: :cbor cannot be combined with a streaming block",
+            ));
+        }
+        if !sinks.is_empty() && decode_cbor {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                "decode: :cbor cannot be combined with :response_targets",
+            ));
+        }
+        if (block || !sinks.is_empty()) && decompress {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                "decompress: true cannot be combined with a streaming block or :response_targets",
+            ));
+        }
 
-        if block {
-            // Streaming mode — yield chunks to the Ruby block.
+        if block || !sinks.is_empty() {
+            // Streaming mode — hand chunks to the sinks, then the block.
             // Headers and status are captured via the on_headers callback
             // before any body chunks are yielded.
-            let block_proc = ruby.block_proc()?;
+            let block_proc = if block { Some(ruby.block_proc()?) } else { None };
+            // The first exception raised by a sink; later chunks are dropped
+            // and it is re-raised once the request finishes.
+            let mut sink_error: Option<Error> = None;
 
             let mut captured_status: i32 = 0;
             let mut captured_headers: Vec<(String, String)> = Vec::new();
+            let mut yielded = false;
+            // Retries only happen before the first chunk, so this never
+            // covers more than one attempt's body.
+            let mut checksum = verify_checksum
+                .as_ref()
+                .map(|check| RunningChecksum::new(check.algorithm));
 
-            http::make_streaming_request(
-                cm.as_ptr(),
-                &method,
-                &path,
-                &header_vec,
-                body_bytes,
-                read_timeout_ms,
-                |status, hdrs| {
-                    captured_status = status;
-                    captured_headers = hdrs.to_vec();
-                },
-                |chunk| {
-                    // Yield chunk to the Ruby block (GVL is held here)
-                    let rb_chunk = ruby.str_from_slice(chunk);
-                    let _ = block_proc.call::<_, Value>((rb_chunk,));
-                },
-            )
-            .map_err(|e| -> Error { e.into() })?;
+            let mut attempt = 1;
+            let timing = loop {
+                // Set from the headers when the response is one that will be
+                // retried after its Retry-After, so its body is dropped.
+                let retry_delay = Cell::new(None);
+                // No GVL release between this check and the acquisition in
+                // make_streaming_request, so a concurrent #close can't slip in.
+                cm.ensure_open().map_err(|e| -> Error { e.into() })?;
+                let result = http::make_streaming_request(
+                    cm,
+                    &method,
+                    &path,
+                    &header_vec,
+                    attempt_body(&mut body_bytes, attempt <= retries),
+                    &settings,
+                    |status, hdrs| {
+                        captured_status = status;
+                        captured_headers = hdrs.to_vec();
+                        if attempt <= retries {
+                            retry_delay.set(settings.retry_after(status, hdrs));
+                        }
+                    },
+                    |chunk| {
+                        if retry_delay.get().is_some() {
+                            return;
+                        }
+                        // Yield chunk to the sinks and block (GVL is held here)
+                        yielded = true;
+                        if let Some(checksum) = &mut checksum {
+                            checksum.update(chunk);
+                        }
+                        if sink_error.is_some() {
+                            return;
+                        }
+                        let rb_chunk = ruby.str_from_slice(chunk);
+                        for (sink, method) in &sinks {
+                            if let Err(e) = sink.funcall::<_, _, Value>(*method, (rb_chunk,)) {
+                                sink_error = Some(e);
+                                return;
+                            }
+                        }
+                        if let Some(block_proc) = &block_proc {
+                            let _ = block_proc.call::<_, Value>((rb_chunk,));
+                        }
+                    },
+                );
+                match result {
+                    Ok(timing) => match retry_delay.get() {
+                        Some(delay) => {
+                            http::sleep(delay);
+                            attempt += 1;
+                        }
+                        None => break timing,
+                    },
+                    // Chunks already handed to the block can't be taken back.
+                    Err(e) if attempt <= retries && !yielded && e.is_retryable() => attempt += 1,
+                    Err(e) => return Err(e.into()),
+                }
+            };
+
+            if let Some(e) = sink_error {
+                return Err(e);
+            }
+            if let (Some(check), Some(checksum)) = (&verify_checksum, &checksum) {
+                check.verify(captured_status, &captured_headers, checksum)?;
+            }
 
-            // Build return value: [status_code, headers_array]
-            let rb_headers = build_ruby_headers(ruby, &captured_headers);
-            let arr = RArray::from_slice(&[
-                ruby.into_value(captured_status),
-                rb_headers.as_value(),
-            ]);
-            Ok(arr.as_value())
+            let response = Response::new(ruby, captured_status, captured_headers, None)
+                .with_attempts(attempt)
+                .with_timing(timing);
+            Ok(typed_data::Obj::wrap(response).as_value())
         } else {
             // Buffered mode — return complete response
-            let response = http::make_request(
-                cm.as_ptr(),
-                &method,
-                &path,
-                &header_vec,
-                body_bytes,
-                read_timeout_ms,
-            )
-            .map_err(|e| -> Error { e.into() })?;
+            let mut attempt = 1;
+            let mut response = loop {
+                cm.ensure_open().map_err(|e| -> Error { e.into() })?;
+                match http::make_request(
+                    cm,
+                    &method,
+                    &path,
+                    &header_vec,
+                    attempt_body(&mut body_bytes, attempt <= retries),
+                    &settings,
+                ) {
+                    Ok(response) => {
+                        match settings.retry_after(response.status_code, &response.headers) {
+                            Some(delay) if attempt <= retries => {
+                                http::sleep(delay);
+                                attempt += 1;
+                            }
+                            _ => break response,
+                        }
+                    }
+                    Err(e) if attempt <= retries && e.is_retryable() => attempt += 1,
+                    Err(e) => return Err(e.into()),
+                }
+            };
+
+            if let Some(check) = &verify_checksum {
+                check.verify_body(&response)?;
+            }
+            if decompress {
+                decompress_body(ruby, &mut response)?;
+            }
+            let timing = response.timing;
+            let response = if decode_cbor {
+                decode_cbor_response(ruby, response)?
+            } else {
+                Response::new(
+                    ruby,
+                    response.status_code,
+                    response.headers,
+                    Some(&response.body),
+                )
+            };
+            let response = response.with_attempts(attempt).with_timing(timing);
+            Ok(typed_data::Obj::wrap(response).as_value())
+        }
+    }
+
+    /// Ruby: `pool.request_async(method, path, headers, body = nil, options = {})`
+    ///
+    /// Sends the request and returns an `AwsCrt::Http::PendingResponse`
+    /// at once, without waiting for a connection; `#value` on it returns the
+    /// `Response`. Takes `request`'s options except those that need the
+    /// calling thread while the response arrives: no block or
+    /// `:response_targets`, and failed requests are not retried
+    /// (`:max_retries`, DNS failure retries). Not available with
+    /// `fair_queue: true`, whose admission blocks.
+    fn rb_request_async(
+        ruby: &Ruby,
+        rb_self: typed_data::Obj<Self>,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let inner = rb_self.inner.borrow();
+        let cm = inner.as_ref().ok_or_else(|| not_initialized(ruby))?;
+        if rb_self.queue.borrow().is_some() {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                "request_async cannot be used with fair_queue: true",
+            ));
+        }
+        if ruby.block_given() {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                "request_async does not take a block; call #value on the result",
+            ));
+        }
+        let request = rb_self.prepare_request(args)?;
+        if let Some(opts) = &request.opts {
+            if !parse_response_targets(opts)?.is_empty() {
+                return Err(Error::new(
+                    magnus::exception::arg_error(),
+                    ":response_targets cannot be used with request_async",
+                ));
+            }
+        }
+
+        cm.ensure_open().map_err(|e| -> Error { e.into() })?;
+        let pending = http::start_request(
+            cm.as_ptr(),
+            &request.method,
+            &request.path,
+            &request.headers,
+            request.body,
+            &request.settings,
+        )
+        .map_err(|e| -> Error { e.into() })?;
+        let handle = PendingResponse::new(
+            pending,
+            request.decode_cbor,
+            request.decompress,
+            request.verify_checksum,
+            rb_self.as_value(),
+        );
+        Ok(typed_data::Obj::wrap(handle).as_value())
+    }
 
-            // Build return value: [status_code, headers_array, body_string]
-            let rb_headers = build_ruby_headers(ruby, &response.headers);
-            let rb_body = ruby.str_from_slice(&response.body);
-            let arr = RArray::from_slice(&[
-                ruby.into_value(response.status_code),
-                rb_headers.as_value(),
-                rb_body.as_value(),
-            ]);
-            Ok(arr.as_value())
+    /// Ruby: `pool.close`
+    ///
+    /// Shuts the pool down. Requests still waiting for a connection fail
+    /// with `AwsCrt::Http::ConnectionError`, and later requests raise the
+    /// same. Requests that already hold a connection run to completion.
+    /// Idempotent.
+    fn rb_close(&self) {
+        if let Some(queue) = self.queue.borrow().as_ref() {
+            queue.close();
         }
+        if let Some(cm) = self.inner.borrow().as_ref() {
+            cm.shutdown();
+        }
+    }
+
+    /// Ruby: `pool.closed?`
+    fn rb_closed_p(&self) -> bool {
+        self.inner
+            .borrow()
+            .as_ref()
+            .is_some_and(ConnectionManager::is_shut_down)
+    }
+
+    fn endpoint(&self, ruby: &Ruby) -> Result<Endpoint, Error> {
+        self.endpoint
+            .borrow()
+            .clone()
+            .ok_or_else(|| not_initialized(ruby))
+    }
+
+    /// Ruby: `pool.endpoint` — `"scheme://host:port"`, always with the port.
+    fn rb_endpoint(ruby: &Ruby, rb_self: &Self) -> Result<String, Error> {
+        let endpoint = rb_self.endpoint(ruby)?;
+        Ok(format!("{}://{}:{}", endpoint.scheme, endpoint.host, endpoint.port))
+    }
+
+    /// Ruby: `pool.scheme` — `"http"` or `"https"`.
+    fn rb_scheme(ruby: &Ruby, rb_self: &Self) -> Result<String, Error> {
+        Ok(rb_self.endpoint(ruby)?.scheme)
+    }
+
+    /// Ruby: `pool.host`
+    fn rb_host(ruby: &Ruby, rb_self: &Self) -> Result<String, Error> {
+        Ok(rb_self.endpoint(ruby)?.host)
+    }
+
+    /// Ruby: `pool.port`
+    fn rb_port(ruby: &Ruby, rb_self: &Self) -> Result<u32, Error> {
+        Ok(rb_self.endpoint(ruby)?.port)
     }
 }
 
@@ -309,8 +988,15 @@ impl ConnectionPool {
 // Helper functions
 // ---------------------------------------------------------------------------
 
+fn not_initialized(ruby: &Ruby) -> Error {
+    Error::new(
+        ruby.exception_runtime_error(),
+        "ConnectionPool not initialized",
+    )
+}
+
 /// Parse an endpoint string like "https://example.com:443" into (scheme, host, port).
-fn parse_endpoint(endpoint: &str) -> Result<(String, String, u32), Error> {
+pub(crate) fn parse_endpoint(endpoint: &str) -> Result<(String, String, u32), Error> {
     // Split scheme
     let (scheme, rest) = endpoint
         .split_once("://")
@@ -362,6 +1048,232 @@ fn parse_endpoint(endpoint: &str) -> Result<(String, String, u32), Error> {
     Ok((scheme, host, port))
 }
 
+/// Parse the `:trailer_checksum` request option.
+fn parse_trailer_checksum(opts: &RHash) -> Result<Option<TrailerChecksum>, Error> {
+    match hash_get_string(opts, "trailer_checksum")? {
+        Some(name) => TrailerChecksum::from_name(&name).map(Some).ok_or_else(|| {
+            Error::new(
+                magnus::exception::arg_error(),
+                format!(
+                    "invalid trailer_checksum '{}': must be CRC32, CRC32C, or CRC64NVME",
+                    name
+                ),
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Parse the `:verify_checksum` request option: a Hash with one entry,
+/// from an algorithm name (Symbol or String, any case) to the expected
+/// base64 checksum or nil.
+fn parse_verify_checksum(opts: &RHash) -> Result<Option<ChecksumCheck>, Error> {
+    let val: Option<Value> = opts.lookup(Symbol::new("verify_checksum"))?;
+    let hash = match val {
+        None => return Ok(None),
+        Some(v) if v.is_nil() => return Ok(None),
+        Some(v) => match RHash::from_value(v) {
+            Some(hash) if hash.len() == 1 => hash,
+            _ => {
+                return Err(Error::new(
+                    magnus::exception::arg_error(),
+                    format!(
+                        "invalid verify_checksum {}: must be a Hash with one algorithm, \
+                         e.g. {{crc32c: \"base64...\"}}",
+                        v.inspect()
+                    ),
+                ))
+            }
+        },
+    };
+
+    let (key, expected) = hash
+        .to_vec::<Value, Option<RString>>()?
+        .into_iter()
+        .next()
+        .expect("hash has one entry");
+    let name = match Symbol::from_value(key) {
+        Some(sym) => sym.name()?.into_owned(),
+        None => String::try_convert(key)?,
+    };
+    let algorithm = TrailerChecksum::from_name(&name).ok_or_else(|| {
+        Error::new(
+            magnus::exception::arg_error(),
+            format!(
+                "invalid verify_checksum algorithm '{}': must be crc32, crc32c, or crc64nvme",
+                name
+            ),
+        )
+    })?;
+    let expected = match expected {
+        Some(s) => Some(s.to_string()?),
+        None => None,
+    };
+    Ok(Some(ChecksumCheck { algorithm, expected }))
+}
+
+/// Parse the `:decode` request option. Only `:cbor` is supported.
+fn parse_decode(opts: &RHash) -> Result<bool, Error> {
+    let val: Option<Value> = opts.lookup(Symbol::new("decode"))?;
+    match val {
+        None => Ok(false),
+        Some(v) if v.is_nil() => Ok(false),
+        Some(v) => match Symbol::from_value(v) {
+            Some(sym) if sym.name()? == "cbor" => Ok(true),
+            _ => Err(Error::new(
+                magnus::exception::arg_error(),
+                format!("invalid decode {}: must be :cbor", v.inspect()),
+            )),
+        },
+    }
+}
+
+/// Parse `:response_targets` into `(sink, method)` pairs, preferring `<<`
+/// over `update` for sinks that respond to both.
+fn parse_response_targets(opts: &RHash) -> Result<Vec<(Value, &'static str)>, Error> {
+    let val: Option<Value> = opts.lookup(Symbol::new("response_targets"))?;
+    let targets = match val {
+        None => return Ok(Vec::new()),
+        Some(v) if v.is_nil() => return Ok(Vec::new()),
+        Some(v) => RArray::from_value(v).ok_or_else(|| {
+            Error::new(
+                magnus::exception::arg_error(),
+                format!("invalid response_targets {}: must be an Array", v.inspect()),
+            )
+        })?,
+    };
+
+    let mut sinks = Vec::with_capacity(targets.len());
+    for sink in targets.each() {
+        let sink = sink?;
+        let method = if sink.respond_to("<<", false)? {
+            "<<"
+        } else if sink.respond_to("update", false)? {
+            "update"
+        } else {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                format!(
+                    "invalid response target {}: must respond to << or update",
+                    sink.inspect()
+                ),
+            ));
+        };
+        sinks.push((sink, method));
+    }
+    Ok(sinks)
+}
+
+/// The body to send on one attempt: a copy while a retry may still need the
+/// original, otherwise the original itself.
+fn attempt_body(body: &mut Option<Vec<u8>>, may_retry: bool) -> Option<Vec<u8>> {
+    if may_retry {
+        body.clone()
+    } else {
+        body.take()
+    }
+}
+
+/// Decode a buffered CBOR response body straight from the native buffer.
+///
+/// An empty body decodes to nil. A body that isn't valid CBOR raises
+/// `AwsCrt::Cbor::Error` naming the status and Content-Type, since a
+/// failure usually means the server sent something else (an HTML error
+/// page from a proxy, say).
+fn decode_cbor_response(ruby: &Ruby, response: http::HttpResponse) -> Result<Response, Error> {
+    let data = decode_cbor_body(ruby, &response)?;
+    Ok(Response::decoded(ruby, response.status_code, response.headers, data))
+}
+
+/// The decoded body for `decode_cbor_response`, leaving `response` intact.
+pub(crate) fn decode_cbor_body(ruby: &Ruby, response: &http::HttpResponse) -> Result<Value, Error> {
+    if response.body.is_empty() {
+        Ok(ruby.qnil().as_value())
+    } else {
+        cbor::decode_slice(ruby, &response.body).map_err(|e| {
+            let content_type = response
+                .headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                .map_or("none", |(_, value)| value.as_str());
+            Error::new(
+                cbor::cbor_error(ruby),
+                format!(
+                    "HTTP {} response body (Content-Type: {}) is not valid CBOR: {}",
+                    response.status_code, content_type, e
+                ),
+            )
+        })
+    }
+}
+
+/// Undo a gzip or deflate `Content-Encoding` (`decompress: true`), in
+/// place. A body with any other coding — or none — is left as it is. Once
+/// decoded, the Content-Encoding header is dropped and Content-Length gives
+/// the decoded size.
+pub(crate) fn decompress_body(ruby: &Ruby, response: &mut http::HttpResponse) -> Result<(), Error> {
+    let codings: Vec<String> = response
+        .headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("content-encoding"))
+        .flat_map(|(_, value)| value.split(','))
+        .map(|coding| coding.trim().to_ascii_lowercase())
+        .filter(|coding| !coding.is_empty() && coding != "identity")
+        .collect();
+    let supported = |coding: &String| matches!(coding.as_str(), "gzip" | "x-gzip" | "deflate");
+    if codings.is_empty() || !codings.iter().all(supported) {
+        return Ok(());
+    }
+
+    ruby.eval::<Value>("require 'zlib'")?;
+    let zlib: RModule = ruby.class_object().const_get("Zlib")?;
+    let mut body = ruby.str_from_slice(&response.body);
+    // Codings are listed in the order they were applied.
+    for coding in codings.iter().rev() {
+        let decoded = if coding == "deflate" {
+            inflate(zlib, body)
+        } else {
+            zlib.funcall("gunzip", (body,))
+        };
+        body = decoded.map_err(|e| {
+            error::http_error(format!("could not decompress {} response body: {}", coding, e))
+        })?;
+    }
+
+    response.body = unsafe { body.as_slice() }.to_vec();
+    response
+        .headers
+        .retain(|(name, _)| !name.eq_ignore_ascii_case("content-encoding"));
+    let length = response.body.len().to_string();
+    for (name, value) in response.headers.iter_mut() {
+        if name.eq_ignore_ascii_case("content-length") {
+            *value = length.clone();
+        }
+    }
+    Ok(())
+}
+
+/// Inflate a `deflate` body. The coding means a zlib stream, but some
+/// servers send raw deflate data without the zlib header, so a header error
+/// retries with `Zlib::Inflate.new(-Zlib::MAX_WBITS)`.
+fn inflate(zlib: RModule, body: RString) -> Result<RString, Error> {
+    let inflate: RClass = zlib.const_get("Inflate")?;
+    let err = match inflate.funcall("inflate", (body,)) {
+        Ok(decoded) => return Ok(decoded),
+        Err(err) => err,
+    };
+    if !err.is_kind_of(zlib.const_get::<_, RClass>("DataError")?) {
+        return Err(err);
+    }
+    let max_wbits: i32 = zlib.const_get("MAX_WBITS")?;
+    let raw = inflate.new_instance((-max_wbits,))?;
+    // `<<` keeps the output buffered; `finish` returns all of it.
+    let _: Value = raw.funcall("<<", (body,))?;
+    let decoded: RString = raw.funcall("finish", ())?;
+    let _: Value = raw.funcall("close", ())?;
+    Ok(decoded)
+}
+
 /// Parse proxy options from a Ruby Hash's :proxy key.
 fn parse_proxy_options(opts: &RHash) -> Result<Option<ProxyOptions>, Error> {
     let sym = Symbol::new("proxy");
@@ -404,19 +1316,6 @@ fn parse_proxy_options(opts: &RHash) -> Result<Option<ProxyOptions>, Error> {
     }
 }
 
-/// Convert response headers Vec<(String, String)> to a Ruby Array of [name, value] pairs.
-fn build_ruby_headers(ruby: &Ruby, headers: &[(String, String)]) -> RArray {
-    let arr = RArray::with_capacity(headers.len());
-    for (name, value) in headers {
-        let pair = RArray::from_slice(&[
-            ruby.str_new(name).as_value(),
-            ruby.str_new(value).as_value(),
-        ]);
-        let _ = arr.push(pair);
-    }
-    arr
-}
-
 // ---------------------------------------------------------------------------
 // Registration
 // ---------------------------------------------------------------------------
@@ -434,6 +1333,16 @@ pub fn define_connection_pool(
         method!(ConnectionPool::rb_initialize, -1),
     )?;
     class.define_method("request", method!(ConnectionPool::rb_request, -1))?;
+    class.define_method(
+        "request_async",
+        method!(ConnectionPool::rb_request_async, -1),
+    )?;
+    class.define_method("close", method!(ConnectionPool::rb_close, 0))?;
+    class.define_method("closed?", method!(ConnectionPool::rb_closed_p, 0))?;
+    class.define_method("endpoint", method!(ConnectionPool::rb_endpoint, 0))?;
+    class.define_method("scheme", method!(ConnectionPool::rb_scheme, 0))?;
+    class.define_method("host", method!(ConnectionPool::rb_host, 0))?;
+    class.define_method("port", method!(ConnectionPool::rb_port, 0))?;
 
     Ok(())
 }
@@ -0,0 +1,251 @@
+//! Ruby-facing `AwsCrt::Http::Response` class.
+//!
+//! Returned by `ConnectionPool#request`. Keeps the headers in arrival order
+//! (duplicates included) and builds a lowercase-name index over them so
+//! `response["content-type"]` finds a header whatever casing the server
+//! used.
+//!
+//! The status, header pairs and body can still be destructured like the
+//! arrays `request` used to return (`status, headers, body = ...`) via
+//! `#to_ary`. The Hash-valued `#headers` is defined in Ruby
+//! (lib/aws_crt/http/response.rb) on top of `#header_pairs`.
+
+use std::collections::HashMap;
+
+use magnus::gc::Marker;
+use magnus::prelude::*;
+use magnus::rb_sys::{AsRawValue, FromRawValue};
+use magnus::{method, DataTypeFunctions, Error, RArray, RHash, RString, Ruby, Symbol, Value};
+use rb_sys::VALUE;
+
+use crate::http::RequestTiming;
+
+/// Ruby class `AwsCrt::Http::Response`.
+///
+/// Immutable once built. The Ruby headers array and body string are created
+/// once at construction and marked here, so repeated reads don't copy.
+#[magnus::wrap(class = "AwsCrt::Http::Response", mark, free_immediately, size)]
+pub struct Response {
+    status: i32,
+    headers: Vec<(String, String)>,
+    /// Lowercased header name -> positions in `headers`.
+    index: HashMap<String, Vec<usize>>,
+    /// Ruby Array of `[name, value]` pairs.
+    rb_headers: VALUE,
+    /// Ruby String body, or nil for a streamed or decoded response.
+    rb_body: VALUE,
+    /// The decoded body (`decode: :cbor`), or nil.
+    rb_data: VALUE,
+    decoded: bool,
+    /// Attempts made, including the one that succeeded (1 = no retries).
+    attempts: u32,
+    /// Timing of the successful attempt, if known.
+    timing: Option<RequestTiming>,
+}
+
+impl DataTypeFunctions for Response {
+    fn mark(&self, marker: &Marker) {
+        unsafe {
+            marker.mark(Value::from_raw(self.rb_headers));
+            marker.mark(Value::from_raw(self.rb_body));
+            marker.mark(Value::from_raw(self.rb_data));
+        }
+    }
+}
+
+impl Response {
+    /// Build a response. `body` is `None` for streamed responses, whose body
+    /// was already yielded to the caller's block.
+    pub fn new(
+        ruby: &Ruby,
+        status: i32,
+        headers: Vec<(String, String)>,
+        body: Option<&[u8]>,
+    ) -> Self {
+        let rb_body = match body {
+            Some(bytes) => ruby.str_from_slice(bytes).as_value(),
+            None => ruby.qnil().as_value(),
+        };
+        Self::build(ruby, status, headers, rb_body, ruby.qnil().as_value(), false)
+    }
+
+    /// Build a response whose body was decoded in native code; `data` is the
+    /// decoded object and no body String is kept.
+    pub fn decoded(ruby: &Ruby, status: i32, headers: Vec<(String, String)>, data: Value) -> Self {
+        Self::build(ruby, status, headers, ruby.qnil().as_value(), data, true)
+    }
+
+    fn build(
+        ruby: &Ruby,
+        status: i32,
+        headers: Vec<(String, String)>,
+        rb_body: Value,
+        rb_data: Value,
+        decoded: bool,
+    ) -> Self {
+        let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, (name, _)) in headers.iter().enumerate() {
+            index.entry(name.to_ascii_lowercase()).or_default().push(i);
+        }
+
+        let rb_headers = RArray::with_capacity(headers.len());
+        for (name, value) in &headers {
+            let pair = RArray::from_slice(&[
+                ruby.str_new(name).as_value(),
+                ruby.str_new(value).as_value(),
+            ]);
+            let _ = rb_headers.push(pair);
+        }
+
+        Self {
+            status,
+            headers,
+            index,
+            rb_headers: rb_headers.as_raw(),
+            rb_body: rb_body.as_raw(),
+            rb_data: rb_data.as_raw(),
+            decoded,
+            attempts: 1,
+            timing: None,
+        }
+    }
+
+    /// Record that the request succeeded on attempt number `attempts`.
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Record how long the successful attempt took.
+    pub fn with_timing(mut self, timing: RequestTiming) -> Self {
+        self.timing = Some(timing);
+        self
+    }
+
+    /// All values of a header, case-insensitively, in arrival order.
+    fn values(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.index
+            .get(&name.to_ascii_lowercase())
+            .into_iter()
+            .flatten()
+            .map(|&i| self.headers[i].1.as_str())
+    }
+
+    fn rb_status(&self) -> i32 {
+        self.status
+    }
+
+    fn rb_header_pairs(&self) -> RArray {
+        unsafe { RArray::from_value(Value::from_raw(self.rb_headers)).unwrap() }
+    }
+
+    fn rb_body(&self) -> Option<RString> {
+        unsafe { RString::from_value(Value::from_raw(self.rb_body)) }
+    }
+
+    fn rb_data(&self) -> Value {
+        unsafe { Value::from_raw(self.rb_data) }
+    }
+
+    fn rb_attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    fn rb_retries(&self) -> u32 {
+        self.attempts - 1
+    }
+
+    /// `{connect_ms:, ttfb_ms:, first_body_byte_ms:, total_ms:, send_ms:,
+    /// receive_ms:}` in milliseconds (Float, or nil for a stage that wasn't
+    /// reached), or nil when the response carries no timing.
+    fn rb_timing(ruby: &Ruby, rb_self: &Self) -> Result<Option<RHash>, Error> {
+        let Some(timing) = rb_self.timing else {
+            return Ok(None);
+        };
+        let hash = ruby.hash_new();
+        hash.aset(Symbol::new("connect_ms"), timing.connect_ms)?;
+        hash.aset(Symbol::new("ttfb_ms"), timing.ttfb_ms)?;
+        hash.aset(Symbol::new("first_body_byte_ms"), timing.first_body_byte_ms)?;
+        hash.aset(Symbol::new("total_ms"), timing.total_ms)?;
+        hash.aset(Symbol::new("send_ms"), timing.send_ms)?;
+        hash.aset(Symbol::new("receive_ms"), timing.receive_ms)?;
+        Ok(Some(hash))
+    }
+
+    /// Milliseconds the server's clock was ahead of ours (negative when
+    /// behind), from the `Date` header, or nil without one.
+    fn rb_clock_skew_ms(&self) -> Option<i64> {
+        self.timing.and_then(|timing| timing.clock_skew_ms)
+    }
+
+    fn rb_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// `response[name]` — the header's value, or nil. Repeated headers are
+    /// joined with ", " as RFC 9110 allows.
+    fn rb_aref(&self, name: String) -> Option<String> {
+        let values: Vec<&str> = self.values(&name).collect();
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.join(", "))
+        }
+    }
+
+    /// Every `Set-Cookie` value, one element per header line. Cookies can't
+    /// be joined the way `[]` joins other repeated headers: `Expires`
+    /// attributes contain commas.
+    fn rb_cookies(&self) -> Vec<String> {
+        self.values("set-cookie").map(str::to_owned).collect()
+    }
+
+    fn rb_key_p(&self, name: String) -> bool {
+        self.index.contains_key(&name.to_ascii_lowercase())
+    }
+
+    /// `[status, headers, body]`, `[status, headers, data]` when decoded, or
+    /// `[status, headers]` when streamed.
+    fn rb_to_ary(ruby: &Ruby, rb_self: &Self) -> RArray {
+        let status = ruby.into_value(rb_self.status);
+        let headers = rb_self.rb_header_pairs().as_value();
+        if rb_self.decoded {
+            return RArray::from_slice(&[status, headers, rb_self.rb_data()]);
+        }
+        match rb_self.rb_body() {
+            Some(body) => RArray::from_slice(&[status, headers, body.as_value()]),
+            None => RArray::from_slice(&[status, headers]),
+        }
+    }
+
+    fn rb_inspect(&self) -> String {
+        format!(
+            "#<AwsCrt::Http::Response status={} headers={}>",
+            self.status,
+            self.headers.len()
+        )
+    }
+}
+
+/// Register the `AwsCrt::Http::Response` class with magnus.
+pub fn define_response(ruby: &Ruby, http_module: &magnus::RModule) -> Result<(), Error> {
+    let class = http_module.define_class("Response", ruby.class_object())?;
+    class.undef_default_alloc_func();
+    class.define_method("status", method!(Response::rb_status, 0))?;
+    class.define_method("header_pairs", method!(Response::rb_header_pairs, 0))?;
+    class.define_method("body", method!(Response::rb_body, 0))?;
+    class.define_method("data", method!(Response::rb_data, 0))?;
+    class.define_method("success?", method!(Response::rb_success, 0))?;
+    class.define_method("attempts", method!(Response::rb_attempts, 0))?;
+    class.define_method("retries", method!(Response::rb_retries, 0))?;
+    class.define_method("timing", method!(Response::rb_timing, 0))?;
+    class.define_method("clock_skew_ms", method!(Response::rb_clock_skew_ms, 0))?;
+    class.define_method("[]", method!(Response::rb_aref, 1))?;
+    class.define_method("key?", method!(Response::rb_key_p, 1))?;
+    class.define_method("cookies", method!(Response::rb_cookies, 0))?;
+    class.define_method("to_ary", method!(Response::rb_to_ary, 0))?;
+    class.define_method("to_a", method!(Response::rb_to_ary, 0))?;
+    class.define_method("inspect", method!(Response::rb_inspect, 0))?;
+
+    Ok(())
+}
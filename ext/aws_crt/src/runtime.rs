@@ -9,8 +9,11 @@
 //! Thread safety is guaranteed by `OnceLock` — multiple Ruby threads calling
 //! `CrtRuntime::get()` concurrently will all receive the same instance, and
 //! the underlying CRT resources are initialized exactly once.
+//!
+//! A small set of process-wide settings (see `RuntimeConfig`) can be applied
+//! with `CrtRuntime::configure` before the runtime is first initialized.
 
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 
 use crate::error::CrtError;
 
@@ -64,12 +67,25 @@ struct AwsHostResolverDefaultOptions {
     system_clock_override_fn: *const std::ffi::c_void,
 }
 
+/// Mirrors `struct aws_host_resolution_config` from aws-c-io/host_resolver.h.
+///
+/// Always obtained from `aws_host_resolver_init_default_resolution_config`
+/// so `impl_fn` points at the default resolver implementation; we only
+/// override `max_ttl`.
+#[repr(C)]
+struct AwsHostResolutionConfig {
+    impl_fn: *const std::ffi::c_void,
+    max_ttl: usize,
+    impl_data: *mut std::ffi::c_void,
+    resolve_frequency_ns: u64,
+}
+
 /// Mirrors `struct aws_client_bootstrap_options` from aws-c-io/channel_bootstrap.h.
 #[repr(C)]
 struct AwsClientBootstrapOptions {
     event_loop_group: *mut AwsEventLoopGroup,
     host_resolver: *mut AwsHostResolver,
-    host_resolution_config: *const std::ffi::c_void,
+    host_resolution_config: *const AwsHostResolutionConfig,
     on_shutdown_complete: *const std::ffi::c_void,
     user_data: *const std::ffi::c_void,
 }
@@ -93,6 +109,7 @@ extern "C" {
         options: *const AwsHostResolverDefaultOptions,
     ) -> *mut AwsHostResolver;
     fn aws_host_resolver_release(resolver: *mut AwsHostResolver);
+    fn aws_host_resolver_init_default_resolution_config() -> AwsHostResolutionConfig;
 
     fn aws_client_bootstrap_new(
         allocator: *mut AwsAllocator,
@@ -109,6 +126,61 @@ extern "C" {
     );
 }
 
+// ---------------------------------------------------------------------------
+// RuntimeConfig — settings applied when the runtime is first initialized
+// ---------------------------------------------------------------------------
+
+/// Process-wide settings consumed by `CrtRuntime::init`.
+///
+/// `None` fields keep the CRT defaults.
+#[derive(Clone, Copy, Debug)]
+pub struct RuntimeConfig {
+    /// Maximum time, in seconds, a resolved DNS record is cached by the
+    /// client bootstrap before it is re-resolved. CRT default: 30.
+    pub dns_ttl_secs: Option<usize>,
+    /// How many times a connection pool request whose DNS lookup failed
+    /// (`AWS_IO_DNS_QUERY_FAILED`) asks for a connection again. The CRT
+    /// resolver itself never retries a failed query. Default: 0.
+    pub dns_failure_retries: u32,
+    /// Delay before the first DNS retry, doubled for each later one.
+    pub dns_retry_backoff_ms: u64,
+}
+
+/// Default `RuntimeConfig::dns_retry_backoff_ms`.
+pub const DEFAULT_DNS_RETRY_BACKOFF_MS: u64 = 100;
+
+impl RuntimeConfig {
+    const DEFAULT: RuntimeConfig = RuntimeConfig {
+        dns_ttl_secs: None,
+        dns_failure_retries: 0,
+        dns_retry_backoff_ms: DEFAULT_DNS_RETRY_BACKOFF_MS,
+    };
+
+    /// Delay before DNS retry number `retry` (1-based).
+    pub fn dns_retry_delay(&self, retry: u32) -> std::time::Duration {
+        let factor = 1u64 << retry.saturating_sub(1).min(16);
+        std::time::Duration::from_millis(self.dns_retry_backoff_ms.saturating_mul(factor))
+    }
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Pending configuration plus a flag recording whether `init` has already
+/// consumed it. Both live under one lock so `configure` cannot race `init`.
+struct ConfigSlot {
+    config: RuntimeConfig,
+    applied: bool,
+}
+
+static CONFIG: Mutex<ConfigSlot> = Mutex::new(ConfigSlot {
+    config: RuntimeConfig::DEFAULT,
+    applied: false,
+});
+
 // ---------------------------------------------------------------------------
 // CrtRuntime — singleton holding shared CRT resources
 // ---------------------------------------------------------------------------
@@ -128,6 +200,8 @@ pub struct CrtRuntime {
     #[allow(dead_code)]
     host_resolver: *mut AwsHostResolver,
     client_bootstrap: *mut AwsClientBootstrap,
+    /// The settings the runtime was initialized with.
+    config: RuntimeConfig,
 }
 
 // The CRT resources are internally thread-safe (event loop group, host resolver,
@@ -151,6 +225,22 @@ impl CrtRuntime {
         RUNTIME.get_or_init(|| Self::init().expect("Failed to initialize CRT runtime"))
     }
 
+    /// Update the configuration used when the runtime is initialized.
+    ///
+    /// `update` edits the pending settings in place, so repeated calls only
+    /// change the fields each one touches. Returns `false` (and changes
+    /// nothing) if the runtime has already been initialized — the bootstrap
+    /// copies its settings at creation, so later changes would be silently
+    /// ignored.
+    pub fn configure(update: impl FnOnce(&mut RuntimeConfig)) -> bool {
+        let mut slot = CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+        if slot.applied {
+            return false;
+        }
+        update(&mut slot.config);
+        true
+    }
+
     /// Returns the shared allocator pointer.
     pub fn allocator(&self) -> *mut AwsAllocator {
         self.allocator
@@ -161,8 +251,19 @@ impl CrtRuntime {
         self.client_bootstrap
     }
 
+    /// Returns the settings the runtime was initialized with.
+    pub fn config(&self) -> &RuntimeConfig {
+        &self.config
+    }
+
     /// Initialize all CRT resources. Called exactly once by `OnceLock`.
     fn init() -> Result<CrtRuntime, CrtError> {
+        let config = {
+            let mut slot = CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+            slot.applied = true;
+            slot.config
+        };
+
         let allocator = unsafe { aws_default_allocator() };
 
         // aws_http_library_init transitively initializes:
@@ -203,11 +304,20 @@ impl CrtRuntime {
             return Err(CrtError::last_error());
         }
 
+        // Host resolution config — start from the CRT defaults and apply
+        // any overrides. The bootstrap copies this struct, so a stack value
+        // is fine.
+        let mut resolution_config =
+            unsafe { aws_host_resolver_init_default_resolution_config() };
+        if let Some(ttl) = config.dns_ttl_secs {
+            resolution_config.max_ttl = ttl;
+        }
+
         // Client bootstrap — binds the event loop group and host resolver
         let bootstrap_options = AwsClientBootstrapOptions {
             event_loop_group,
             host_resolver,
-            host_resolution_config: std::ptr::null(),
+            host_resolution_config: &resolution_config,
             on_shutdown_complete: std::ptr::null(),
             user_data: std::ptr::null(),
         };
@@ -232,6 +342,7 @@ impl CrtRuntime {
             event_loop_group,
             host_resolver,
             client_bootstrap,
+            config,
         })
     }
 }
@@ -8,10 +8,16 @@
 //! to ensure they outlive the underlying CRT client (which holds pointers
 //! into them). The shared CRT runtime resources (Event Loop Group, Host
 //! Resolver, Client Bootstrap) are obtained from `CrtRuntime::get()`.
+//!
+//! By default requests go to AWS (`<bucket>.s3.<region>.amazonaws.com`).
+//! A custom `S3Endpoint` sends every request to one host instead, with
+//! path-style addressing (`/<bucket>/<key>`), for S3-compatible services.
+//! `use_tls: false` (implied by an `http` endpoint) connects in plaintext.
 
-use crate::credentials::{AwsByteCursor, CredentialsProvider};
+use crate::credentials::{AwsByteCursor, CredentialsProvider, SecretString};
 use crate::error::CrtError;
 use crate::runtime::{AwsAllocator, AwsClientBootstrap, CrtRuntime};
+use crate::s3_request::TransferLimiter;
 use crate::signing::{AwsSigningConfigAws, SigningConfig};
 use crate::tls::{AwsTlsCtx, TlsContext, TlsOptions};
 
@@ -24,6 +30,14 @@ pub struct AwsS3Client {
     _opaque: [u8; 0],
 }
 
+/// `enum aws_s3_meta_request_tls_mode` values.
+const AWS_MR_TLS_ENABLED: u32 = 0;
+const AWS_MR_TLS_DISABLED: u32 = 1;
+
+/// `enum aws_s3_meta_request_compute_content_md5` values.
+const AWS_MR_CONTENT_MD5_DISABLED: u32 = 0;
+const AWS_MR_CONTENT_MD5_ENABLED: u32 = 1;
+
 // ---------------------------------------------------------------------------
 // TLS connection options buffer (reused from connection_manager pattern)
 // ---------------------------------------------------------------------------
@@ -37,6 +51,85 @@ struct TlsConnectionOptionsBuffer {
     _data: [u8; 128],
 }
 
+/// Opaque buffer for `struct aws_uri`.
+///
+/// The struct is 200 bytes on 64-bit targets; 256 leaves headroom, as with
+/// `TlsConnectionOptionsBuffer`.
+#[repr(C, align(8))]
+struct AwsUriBuffer {
+    _data: [u8; 256],
+}
+
+// ---------------------------------------------------------------------------
+// Custom endpoint
+// ---------------------------------------------------------------------------
+
+/// A custom endpoint (`scheme://host[:port]`) for S3-compatible services.
+pub struct S3Endpoint {
+    /// `http` or `https`.
+    pub scheme: String,
+    pub host: String,
+    pub port: u32,
+    /// The parsed `aws_uri` handed to each meta-request, so the CRT connects
+    /// to this host and port. Set by `S3Client::new`.
+    uri: Option<Box<AwsUriBuffer>>,
+}
+
+impl S3Endpoint {
+    pub fn new(scheme: String, host: String, port: u32) -> Self {
+        Self {
+            scheme,
+            host,
+            port,
+            uri: None,
+        }
+    }
+
+    /// Whether connections use TLS.
+    pub fn tls(&self) -> bool {
+        self.scheme == "https"
+    }
+
+    /// The `Host` header value: the host, plus the port unless it is the
+    /// scheme's default.
+    pub fn authority(&self) -> String {
+        let default_port = if self.tls() { 443 } else { 80 };
+        if self.port == default_port {
+            self.host.clone()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    /// Pointer to the parsed URI for `aws_s3_meta_request_options.endpoint`
+    /// (null before `S3Client::new` has parsed it).
+    pub fn uri_ptr(&self) -> *const std::ffi::c_void {
+        self.uri.as_ref().map_or(std::ptr::null(), |uri| {
+            &**uri as *const AwsUriBuffer as *const std::ffi::c_void
+        })
+    }
+
+    fn parse_uri(&mut self, allocator: *mut AwsAllocator) -> Result<(), CrtError> {
+        let text = format!("{}://{}:{}", self.scheme, self.host, self.port);
+        let cursor = AwsByteCursor::from_str(&text);
+        // aws_uri_init_parse copies the text into the URI's own buffer.
+        let mut uri = Box::new(AwsUriBuffer { _data: [0; 256] });
+        if unsafe { aws_uri_init_parse(&mut *uri, allocator, &cursor) } != 0 {
+            return Err(CrtError::last_error());
+        }
+        self.uri = Some(uri);
+        Ok(())
+    }
+}
+
+impl Drop for S3Endpoint {
+    fn drop(&mut self) {
+        if let Some(uri) = self.uri.as_mut() {
+            unsafe { aws_uri_clean_up(&mut **uri) };
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // aws_s3_client_config — full struct layout matching the C header
 // ---------------------------------------------------------------------------
@@ -116,6 +209,16 @@ extern "C" {
     /// is released.
     fn aws_s3_client_release(client: *mut AwsS3Client) -> *mut AwsS3Client;
 
+    /// Connections the client allows in flight at once: the count it
+    /// derived from `throughput_target_gbps`, capped by
+    /// `max_active_connections_override`. Declared in the CRT's private
+    /// client header but exported. A null meta-request asks for the
+    /// client-wide limit.
+    fn aws_s3_client_get_max_active_connections(
+        client: *mut AwsS3Client,
+        meta_request: *mut std::ffi::c_void,
+    ) -> u32;
+
     fn aws_tls_connection_options_init_from_ctx(
         conn_options: *mut TlsConnectionOptionsBuffer,
         ctx: *mut AwsTlsCtx,
@@ -124,13 +227,21 @@ extern "C" {
     fn aws_tls_connection_options_clean_up(
         conn_options: *mut TlsConnectionOptionsBuffer,
     );
+
+    fn aws_uri_init_parse(
+        uri: *mut AwsUriBuffer,
+        allocator: *mut AwsAllocator,
+        uri_str: *const AwsByteCursor,
+    ) -> i32;
+
+    fn aws_uri_clean_up(uri: *mut AwsUriBuffer);
 }
 
 // ---------------------------------------------------------------------------
 // S3 library initialization — called once via OnceLock
 // ---------------------------------------------------------------------------
 
-use std::sync::Once;
+use std::sync::{Arc, Mutex, Once};
 
 static S3_LIB_INIT: Once = Once::new();
 
@@ -151,16 +262,138 @@ fn ensure_s3_library_init() {
 // ---------------------------------------------------------------------------
 
 /// Configuration options for creating an S3 client.
+///
+/// Credential fields are `SecretString`s so they are zeroed when the options
+/// are dropped at the end of `S3Client::new`.
 pub struct S3ClientOptions {
     pub region: String,
-    pub access_key_id: String,
-    pub secret_access_key: String,
-    pub session_token: Option<String>,
+    pub access_key_id: SecretString,
+    pub secret_access_key: SecretString,
+    pub session_token: Option<SecretString>,
     pub throughput_target_gbps: f64,
     pub part_size: u64,
     pub multipart_upload_threshold: u64,
     pub memory_limit_in_bytes: u64,
     pub max_active_connections_override: u32,
+    /// Have the CRT compute and attach `Content-MD5` to every upload request.
+    pub compute_content_md5: bool,
+    /// Maximum meta-requests in flight at once across all threads
+    /// (0 = unlimited).
+    pub max_concurrent_transfers: usize,
+    /// Send every request to this endpoint, path-style, instead of AWS.
+    pub endpoint: Option<S3Endpoint>,
+    /// Connect with TLS. When false no TLS context is created and requests
+    /// go over plain HTTP (port 80 unless the endpoint says otherwise).
+    pub use_tls: bool,
+    /// Semicolon-delimited ALPN protocols offered in the TLS handshake
+    /// (e.g. "h2;http/1.1"); `None` offers none.
+    pub alpn_list: Option<String>,
+}
+
+/// The parts of `S3ClientOptions` passed to `aws_s3_client_new`, kept so
+/// the paced client can be created later with the same configuration.
+struct ClientSettings {
+    region: String,
+    throughput_target_gbps: f64,
+    part_size: u64,
+    multipart_upload_threshold: u64,
+    memory_limit_in_bytes: u64,
+    max_active_connections_override: u32,
+    compute_content_md5: bool,
+}
+
+/// The CRT's part size when none is configured.
+const DEFAULT_PART_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Parts of read window the paced client starts each GET with.
+const PACED_READ_WINDOW_PARTS: u64 = 2;
+
+/// Create an `aws_s3_client`. With `read_window`, the client has read
+/// backpressure and each GET starts with that many bytes of window.
+fn new_crt_client(
+    settings: &ClientSettings,
+    signing_config: &SigningConfig,
+    tls_ctx: Option<&TlsContext>,
+    read_window: Option<usize>,
+) -> Result<*mut AwsS3Client, CrtError> {
+    let rt = CrtRuntime::get();
+
+    // Initialize TLS connection options from the context
+    let mut tls_conn_opts =
+        std::mem::MaybeUninit::<TlsConnectionOptionsBuffer>::zeroed();
+    if let Some(tls_ctx) = tls_ctx {
+        let tls_conn_ptr = tls_conn_opts.as_mut_ptr();
+        unsafe {
+            aws_tls_connection_options_init_from_ctx(tls_conn_ptr, tls_ctx.as_ptr());
+        }
+    }
+
+    // Build the region byte cursor — must outlive the config struct
+    let region_cursor = AwsByteCursor::from_str(&settings.region);
+
+    // Build the S3 client config
+    let config = AwsS3ClientConfig {
+        max_active_connections_override: settings.max_active_connections_override,
+        _pad0: 0,
+        region: region_cursor,
+        client_bootstrap: rt.client_bootstrap(),
+        tls_mode: if tls_ctx.is_some() {
+            AWS_MR_TLS_ENABLED
+        } else {
+            AWS_MR_TLS_DISABLED
+        },
+        _pad1: 0,
+        tls_connection_options: if tls_ctx.is_some() {
+            tls_conn_opts.as_ptr()
+        } else {
+            std::ptr::null()
+        },
+        fio_opts: std::ptr::null(),
+        signing_config: signing_config.as_ptr(),
+        part_size: settings.part_size,
+        max_part_size: 0,
+        multipart_upload_threshold: settings.multipart_upload_threshold,
+        throughput_target_gbps: settings.throughput_target_gbps,
+        memory_limit_in_bytes: settings.memory_limit_in_bytes,
+        retry_strategy: std::ptr::null(),
+        compute_content_md5: if settings.compute_content_md5 {
+            AWS_MR_CONTENT_MD5_ENABLED
+        } else {
+            AWS_MR_CONTENT_MD5_DISABLED
+        },
+        _pad2: 0,
+        shutdown_callback: std::ptr::null(),
+        shutdown_callback_user_data: std::ptr::null(),
+        proxy_options: std::ptr::null(),
+        proxy_ev_settings: std::ptr::null(),
+        connect_timeout_ms: 0,
+        _pad3: 0,
+        tcp_keep_alive_options: std::ptr::null(),
+        monitoring_options: std::ptr::null(),
+        enable_read_backpressure: read_window.is_some(),
+        _pad4: [0; 7],
+        initial_read_window: read_window.unwrap_or(0),
+        enable_s3express: false,
+        _pad5: [0; 7],
+        s3express_provider_override_factory: std::ptr::null(),
+        factory_user_data: std::ptr::null(),
+        network_interface_names_array: std::ptr::null(),
+        num_network_interface_names: 0,
+        buffer_pool_factory_fn: std::ptr::null(),
+        buffer_pool_user_data: std::ptr::null(),
+    };
+
+    let client = unsafe { aws_s3_client_new(rt.allocator(), &config) };
+
+    // Clean up TLS connection options (the CRT deep-copies what it needs)
+    if tls_ctx.is_some() {
+        unsafe { aws_tls_connection_options_clean_up(tls_conn_opts.as_mut_ptr()) };
+    }
+
+    if client.is_null() {
+        return Err(CrtError::last_error());
+    }
+    Ok(client)
 }
 
 /// A CRT S3 client wrapping `aws_s3_client`.
@@ -171,13 +404,20 @@ pub struct S3ClientOptions {
 /// are obtained from `CrtRuntime::get()` and live for the process lifetime.
 pub struct S3Client {
     client: *mut AwsS3Client,
-    region: String,
+    /// A second CRT client with read backpressure, for `pipe_object`'s
+    /// GETs; null until first used.
+    paced_client: Mutex<*mut AwsS3Client>,
+    /// What the CRT clients were created with.
+    settings: ClientSettings,
     // Owned resources that must outlive the CRT client.
     // The CRT client holds pointers into these, so they must not be dropped
     // before the client is released.
     _credentials_provider: CredentialsProvider,
     signing_config: Box<SigningConfig>,
-    _tls_ctx: TlsContext,
+    /// `None` when `use_tls` is off.
+    tls_ctx: Option<TlsContext>,
+    endpoint: Option<S3Endpoint>,
+    limiter: Option<Arc<TransferLimiter>>,
 }
 
 // The CRT S3 client is internally thread-safe — it manages its own
@@ -191,7 +431,7 @@ impl S3Client {
     /// Initializes the S3 library (if not already done), creates a credentials
     /// provider and signing config from the provided options, sets up TLS,
     /// and creates the underlying CRT S3 client bound to the shared bootstrap.
-    pub fn new(options: S3ClientOptions) -> Result<Self, CrtError> {
+    pub fn new(mut options: S3ClientOptions) -> Result<Self, CrtError> {
         ensure_s3_library_init();
 
         let rt = CrtRuntime::get();
@@ -199,9 +439,9 @@ impl S3Client {
 
         // Create credentials provider
         let credentials_provider = CredentialsProvider::new_static(
-            &options.access_key_id,
-            &options.secret_access_key,
-            options.session_token.as_deref(),
+            options.access_key_id.as_str(),
+            options.secret_access_key.as_str(),
+            options.session_token.as_ref().map(SecretString::as_str),
         )?;
 
         // Create signing config (boxed so it has a stable address)
@@ -210,75 +450,42 @@ impl S3Client {
             &credentials_provider,
         )?);
 
-        // Create TLS context with default options (verify peer, platform-native TLS)
-        let tls_ctx = TlsContext::new(&TlsOptions::default())?;
-
-        // Initialize TLS connection options from the context
-        let mut tls_conn_opts =
-            std::mem::MaybeUninit::<TlsConnectionOptionsBuffer>::zeroed();
-        let tls_conn_ptr = tls_conn_opts.as_mut_ptr();
-        unsafe {
-            aws_tls_connection_options_init_from_ctx(tls_conn_ptr, tls_ctx.as_ptr());
+        if let Some(endpoint) = options.endpoint.as_mut() {
+            endpoint.parse_uri(allocator)?;
         }
 
-        // Build the region byte cursor — must outlive the config struct
-        let region_cursor = AwsByteCursor::from_str(&options.region);
+        // Create TLS context with default options (verify peer, platform-native
+        // TLS) plus any ALPN list, unless connections are plaintext
+        let tls_ctx = if options.use_tls {
+            Some(TlsContext::new(&TlsOptions {
+                alpn_list: options.alpn_list.take(),
+                ..TlsOptions::default()
+            })?)
+        } else {
+            None
+        };
 
-        // Build the S3 client config
-        let config = AwsS3ClientConfig {
-            max_active_connections_override: options.max_active_connections_override,
-            _pad0: 0,
-            region: region_cursor,
-            client_bootstrap: rt.client_bootstrap(),
-            tls_mode: 0, // AWS_MR_TLS_ENABLED = 0
-            _pad1: 0,
-            tls_connection_options: tls_conn_opts.as_ptr(),
-            fio_opts: std::ptr::null(),
-            signing_config: signing_config.as_ptr(),
+        let settings = ClientSettings {
+            region: options.region,
+            throughput_target_gbps: options.throughput_target_gbps,
             part_size: options.part_size,
-            max_part_size: 0,
             multipart_upload_threshold: options.multipart_upload_threshold,
-            throughput_target_gbps: options.throughput_target_gbps,
             memory_limit_in_bytes: options.memory_limit_in_bytes,
-            retry_strategy: std::ptr::null(),
-            compute_content_md5: 0, // AWS_MR_CONTENT_MD5_DISABLED
-            _pad2: 0,
-            shutdown_callback: std::ptr::null(),
-            shutdown_callback_user_data: std::ptr::null(),
-            proxy_options: std::ptr::null(),
-            proxy_ev_settings: std::ptr::null(),
-            connect_timeout_ms: 0,
-            _pad3: 0,
-            tcp_keep_alive_options: std::ptr::null(),
-            monitoring_options: std::ptr::null(),
-            enable_read_backpressure: false,
-            _pad4: [0; 7],
-            initial_read_window: 0,
-            enable_s3express: false,
-            _pad5: [0; 7],
-            s3express_provider_override_factory: std::ptr::null(),
-            factory_user_data: std::ptr::null(),
-            network_interface_names_array: std::ptr::null(),
-            num_network_interface_names: 0,
-            buffer_pool_factory_fn: std::ptr::null(),
-            buffer_pool_user_data: std::ptr::null(),
+            max_active_connections_override: options.max_active_connections_override,
+            compute_content_md5: options.compute_content_md5,
         };
-
-        let client = unsafe { aws_s3_client_new(allocator, &config) };
-
-        // Clean up TLS connection options (the CRT deep-copies what it needs)
-        unsafe { aws_tls_connection_options_clean_up(tls_conn_opts.as_mut_ptr()) };
-
-        if client.is_null() {
-            return Err(CrtError::last_error());
-        }
+        let client = new_crt_client(&settings, &signing_config, tls_ctx.as_ref(), None)?;
 
         Ok(S3Client {
             client,
-            region: options.region,
+            paced_client: Mutex::new(std::ptr::null_mut()),
+            settings,
             _credentials_provider: credentials_provider,
             signing_config,
-            _tls_ctx: tls_ctx,
+            tls_ctx,
+            endpoint: options.endpoint,
+            limiter: (options.max_concurrent_transfers > 0)
+                .then(|| Arc::new(TransferLimiter::new(options.max_concurrent_transfers))),
         })
     }
 
@@ -296,7 +503,57 @@ impl S3Client {
 
     /// Returns the region this client is configured for.
     pub fn region(&self) -> &str {
-        &self.region
+        &self.settings.region
+    }
+
+    /// Returns the custom endpoint, if the client was created with one.
+    pub fn endpoint(&self) -> Option<&S3Endpoint> {
+        self.endpoint.as_ref()
+    }
+
+    /// Whether requests are sent over TLS.
+    pub fn uses_tls(&self) -> bool {
+        self.tls_ctx.is_some()
+    }
+
+    /// Returns the number of connections the CRT will keep active at once
+    /// for this client.
+    pub fn max_active_connections(&self) -> u32 {
+        unsafe { aws_s3_client_get_max_active_connections(self.client, std::ptr::null_mut()) }
+    }
+
+    /// Returns the limiter shared by this client's meta-requests, if
+    /// `max_concurrent_transfers` was set.
+    pub fn transfer_limiter(&self) -> Option<&Arc<TransferLimiter>> {
+        self.limiter.as_ref()
+    }
+
+    /// Returns the part size the client was configured with, or 0 if the
+    /// CRT picks it.
+    pub fn part_size(&self) -> u64 {
+        self.settings.part_size
+    }
+
+    /// Returns the client used for GETs whose body is consumed at the
+    /// reader's pace (see `s3_request::pipe_object`), creating it on first
+    /// use. It shares this client's configuration, but the CRT only fetches
+    /// `PACED_READ_WINDOW_PARTS` parts beyond what has been consumed.
+    pub fn paced_client(&self) -> Result<*mut AwsS3Client, CrtError> {
+        let mut paced = self.paced_client.lock().unwrap();
+        if paced.is_null() {
+            let part_size = match self.settings.part_size {
+                0 => DEFAULT_PART_SIZE,
+                size => size,
+            };
+            let window = (part_size * PACED_READ_WINDOW_PARTS) as usize;
+            *paced = new_crt_client(
+                &self.settings,
+                &self.signing_config,
+                self.tls_ctx.as_ref(),
+                Some(window),
+            )?;
+        }
+        Ok(*paced)
     }
 }
 
@@ -309,6 +566,10 @@ impl Drop for S3Client {
         // needs from them during client creation.
         unsafe {
             aws_s3_client_release(self.client);
+            let paced = *self.paced_client.get_mut().unwrap();
+            if !paced.is_null() {
+                aws_s3_client_release(paced);
+            }
         }
     }
 }
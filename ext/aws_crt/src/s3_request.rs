@@ -21,14 +21,54 @@
 //! - `send_filepath`: CRT reads directly from file via parallel I/O (fastest)
 //! - Buffer (String): in-memory body bytes passed to CRT
 //! - Read+buffer (IO): IO contents read into memory, then passed to CRT
+//!
+//! **Other operations (default_request)**: a DEFAULT meta-request sent as a
+//! single HTTP request, with an optional in-memory body and a buffered
+//! response body. Used for the manual multipart upload operations.
+//!
+//! # Batched GETs
+//!
+//! `get_objects` keeps several GET meta-requests in flight at once from a
+//! single Ruby thread. Each meta-request still has its own shared state,
+//! but its finish callback also pushes the request's index onto a batch
+//! queue, so the Ruby thread waits (without the GVL) on one condvar for
+//! whichever request finishes next instead of blocking on each in turn.
+//!
+//! # Transfer limit
+//!
+//! A client built with `:max_concurrent_transfers` shares a
+//! `TransferLimiter` across all its operations. Each meta-request takes a
+//! permit before `aws_s3_client_make_meta_request` (waiting without the GVL
+//! while none are free) and `finish_callback` hands it back, so at most N
+//! transfers run at once no matter how many Ruby threads call in.
+//!
+//! # Piping
+//!
+//! `pipe_object` runs a GET and a PUT together, moving the GET's body into
+//! the PUT with `aws_s3_meta_request_write` as it arrives. The GET goes
+//! through a client with read backpressure, and its window is only widened
+//! as the PUT takes the data, so memory stays bounded by the window and
+//! the CRT's upload buffers however large the object.
+//!
+//! # Upload review
+//!
+//! For multipart uploads the CRT can pause before CompleteMultipartUpload
+//! and ask whether to proceed. The review callback runs on a CRT thread
+//! without the GVL, so it hands the part list to the waiting Ruby thread
+//! through the shared state and blocks until the Ruby thread (which
+//! re-acquires the GVL to run the caller's hook) posts a decision.
 
 use std::ffi::CString;
 use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::SystemTime;
 
+use crate::aws_chunked::{self, TrailerChecksum};
 use crate::credentials::AwsByteCursor;
 use crate::error::CrtError;
+use crate::http::clock_skew_ms;
 use crate::runtime::AwsAllocator;
-use crate::s3_client::AwsS3Client;
+use crate::s3_client::{AwsS3Client, S3Endpoint};
 use crate::signing::AwsSigningConfigAws;
 
 // ---------------------------------------------------------------------------
@@ -54,6 +94,12 @@ struct AwsInputStream {
     _opaque: [u8; 0],
 }
 
+/// Opaque `aws_future_void`, returned by `aws_s3_meta_request_write`.
+#[repr(C)]
+struct AwsFutureVoid {
+    _opaque: [u8; 0],
+}
+
 // ---------------------------------------------------------------------------
 // FFI struct mirrors
 // ---------------------------------------------------------------------------
@@ -68,6 +114,7 @@ struct AwsHttpHeader {
 }
 
 /// CRT S3 meta-request types.
+const AWS_S3_META_REQUEST_TYPE_DEFAULT: i32 = 0;
 const AWS_S3_META_REQUEST_TYPE_GET_OBJECT: i32 = 1;
 const AWS_S3_META_REQUEST_TYPE_PUT_OBJECT: i32 = 2;
 
@@ -84,6 +131,23 @@ const AWS_SCL_NONE: i32 = 0;
 const AWS_SCL_HEADER: i32 = 1;
 const AWS_SCL_TRAILER: i32 = 2;
 
+/// `AWS_ERROR_S3_CANCELED` from aws-c-s3/s3.h — raised by the upload review
+/// callback to abort a rejected upload.
+const AWS_ERROR_S3_CANCELED: i32 = 0x3800 + 11;
+
+/// `AWS_ERROR_S3_RESPONSE_CHECKSUM_MISMATCH` — a GET body did not match the
+/// checksum S3 sent with it.
+const AWS_ERROR_S3_RESPONSE_CHECKSUM_MISMATCH: i32 = 0x3800 + 14;
+
+/// Response checksum headers in the order the CRT picks one to validate.
+const RESPONSE_CHECKSUM_HEADERS: [(&str, &str); 5] = [
+    ("x-amz-checksum-crc64nvme", "CRC64NVME"),
+    ("x-amz-checksum-crc32c", "CRC32C"),
+    ("x-amz-checksum-crc32", "CRC32"),
+    ("x-amz-checksum-sha1", "SHA1"),
+    ("x-amz-checksum-sha256", "SHA256"),
+];
+
 /// Mirrors `struct aws_s3_checksum_config` from aws-c-s3/s3_client.h.
 ///
 /// Controls automatic checksum computation (for uploads) and validation
@@ -105,6 +169,37 @@ struct AwsS3ChecksumConfig {
     validate_checksum_algorithms: *const std::ffi::c_void,
 }
 
+/// Mirrors `struct aws_s3_file_io_options` from aws-c-s3/s3_client.h.
+///
+/// Tunes how the CRT does file I/O for `recv_filepath` / `send_filepath`
+/// transfers. Marked experimental upstream; all-zero is the default
+/// behavior, so a null `fio_opts` and a zeroed struct are equivalent.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AwsS3FileIoOptions {
+    /// Read file parts straight into the request instead of buffering each
+    /// part in memory first (uploads).
+    pub should_stream: bool,
+    _pad0: [u8; 7],
+    /// Expected disk throughput in Gbps, used to pace streamed parts so
+    /// connections aren't starved waiting on the disk. 0 = unset.
+    pub disk_throughput_gbps: f64,
+    /// Open files with O_DIRECT to bypass the page cache (Linux only).
+    pub direct_io: bool,
+    _pad1: [u8; 7],
+}
+
+impl AwsS3FileIoOptions {
+    pub fn new(should_stream: bool, disk_throughput_gbps: f64, direct_io: bool) -> Self {
+        Self {
+            should_stream,
+            disk_throughput_gbps,
+            direct_io,
+            ..Self::default()
+        }
+    }
+}
+
 /// Mirrors `struct aws_s3_meta_request_options` from aws-c-s3/s3_client.h.
 ///
 /// This is a large struct with many fields. We define the layout to match
@@ -116,6 +211,11 @@ struct AwsS3MetaRequestOptions {
     // 4 bytes padding to align aws_byte_cursor (8-byte aligned)
     _pad0: u32,
     // struct aws_byte_cursor operation_name
+    //
+    // Only read for AWS_S3_META_REQUEST_TYPE_DEFAULT, where it names the S3
+    // operation (e.g. "ListObjectsV2") for the CRT's retry handling and
+    // request metrics. GET_OBJECT and PUT_OBJECT name their own requests, so
+    // it stays empty here; a DEFAULT meta-request must set it.
     operation_name: AwsByteCursor,
     // const struct aws_signing_config_aws *
     signing_config: *const AwsSigningConfigAws,
@@ -135,7 +235,7 @@ struct AwsS3MetaRequestOptions {
     _pad2: [u8; 7],
     // struct aws_byte_cursor send_filepath
     send_filepath: AwsByteCursor,
-    // struct aws_s3_file_io_options *fio_opts
+    // const struct aws_s3_file_io_options *fio_opts
     fio_opts: *const std::ffi::c_void,
     // struct aws_async_input_stream *send_async_stream
     send_async_stream: *const std::ffi::c_void,
@@ -196,9 +296,21 @@ struct AwsS3MetaRequestOptions {
         ),
     >,
     // telemetry_callback
-    telemetry_callback: *const std::ffi::c_void,
+    telemetry_callback: Option<
+        unsafe extern "C" fn(
+            meta_request: *mut AwsS3MetaRequest,
+            metrics: *mut AwsS3RequestMetrics,
+            user_data: *mut std::ffi::c_void,
+        ),
+    >,
     // upload_review_callback
-    upload_review_callback: *const std::ffi::c_void,
+    upload_review_callback: Option<
+        unsafe extern "C" fn(
+            meta_request: *mut AwsS3MetaRequest,
+            review: *const AwsS3UploadReview,
+            user_data: *mut std::ffi::c_void,
+        ) -> i32,
+    >,
     // const struct aws_uri *endpoint
     endpoint: *const std::ffi::c_void,
     // struct aws_s3_meta_request_resume_token *resume_token
@@ -246,12 +358,45 @@ struct AwsS3MetaRequestProgress {
     content_length: u64,
 }
 
+/// Mirrors `struct aws_s3_upload_part_review`.
+#[repr(C)]
+struct AwsS3UploadPartReview {
+    size: u64,
+    /// Base64 checksum of the part (empty if no checksum algorithm).
+    checksum: AwsByteCursor,
+}
+
+/// Mirrors `struct aws_s3_upload_review`.
+#[repr(C)]
+struct AwsS3UploadReview {
+    checksum_algorithm: i32, // enum aws_s3_checksum_algorithm
+    // 4 bytes padding to align size_t
+    _pad0: u32,
+    part_count: usize,
+    part_array: *const AwsS3UploadPartReview,
+}
+
 /// Opaque CRT headers collection.
 #[repr(C)]
 struct AwsHttpHeaders {
     _opaque: [u8; 0],
 }
 
+/// Opaque `struct aws_s3_request_metrics`, read through its accessors.
+#[repr(C)]
+struct AwsS3RequestMetrics {
+    _opaque: [u8; 0],
+}
+
+/// Mirrors `struct aws_string` from aws-c-common: a length-prefixed byte
+/// string whose bytes follow the header.
+#[repr(C)]
+struct AwsString {
+    allocator: *mut AwsAllocator,
+    len: usize,
+    bytes: [u8; 0],
+}
+
 /// Mirrors `struct aws_byte_buf` from aws-c-common.
 #[repr(C)]
 struct AwsByteBuf {
@@ -306,6 +451,21 @@ extern "C" {
     fn aws_s3_meta_request_release(
         meta_request: *mut AwsS3MetaRequest,
     ) -> *mut AwsS3MetaRequest;
+    fn aws_s3_meta_request_cancel(meta_request: *mut AwsS3MetaRequest);
+    fn aws_s3_meta_request_increment_read_window(
+        meta_request: *mut AwsS3MetaRequest,
+        bytes: u64,
+    );
+
+    // Async writes (send_using_async_writes)
+    fn aws_s3_meta_request_write(
+        meta_request: *mut AwsS3MetaRequest,
+        data: AwsByteCursor,
+        eof: bool,
+    ) -> *mut AwsFutureVoid;
+    fn aws_future_void_wait(future: *mut AwsFutureVoid, timeout_ns: u64) -> bool;
+    fn aws_future_void_get_error(future: *const AwsFutureVoid) -> i32;
+    fn aws_future_void_release(future: *mut AwsFutureVoid) -> *mut AwsFutureVoid;
 
     // HTTP headers iteration
     fn aws_http_headers_count(
@@ -316,6 +476,42 @@ extern "C" {
         index: usize,
         out_header: *mut AwsHttpHeader,
     ) -> i32;
+    fn aws_http_headers_get(
+        headers: *const AwsHttpHeaders,
+        name: AwsByteCursor,
+        out_value: *mut AwsByteCursor,
+    ) -> i32;
+
+    // Per-request metrics (telemetry_callback)
+    fn aws_s3_request_metrics_get_operation_name(
+        metrics: *const AwsS3RequestMetrics,
+        out_operation_name: *mut *const AwsString,
+    ) -> i32;
+    fn aws_s3_request_metrics_get_response_status_code(
+        metrics: *const AwsS3RequestMetrics,
+        out_response_status: *mut i32,
+    ) -> i32;
+    fn aws_s3_request_metrics_get_error_code(metrics: *const AwsS3RequestMetrics) -> i32;
+    fn aws_s3_request_metrics_get_connection_id(
+        metrics: *const AwsS3RequestMetrics,
+        out_connection_id: *mut usize,
+    ) -> i32;
+    fn aws_s3_request_metrics_get_start_timestamp_ns(
+        metrics: *const AwsS3RequestMetrics,
+        out_start_time: *mut u64,
+    );
+    fn aws_s3_request_metrics_get_total_duration_ns(
+        metrics: *const AwsS3RequestMetrics,
+        out_total_duration: *mut u64,
+    );
+    fn aws_s3_request_metrics_get_request_headers(
+        metrics: *const AwsS3RequestMetrics,
+        out_request_headers: *mut *const AwsHttpHeaders,
+    ) -> i32;
+    fn aws_s3_request_metrics_get_response_headers(
+        metrics: *const AwsS3RequestMetrics,
+        out_response_headers: *mut *const AwsHttpHeaders,
+    ) -> i32;
 
     // Ruby GVL management
     fn rb_thread_call_without_gvl(
@@ -331,6 +527,9 @@ extern "C" {
     fn aws_get_checksum_algorithm_name(
         algorithm: i32,
     ) -> AwsByteCursor;
+
+    // Set the thread-local CRT error (the non-inline half of aws_raise_error)
+    fn aws_raise_error_private(err: i32);
 }
 
 // ---------------------------------------------------------------------------
@@ -360,6 +559,24 @@ struct MetaRequestState {
     checksum_validated: Option<String>,
     /// Total bytes transferred (updated by progress_callback).
     bytes_transferred: u64,
+    /// Whether an upload review waits for a decision from the Ruby thread
+    /// (an `on_upload_review` hook was given) or is only recorded.
+    hold_review: bool,
+    /// Upload review waiting for a decision from the Ruby thread.
+    pending_review: Option<UploadReview>,
+    /// Parts recorded by an upload review that did not wait for a decision.
+    reviewed_parts: Option<Vec<UploadPartReview>>,
+    /// Decision posted by the Ruby thread for `pending_review`.
+    review_decision: Option<bool>,
+    /// Batch to notify on completion (`get_objects` only).
+    batch: Option<BatchSlot>,
+    /// Transfer permit held while the CRT runs this meta-request.
+    permit: Option<Arc<TransferLimiter>>,
+    /// Metrics for each HTTP request the CRT made, when telemetry was
+    /// requested.
+    telemetry: Option<Vec<RequestTelemetry>>,
+    /// From the response's `Date` header, as it arrived.
+    clock_skew_ms: Option<i64>,
     /// Set to true when finish_callback fires.
     complete: bool,
 }
@@ -369,6 +586,16 @@ unsafe impl Send for MetaRequestState {}
 
 type SharedState = Arc<(Mutex<MetaRequestState>, Condvar)>;
 
+/// Queue of finished request indices, shared by every meta-request in a
+/// `get_objects` batch.
+type BatchSignal = Arc<(Mutex<Vec<usize>>, Condvar)>;
+
+/// A meta-request's position in a batch.
+struct BatchSlot {
+    signal: BatchSignal,
+    index: usize,
+}
+
 // ---------------------------------------------------------------------------
 // CRT callbacks (run on CRT event loop threads)
 // ---------------------------------------------------------------------------
@@ -407,6 +634,9 @@ unsafe extern "C" fn headers_callback(
                     std::slice::from_raw_parts(header.value.ptr, header.value.len),
                 )
                 .to_string();
+                if name.eq_ignore_ascii_case("date") {
+                    guard.clock_skew_ms = clock_skew_ms(&value, SystemTime::now());
+                }
                 guard.headers.push((name, value));
             }
         }
@@ -434,6 +664,35 @@ unsafe extern "C" fn body_callback(
     0 // AWS_OP_SUCCESS
 }
 
+/// Signature of `aws_s3_meta_request_options.body_callback`.
+type BodyCallback = unsafe extern "C" fn(
+    meta_request: *mut AwsS3MetaRequest,
+    body: *const AwsByteCursor,
+    range_start: u64,
+    user_data: *mut std::ffi::c_void,
+) -> i32;
+
+/// Called per body chunk of a piped GET.
+///
+/// Appends the chunk like `body_callback` and wakes the Ruby thread to pass
+/// it on. The client's read window bounds how much can pile up.
+unsafe extern "C" fn pipe_body_callback(
+    _meta_request: *mut AwsS3MetaRequest,
+    body: *const AwsByteCursor,
+    _range_start: u64,
+    user_data: *mut std::ffi::c_void,
+) -> i32 {
+    let state = &*(user_data as *const SharedState);
+    let cursor = &*body;
+    let bytes = std::slice::from_raw_parts(cursor.ptr, cursor.len);
+
+    let mut guard = state.0.lock().unwrap();
+    guard.body.extend_from_slice(bytes);
+    state.1.notify_one();
+
+    0 // AWS_OP_SUCCESS
+}
+
 /// Called once when the meta-request completes (success or failure).
 ///
 /// Sets the error code, captures error response data if present, records
@@ -450,8 +709,9 @@ unsafe extern "C" fn finish_callback(
     let mut guard = state.0.lock().unwrap();
     guard.error_code = r.error_code;
 
-    // Capture error response data if present
-    if r.response_status >= 400 {
+    // Capture error response data if present. Redirects count too: a 301 or
+    // 307 for a bucket in another region carries `x-amz-bucket-region`.
+    if r.response_status >= 300 {
         guard.error_response_status = r.response_status;
 
         // Extract error response headers
@@ -507,6 +767,20 @@ unsafe extern "C" fn finish_callback(
 
     guard.complete = true;
     state.1.notify_one();
+
+    // Take the batch handle and permit and drop our lock before signalling:
+    // once the index is queued the Ruby thread may free this request's state.
+    let batch = guard.batch.take();
+    let permit = guard.permit.take();
+    drop(guard);
+    if let Some(limiter) = permit {
+        limiter.release();
+    }
+    if let Some(slot) = batch {
+        let (queue, cvar) = &*slot.signal;
+        queue.lock().unwrap().push(slot.index);
+        cvar.notify_one();
+    }
 }
 
 /// Called with progress updates (bytes transferred).
@@ -522,6 +796,166 @@ unsafe extern "C" fn progress_callback(
     guard.bytes_transferred += p.bytes_transferred;
 }
 
+/// Metrics for one HTTP request (a part, or a control request such as
+/// CreateMultipartUpload) of a meta-request, reported by the CRT's
+/// telemetry callback. Failed attempts that the CRT retried appear too,
+/// with a non-zero `error_code`.
+#[derive(Clone, Debug)]
+pub struct RequestTelemetry {
+    /// S3 operation, e.g. `UploadPart` or `GetObject`.
+    pub operation: Option<String>,
+    pub status_code: Option<i32>,
+    pub error_code: i32,
+    /// Payload bytes: the request's `Content-Length` for uploads, the
+    /// response's for downloads.
+    pub bytes: Option<u64>,
+    pub connection_id: Option<usize>,
+    /// Start time on the CRT's monotonic clock.
+    pub start_ns: u64,
+    pub duration_ns: u64,
+}
+
+/// Read a header's value from a CRT headers collection.
+unsafe fn metrics_header(headers: *const AwsHttpHeaders, name: &str) -> Option<String> {
+    if headers.is_null() {
+        return None;
+    }
+    let mut value = AwsByteCursor { len: 0, ptr: std::ptr::null() };
+    if aws_http_headers_get(headers, AwsByteCursor::from_str(name), &mut value) != 0 {
+        return None;
+    }
+    std::str::from_utf8(std::slice::from_raw_parts(value.ptr, value.len))
+        .ok()
+        .map(str::to_string)
+}
+
+/// Called after each HTTP request the CRT makes for a meta-request.
+unsafe extern "C" fn telemetry_callback(
+    _meta_request: *mut AwsS3MetaRequest,
+    metrics: *mut AwsS3RequestMetrics,
+    user_data: *mut std::ffi::c_void,
+) {
+    let state = &*(user_data as *const SharedState);
+
+    let mut operation: *const AwsString = std::ptr::null();
+    let operation = if aws_s3_request_metrics_get_operation_name(metrics, &mut operation) == 0
+        && !operation.is_null()
+    {
+        let op = &*operation;
+        std::str::from_utf8(std::slice::from_raw_parts(op.bytes.as_ptr(), op.len))
+            .ok()
+            .map(str::to_string)
+    } else {
+        None
+    };
+
+    let mut status = 0i32;
+    let status_code =
+        (aws_s3_request_metrics_get_response_status_code(metrics, &mut status) == 0).then_some(status);
+    let mut connection = 0usize;
+    let connection_id =
+        (aws_s3_request_metrics_get_connection_id(metrics, &mut connection) == 0).then_some(connection);
+    let mut start_ns = 0u64;
+    aws_s3_request_metrics_get_start_timestamp_ns(metrics, &mut start_ns);
+    let mut duration_ns = 0u64;
+    aws_s3_request_metrics_get_total_duration_ns(metrics, &mut duration_ns);
+
+    // Uploads carry the payload in the request, downloads in the response.
+    let mut headers: *const AwsHttpHeaders = std::ptr::null();
+    let found = if operation.as_deref() == Some("GetObject") {
+        aws_s3_request_metrics_get_response_headers(metrics, &mut headers)
+    } else {
+        aws_s3_request_metrics_get_request_headers(metrics, &mut headers)
+    };
+    let bytes = if found == 0 {
+        metrics_header(headers, "Content-Length").and_then(|v| v.parse().ok())
+    } else {
+        None
+    };
+
+    let record = RequestTelemetry {
+        operation,
+        status_code,
+        error_code: aws_s3_request_metrics_get_error_code(metrics),
+        bytes,
+        connection_id,
+        start_ns,
+        duration_ns,
+    };
+    if let Some(telemetry) = state.0.lock().unwrap().telemetry.as_mut() {
+        telemetry.push(record);
+    }
+}
+
+/// Look up the CRT's name for a checksum algorithm (e.g. "CRC32").
+unsafe fn checksum_algorithm_name(algorithm: i32) -> Option<String> {
+    if algorithm == AWS_SCA_NONE {
+        return None;
+    }
+    let cursor = aws_get_checksum_algorithm_name(algorithm);
+    if cursor.ptr.is_null() || cursor.len == 0 {
+        return None;
+    }
+    std::str::from_utf8(std::slice::from_raw_parts(cursor.ptr, cursor.len))
+        .ok()
+        .map(str::to_string)
+}
+
+/// Called before the CRT completes a multipart upload.
+///
+/// Copies the part list into shared state. Without a review hook that is
+/// all, and the upload completes. With one, wakes the Ruby thread and
+/// blocks this CRT thread until the Ruby thread posts a decision. Returning
+/// an error aborts the upload.
+unsafe extern "C" fn upload_review_callback(
+    _meta_request: *mut AwsS3MetaRequest,
+    review: *const AwsS3UploadReview,
+    user_data: *mut std::ffi::c_void,
+) -> i32 {
+    let state = &*(user_data as *const SharedState);
+    let r = &*review;
+
+    let parts = (0..r.part_count)
+        .map(|i| {
+            let part = &*r.part_array.add(i);
+            let checksum = if part.checksum.ptr.is_null() || part.checksum.len == 0 {
+                None
+            } else {
+                std::str::from_utf8(std::slice::from_raw_parts(
+                    part.checksum.ptr,
+                    part.checksum.len,
+                ))
+                .ok()
+                .map(str::to_string)
+            };
+            UploadPartReview { size: part.size, checksum }
+        })
+        .collect();
+
+    let (lock, cvar) = &**state;
+    let mut guard = lock.lock().unwrap();
+    if !guard.hold_review {
+        guard.reviewed_parts = Some(parts);
+        return 0; // AWS_OP_SUCCESS
+    }
+    guard.pending_review = Some(UploadReview {
+        checksum_algorithm: checksum_algorithm_name(r.checksum_algorithm),
+        parts,
+    });
+    cvar.notify_all();
+
+    while guard.review_decision.is_none() {
+        guard = cvar.wait(guard).unwrap();
+    }
+
+    if guard.review_decision.take().unwrap_or(false) {
+        0 // AWS_OP_SUCCESS
+    } else {
+        aws_raise_error_private(AWS_ERROR_S3_CANCELED);
+        -1 // AWS_OP_ERR
+    }
+}
+
 // ---------------------------------------------------------------------------
 // GVL release wrapper
 // ---------------------------------------------------------------------------
@@ -532,7 +966,8 @@ struct WaitData {
 }
 
 /// Called without the GVL — blocks on the condvar until the meta-request
-/// completes. Same pattern as `http.rs`.
+/// completes (or an upload review needs the Ruby thread). Same pattern as
+/// `http.rs`.
 unsafe extern "C" fn wait_for_completion(
     data: *mut std::ffi::c_void,
 ) -> *mut std::ffi::c_void {
@@ -540,23 +975,172 @@ unsafe extern "C" fn wait_for_completion(
     let (lock, cvar) = &*wait_data.state;
 
     let mut guard = lock.lock().unwrap();
-    while !guard.complete {
+    while !guard.complete && guard.pending_review.is_none() {
+        guard = cvar.wait(guard).unwrap();
+    }
+
+    std::ptr::null_mut()
+}
+
+/// Release the GVL and wait for the meta-request that owns `state` to
+/// finish.
+fn wait_for_finish(state: &SharedState) {
+    let wait_data = WaitData {
+        state: Arc::clone(state),
+    };
+    unsafe {
+        rb_thread_call_without_gvl(
+            wait_for_completion,
+            &wait_data as *const WaitData as *mut std::ffi::c_void,
+            std::ptr::null(),
+            std::ptr::null(),
+        );
+    }
+}
+
+/// Called without the GVL — blocks until a piped GET has delivered body
+/// bytes or finished.
+unsafe extern "C" fn wait_for_pipe_data(data: *mut std::ffi::c_void) -> *mut std::ffi::c_void {
+    let wait_data = &*(data as *const WaitData);
+    let (lock, cvar) = &*wait_data.state;
+
+    let mut guard = lock.lock().unwrap();
+    while !guard.complete && guard.body.is_empty() {
+        guard = cvar.wait(guard).unwrap();
+    }
+
+    std::ptr::null_mut()
+}
+
+/// Nanoseconds per `aws_future_void_wait` call in `wait_for_future`.
+const FUTURE_WAIT_NS: u64 = 1_000_000_000;
+
+/// Called without the GVL — blocks until an `aws_future_void` completes.
+unsafe extern "C" fn wait_for_future(data: *mut std::ffi::c_void) -> *mut std::ffi::c_void {
+    let future = data as *mut AwsFutureVoid;
+    while !aws_future_void_wait(future, FUTURE_WAIT_NS) {}
+
+    std::ptr::null_mut()
+}
+
+/// Data passed to `wait_for_batch`.
+struct BatchWaitData {
+    signal: BatchSignal,
+}
+
+/// Called without the GVL — blocks until at least one request in the batch
+/// has finished.
+unsafe extern "C" fn wait_for_batch(data: *mut std::ffi::c_void) -> *mut std::ffi::c_void {
+    let wait_data = &*(data as *const BatchWaitData);
+    let (queue, cvar) = &*wait_data.signal;
+
+    let mut guard = queue.lock().unwrap();
+    while guard.is_empty() {
         guard = cvar.wait(guard).unwrap();
     }
 
     std::ptr::null_mut()
 }
 
+// ---------------------------------------------------------------------------
+// Transfer limiter
+// ---------------------------------------------------------------------------
+
+/// Counting semaphore capping how many meta-requests a client has in flight.
+pub struct TransferLimiter {
+    available: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl TransferLimiter {
+    /// A limiter allowing `max_transfers` meta-requests at once.
+    pub fn new(max_transfers: usize) -> Self {
+        Self {
+            available: Mutex::new(max_transfers),
+            freed: Condvar::new(),
+        }
+    }
+
+    fn release(&self) {
+        *self.available.lock().unwrap() += 1;
+        self.freed.notify_one();
+    }
+}
+
+/// Called without the GVL — blocks until a permit is free and takes it.
+unsafe extern "C" fn wait_for_permit(data: *mut std::ffi::c_void) -> *mut std::ffi::c_void {
+    let limiter = &*(data as *const TransferLimiter);
+
+    let mut available = limiter.available.lock().unwrap();
+    while *available == 0 {
+        available = limiter.freed.wait(available).unwrap();
+    }
+    *available -= 1;
+
+    std::ptr::null_mut()
+}
+
+/// Take a permit for the meta-request that owns `state`, if the client has
+/// a limiter. `finish_callback` returns it; if the meta-request is never
+/// created, `return_unused_permit` must be called instead.
+fn acquire_permit(limiter: Option<&Arc<TransferLimiter>>, state: &SharedState) {
+    let Some(limiter) = limiter else { return };
+    unsafe {
+        rb_thread_call_without_gvl(
+            wait_for_permit,
+            Arc::as_ptr(limiter) as *mut std::ffi::c_void,
+            std::ptr::null(),
+            std::ptr::null(),
+        );
+    }
+    state.0.lock().unwrap().permit = Some(Arc::clone(limiter));
+}
+
+fn return_unused_permit(state: &SharedState) {
+    let permit = state.0.lock().unwrap().permit.take();
+    if let Some(limiter) = permit {
+        limiter.release();
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Response type
 // ---------------------------------------------------------------------------
 
+/// A part of a multipart upload, as presented for review.
+pub struct UploadPartReview {
+    pub size: u64,
+    /// Base64 checksum of the part, if a checksum algorithm was used.
+    pub checksum: Option<String>,
+}
+
+/// The parts of a multipart upload awaiting approval before completion.
+pub struct UploadReview {
+    /// Checksum algorithm name (e.g. "CRC32"), if one was used.
+    pub checksum_algorithm: Option<String>,
+    pub parts: Vec<UploadPartReview>,
+}
+
+/// Upload review hook: returns `true` to complete the upload, `false` to
+/// abort it. Invoked on the Ruby thread with the GVL held.
+pub type UploadReviewFn<'a> = &'a mut dyn FnMut(&UploadReview) -> bool;
+
 /// The result of an S3 meta-request.
 pub struct S3Response {
     pub status_code: i32,
     pub headers: Vec<(String, String)>,
+    /// Buffered response body. `None` when the body was not buffered
+    /// (recv_filepath mode, or a PUT); `Some(empty)` for a zero-byte object.
     pub body: Option<Vec<u8>>,
     pub checksum_validated: Option<String>,
+    /// Per-request metrics, when telemetry was requested.
+    pub telemetry: Option<Vec<RequestTelemetry>>,
+    /// The parts of a multipart upload with a checksum algorithm, in part
+    /// number order, as the CRT reported them before completing it.
+    pub parts: Option<Vec<UploadPartReview>>,
+    /// How far S3's clock was ahead of ours, in milliseconds, from the
+    /// `Date` header (see `http::clock_skew_ms`).
+    pub clock_skew_ms: Option<i64>,
 }
 
 /// Error data from a failed S3 meta-request.
@@ -569,6 +1153,40 @@ pub struct S3ErrorData {
     pub headers: Vec<(String, String)>,
     /// Body from the error response (typically S3 XML error).
     pub body: Vec<u8>,
+    /// Details of a failed response checksum validation.
+    pub checksum_mismatch: Option<Box<ChecksumMismatch>>,
+}
+
+/// Expected vs computed checksum for `AWS_ERROR_S3_RESPONSE_CHECKSUM_MISMATCH`.
+///
+/// The CRT reports only the error code, so this is rebuilt from the
+/// response: `expected` is the `x-amz-checksum-*` header the CRT validated
+/// against, and `computed` is recalculated over the buffered body. It is
+/// `None` when the body went to a file, or for SHA algorithms.
+pub struct ChecksumMismatch {
+    pub algorithm: String,
+    pub expected: String,
+    pub computed: Option<String>,
+}
+
+impl ChecksumMismatch {
+    fn from_response(headers: &[(String, String)], body: Option<&[u8]>) -> Option<Box<Self>> {
+        let (algorithm, expected) = RESPONSE_CHECKSUM_HEADERS.iter().find_map(|(header, algorithm)| {
+            headers
+                .iter()
+                .find(|(name, value)| name.eq_ignore_ascii_case(header) && !value.contains('-'))
+                .map(|(_, value)| (*algorithm, value.clone()))
+        })?;
+        let computed = match (body, TrailerChecksum::from_name(algorithm)) {
+            (Some(body), Some(crc)) => Some(aws_chunked::checksum_base64(body, crc)),
+            _ => None,
+        };
+        Some(Box::new(Self {
+            algorithm: algorithm.to_string(),
+            expected,
+            computed,
+        }))
+    }
 }
 
 /// Result type for S3 operations — either a successful response or error data.
@@ -578,15 +1196,41 @@ pub type S3Result = Result<S3Response, S3ErrorData>;
 // HTTP request message builder
 // ---------------------------------------------------------------------------
 
+/// Bucket-ownership and billing headers that apply to every S3 operation.
+/// `build_s3_request_message` adds them, so each operation sends them the
+/// same way.
+#[derive(Clone, Copy, Default)]
+pub struct GovernanceHeaders<'a> {
+    /// `x-amz-expected-bucket-owner`: the account ID that must own the
+    /// bucket, or S3 answers 403.
+    pub expected_bucket_owner: Option<&'a str>,
+    /// `x-amz-request-payer`: `requester` to accept the charges of a
+    /// Requester Pays bucket.
+    pub request_payer: Option<&'a str>,
+}
+
+impl GovernanceHeaders<'_> {
+    fn pairs(&self) -> [(&'static str, Option<&str>); 2] {
+        [
+            ("x-amz-expected-bucket-owner", self.expected_bucket_owner),
+            ("x-amz-request-payer", self.request_payer),
+        ]
+    }
+}
+
 /// Build a CRT HTTP request message for an S3 operation.
 ///
 /// Sets the method, path (/<key>), and Host header using the virtual-hosted
-/// style endpoint: `<bucket>.s3.<region>.amazonaws.com`.
+/// style endpoint: `<bucket>.s3.<region>.amazonaws.com`, followed by the
+/// governance headers and then `extra_headers`. With a custom `endpoint` the
+/// request is path-style instead: path `/<bucket>/<key>`, Host the endpoint's.
 fn build_s3_request_message(
     method: &str,
     bucket: &str,
     key: &str,
     region: &str,
+    endpoint: Option<&S3Endpoint>,
+    governance: GovernanceHeaders,
     extra_headers: &[(String, String)],
 ) -> Result<*mut AwsHttpMessage, CrtError> {
     let allocator = unsafe { aws_default_allocator() };
@@ -609,14 +1253,21 @@ fn build_s3_request_message(
     } else {
         format!("/{}", key)
     };
+    let path = match endpoint {
+        Some(_) => format!("/{}{}", bucket, path),
+        None => path,
+    };
     let path_cursor = AwsByteCursor::from_str(&path);
     if unsafe { aws_http_message_set_request_path(request, path_cursor) } != 0 {
         unsafe { aws_http_message_release(request) };
         return Err(CrtError::last_error());
     }
 
-    // Set Host header — virtual-hosted style
-    let host = format!("{}.s3.{}.amazonaws.com", bucket, region);
+    // Set Host header — virtual-hosted style unless there's an endpoint
+    let host = match endpoint {
+        Some(endpoint) => endpoint.authority(),
+        None => format!("{}.s3.{}.amazonaws.com", bucket, region),
+    };
     let host_header = AwsHttpHeader {
         name: AwsByteCursor::from_str("Host"),
         value: AwsByteCursor::from_str(&host),
@@ -628,8 +1279,14 @@ fn build_s3_request_message(
         return Err(CrtError::last_error());
     }
 
-    // Add extra headers (Content-Type, Content-Length, etc.)
-    for (name, value) in extra_headers {
+    // Add governance headers, then extra headers (Content-Type,
+    // Content-Length, etc.)
+    let governance_headers = governance
+        .pairs()
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)));
+    let extra = extra_headers.iter().map(|(n, v)| (n.as_str(), v.as_str()));
+    for (name, value) in governance_headers.chain(extra) {
         let header = AwsHttpHeader {
             name: AwsByteCursor::from_str(name),
             value: AwsByteCursor::from_str(value),
@@ -645,6 +1302,31 @@ fn build_s3_request_message(
     Ok(request)
 }
 
+/// Percent-encode a string per RFC 3986, leaving only unreserved
+/// characters (`A-Z a-z 0-9 - _ . ~`) as-is.
+fn uri_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for &b in s.as_bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+/// Encode object tags as an `x-amz-tagging` header value.
+///
+/// Produces `key1=value1&key2=value2` with keys and values percent-encoded,
+/// preserving the order of `tags`.
+pub fn encode_tagging(tags: &[(String, String)]) -> String {
+    tags.iter()
+        .map(|(k, v)| format!("{}={}", uri_encode(k), uri_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
 // ---------------------------------------------------------------------------
 // Checksum configuration helper
 // ---------------------------------------------------------------------------
@@ -679,6 +1361,14 @@ fn new_shared_state() -> SharedState {
             error_response_body: Vec::new(),
             checksum_validated: None,
             bytes_transferred: 0,
+            hold_review: false,
+            pending_review: None,
+            reviewed_parts: None,
+            review_decision: None,
+            batch: None,
+            permit: None,
+            telemetry: None,
+            clock_skew_ms: None,
             complete: false,
         }),
         Condvar::new(),
@@ -693,11 +1383,17 @@ fn extract_result(state: &SharedState, include_body: bool) -> S3Result {
 
     // Check for CRT-level errors (network failures, etc.)
     if guard.error_code != 0 {
+        let checksum_mismatch = if guard.error_code == AWS_ERROR_S3_RESPONSE_CHECKSUM_MISMATCH {
+            ChecksumMismatch::from_response(&guard.headers, include_body.then_some(&guard.body[..]))
+        } else {
+            None
+        };
         return Err(S3ErrorData {
             error_code: guard.error_code,
             status_code: guard.error_response_status,
             headers: std::mem::take(&mut guard.error_response_headers),
             body: std::mem::take(&mut guard.error_response_body),
+            checksum_mismatch,
         });
     }
 
@@ -708,12 +1404,13 @@ fn extract_result(state: &SharedState, include_body: bool) -> S3Result {
             status_code: guard.error_response_status,
             headers: std::mem::take(&mut guard.error_response_headers),
             body: std::mem::take(&mut guard.error_response_body),
+            checksum_mismatch: None,
         });
     }
 
+    // An empty buffer is a legitimate zero-byte object, not a missing body.
     let body = if include_body {
-        let b = std::mem::take(&mut guard.body);
-        if b.is_empty() { None } else { Some(b) }
+        Some(std::mem::take(&mut guard.body))
     } else {
         None
     };
@@ -723,6 +1420,9 @@ fn extract_result(state: &SharedState, include_body: bool) -> S3Result {
         headers: std::mem::take(&mut guard.headers),
         body,
         checksum_validated: guard.checksum_validated.take(),
+        telemetry: guard.telemetry.take(),
+        parts: None,
+        clock_skew_ms: guard.clock_skew_ms,
     })
 }
 
@@ -737,38 +1437,84 @@ pub struct GetObjectOptions<'a> {
     pub bucket: &'a str,
     pub key: &'a str,
     pub region: &'a str,
+    /// The client's custom endpoint, if any.
+    pub endpoint: Option<&'a S3Endpoint>,
     /// If set, CRT writes directly to this file path (recv_filepath mode).
     pub recv_filepath: Option<&'a str>,
+    /// File I/O tuning for `recv_filepath` (CRT defaults when `None`).
+    pub file_io: Option<AwsS3FileIoOptions>,
     /// Whether to validate the response checksum.
     pub validate_checksum: bool,
+    /// `Range` header value, e.g. `bytes=1024-` (optional).
+    pub range: Option<&'a str>,
+    /// `If-Range` header value, an ETag or HTTP date (optional). With it the
+    /// server answers 206 with the range if the object is unchanged, or 200
+    /// with the whole object if it changed.
+    pub if_range: Option<&'a str>,
+    /// Bucket-owner and request-payer headers.
+    pub governance: GovernanceHeaders<'a>,
+    /// The client's transfer limiter, if it has one.
+    pub limiter: Option<&'a Arc<TransferLimiter>>,
+    /// Collect per-request metrics from the CRT's telemetry callback.
+    pub telemetry: bool,
 }
 
-/// Execute a GET_OBJECT meta-request.
-///
-/// Builds the HTTP request, configures the meta-request with the appropriate
-/// body handling mode, releases the GVL during the blocking wait, and returns
-/// the response.
+/// `fio_opts` for a meta-request. The CRT copies the struct while creating
+/// the meta-request, so it only needs to live until then.
+fn file_io_ptr(file_io: Option<&AwsS3FileIoOptions>) -> *const std::ffi::c_void {
+    file_io.map_or(std::ptr::null(), |f| f as *const AwsS3FileIoOptions as *const std::ffi::c_void)
+}
+
+/// A meta-request that has been started, with the message it was built
+/// from. Both are released once the request has finished.
+struct StartedRequest {
+    meta_request: *mut AwsS3MetaRequest,
+    message: *mut AwsHttpMessage,
+}
+
+impl StartedRequest {
+    /// Release the CRT handles. Only call after the finish callback fired.
+    unsafe fn release(self) {
+        aws_s3_meta_request_release(self.meta_request);
+        aws_http_message_release(self.message);
+    }
+}
+
+/// Build and start a GET_OBJECT meta-request whose body chunks (unless it
+/// has a `recv_filepath`) go to `on_body`.
 ///
-/// When `recv_filepath` is set, the CRT writes the response body directly to
-/// the file using parallel I/O — no body data passes through Rust or Ruby.
-/// Otherwise, the body is buffered in memory via `body_callback`.
-pub fn get_object(options: GetObjectOptions) -> S3Result {
-    let request = build_s3_request_message(
+/// `state` is passed to the CRT callbacks as user data, so it must stay at
+/// the same address until the request has finished.
+fn start_get_object(
+    options: &GetObjectOptions,
+    state: &SharedState,
+    on_body: BodyCallback,
+) -> Result<StartedRequest, S3ErrorData> {
+    let mut extra_headers: Vec<(String, String)> = Vec::new();
+    if let Some(range) = options.range {
+        extra_headers.push(("Range".to_string(), range.to_string()));
+    }
+    if let Some(if_range) = options.if_range {
+        extra_headers.push(("If-Range".to_string(), if_range.to_string()));
+    }
+
+    let request = build_s3_request_message(
         "GET",
         options.bucket,
         options.key,
         options.region,
-        &[],
+        options.endpoint,
+        options.governance,
+        &extra_headers,
     )
     .map_err(|e| S3ErrorData {
         error_code: -1,
         status_code: 0,
         headers: Vec::new(),
         body: e.to_string().into_bytes(),
+        checksum_mismatch: None,
     })?;
 
-    let state = new_shared_state();
-
     // Build checksum config for validation if requested
     let checksum_config = if options.validate_checksum {
         Some(AwsS3ChecksumConfig {
@@ -813,7 +1559,7 @@ pub fn get_object(options: GetObjectOptions) -> S3Result {
         recv_file_delete_on_failure: false,
         _pad2: [0; 7],
         send_filepath: AwsByteCursor { len: 0, ptr: std::ptr::null() },
-        fio_opts: std::ptr::null(),
+        fio_opts: file_io_ptr(options.file_io.as_ref()),
         send_async_stream: std::ptr::null(),
         send_using_async_writes: false,
         _pad3: [0; 7],
@@ -825,17 +1571,21 @@ pub fn get_object(options: GetObjectOptions) -> S3Result {
         force_dynamic_part_size: false,
         _pad4: [0; 7],
         multipart_upload_threshold: 0,
-        user_data: &state as *const SharedState as *mut std::ffi::c_void,
+        user_data: state as *const SharedState as *mut std::ffi::c_void,
         headers_callback: Some(headers_callback),
         // No body callback when using recv_filepath — CRT writes directly to file
-        body_callback: if use_recv_filepath { None } else { Some(body_callback) },
+        body_callback: if use_recv_filepath { None } else { Some(on_body) },
         body_callback_ex: std::ptr::null(),
         finish_callback: Some(finish_callback),
         shutdown_callback: None,
         progress_callback: Some(progress_callback),
-        telemetry_callback: std::ptr::null(),
-        upload_review_callback: std::ptr::null(),
-        endpoint: std::ptr::null(),
+        telemetry_callback: if options.telemetry {
+            Some(telemetry_callback)
+        } else {
+            None
+        },
+        upload_review_callback: None,
+        endpoint: options.endpoint.map_or(std::ptr::null(), S3Endpoint::uri_ptr),
         resume_token: std::ptr::null(),
         object_size_hint: std::ptr::null(),
         copy_source_uri: AwsByteCursor { len: 0, ptr: std::ptr::null() },
@@ -843,11 +1593,13 @@ pub fn get_object(options: GetObjectOptions) -> S3Result {
         _pad5: 0,
     };
 
+    acquire_permit(options.limiter, state);
     let meta_request = unsafe {
         aws_s3_client_make_meta_request(options.client, &meta_request_options)
     };
 
     if meta_request.is_null() {
+        return_unused_permit(state);
         unsafe { aws_http_message_release(request) };
         let err = CrtError::last_error();
         return Err(S3ErrorData {
@@ -855,9 +1607,32 @@ pub fn get_object(options: GetObjectOptions) -> S3Result {
             status_code: 0,
             headers: Vec::new(),
             body: err.to_string().into_bytes(),
+            checksum_mismatch: None,
         });
     }
 
+    Ok(StartedRequest {
+        meta_request,
+        message: request,
+    })
+}
+
+/// Execute a GET_OBJECT meta-request.
+///
+/// Builds the HTTP request, configures the meta-request with the appropriate
+/// body handling mode, releases the GVL during the blocking wait, and returns
+/// the response.
+///
+/// When `recv_filepath` is set, the CRT writes the response body directly to
+/// the file using parallel I/O — no body data passes through Rust or Ruby.
+/// Otherwise, the body is buffered in memory via `body_callback`.
+pub fn get_object(options: GetObjectOptions) -> S3Result {
+    let state = new_shared_state();
+    if options.telemetry {
+        state.0.lock().unwrap().telemetry = Some(Vec::new());
+    }
+    let started = start_get_object(&options, &state, body_callback)?;
+
     // Release the GVL and wait for completion
     let wait_data = WaitData {
         state: Arc::clone(&state),
@@ -872,19 +1647,164 @@ pub fn get_object(options: GetObjectOptions) -> S3Result {
     }
 
     // Clean up CRT resources
-    unsafe {
-        aws_s3_meta_request_release(meta_request);
-        aws_http_message_release(request);
-    }
+    unsafe { started.release() };
 
     // Extract result — include body only when not using recv_filepath
-    extract_result(&state, !use_recv_filepath)
+    extract_result(&state, options.recv_filepath.is_none())
+}
+
+// ---------------------------------------------------------------------------
+// Public API: get_objects
+// ---------------------------------------------------------------------------
+
+/// Options for a batch of buffered GET_OBJECT meta-requests.
+pub struct GetObjectsOptions<'a> {
+    pub client: *mut AwsS3Client,
+    pub signing_config: *const AwsSigningConfigAws,
+    pub bucket: &'a str,
+    pub keys: &'a [String],
+    pub region: &'a str,
+    /// The client's custom endpoint, if any.
+    pub endpoint: Option<&'a S3Endpoint>,
+    /// Whether to validate each response checksum.
+    pub validate_checksum: bool,
+    /// Maximum number of meta-requests in flight at once (at least 1).
+    pub max_concurrency: usize,
+    /// Bucket-owner and request-payer headers, sent on every GET.
+    pub governance: GovernanceHeaders<'a>,
+    /// The client's transfer limiter, if it has one.
+    pub limiter: Option<&'a Arc<TransferLimiter>>,
+}
+
+/// Download several objects concurrently, buffering each body in memory.
+///
+/// Starts up to `max_concurrency` meta-requests and lets the CRT schedule
+/// them across its connections. The calling thread releases the GVL once
+/// per wake-up rather than once per object: it waits on the batch queue,
+/// collects whichever requests finished, and tops the batch back up.
+///
+/// Returns one result per key, in the order of `keys`.
+pub fn get_objects(options: GetObjectsOptions) -> Vec<S3Result> {
+    let count = options.keys.len();
+    let max_concurrency = options.max_concurrency.max(1);
+    let signal: BatchSignal = Arc::new((Mutex::new(Vec::new()), Condvar::new()));
+
+    // Boxed so each state keeps its address while the CRT holds a pointer.
+    let mut in_flight: Vec<Option<(Box<SharedState>, StartedRequest)>> =
+        (0..count).map(|_| None).collect();
+    let mut results: Vec<Option<S3Result>> = (0..count).map(|_| None).collect();
+    let mut next = 0;
+    let mut active = 0;
+
+    while next < count || active > 0 {
+        while active < max_concurrency && next < count {
+            let state = Box::new(new_shared_state());
+            state.0.lock().unwrap().batch = Some(BatchSlot {
+                signal: Arc::clone(&signal),
+                index: next,
+            });
+
+            let get_options = GetObjectOptions {
+                client: options.client,
+                signing_config: options.signing_config,
+                bucket: options.bucket,
+                key: &options.keys[next],
+                region: options.region,
+                endpoint: options.endpoint,
+                recv_filepath: None,
+                file_io: None,
+                validate_checksum: options.validate_checksum,
+                range: None,
+                if_range: None,
+                governance: options.governance,
+                limiter: options.limiter,
+                telemetry: false,
+            };
+            match start_get_object(&get_options, &state, body_callback) {
+                Ok(started) => {
+                    in_flight[next] = Some((state, started));
+                    active += 1;
+                }
+                Err(error) => results[next] = Some(Err(error)),
+            }
+            next += 1;
+        }
+
+        if active == 0 {
+            continue;
+        }
+
+        let wait_data = BatchWaitData {
+            signal: Arc::clone(&signal),
+        };
+        unsafe {
+            rb_thread_call_without_gvl(
+                wait_for_batch,
+                &wait_data as *const BatchWaitData as *mut std::ffi::c_void,
+                std::ptr::null(),
+                std::ptr::null(),
+            );
+        }
+
+        let finished = std::mem::take(&mut *signal.0.lock().unwrap());
+        for index in finished {
+            if let Some((state, started)) = in_flight[index].take() {
+                unsafe { started.release() };
+                results[index] = Some(extract_result(&state, true));
+                active -= 1;
+            }
+        }
+    }
+
+    results.into_iter().map(|r| r.expect("every key has a result")).collect()
 }
 
 // ---------------------------------------------------------------------------
 // Public API: put_object
 // ---------------------------------------------------------------------------
 
+/// S3's limit on the number of parts in one multipart upload.
+pub const MAX_UPLOAD_PARTS: u64 = 10_000;
+
+/// The CRT's upload part size when the client doesn't configure one.
+const DEFAULT_PART_SIZE: u64 = 8 * 1024 * 1024;
+
+/// S3's limit on the size of one part.
+const MAX_PART_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Pick the part size for an upload of `content_length` bytes so that it
+/// needs at most `max_parts` parts.
+///
+/// Returns `Ok(0)` when the client's part size (`client_part_size`, 0 for
+/// the CRT default) already fits, otherwise the smallest whole number of
+/// MiB that does. Fails with a message when even S3's 5 GiB maximum part
+/// size would need too many parts.
+pub fn upload_part_size(
+    content_length: u64,
+    client_part_size: u64,
+    max_parts: u64,
+) -> Result<u64, String> {
+    let part_size = if client_part_size == 0 {
+        DEFAULT_PART_SIZE
+    } else {
+        client_part_size
+    };
+    if content_length.div_ceil(part_size) <= max_parts {
+        return Ok(0);
+    }
+
+    const MIB: u64 = 1024 * 1024;
+    let needed = content_length.div_ceil(max_parts).div_ceil(MIB) * MIB;
+    if needed > MAX_PART_SIZE {
+        return Err(format!(
+            "a {}-byte upload cannot be split into {} parts: each part would need {} bytes, \
+             above S3's {}-byte part size limit",
+            content_length, max_parts, needed, MAX_PART_SIZE
+        ));
+    }
+    Ok(needed)
+}
+
 /// Options for a PUT_OBJECT meta-request.
 pub struct PutObjectOptions<'a> {
     pub client: *mut AwsS3Client,
@@ -892,16 +1812,49 @@ pub struct PutObjectOptions<'a> {
     pub bucket: &'a str,
     pub key: &'a str,
     pub region: &'a str,
+    /// The client's custom endpoint, if any.
+    pub endpoint: Option<&'a S3Endpoint>,
     /// If set, CRT reads directly from this file path (send_filepath mode).
     pub send_filepath: Option<&'a str>,
+    /// File I/O tuning for `send_filepath` (CRT defaults when `None`).
+    pub file_io: Option<AwsS3FileIoOptions>,
     /// In-memory body bytes (used when send_filepath is None).
     pub body: Option<Vec<u8>>,
     /// Content-Length header value (optional).
     pub content_length: Option<u64>,
+    /// Part size for this upload (0 = the client's; see `upload_part_size`).
+    pub part_size: u64,
     /// Content-Type header value (optional).
     pub content_type: Option<&'a str>,
+    /// Cache-Control header value (optional).
+    pub cache_control: Option<&'a str>,
+    /// Content-Disposition header value (optional).
+    pub content_disposition: Option<&'a str>,
+    /// Content-Encoding header value (optional).
+    pub content_encoding: Option<&'a str>,
+    /// Expires header value, an RFC 1123 date (optional).
+    pub expires: Option<&'a str>,
     /// Checksum algorithm to compute (CRC32, CRC32C, SHA1, SHA256).
     pub checksum_algorithm: Option<i32>,
+    /// Pre-encoded `x-amz-tagging` header value (see `encode_tagging`).
+    pub tagging: Option<&'a str>,
+    /// `x-amz-object-lock-mode` (GOVERNANCE or COMPLIANCE).
+    pub object_lock_mode: Option<&'a str>,
+    /// `x-amz-object-lock-retain-until-date`, an ISO 8601 timestamp.
+    pub object_lock_retain_until: Option<&'a str>,
+    /// `x-amz-object-lock-legal-hold` (ON or OFF).
+    pub object_lock_legal_hold: Option<&'a str>,
+    /// Called before a multipart upload is completed (see `UploadReviewFn`).
+    pub upload_review: Option<UploadReviewFn<'a>>,
+    /// Bucket-owner and request-payer headers.
+    pub governance: GovernanceHeaders<'a>,
+    /// The client's transfer limiter, if it has one.
+    pub limiter: Option<&'a Arc<TransferLimiter>>,
+    /// Checksum the body locally and compare it with the `x-amz-checksum-*`
+    /// header of the response, without sending a checksum (no trailer).
+    pub verify_checksum: Option<TrailerChecksum>,
+    /// Collect per-request metrics from the CRT's telemetry callback.
+    pub telemetry: bool,
 }
 
 /// Execute a PUT_OBJECT meta-request.
@@ -922,12 +1875,31 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
     if let Some(cl) = options.content_length {
         extra_headers.push(("Content-Length".to_string(), cl.to_string()));
     }
+    let object_headers = [
+        ("Cache-Control", options.cache_control),
+        ("Content-Disposition", options.content_disposition),
+        ("Content-Encoding", options.content_encoding),
+        ("Expires", options.expires),
+        ("x-amz-object-lock-mode", options.object_lock_mode),
+        ("x-amz-object-lock-retain-until-date", options.object_lock_retain_until),
+        ("x-amz-object-lock-legal-hold", options.object_lock_legal_hold),
+    ];
+    for (name, value) in object_headers {
+        if let Some(value) = value {
+            extra_headers.push((name.to_string(), value.to_string()));
+        }
+    }
+    if let Some(tagging) = options.tagging {
+        extra_headers.push(("x-amz-tagging".to_string(), tagging.to_string()));
+    }
 
     let request = build_s3_request_message(
         "PUT",
         options.bucket,
         options.key,
         options.region,
+        options.endpoint,
+        options.governance,
         &extra_headers,
     )
     .map_err(|e| S3ErrorData {
@@ -935,6 +1907,7 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
         status_code: 0,
         headers: Vec::new(),
         body: e.to_string().into_bytes(),
+        checksum_mismatch: None,
     })?;
 
     // Set up body stream if we have in-memory body data (not send_filepath).
@@ -961,6 +1934,7 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
                         status_code: 0,
                         headers: Vec::new(),
                         body: err.to_string().into_bytes(),
+                        checksum_mismatch: None,
                     });
                 }
                 unsafe { aws_http_message_set_body_stream(request, stream) };
@@ -975,7 +1949,26 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
         (std::ptr::null_mut(), None)
     };
 
+    // Start the local checksum for verify_checksum. A file is read on a
+    // helper thread while the CRT uploads it.
+    let local_checksum = match (options.verify_checksum, options.send_filepath) {
+        (None, _) => None,
+        (Some(algorithm), Some(path)) => {
+            let path = path.to_string();
+            Some(LocalChecksum::Pending(std::thread::spawn(move || {
+                aws_chunked::file_checksum_base64(&path, algorithm)
+            })))
+        }
+        (Some(algorithm), None) => Some(LocalChecksum::Ready(aws_chunked::checksum_base64(
+            _body_data.as_deref().unwrap_or(&[]),
+            algorithm,
+        ))),
+    };
+
     let state = new_shared_state();
+    if options.telemetry {
+        state.0.lock().unwrap().telemetry = Some(Vec::new());
+    }
 
     // Build checksum config if an algorithm was specified
     let checksum_config = options.checksum_algorithm.map(|algo| AwsS3ChecksumConfig {
@@ -987,6 +1980,9 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
         _pad0: [0; 7],
         validate_checksum_algorithms: std::ptr::null(),
     });
+    // The upload review is where the CRT reports each part's checksum, so
+    // take it whenever there are checksums to report.
+    let report_parts = checksum_config.is_some();
 
     // Build send_filepath CString if provided (must outlive the options struct)
     let send_filepath_c = options
@@ -1014,7 +2010,7 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
         recv_file_delete_on_failure: false,
         _pad2: [0; 7],
         send_filepath: send_filepath_cursor,
-        fio_opts: std::ptr::null(),
+        fio_opts: file_io_ptr(options.file_io.as_ref()),
         send_async_stream: std::ptr::null(),
         send_using_async_writes: false,
         _pad3: [0; 7],
@@ -1022,7 +2018,7 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
             .as_ref()
             .map(|c| c as *const AwsS3ChecksumConfig)
             .unwrap_or(std::ptr::null()),
-        part_size: 0,
+        part_size: options.part_size,
         force_dynamic_part_size: false,
         _pad4: [0; 7],
         multipart_upload_threshold: 0,
@@ -1033,9 +2029,17 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
         finish_callback: Some(finish_callback),
         shutdown_callback: None,
         progress_callback: Some(progress_callback),
-        telemetry_callback: std::ptr::null(),
-        upload_review_callback: std::ptr::null(),
-        endpoint: std::ptr::null(),
+        telemetry_callback: if options.telemetry {
+            Some(telemetry_callback)
+        } else {
+            None
+        },
+        upload_review_callback: if options.upload_review.is_some() || report_parts {
+            Some(upload_review_callback)
+        } else {
+            None
+        },
+        endpoint: options.endpoint.map_or(std::ptr::null(), S3Endpoint::uri_ptr),
         resume_token: std::ptr::null(),
         object_size_hint: std::ptr::null(),
         copy_source_uri: AwsByteCursor { len: 0, ptr: std::ptr::null() },
@@ -1043,11 +2047,14 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
         _pad5: 0,
     };
 
+    state.0.lock().unwrap().hold_review = options.upload_review.is_some();
+    acquire_permit(options.limiter, &state);
     let meta_request = unsafe {
         aws_s3_client_make_meta_request(options.client, &meta_request_options)
     };
 
     if meta_request.is_null() {
+        return_unused_permit(&state);
         unsafe {
             if !body_stream.is_null() {
                 aws_input_stream_release(body_stream);
@@ -1060,10 +2067,554 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
             status_code: 0,
             headers: Vec::new(),
             body: err.to_string().into_bytes(),
+            checksum_mismatch: None,
+        });
+    }
+
+    // Release the GVL and wait for completion. If the CRT asks for an
+    // upload review, come back with the GVL to run the hook, post the
+    // decision, and resume waiting.
+    let wait_data = WaitData {
+        state: Arc::clone(&state),
+    };
+    let mut upload_review = options.upload_review;
+    let mut reviewed_parts = None;
+    loop {
+        unsafe {
+            rb_thread_call_without_gvl(
+                wait_for_completion,
+                &wait_data as *const WaitData as *mut std::ffi::c_void,
+                std::ptr::null(),
+                std::ptr::null(),
+            );
+        }
+
+        let pending = state.0.lock().unwrap().pending_review.take();
+        let Some(review) = pending else { break };
+        let approved = upload_review.as_mut().is_none_or(|review_fn| review_fn(&review));
+        if report_parts {
+            reviewed_parts = Some(review.parts);
+        }
+
+        let mut guard = state.0.lock().unwrap();
+        guard.review_decision = Some(approved);
+        state.1.notify_all();
+    }
+
+    // Clean up CRT resources
+    unsafe {
+        aws_s3_meta_request_release(meta_request);
+        if !body_stream.is_null() {
+            aws_input_stream_release(body_stream);
+        }
+        aws_http_message_release(request);
+    }
+    // _body_data is dropped here, which is safe because the input stream
+    // has already been released above.
+
+    if report_parts && reviewed_parts.is_none() {
+        reviewed_parts = state.0.lock().unwrap().reviewed_parts.take();
+    }
+
+    // PUT responses don't include a body
+    let result = extract_result(&state, false).map(|mut response| {
+        response.parts = reviewed_parts;
+        response
+    });
+    match (options.verify_checksum, local_checksum) {
+        (Some(algorithm), Some(local)) => verify_upload_checksum(result, algorithm, local),
+        _ => result,
+    }
+}
+
+/// The locally computed checksum of an upload body for `verify_checksum`.
+enum LocalChecksum {
+    Ready(String),
+    /// Being computed from the file on a helper thread.
+    Pending(JoinHandle<std::io::Result<String>>),
+}
+
+/// Compare a successful upload's `x-amz-checksum-*` response header with
+/// the checksum computed locally. A mismatch becomes an error carrying
+/// `ChecksumMismatch` (`expected` is what S3 reported, `computed` ours); a
+/// match sets `checksum_validated`. Stores that return no full-object
+/// checksum for the algorithm leave the response unverified.
+fn verify_upload_checksum(result: S3Result, algorithm: TrailerChecksum, local: LocalChecksum) -> S3Result {
+    let computed = match local {
+        LocalChecksum::Ready(checksum) => Ok(checksum),
+        LocalChecksum::Pending(handle) => handle
+            .join()
+            .unwrap_or_else(|_| Err(std::io::Error::other("checksum thread panicked"))),
+    };
+    let mut response = result?;
+    let computed = computed.map_err(|e| S3ErrorData {
+        error_code: -1,
+        status_code: 0,
+        headers: Vec::new(),
+        body: format!("could not checksum the upload body: {}", e).into_bytes(),
+        checksum_mismatch: None,
+    })?;
+
+    let reported = response
+        .headers
+        .iter()
+        .find(|(name, value)| name.eq_ignore_ascii_case(algorithm.header_name()) && !value.contains('-'))
+        .map(|(_, value)| value.clone());
+    let Some(reported) = reported else {
+        return Ok(response);
+    };
+    if reported != computed {
+        return Err(S3ErrorData {
+            error_code: 0,
+            status_code: response.status_code,
+            headers: response.headers,
+            body: Vec::new(),
+            checksum_mismatch: Some(Box::new(ChecksumMismatch {
+                algorithm: algorithm.name().to_string(),
+                expected: reported,
+                computed: Some(computed),
+            })),
+        });
+    }
+    response.checksum_validated = Some(algorithm.name().to_string());
+    Ok(response)
+}
+
+// ---------------------------------------------------------------------------
+// Public API: pipe_object
+// ---------------------------------------------------------------------------
+
+/// Pipe transform: given the next piece of the source, or `None` once it is
+/// exhausted, returns the bytes to upload in its place, or `None` to
+/// abandon the pipe. Invoked on the Ruby thread with the GVL held.
+pub type PipeTransformFn<'a> = &'a mut dyn FnMut(Option<&[u8]>) -> Option<Vec<u8>>;
+
+/// Options for streaming one object into another.
+pub struct PipeObjectOptions<'a> {
+    /// The GET of the source. Its `client` must have read backpressure
+    /// (`S3Client::paced_client`); its `recv_filepath` must be `None`.
+    pub source: GetObjectOptions<'a>,
+    /// Client for the upload.
+    pub client: *mut AwsS3Client,
+    pub signing_config: *const AwsSigningConfigAws,
+    pub bucket: &'a str,
+    pub key: &'a str,
+    pub region: &'a str,
+    /// The client's custom endpoint, if any.
+    pub endpoint: Option<&'a S3Endpoint>,
+    /// Part size for the upload (0 = the client's).
+    pub part_size: u64,
+    /// Content-Type header value (optional).
+    pub content_type: Option<&'a str>,
+    /// Bucket-owner and request-payer headers for the upload.
+    pub governance: GovernanceHeaders<'a>,
+    /// The client's transfer limiter, if it has one. The pipe takes one
+    /// permit for both meta-requests.
+    pub limiter: Option<&'a Arc<TransferLimiter>>,
+    /// Applied to each piece before it is uploaded; without it the bytes
+    /// are copied as they are.
+    pub transform: Option<PipeTransformFn<'a>>,
+}
+
+/// How feeding a pipe's upload from its GET ended.
+enum PipeEnd {
+    /// The whole source was written and the upload told it was complete.
+    Finished,
+    /// The GET failed.
+    SourceFailed,
+    /// The upload stopped taking writes.
+    UploadFailed,
+    /// The transform gave up.
+    Abandoned,
+}
+
+/// Hand `data` to an upload started with `send_using_async_writes` and
+/// wait (without the GVL) until the CRT has taken it, which it only does
+/// once it has room — this is what paces the pipe. Returns false if the
+/// upload has failed.
+fn write_to_upload(meta_request: *mut AwsS3MetaRequest, data: &[u8], eof: bool) -> bool {
+    let cursor = AwsByteCursor {
+        len: data.len(),
+        ptr: data.as_ptr(),
+    };
+    unsafe {
+        // `data` must stay valid until the future completes.
+        let future = aws_s3_meta_request_write(meta_request, cursor, eof);
+        rb_thread_call_without_gvl(
+            wait_for_future,
+            future as *mut std::ffi::c_void,
+            std::ptr::null(),
+            std::ptr::null(),
+        );
+        let error = aws_future_void_get_error(future);
+        aws_future_void_release(future);
+        error == 0
+    }
+}
+
+/// Move the GET's body into the upload as it arrives, widening the GET's
+/// read window by what has been passed on, until one side ends.
+fn feed_pipe(
+    get: *mut AwsS3MetaRequest,
+    get_state: &SharedState,
+    put: *mut AwsS3MetaRequest,
+    mut transform: Option<PipeTransformFn>,
+) -> PipeEnd {
+    let wait_data = WaitData {
+        state: Arc::clone(get_state),
+    };
+    loop {
+        unsafe {
+            rb_thread_call_without_gvl(
+                wait_for_pipe_data,
+                &wait_data as *const WaitData as *mut std::ffi::c_void,
+                std::ptr::null(),
+                std::ptr::null(),
+            );
+        }
+
+        // The finish callback comes after the last body callback, so a
+        // finished GET has nothing more to deliver once this is taken.
+        let (chunk, finished) = {
+            let mut guard = get_state.0.lock().unwrap();
+            if guard.complete && (guard.error_code != 0 || guard.error_response_status >= 400) {
+                return PipeEnd::SourceFailed;
+            }
+            (std::mem::take(&mut guard.body), guard.complete)
+        };
+
+        if !chunk.is_empty() {
+            let written = match transform.as_mut() {
+                Some(transform) => match transform(Some(&chunk)) {
+                    Some(output) => output.is_empty() || write_to_upload(put, &output, false),
+                    None => return PipeEnd::Abandoned,
+                },
+                None => write_to_upload(put, &chunk, false),
+            };
+            if !written {
+                return PipeEnd::UploadFailed;
+            }
+            unsafe { aws_s3_meta_request_increment_read_window(get, chunk.len() as u64) };
+        }
+
+        if finished {
+            let last = match transform.as_mut() {
+                Some(transform) => match transform(None) {
+                    Some(output) => output,
+                    None => return PipeEnd::Abandoned,
+                },
+                None => Vec::new(),
+            };
+            // The CRT splits the upload into parts itself, so ending it
+            // with an empty write never adds an empty part.
+            return if write_to_upload(put, &last, true) {
+                PipeEnd::Finished
+            } else {
+                PipeEnd::UploadFailed
+            };
+        }
+    }
+}
+
+/// Stream one object into another without holding it in memory.
+///
+/// Starts a PUT_OBJECT meta-request fed by `aws_s3_meta_request_write`
+/// (`send_using_async_writes`) and a GET_OBJECT on a client with read
+/// backpressure, then moves the GET's body chunks into the upload on this
+/// thread, through `transform` if given. Each write waits until the CRT
+/// has room for it, and the GET's read window only grows by what has been
+/// written, so the download runs at the upload's pace. Both keep the
+/// CRT's parallelism: the GET fetches ranged parts concurrently (after the
+/// first, each carries `If-Match` with the first response's ETag, so a
+/// source that changes mid-copy fails the GET rather than mixing
+/// versions) and the PUT uploads parts concurrently.
+///
+/// If either side fails, or the transform gives up, the other is
+/// cancelled; the CRT aborts the multipart upload.
+pub fn pipe_object(options: PipeObjectOptions) -> S3Result {
+    let mut extra_headers: Vec<(String, String)> = Vec::new();
+    if let Some(ct) = options.content_type {
+        extra_headers.push(("Content-Type".to_string(), ct.to_string()));
+    }
+
+    let request = build_s3_request_message(
+        "PUT",
+        options.bucket,
+        options.key,
+        options.region,
+        options.endpoint,
+        options.governance,
+        &extra_headers,
+    )
+    .map_err(|e| S3ErrorData {
+        error_code: -1,
+        status_code: 0,
+        headers: Vec::new(),
+        body: e.to_string().into_bytes(),
+        checksum_mismatch: None,
+    })?;
+
+    let put_state = new_shared_state();
+    let get_state = new_shared_state();
+
+    let meta_request_options = AwsS3MetaRequestOptions {
+        meta_request_type: AWS_S3_META_REQUEST_TYPE_PUT_OBJECT,
+        _pad0: 0,
+        operation_name: AwsByteCursor { len: 0, ptr: std::ptr::null() },
+        signing_config: options.signing_config,
+        message: request,
+        recv_filepath: AwsByteCursor { len: 0, ptr: std::ptr::null() },
+        recv_file_option: 0,
+        _pad1: 0,
+        recv_file_position: 0,
+        recv_file_delete_on_failure: false,
+        _pad2: [0; 7],
+        send_filepath: AwsByteCursor { len: 0, ptr: std::ptr::null() },
+        fio_opts: std::ptr::null(),
+        send_async_stream: std::ptr::null(),
+        send_using_async_writes: true,
+        _pad3: [0; 7],
+        checksum_config: std::ptr::null(),
+        part_size: options.part_size,
+        force_dynamic_part_size: false,
+        _pad4: [0; 7],
+        multipart_upload_threshold: 0,
+        user_data: &put_state as *const SharedState as *mut std::ffi::c_void,
+        headers_callback: Some(headers_callback),
+        body_callback: None,
+        body_callback_ex: std::ptr::null(),
+        finish_callback: Some(finish_callback),
+        shutdown_callback: None,
+        progress_callback: Some(progress_callback),
+        telemetry_callback: None,
+        upload_review_callback: None,
+        endpoint: options.endpoint.map_or(std::ptr::null(), S3Endpoint::uri_ptr),
+        resume_token: std::ptr::null(),
+        object_size_hint: std::ptr::null(),
+        copy_source_uri: AwsByteCursor { len: 0, ptr: std::ptr::null() },
+        max_active_connections_override: 0,
+        _pad5: 0,
+    };
+
+    // One permit covers the pair: waiting for a second while holding the
+    // first could wait forever.
+    acquire_permit(options.limiter, &put_state);
+    let meta_request = unsafe {
+        aws_s3_client_make_meta_request(options.client, &meta_request_options)
+    };
+    if meta_request.is_null() {
+        return_unused_permit(&put_state);
+        unsafe { aws_http_message_release(request) };
+        let err = CrtError::last_error();
+        return Err(S3ErrorData {
+            error_code: -1,
+            status_code: 0,
+            headers: Vec::new(),
+            body: err.to_string().into_bytes(),
+            checksum_mismatch: None,
+        });
+    }
+    let put = StartedRequest {
+        meta_request,
+        message: request,
+    };
+
+    let get = match start_get_object(&options.source, &get_state, pipe_body_callback) {
+        Ok(get) => get,
+        Err(error) => {
+            unsafe { aws_s3_meta_request_cancel(put.meta_request) };
+            wait_for_finish(&put_state);
+            unsafe { put.release() };
+            return Err(error);
+        }
+    };
+
+    let end = feed_pipe(
+        get.meta_request,
+        &get_state,
+        put.meta_request,
+        options.transform,
+    );
+    unsafe {
+        match end {
+            PipeEnd::Finished => {}
+            PipeEnd::SourceFailed => aws_s3_meta_request_cancel(put.meta_request),
+            PipeEnd::UploadFailed => aws_s3_meta_request_cancel(get.meta_request),
+            PipeEnd::Abandoned => {
+                aws_s3_meta_request_cancel(get.meta_request);
+                aws_s3_meta_request_cancel(put.meta_request);
+            }
+        }
+    }
+    wait_for_finish(&get_state);
+    wait_for_finish(&put_state);
+    unsafe {
+        get.release();
+        put.release();
+    }
+
+    match end {
+        PipeEnd::Finished | PipeEnd::UploadFailed => extract_result(&put_state, false),
+        // The GET's error explains the cancelled upload.
+        PipeEnd::SourceFailed | PipeEnd::Abandoned => {
+            extract_result(&get_state, false).and_then(|_| extract_result(&put_state, false))
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public API: default_request
+// ---------------------------------------------------------------------------
+
+/// Options for a DEFAULT meta-request: one S3 operation the CRT sends as a
+/// single HTTP request, without splitting it into parts.
+pub struct DefaultRequestOptions<'a> {
+    pub client: *mut AwsS3Client,
+    pub signing_config: *const AwsSigningConfigAws,
+    pub bucket: &'a str,
+    pub key: &'a str,
+    pub region: &'a str,
+    /// The client's custom endpoint, if any.
+    pub endpoint: Option<&'a S3Endpoint>,
+    /// HTTP method, e.g. `POST`.
+    pub method: &'a str,
+    /// S3 operation name, e.g. `CreateMultipartUpload`, used by the CRT for
+    /// retries and request metrics.
+    pub operation_name: &'a str,
+    /// Query string without the leading `?` (e.g. `uploads`), already
+    /// percent-encoded.
+    pub query: Option<&'a str>,
+    /// Request body, sent with a Content-Length header.
+    pub body: Option<Vec<u8>>,
+    /// Headers sent after the governance headers.
+    pub extra_headers: Vec<(String, String)>,
+    /// Bucket-owner and request-payer headers.
+    pub governance: GovernanceHeaders<'a>,
+    /// The client's transfer limiter, if it has one.
+    pub limiter: Option<&'a Arc<TransferLimiter>>,
+}
+
+/// Execute a DEFAULT meta-request and buffer the response body.
+///
+/// The CRT signs and retries the request like any other meta-request but
+/// otherwise sends it as given, so this suits operations that take a small
+/// body (or none) and answer with XML.
+pub fn default_request(options: DefaultRequestOptions) -> S3Result {
+    let mut extra_headers = options.extra_headers;
+    if let Some(body) = &options.body {
+        extra_headers.push(("Content-Length".to_string(), body.len().to_string()));
+    }
+    let path = match options.query {
+        Some(query) => format!("{}?{}", options.key, query),
+        None => options.key.to_string(),
+    };
+
+    let request = build_s3_request_message(
+        options.method,
+        options.bucket,
+        &path,
+        options.region,
+        options.endpoint,
+        options.governance,
+        &extra_headers,
+    )
+    .map_err(|e| S3ErrorData {
+        error_code: -1,
+        status_code: 0,
+        headers: Vec::new(),
+        body: e.to_string().into_bytes(),
+        checksum_mismatch: None,
+    })?;
+
+    // As in put_object, the body bytes must outlive the input stream.
+    let body_data = options.body.filter(|data| !data.is_empty());
+    let body_stream = match &body_data {
+        Some(data) => {
+            let cursor = AwsByteCursor {
+                len: data.len(),
+                ptr: data.as_ptr(),
+            };
+            let stream =
+                unsafe { aws_input_stream_new_from_cursor(aws_default_allocator(), &cursor) };
+            if stream.is_null() {
+                unsafe { aws_http_message_release(request) };
+                let err = CrtError::last_error();
+                return Err(S3ErrorData {
+                    error_code: -1,
+                    status_code: 0,
+                    headers: Vec::new(),
+                    body: err.to_string().into_bytes(),
+                    checksum_mismatch: None,
+                });
+            }
+            unsafe { aws_http_message_set_body_stream(request, stream) };
+            stream
+        }
+        None => std::ptr::null_mut(),
+    };
+
+    let state = new_shared_state();
+    let meta_request_options = AwsS3MetaRequestOptions {
+        meta_request_type: AWS_S3_META_REQUEST_TYPE_DEFAULT,
+        _pad0: 0,
+        operation_name: AwsByteCursor::from_str(options.operation_name),
+        signing_config: options.signing_config,
+        message: request,
+        recv_filepath: AwsByteCursor { len: 0, ptr: std::ptr::null() },
+        recv_file_option: 0,
+        _pad1: 0,
+        recv_file_position: 0,
+        recv_file_delete_on_failure: false,
+        _pad2: [0; 7],
+        send_filepath: AwsByteCursor { len: 0, ptr: std::ptr::null() },
+        fio_opts: std::ptr::null(),
+        send_async_stream: std::ptr::null(),
+        send_using_async_writes: false,
+        _pad3: [0; 7],
+        checksum_config: std::ptr::null(),
+        part_size: 0,
+        force_dynamic_part_size: false,
+        _pad4: [0; 7],
+        multipart_upload_threshold: 0,
+        user_data: &state as *const SharedState as *mut std::ffi::c_void,
+        headers_callback: Some(headers_callback),
+        body_callback: Some(body_callback),
+        body_callback_ex: std::ptr::null(),
+        finish_callback: Some(finish_callback),
+        shutdown_callback: None,
+        progress_callback: None,
+        telemetry_callback: None,
+        upload_review_callback: None,
+        endpoint: options.endpoint.map_or(std::ptr::null(), S3Endpoint::uri_ptr),
+        resume_token: std::ptr::null(),
+        object_size_hint: std::ptr::null(),
+        copy_source_uri: AwsByteCursor { len: 0, ptr: std::ptr::null() },
+        max_active_connections_override: 0,
+        _pad5: 0,
+    };
+
+    acquire_permit(options.limiter, &state);
+    let meta_request =
+        unsafe { aws_s3_client_make_meta_request(options.client, &meta_request_options) };
+
+    if meta_request.is_null() {
+        return_unused_permit(&state);
+        unsafe {
+            if !body_stream.is_null() {
+                aws_input_stream_release(body_stream);
+            }
+            aws_http_message_release(request);
+        }
+        let err = CrtError::last_error();
+        return Err(S3ErrorData {
+            error_code: -1,
+            status_code: 0,
+            headers: Vec::new(),
+            body: err.to_string().into_bytes(),
+            checksum_mismatch: None,
         });
     }
 
-    // Release the GVL and wait for completion
     let wait_data = WaitData {
         state: Arc::clone(&state),
     };
@@ -1076,7 +2627,6 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
         );
     }
 
-    // Clean up CRT resources
     unsafe {
         aws_s3_meta_request_release(meta_request);
         if !body_stream.is_null() {
@@ -1084,9 +2634,7 @@ pub fn put_object(options: PutObjectOptions) -> S3Result {
         }
         aws_http_message_release(request);
     }
-    // _body_data is dropped here, which is safe because the input stream
-    // has already been released above.
+    drop(body_data);
 
-    // PUT responses don't include a body
-    extract_result(&state, false)
+    extract_result(&state, true)
 }
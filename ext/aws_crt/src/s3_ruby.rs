@@ -7,17 +7,26 @@
 //!
 //! # Return format
 //!
-//! On success: Ruby Hash with keys :status_code, :headers, :body, :checksum_validated
+//! On success: Ruby Hash with keys :status_code, :headers, :body, :checksum_validated,
+//!   :last_modified, :content_length, :storage_class
 //! On error: Ruby Hash with keys :error, :error_code, :status_code, :headers, :body
 
 use std::cell::RefCell;
+use std::collections::HashSet;
 
 use magnus::prelude::*;
 use magnus::typed_data;
-use magnus::{method, Error, RHash, RString, Ruby, Symbol, Value};
+use magnus::{method, Error, RArray, RHash, RString, Ruby, Symbol, Value};
 
-use crate::s3_client::{S3Client, S3ClientOptions};
-use crate::s3_request::{self, GetObjectOptions, PutObjectOptions, S3ErrorData};
+use crate::aws_chunked::TrailerChecksum;
+use crate::credentials::SecretString;
+use crate::http::parse_http_date;
+use crate::s3_client::{S3Client, S3ClientOptions, S3Endpoint};
+use crate::s3_request::{
+    self, AwsS3FileIoOptions, DefaultRequestOptions, GetObjectOptions, GetObjectsOptions,
+    GovernanceHeaders, PipeObjectOptions, PutObjectOptions, RequestTelemetry, S3ErrorData,
+    UploadPartReview, UploadReview,
+};
 
 // ---------------------------------------------------------------------------
 // Hash extraction helpers (same pattern as pool.rs)
@@ -103,6 +112,17 @@ fn hash_get_f64(hash: &RHash, key: &str, default: f64) -> Result<f64, Error> {
     }
 }
 
+/// Extract a boolean option from a Ruby Hash by symbol key (Ruby truthiness).
+fn hash_get_bool(hash: &RHash, key: &str, default: bool) -> Result<bool, Error> {
+    let sym = Symbol::new(key);
+    let val: Option<Value> = hash.lookup(sym)?;
+    match val {
+        Some(v) if v.is_nil() => Ok(default),
+        Some(v) => Ok(v.to_bool()),
+        None => Ok(default),
+    }
+}
+
 /// Extract a Value option from a Ruby Hash by symbol key (returns None if absent/nil).
 fn hash_get_value(hash: &RHash, key: &str) -> Result<Option<Value>, Error> {
     let sym = Symbol::new(key);
@@ -114,13 +134,264 @@ fn hash_get_value(hash: &RHash, key: &str) -> Result<Option<Value>, Error> {
     }
 }
 
+/// Extract a required Hash from a Ruby Hash by symbol key.
+fn hash_get_hash_required(hash: &RHash, key: &str) -> Result<RHash, Error> {
+    match hash_get_value(hash, key)? {
+        Some(v) => RHash::try_convert(v),
+        None => Err(Error::new(
+            magnus::exception::arg_error(),
+            format!("missing required option :{}", key),
+        )),
+    }
+}
+
+/// Extract an optional header value from a Ruby Hash by symbol key,
+/// rejecting values that would break the header framing.
+fn hash_get_header_value(hash: &RHash, key: &str) -> Result<Option<String>, Error> {
+    let value = hash_get_string(hash, key)?;
+    if let Some(v) = &value {
+        if v.bytes().any(|b| b == b'\r' || b == b'\n' || b == 0) {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                format!(":{} must not contain CR, LF, or NUL characters", key),
+            ));
+        }
+    }
+    Ok(value)
+}
+
+/// Parse the `:file_io` option into the CRT's file I/O tuning struct.
+///
+/// Keys: `:should_stream` (Boolean), `:disk_throughput_gbps` (Float),
+/// `:direct_io` (Boolean). Returns `None` when the option is absent so the
+/// CRT keeps its defaults.
+fn parse_file_io(params: &RHash) -> Result<Option<AwsS3FileIoOptions>, Error> {
+    let Some(val) = hash_get_value(params, "file_io")? else { return Ok(None) };
+    let opts = RHash::try_convert(val)?;
+    let disk_throughput_gbps = hash_get_f64(&opts, "disk_throughput_gbps", 0.0)?;
+    if !(disk_throughput_gbps >= 0.0 && disk_throughput_gbps.is_finite()) {
+        return Err(Error::new(
+            magnus::exception::arg_error(),
+            ":file_io :disk_throughput_gbps must be a non-negative number",
+        ));
+    }
+    Ok(Some(AwsS3FileIoOptions::new(
+        hash_get_bool(&opts, "should_stream", false)?,
+        disk_throughput_gbps,
+        hash_get_bool(&opts, "direct_io", false)?,
+    )))
+}
+
+/// Read the required `:bucket` param and check it against the S3 bucket
+/// naming rules. Requests go to `<bucket>.s3.<region>.amazonaws.com`, so a
+/// bad name otherwise surfaces as a DNS or TLS failure. `allow_dots` is set
+/// unless requests are virtual-hosted over TLS (see `allows_dotted_buckets`).
+fn hash_get_bucket(hash: &RHash, allow_dots: bool) -> Result<String, Error> {
+    let bucket = hash_get_string_required(hash, "bucket")?;
+    if let Some(problem) = bucket_name_problem(&bucket, allow_dots) {
+        return Err(Error::new(
+            magnus::exception::arg_error(),
+            format!("invalid bucket name {:?}: {}", bucket, problem),
+        ));
+    }
+    Ok(bucket)
+}
+
+/// The first naming rule `bucket` breaks, if any.
+fn bucket_name_problem(bucket: &str, allow_dots: bool) -> Option<&'static str> {
+    if !(3..=63).contains(&bucket.len()) {
+        return Some("bucket names must be between 3 and 63 characters long");
+    }
+    if !bucket
+        .bytes()
+        .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'.')
+    {
+        return Some(
+            "bucket names may only contain lowercase letters, digits, hyphens and dots",
+        );
+    }
+    let alnum = |b: u8| b.is_ascii_lowercase() || b.is_ascii_digit();
+    let bytes = bucket.as_bytes();
+    if !alnum(bytes[0]) || !alnum(bytes[bytes.len() - 1]) {
+        return Some("bucket names must begin and end with a letter or digit");
+    }
+    if bucket.starts_with("xn--") || bucket.starts_with("sthree-") {
+        return Some("bucket names must not start with the reserved prefixes xn-- or sthree-");
+    }
+    if bucket.split('.').count() == 4 && bucket.split('.').all(|p| p.parse::<u8>().is_ok()) {
+        return Some("bucket names must not be formatted as an IP address");
+    }
+    if bucket.contains('.') && !allow_dots {
+        return Some(
+            "bucket names with dots can't use virtual-hosted-style requests over TLS \
+             (they don't match the *.s3.<region>.amazonaws.com certificate); \
+             use a custom :endpoint for path-style requests, or use_tls: false",
+        );
+    }
+    None
+}
+
+/// Owned values of `:expected_bucket_owner` and `:request_payer`, borrowed
+/// into `GovernanceHeaders` for the request.
+struct GovernanceParams {
+    expected_bucket_owner: Option<String>,
+    request_payer: Option<String>,
+}
+
+impl GovernanceParams {
+    /// Read the params. `:request_payer` may be a String or Symbol and must
+    /// be `requester`, the only value S3 defines.
+    fn from_hash(params: &RHash) -> Result<Self, Error> {
+        let expected_bucket_owner = hash_get_header_value(params, "expected_bucket_owner")?;
+        let request_payer = match hash_get_value(params, "request_payer")? {
+            Some(val) => {
+                let payer: String = match Symbol::from_value(val) {
+                    Some(sym) => sym.name()?.into_owned(),
+                    None => magnus::TryConvert::try_convert(val)?,
+                };
+                if payer != "requester" {
+                    return Err(Error::new(
+                        magnus::exception::arg_error(),
+                        format!("invalid :request_payer {:?}: must be \"requester\"", payer),
+                    ));
+                }
+                Some(payer)
+            }
+            None => None,
+        };
+        Ok(Self { expected_bucket_owner, request_payer })
+    }
+
+    fn headers(&self) -> GovernanceHeaders<'_> {
+        GovernanceHeaders {
+            expected_bucket_owner: self.expected_bucket_owner.as_deref(),
+            request_payer: self.request_payer.as_deref(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Header parsing helpers
+// ---------------------------------------------------------------------------
+
+/// Find a header value by case-insensitive name.
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// Header prefix S3 uses for user-defined object metadata.
+const USER_METADATA_PREFIX: &str = "x-amz-meta-";
+
+/// A parsed `Content-Range` header.
+#[derive(Default)]
+struct ContentRange {
+    /// Inclusive `(start, end)`; `None` for the unsatisfied form a 416
+    /// carries (`bytes */4096`).
+    range: Option<(u64, u64)>,
+    /// `None` for the unknown-length form (`bytes 0-1023/*`).
+    total: Option<u64>,
+}
+
+/// Parse a `Content-Range` value (`bytes 0-1023/4096`). Returns `None` if
+/// the value isn't a byte range at all.
+fn parse_content_range(s: &str) -> Option<ContentRange> {
+    let rest = s.trim().strip_prefix("bytes")?;
+    let (range, total) = rest.trim_start().split_once('/')?;
+
+    let total = match total.trim() {
+        "*" => None,
+        t => Some(t.parse::<u64>().ok()?),
+    };
+    let range = match range.trim() {
+        "*" => None,
+        r => {
+            let (start, end) = r.split_once('-')?;
+            let start: u64 = start.trim().parse().ok()?;
+            let end: u64 = end.trim().parse().ok()?;
+            if end < start {
+                return None;
+            }
+            Some((start, end))
+        }
+    };
+    if range.is_none() && total.is_none() {
+        return None;
+    }
+
+    Some(ContentRange { range, total })
+}
+
+/// Add the parsed `:last_modified`, `:content_length`, `:storage_class`,
+/// `:range_start`, `:range_end`, and `:total_size` convenience fields to a
+/// result hash. Each is nil when the header is absent or unparseable.
+///
+/// Also adds `:metadata`, a Hash of the `x-amz-meta-*` headers with the
+/// prefix stripped and names lowercased (empty when there are none).
+fn add_metadata_fields(
+    ruby: &Ruby,
+    hash: &RHash,
+    headers: &[(String, String)],
+) -> Result<(), Error> {
+    let last_modified = match find_header(headers, "last-modified").and_then(parse_http_date) {
+        Some(secs) => {
+            let time_class: magnus::RClass = ruby.class_object().const_get("Time")?;
+            let time: Value = time_class.funcall("at", (secs,))?;
+            time.funcall::<_, _, Value>("utc", ())?
+        }
+        None => ruby.qnil().as_value(),
+    };
+    hash.aset(Symbol::new("last_modified"), last_modified)?;
+
+    let content_length =
+        find_header(headers, "content-length").and_then(|v| v.trim().parse::<u64>().ok());
+    hash.aset(Symbol::new("content_length"), content_length)?;
+
+    let storage_class = find_header(headers, "x-amz-storage-class");
+    hash.aset(Symbol::new("storage_class"), storage_class)?;
+
+    let content_range = find_header(headers, "content-range")
+        .and_then(parse_content_range)
+        .unwrap_or_default();
+    hash.aset(Symbol::new("range_start"), content_range.range.map(|(start, _)| start))?;
+    hash.aset(Symbol::new("range_end"), content_range.range.map(|(_, end)| end))?;
+    hash.aset(Symbol::new("total_size"), content_range.total)?;
+
+    let metadata = RHash::new();
+    for (name, value) in headers {
+        let Some(prefix) = name.get(..USER_METADATA_PREFIX.len()) else { continue };
+        if prefix.eq_ignore_ascii_case(USER_METADATA_PREFIX) {
+            let key = name[USER_METADATA_PREFIX.len()..].to_ascii_lowercase();
+            metadata.aset(ruby.str_new(&key), ruby.str_new(value))?;
+        }
+    }
+    hash.aset(Symbol::new("metadata"), metadata)?;
+
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Response building helpers
 // ---------------------------------------------------------------------------
 
+/// An ASCII-8BIT Ruby String holding exactly `bytes`. Object and error
+/// bodies are arbitrary bytes, so they are never tagged UTF-8 (which would
+/// make invalid sequences raise or be replaced when the caller touches them).
+fn binary_string(ruby: &Ruby, bytes: &[u8]) -> RString {
+    ruby.enc_str_new(bytes, ruby.ascii8bit_encoding())
+}
+
 /// Build a Ruby Hash from a successful S3Response.
 ///
-/// Returns: { status_code: Integer, headers: Hash, body: String|nil, checksum_validated: String|nil }
+/// Returns: { status_code: Integer, headers: Hash, body: String|nil, checksum_validated: String|nil,
+///            last_modified: Time|nil, content_length: Integer|nil, storage_class: String|nil,
+///            range_start: Integer|nil, range_end: Integer|nil, total_size: Integer|nil,
+///            metadata: Hash, clock_skew_ms: Integer|nil }
+///
+/// `:telemetry` is added when it was requested, and `:parts` after a
+/// multipart upload with a checksum algorithm.
 fn build_success_hash(ruby: &Ruby, response: &s3_request::S3Response) -> Result<Value, Error> {
     let hash = RHash::new();
 
@@ -136,10 +407,10 @@ fn build_success_hash(ruby: &Ruby, response: &s3_request::S3Response) -> Result<
     }
     hash.aset(Symbol::new("headers"), headers_hash)?;
 
-    // Body: String or nil
+    // Body: String (possibly empty) when buffered, nil in file-path mode
     match &response.body {
         Some(body_bytes) => {
-            hash.aset(Symbol::new("body"), ruby.str_from_slice(body_bytes).as_value())?;
+            hash.aset(Symbol::new("body"), binary_string(ruby, body_bytes).as_value())?;
         }
         None => {
             hash.aset(Symbol::new("body"), ruby.qnil().as_value())?;
@@ -156,12 +427,106 @@ fn build_success_hash(ruby: &Ruby, response: &s3_request::S3Response) -> Result<
         }
     }
 
+    add_metadata_fields(ruby, &hash, &response.headers)?;
+    hash.aset(Symbol::new("clock_skew_ms"), response.clock_skew_ms)?;
+
+    if let Some(records) = &response.telemetry {
+        hash.aset(Symbol::new("telemetry"), build_telemetry_hash(ruby, records)?)?;
+    }
+
+    if let Some(parts) = &response.parts {
+        hash.aset(Symbol::new("parts"), build_parts_array(ruby, parts)?)?;
+    }
+
     Ok(hash.as_value())
 }
 
+/// `[{ part_number:, size:, checksum: }]` for the parts of a completed
+/// multipart upload. Part numbers start at 1.
+fn build_parts_array(ruby: &Ruby, parts: &[UploadPartReview]) -> Result<RArray, Error> {
+    let array = ruby.ary_new_capa(parts.len());
+    for (i, part) in parts.iter().enumerate() {
+        let part_hash = RHash::new();
+        part_hash.aset(Symbol::new("part_number"), i + 1)?;
+        part_hash.aset(Symbol::new("size"), part.size)?;
+        part_hash.aset(Symbol::new("checksum"), part.checksum.as_deref())?;
+        array.push(part_hash)?;
+    }
+    Ok(array)
+}
+
+/// Summarise the CRT's per-request metrics for one transfer.
+///
+/// Returns `{ parts:, retries:, connections:, bytes:, duration_ms:, requests: [...] }`.
+/// `parts` counts the successful data-carrying requests (GetObject,
+/// UploadPart, PutObject); `retries` counts requests that failed and were
+/// tried again. Each request entry carries its `:start_ms` relative to the
+/// first request of the transfer.
+fn build_telemetry_hash(ruby: &Ruby, records: &[RequestTelemetry]) -> Result<RHash, Error> {
+    const NS_PER_MS: f64 = 1_000_000.0;
+
+    let first_start = records.iter().map(|r| r.start_ns).min().unwrap_or(0);
+    let last_end = records
+        .iter()
+        .map(|r| r.start_ns + r.duration_ns)
+        .max()
+        .unwrap_or(first_start);
+
+    let mut parts = 0usize;
+    let mut part_bytes = 0u64;
+    let mut connections: Vec<usize> = Vec::new();
+    let requests = ruby.ary_new_capa(records.len());
+    for record in records {
+        let ok = record.error_code == 0
+            && record.status_code.is_some_and(|s| (200..300).contains(&s));
+        let data_request = matches!(
+            record.operation.as_deref(),
+            Some("GetObject" | "UploadPart" | "PutObject")
+        );
+        if ok && data_request {
+            parts += 1;
+            part_bytes += record.bytes.unwrap_or(0);
+        }
+        if let Some(id) = record.connection_id {
+            if !connections.contains(&id) {
+                connections.push(id);
+            }
+        }
+
+        let entry = RHash::new();
+        entry.aset(Symbol::new("operation"), record.operation.as_deref())?;
+        entry.aset(Symbol::new("status_code"), record.status_code)?;
+        entry.aset(Symbol::new("error_code"), record.error_code)?;
+        entry.aset(Symbol::new("bytes"), record.bytes)?;
+        entry.aset(
+            Symbol::new("start_ms"),
+            (record.start_ns - first_start) as f64 / NS_PER_MS,
+        )?;
+        entry.aset(Symbol::new("duration_ms"), record.duration_ns as f64 / NS_PER_MS)?;
+        requests.push(entry)?;
+    }
+
+    let hash = RHash::new();
+    hash.aset(Symbol::new("parts"), parts)?;
+    hash.aset(
+        Symbol::new("retries"),
+        records.iter().filter(|r| r.error_code != 0).count(),
+    )?;
+    hash.aset(Symbol::new("connections"), connections.len())?;
+    hash.aset(Symbol::new("bytes"), part_bytes)?;
+    hash.aset(
+        Symbol::new("duration_ms"),
+        (last_end - first_start) as f64 / NS_PER_MS,
+    )?;
+    hash.aset(Symbol::new("requests"), requests)?;
+    Ok(hash)
+}
+
 /// Build a Ruby Hash from S3 error data.
 ///
 /// Returns: { error: true, error_code: Integer, status_code: Integer, headers: Hash, body: String }
+/// plus `:checksum_algorithm`, `:expected_checksum` and `:computed_checksum`
+/// (String or nil) for a response checksum mismatch.
 fn build_error_hash(ruby: &Ruby, error: &S3ErrorData) -> Result<Value, Error> {
     let hash = RHash::new();
 
@@ -182,12 +547,100 @@ fn build_error_hash(ruby: &Ruby, error: &S3ErrorData) -> Result<Value, Error> {
     // Error body
     hash.aset(
         Symbol::new("body"),
-        ruby.str_from_slice(&error.body).as_value(),
+        binary_string(ruby, &error.body).as_value(),
     )?;
 
+    if let Some(mismatch) = &error.checksum_mismatch {
+        hash.aset(Symbol::new("checksum_algorithm"), mismatch.algorithm.as_str())?;
+        hash.aset(Symbol::new("expected_checksum"), mismatch.expected.as_str())?;
+        hash.aset(Symbol::new("computed_checksum"), mismatch.computed.as_deref())?;
+    }
+
     Ok(hash.as_value())
 }
 
+/// Read an IO `put_object` body into memory.
+///
+/// An IO that responds to `size` is read to the end. Otherwise nothing says
+/// how long it is, so `content_length` is required and exactly that many
+/// bytes are read; an IO that ends sooner is an error rather than a short
+/// upload.
+fn read_io_body(io: Value, content_length: Option<u64>) -> Result<Vec<u8>, Error> {
+    let sizable: bool = io.funcall("respond_to?", (Symbol::new("size"),))?;
+    let contents: Option<RString> = match content_length {
+        Some(length) if !sizable => io.funcall("read", (length,))?,
+        None if !sizable => {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                ":content_length is required for an IO body that doesn't respond to #size",
+            ))
+        }
+        _ => io.funcall("read", ())?,
+    };
+    let bytes = contents.map_or_else(Vec::new, |s| unsafe { s.as_slice().to_vec() });
+    if let Some(length) = content_length.filter(|_| !sizable) {
+        if (bytes.len() as u64) < length {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                format!(
+                    "IO body ended after {} of the {} bytes given as :content_length",
+                    bytes.len(),
+                    length
+                ),
+            ));
+        }
+    }
+    Ok(bytes)
+}
+
+/// Call the `:on_upload_review` hook with a Hash describing the upload:
+/// `{ checksum_algorithm: String or nil, parts: [{ size:, checksum: }] }`.
+///
+/// Returns the hook's truthiness (true = complete the upload).
+fn call_upload_review(ruby: &Ruby, hook: Value, review: &UploadReview) -> Result<bool, Error> {
+    let parts = ruby.ary_new_capa(review.parts.len());
+    for part in &review.parts {
+        let part_hash = RHash::new();
+        part_hash.aset(Symbol::new("size"), part.size)?;
+        part_hash.aset(Symbol::new("checksum"), part.checksum.as_deref())?;
+        parts.push(part_hash)?;
+    }
+
+    let hash = RHash::new();
+    hash.aset(
+        Symbol::new("checksum_algorithm"),
+        review.checksum_algorithm.as_deref(),
+    )?;
+    hash.aset(Symbol::new("parts"), parts)?;
+
+    let approved: Value = hook.funcall("call", (hash,))?;
+    Ok(approved.to_bool())
+}
+
+/// Call `pipe_object`'s block with the next piece of the source (nil at the
+/// end) and return the bytes it gives back; nil means none.
+fn call_pipe_transform(
+    ruby: &Ruby,
+    transform: Value,
+    chunk: Option<&[u8]>,
+) -> Result<Vec<u8>, Error> {
+    let arg = chunk.map(|bytes| binary_string(ruby, bytes));
+    let output: Value = transform.funcall("call", (arg,))?;
+    if output.is_nil() {
+        return Ok(Vec::new());
+    }
+    let output = RString::from_value(output).ok_or_else(|| {
+        Error::new(
+            ruby.exception_type_error(),
+            format!(
+                "pipe_object block must return a String or nil, got {}",
+                output.class().inspect()
+            ),
+        )
+    })?;
+    Ok(unsafe { output.as_slice().to_vec() })
+}
+
 // ---------------------------------------------------------------------------
 // RubyS3Client — magnus wrapper
 // ---------------------------------------------------------------------------
@@ -215,6 +668,10 @@ impl RubyS3Client {
     ///
     /// options Hash:
     ///   :region (required)
+    ///   :endpoint (optional) — "scheme://host[:port]" of an S3-compatible
+    ///     service; requests use path-style addressing
+    ///   :use_tls (optional) — default true, or the endpoint's scheme; false
+    ///     connects over plain HTTP
     ///   :access_key_id (required)
     ///   :secret_access_key (required)
     ///   :session_token (optional)
@@ -223,11 +680,16 @@ impl RubyS3Client {
     ///   :multipart_upload_threshold (optional, default 0 = CRT auto-tunes)
     ///   :memory_limit_in_bytes (optional, default 0 = CRT default)
     ///   :max_active_connections_override (optional, default 0 = CRT default)
+    ///   :max_concurrent_transfers (optional, default 0 = unlimited)
+    ///   :alpn_list (optional) — semicolon-delimited ALPN protocols, e.g.
+    ///     "h2;http/1.1"; requires TLS
     fn rb_initialize(rb_self: &Self, options: RHash) -> Result<(), Error> {
         let region = hash_get_string_required(&options, "region")?;
-        let access_key_id = hash_get_string_required(&options, "access_key_id")?;
-        let secret_access_key = hash_get_string_required(&options, "secret_access_key")?;
-        let session_token = hash_get_string(&options, "session_token")?;
+        let access_key_id =
+            SecretString::new(hash_get_string_required(&options, "access_key_id")?);
+        let secret_access_key =
+            SecretString::new(hash_get_string_required(&options, "secret_access_key")?);
+        let session_token = hash_get_string(&options, "session_token")?.map(SecretString::new);
 
         let throughput_target_gbps =
             hash_get_f64(&options, "throughput_target_gbps", 10.0)?;
@@ -238,6 +700,35 @@ impl RubyS3Client {
             hash_get_u64(&options, "memory_limit_in_bytes", 0)?;
         let max_active_connections_override =
             hash_get_u32(&options, "max_active_connections_override", 0)?;
+        let compute_content_md5 = hash_get_bool(&options, "compute_content_md5", false)?;
+        let max_concurrent_transfers =
+            hash_get_u64(&options, "max_concurrent_transfers", 0)? as usize;
+        let endpoint = match hash_get_string(&options, "endpoint")? {
+            Some(endpoint) => {
+                let (scheme, host, port) = crate::pool::parse_endpoint(&endpoint)?;
+                Some(S3Endpoint::new(scheme, host, port))
+            }
+            None => None,
+        };
+        let default_tls = endpoint.as_ref().is_none_or(S3Endpoint::tls);
+        let use_tls = hash_get_bool(&options, "use_tls", default_tls)?;
+        if use_tls != default_tls && endpoint.is_some() {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                format!(
+                    ":use_tls => {} contradicts the endpoint's {} scheme",
+                    use_tls,
+                    if default_tls { "https" } else { "http" }
+                ),
+            ));
+        }
+        let alpn_list = hash_get_string(&options, "alpn_list")?.filter(|list| !list.is_empty());
+        if alpn_list.is_some() && !use_tls {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                ":alpn needs TLS, but this client connects over plain HTTP",
+            ));
+        }
 
         let client_options = S3ClientOptions {
             region,
@@ -249,6 +740,11 @@ impl RubyS3Client {
             multipart_upload_threshold,
             memory_limit_in_bytes,
             max_active_connections_override,
+            compute_content_md5,
+            max_concurrent_transfers,
+            endpoint,
+            use_tls,
+            alpn_list,
         };
 
         let client = S3Client::new(client_options).map_err(|e| -> Error { e.into() })?;
@@ -257,6 +753,17 @@ impl RubyS3Client {
         Ok(())
     }
 
+    /// Whether bucket names may contain dots. Only virtual-hosted requests
+    /// over TLS rule them out, as the bucket becomes a label of a host the
+    /// wildcard certificate must match; path-style requests (a custom
+    /// endpoint) and plaintext ones are fine.
+    fn allows_dotted_buckets(&self) -> bool {
+        self.inner
+            .borrow()
+            .as_ref()
+            .is_some_and(|client| client.endpoint().is_some() || !client.uses_tls())
+    }
+
     /// Borrow the inner S3Client, returning an error if not initialized.
     fn with_client<F, T>(ruby: &Ruby, rb_self: &typed_data::Obj<Self>, f: F) -> Result<T, Error>
     where
@@ -278,18 +785,23 @@ impl RubyS3Client {
     /// `_session_token` into the params hash before calling the native method.
     /// This creates a fresh CRT CredentialsProvider + SigningConfig for each
     /// request, ensuring that temporary credentials are never stale.
+    ///
+    /// The temporary Rust copies of the credentials are `SecretString`s and
+    /// are zeroed when this function returns.
     fn build_request_signing_config(
         params: &RHash,
         region: &str,
     ) -> Result<(crate::credentials::CredentialsProvider, Box<crate::signing::SigningConfig>), Error> {
-        let access_key_id = hash_get_string_required(params, "_access_key_id")?;
-        let secret_access_key = hash_get_string_required(params, "_secret_access_key")?;
-        let session_token = hash_get_string(params, "_session_token")?;
+        let access_key_id =
+            SecretString::new(hash_get_string_required(params, "_access_key_id")?);
+        let secret_access_key =
+            SecretString::new(hash_get_string_required(params, "_secret_access_key")?);
+        let session_token = hash_get_string(params, "_session_token")?.map(SecretString::new);
 
         let creds_provider = crate::credentials::CredentialsProvider::new_static(
-            &access_key_id,
-            &secret_access_key,
-            session_token.as_deref(),
+            access_key_id.as_str(),
+            secret_access_key.as_str(),
+            session_token.as_ref().map(SecretString::as_str),
         )
         .map_err(|e| -> Error { e.into() })?;
 
@@ -301,6 +813,48 @@ impl RubyS3Client {
         Ok((creds_provider, signing_config))
     }
 
+    /// Ruby: `client.max_active_connections`
+    ///
+    /// The connection limit the CRT chose for this client, after applying
+    /// `max_active_connections_override`.
+    fn rb_max_active_connections(
+        ruby: &Ruby,
+        rb_self: typed_data::Obj<Self>,
+    ) -> Result<u32, Error> {
+        Self::with_client(ruby, &rb_self, |client| Ok(client.max_active_connections()))
+    }
+
+    /// Ruby: `client.sign_post_policy(params)`
+    ///
+    /// params Hash:
+    ///   :policy (required) — base64-encoded POST policy document
+    ///   :date (required) — credential scope date, `YYYYMMDD`
+    ///   :_secret_access_key (injected by Ruby layer)
+    ///
+    /// Derives the SigV4 signing key for the client's region and the `s3`
+    /// service, and returns the hex signature of the policy.
+    fn rb_sign_post_policy(
+        ruby: &Ruby,
+        rb_self: typed_data::Obj<Self>,
+        params: RHash,
+    ) -> Result<String, Error> {
+        let policy = hash_get_string_required(&params, "policy")?;
+        let date = hash_get_string_required(&params, "date")?;
+        let secret_access_key =
+            SecretString::new(hash_get_string_required(&params, "_secret_access_key")?);
+
+        Self::with_client(ruby, &rb_self, |client| {
+            let signing_key = crate::signing::derive_signing_key(
+                secret_access_key.as_str(),
+                &date,
+                client.region(),
+                "s3",
+            )
+            .map_err(|e| -> Error { e.into() })?;
+            crate::signing::sign_with_key(&signing_key, &policy).map_err(|e| e.into())
+        })
+    }
+
     /// Ruby: `client.get_object(params)` or `client.get_object(params) { |chunk| ... }`
     ///
     /// params Hash:
@@ -308,22 +862,40 @@ impl RubyS3Client {
     ///   :key (required)
     ///   :response_target (optional) — String file path or IO object
     ///   :checksum_mode (optional) — 'ENABLED' to validate
+    ///   :range (optional) — `Range` header, e.g. "bytes=1024-"
+    ///   :if_range (optional) — `If-Range` ETag or HTTP date; requires :range
+    ///   :file_io (optional) — Hash of CRT file I/O tuning (see parse_file_io)
+    ///   :expected_bucket_owner (optional) — account ID that must own the bucket
+    ///   :request_payer (optional) — "requester" for Requester Pays buckets
     ///   :on_progress (optional) — Proc called with bytes_transferred
     ///   :_access_key_id (injected by Ruby layer)
     ///   :_secret_access_key (injected by Ruby layer)
     ///   :_session_token (injected by Ruby layer)
     ///
     /// Returns a Ruby Hash (see build_success_hash / build_error_hash).
+    /// With :if_range, `status_code` is 206 if the range was served and 200
+    /// if the object changed and the whole object came back.
     fn rb_get_object(
         ruby: &Ruby,
         rb_self: typed_data::Obj<Self>,
         params: RHash,
     ) -> Result<Value, Error> {
-        let bucket = hash_get_string_required(&params, "bucket")?;
+        let bucket = hash_get_bucket(&params, rb_self.allows_dotted_buckets())?;
         let key = hash_get_string_required(&params, "key")?;
         let response_target = hash_get_value(&params, "response_target")?;
         let checksum_mode = hash_get_string(&params, "checksum_mode")?;
+        let range = hash_get_header_value(&params, "range")?;
+        let if_range = hash_get_header_value(&params, "if_range")?;
+        if if_range.is_some() && range.is_none() {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                ":if_range requires :range",
+            ));
+        }
+        let file_io = parse_file_io(&params)?;
+        let governance = GovernanceParams::from_hash(&params)?;
         let _on_progress = hash_get_value(&params, "on_progress")?;
+        let telemetry = hash_get_bool(&params, "telemetry", false)?;
 
         // Determine body handling mode
         let validate_checksum = checksum_mode.as_deref() == Some("ENABLED");
@@ -359,8 +931,15 @@ impl RubyS3Client {
                 bucket: &bucket,
                 key: &key,
                 region: client.region(),
+                endpoint: client.endpoint(),
                 recv_filepath: recv_filepath.as_deref(),
+                file_io,
                 validate_checksum,
+                range: range.as_deref(),
+                if_range: if_range.as_deref(),
+                governance: governance.headers(),
+                limiter: client.transfer_limiter(),
+                telemetry,
             };
 
             match s3_request::get_object(options) {
@@ -370,15 +949,104 @@ impl RubyS3Client {
         })
     }
 
+    /// Ruby: `client.get_objects(params)`
+    ///
+    /// params Hash:
+    ///   :bucket (required)
+    ///   :keys (required) — Array of Strings; duplicates are fetched once
+    ///   :max_concurrency (optional, default 16) — GETs in flight at once
+    ///   :checksum_mode (optional) — 'ENABLED' to validate
+    ///   :expected_bucket_owner, :request_payer (optional) — as for get_object
+    ///   :_access_key_id (injected by Ruby layer)
+    ///   :_secret_access_key (injected by Ruby layer)
+    ///   :_session_token (injected by Ruby layer)
+    ///
+    /// Returns a Ruby Hash of key => result Hash (see build_success_hash /
+    /// build_error_hash). Bodies are always buffered.
+    fn rb_get_objects(
+        ruby: &Ruby,
+        rb_self: typed_data::Obj<Self>,
+        params: RHash,
+    ) -> Result<Value, Error> {
+        let bucket = hash_get_bucket(&params, rb_self.allows_dotted_buckets())?;
+        let mut keys: Vec<String> = match hash_get_value(&params, "keys")? {
+            Some(val) => magnus::TryConvert::try_convert(val)?,
+            None => {
+                return Err(Error::new(
+                    magnus::exception::arg_error(),
+                    "missing required option :keys",
+                ))
+            }
+        };
+        // Results are keyed by object key, so a repeated key would only be
+        // fetched again to overwrite its own entry.
+        let mut seen = HashSet::new();
+        keys.retain(|key| seen.insert(key.clone()));
+        let max_concurrency = hash_get_u64(&params, "max_concurrency", 16)?;
+        if max_concurrency == 0 {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                "max_concurrency must be positive",
+            ));
+        }
+        let checksum_mode = hash_get_string(&params, "checksum_mode")?;
+        let validate_checksum = checksum_mode.as_deref() == Some("ENABLED");
+        let governance = GovernanceParams::from_hash(&params)?;
+
+        Self::with_client(ruby, &rb_self, |client| {
+            // One signing config is shared by every GET in the batch.
+            let (_creds_provider, signing_config) =
+                Self::build_request_signing_config(&params, client.region())?;
+
+            let options = GetObjectsOptions {
+                client: client.as_ptr(),
+                signing_config: signing_config.as_ptr(),
+                bucket: &bucket,
+                keys: &keys,
+                region: client.region(),
+                endpoint: client.endpoint(),
+                validate_checksum,
+                max_concurrency: max_concurrency as usize,
+                governance: governance.headers(),
+                limiter: client.transfer_limiter(),
+            };
+
+            let results = RHash::new();
+            for (key, result) in keys.iter().zip(s3_request::get_objects(options)) {
+                let hash = match result {
+                    Ok(response) => build_success_hash(ruby, &response)?,
+                    Err(error) => build_error_hash(ruby, &error)?,
+                };
+                results.aset(ruby.str_new(key), hash)?;
+            }
+            Ok(results.as_value())
+        })
+    }
+
     /// Ruby: `client.put_object(params)`
     ///
     /// params Hash:
     ///   :bucket (required)
     ///   :key (required)
     ///   :body (required) — String, File, or IO object
-    ///   :content_length (optional) — Integer
+    ///   :content_length (optional) — Integer; taken from the File's size or
+    ///     the bytes read from an IO when omitted, and required for an IO
+    ///     that doesn't respond to `size` (exactly that many bytes are read)
+    ///   :max_parts (optional, default 10,000) — Integer; the part size is
+    ///     raised for this upload if the client's would need more parts
     ///   :content_type (optional) — String
+    ///   :cache_control (optional) — String
+    ///   :content_disposition (optional) — String
+    ///   :content_encoding (optional) — String
+    ///   :expires (optional) — String, an RFC 1123 date
     ///   :checksum_algorithm (optional) — 'CRC32', 'CRC32C', 'SHA1', 'SHA256'
+    ///   :tagging (optional) — Hash of String tag keys to String values
+    ///   :file_io (optional) — Hash of CRT file I/O tuning (see parse_file_io)
+    ///   :on_upload_review (optional) — callable invoked before a multipart
+    ///     upload completes; a falsy return aborts the upload and sets
+    ///     `:upload_rejected` in the error hash
+    ///   :expected_bucket_owner (optional) — account ID that must own the bucket
+    ///   :request_payer (optional) — "requester" for Requester Pays buckets
     ///   :on_progress (optional) — Proc called with bytes_transferred
     ///   :_access_key_id (injected by Ruby layer)
     ///   :_secret_access_key (injected by Ruby layer)
@@ -390,13 +1058,62 @@ impl RubyS3Client {
         rb_self: typed_data::Obj<Self>,
         params: RHash,
     ) -> Result<Value, Error> {
-        let bucket = hash_get_string_required(&params, "bucket")?;
+        let bucket = hash_get_bucket(&params, rb_self.allows_dotted_buckets())?;
         let key = hash_get_string_required(&params, "key")?;
         let body_val = hash_get_value(&params, "body")?;
-        let content_length = hash_get_optional_u64(&params, "content_length")?;
+        let mut content_length = hash_get_optional_u64(&params, "content_length")?;
+        let max_parts = hash_get_u64(&params, "max_parts", s3_request::MAX_UPLOAD_PARTS)?;
+        if !(1..=s3_request::MAX_UPLOAD_PARTS).contains(&max_parts) {
+            return Err(Error::new(
+                magnus::exception::arg_error(),
+                format!(
+                    "max_parts must be between 1 and {}",
+                    s3_request::MAX_UPLOAD_PARTS
+                ),
+            ));
+        }
         let content_type = hash_get_string(&params, "content_type")?;
+        let cache_control = hash_get_header_value(&params, "cache_control")?;
+        let content_disposition = hash_get_header_value(&params, "content_disposition")?;
+        let content_encoding = hash_get_header_value(&params, "content_encoding")?;
+        let expires = hash_get_header_value(&params, "expires")?;
+        if let Some(v) = &expires {
+            if parse_http_date(v).is_none() {
+                return Err(Error::new(
+                    magnus::exception::arg_error(),
+                    format!(
+                        "invalid :expires '{}': expected an RFC 1123 date such as 'Wed, 21 Oct 2015 07:28:00 GMT'",
+                        v
+                    ),
+                ));
+            }
+        }
         let checksum_algorithm_name = hash_get_string(&params, "checksum_algorithm")?;
+        let tagging = match hash_get_value(&params, "tagging")? {
+            Some(val) => {
+                let tags: RHash = magnus::TryConvert::try_convert(val)?;
+                Some(s3_request::encode_tagging(&tags.to_vec::<String, String>()?))
+            }
+            None => None,
+        };
+        // Normalized by the Ruby wrapper into header-ready Strings.
+        let object_lock = match hash_get_value(&params, "object_lock")? {
+            Some(val) => Some(RHash::try_convert(val)?),
+            None => None,
+        };
+        let (lock_mode, lock_retain_until, lock_legal_hold) = match &object_lock {
+            Some(lock) => (
+                hash_get_header_value(lock, "mode")?,
+                hash_get_header_value(lock, "retain_until")?,
+                hash_get_header_value(lock, "legal_hold")?,
+            ),
+            None => (None, None, None),
+        };
+        let file_io = parse_file_io(&params)?;
+        let governance = GovernanceParams::from_hash(&params)?;
+        let on_upload_review = hash_get_value(&params, "on_upload_review")?;
         let _on_progress = hash_get_value(&params, "on_progress")?;
+        let telemetry = hash_get_bool(&params, "telemetry", false)?;
 
         // Parse checksum algorithm if provided
         let checksum_algorithm = match &checksum_algorithm_name {
@@ -414,6 +1131,27 @@ impl RubyS3Client {
             }
             None => None,
         };
+        let verify_checksum = match hash_get_string(&params, "verify_checksum")? {
+            Some(name) => {
+                let algorithm = TrailerChecksum::from_name(&name).ok_or_else(|| {
+                    Error::new(
+                        magnus::exception::arg_error(),
+                        format!(
+                            "invalid verify_checksum '{}': must be CRC32, CRC32C, or CRC64NVME",
+                            name
+                        ),
+                    )
+                })?;
+                if checksum_algorithm.is_some() {
+                    return Err(Error::new(
+                        magnus::exception::arg_error(),
+                        "verify_checksum cannot be combined with checksum_algorithm",
+                    ));
+                }
+                Some(algorithm)
+            }
+            None => None,
+        };
 
         // Determine body mode: send_filepath (File), buffer (String), or read+buffer (IO)
         let (send_filepath, body_bytes) = match body_val {
@@ -432,11 +1170,14 @@ impl RubyS3Client {
                     if has_path {
                         // File object — extract path for send_filepath mode
                         let path: String = val.funcall("path", ())?;
+                        if content_length.is_none() {
+                            content_length = Some(val.funcall("size", ())?);
+                        }
                         (Some(path), None)
                     } else {
                         // Generic IO — read contents into memory
-                        let contents: RString = val.funcall("read", ())?;
-                        let bytes = unsafe { contents.as_slice().to_vec() };
+                        let bytes = read_io_body(val, content_length)?;
+                        content_length.get_or_insert(bytes.len() as u64);
                         (None, Some(bytes))
                     }
                 }
@@ -444,25 +1185,277 @@ impl RubyS3Client {
             None => (None, None),
         };
 
+        // The upload's size, when it can be known before sending:
+        // :content_length (explicit, or taken from a File or IO body) or
+        // the String body's length.
+        let upload_length = content_length.or_else(|| body_bytes.as_ref().map(|b| b.len() as u64));
+
         Self::with_client(ruby, &rb_self, |client| {
+            // Check the part count before starting, rather than having S3
+            // reject part 10,001 hours into the upload.
+            let part_size = match upload_length {
+                Some(length) => s3_request::upload_part_size(length, client.part_size(), max_parts)
+                    .map_err(|msg| {
+                        let hint = if max_parts < s3_request::MAX_UPLOAD_PARTS {
+                            "; raise :max_parts"
+                        } else {
+                            ""
+                        };
+                        Error::new(magnus::exception::arg_error(), format!("{}{}", msg, hint))
+                    })?,
+                None => 0,
+            };
+
             // Build per-request signing config with fresh credentials
             let (_creds_provider, signing_config) =
                 Self::build_request_signing_config(&params, client.region())?;
 
+            // The review hook runs on this thread with the GVL held. A Ruby
+            // exception from the hook rejects the upload and is re-raised
+            // once the meta-request has finished.
+            let mut rejected = false;
+            let mut review_error: Option<Error> = None;
+            let mut review_fn = |review: &UploadReview| -> bool {
+                let Some(hook) = on_upload_review else { return true };
+                match call_upload_review(ruby, hook, review) {
+                    Ok(approved) => {
+                        rejected = !approved;
+                        approved
+                    }
+                    Err(e) => {
+                        review_error = Some(e);
+                        rejected = true;
+                        false
+                    }
+                }
+            };
+
             let options = PutObjectOptions {
                 client: client.as_ptr(),
                 signing_config: signing_config.as_ptr(),
                 bucket: &bucket,
                 key: &key,
                 region: client.region(),
+                endpoint: client.endpoint(),
                 send_filepath: send_filepath.as_deref(),
+                file_io,
                 body: body_bytes,
                 content_length,
+                part_size,
                 content_type: content_type.as_deref(),
+                cache_control: cache_control.as_deref(),
+                content_disposition: content_disposition.as_deref(),
+                content_encoding: content_encoding.as_deref(),
+                expires: expires.as_deref(),
                 checksum_algorithm,
+                tagging: tagging.as_deref(),
+                object_lock_mode: lock_mode.as_deref(),
+                object_lock_retain_until: lock_retain_until.as_deref(),
+                object_lock_legal_hold: lock_legal_hold.as_deref(),
+                upload_review: if on_upload_review.is_some() {
+                    Some(&mut review_fn)
+                } else {
+                    None
+                },
+                governance: governance.headers(),
+                limiter: client.transfer_limiter(),
+                verify_checksum,
+                telemetry,
             };
 
-            match s3_request::put_object(options) {
+            let result = s3_request::put_object(options);
+            if let Some(e) = review_error {
+                return Err(e);
+            }
+
+            match result {
+                Ok(response) => build_success_hash(ruby, &response),
+                Err(error) => {
+                    let hash = build_error_hash(ruby, &error)?;
+                    if rejected {
+                        RHash::try_convert(hash)?.aset(Symbol::new("upload_rejected"), true)?;
+                    }
+                    Ok(hash)
+                }
+            }
+        })
+    }
+
+    /// Ruby: `client.pipe_object(params)`
+    ///
+    /// params Hash:
+    ///   :source (required) — Hash of :bucket, :key, and optionally
+    ///     :checksum_mode, :expected_bucket_owner, :request_payer, plus the
+    ///     injected credentials
+    ///   :destination (required) — Hash of :bucket, :key, and optionally
+    ///     :content_type, :expected_bucket_owner, :request_payer, plus the
+    ///     injected credentials
+    ///   :part_size (optional, default 0 = the client's) — upload part size
+    ///   :transform (optional) — Proc called with each piece of the source
+    ///     and then nil, returning the bytes to upload (see
+    ///     s3_request::pipe_object)
+    ///
+    /// Returns a Ruby Hash (see build_success_hash / build_error_hash) for
+    /// the upload, or for the GET if that failed. An exception from the
+    /// transform is re-raised once both requests have stopped.
+    fn rb_pipe_object(
+        ruby: &Ruby,
+        rb_self: typed_data::Obj<Self>,
+        params: RHash,
+    ) -> Result<Value, Error> {
+        let allow_dots = rb_self.allows_dotted_buckets();
+        let source = hash_get_hash_required(&params, "source")?;
+        let destination = hash_get_hash_required(&params, "destination")?;
+        let source_bucket = hash_get_bucket(&source, allow_dots)?;
+        let source_key = hash_get_string_required(&source, "key")?;
+        let checksum_mode = hash_get_string(&source, "checksum_mode")?;
+        let validate_checksum = checksum_mode.as_deref() == Some("ENABLED");
+        let source_governance = GovernanceParams::from_hash(&source)?;
+        let bucket = hash_get_bucket(&destination, allow_dots)?;
+        let key = hash_get_string_required(&destination, "key")?;
+        let content_type = hash_get_string(&destination, "content_type")?;
+        let governance = GovernanceParams::from_hash(&destination)?;
+        let part_size = hash_get_u64(&params, "part_size", 0)?;
+        let transform = hash_get_value(&params, "transform")?;
+
+        Self::with_client(ruby, &rb_self, |client| {
+            let (_source_creds, source_signing) =
+                Self::build_request_signing_config(&source, client.region())?;
+            let (_creds_provider, signing_config) =
+                Self::build_request_signing_config(&destination, client.region())?;
+            let paced_client = client.paced_client().map_err(|e| -> Error { e.into() })?;
+
+            // The transform runs on this thread with the GVL held. A Ruby
+            // exception from it abandons the pipe and is re-raised once
+            // both meta-requests have finished.
+            let mut transform_error: Option<Error> = None;
+            let mut transform_fn = |chunk: Option<&[u8]>| -> Option<Vec<u8>> {
+                let transform = transform?;
+                match call_pipe_transform(ruby, transform, chunk) {
+                    Ok(output) => Some(output),
+                    Err(e) => {
+                        transform_error = Some(e);
+                        None
+                    }
+                }
+            };
+
+            let options = PipeObjectOptions {
+                source: GetObjectOptions {
+                    client: paced_client,
+                    signing_config: source_signing.as_ptr(),
+                    bucket: &source_bucket,
+                    key: &source_key,
+                    region: client.region(),
+                    endpoint: client.endpoint(),
+                    recv_filepath: None,
+                    file_io: None,
+                    validate_checksum,
+                    range: None,
+                    if_range: None,
+                    governance: source_governance.headers(),
+                    limiter: None,
+                    telemetry: false,
+                },
+                client: client.as_ptr(),
+                signing_config: signing_config.as_ptr(),
+                bucket: &bucket,
+                key: &key,
+                region: client.region(),
+                endpoint: client.endpoint(),
+                part_size,
+                content_type: content_type.as_deref(),
+                governance: governance.headers(),
+                limiter: client.transfer_limiter(),
+                transform: if transform.is_some() {
+                    Some(&mut transform_fn)
+                } else {
+                    None
+                },
+            };
+
+            let result = s3_request::pipe_object(options);
+            if let Some(e) = transform_error {
+                return Err(e);
+            }
+
+            match result {
+                Ok(response) => build_success_hash(ruby, &response),
+                Err(error) => build_error_hash(ruby, &error),
+            }
+        })
+    }
+
+    /// Ruby: `client.make_request(params)`
+    ///
+    /// params Hash:
+    ///   :bucket (required)
+    ///   :key (required)
+    ///   :method (required) — HTTP method, e.g. "POST"
+    ///   :operation_name (required) — S3 operation, e.g. "CreateMultipartUpload"
+    ///   :query (optional) — percent-encoded query string without the "?"
+    ///   :body (optional) — String request body
+    ///   :headers (optional) — Hash of extra request headers
+    ///   :expected_bucket_owner, :request_payer (optional) — as for get_object
+    ///   :_access_key_id (injected by Ruby layer)
+    ///   :_secret_access_key (injected by Ruby layer)
+    ///   :_session_token (injected by Ruby layer)
+    ///
+    /// Sends one DEFAULT meta-request. Returns a Ruby Hash (see
+    /// build_success_hash / build_error_hash) with the body buffered.
+    fn rb_make_request(
+        ruby: &Ruby,
+        rb_self: typed_data::Obj<Self>,
+        params: RHash,
+    ) -> Result<Value, Error> {
+        let bucket = hash_get_bucket(&params, rb_self.allows_dotted_buckets())?;
+        let key = hash_get_string_required(&params, "key")?;
+        let method = hash_get_string_required(&params, "method")?;
+        let operation_name = hash_get_string_required(&params, "operation_name")?;
+        let query = hash_get_header_value(&params, "query")?;
+        let body = match hash_get_value(&params, "body")? {
+            Some(val) => Some(unsafe { RString::try_convert(val)?.as_slice().to_vec() }),
+            None => None,
+        };
+        let extra_headers = match hash_get_value(&params, "headers")? {
+            Some(val) => RHash::try_convert(val)?.to_vec::<String, String>()?,
+            None => Vec::new(),
+        };
+        for (name, value) in &extra_headers {
+            if name
+                .bytes()
+                .chain(value.bytes())
+                .any(|b| b == b'\r' || b == b'\n' || b == 0)
+            {
+                return Err(Error::new(
+                    magnus::exception::arg_error(),
+                    format!("header {} must not contain CR, LF, or NUL characters", name),
+                ));
+            }
+        }
+        let governance = GovernanceParams::from_hash(&params)?;
+
+        Self::with_client(ruby, &rb_self, |client| {
+            let (_creds_provider, signing_config) =
+                Self::build_request_signing_config(&params, client.region())?;
+
+            let options = DefaultRequestOptions {
+                client: client.as_ptr(),
+                signing_config: signing_config.as_ptr(),
+                bucket: &bucket,
+                key: &key,
+                region: client.region(),
+                endpoint: client.endpoint(),
+                method: &method,
+                operation_name: &operation_name,
+                query: query.as_deref(),
+                body,
+                extra_headers,
+                governance: governance.headers(),
+                limiter: client.transfer_limiter(),
+            };
+
+            match s3_request::default_request(options) {
                 Ok(response) => build_success_hash(ruby, &response),
                 Err(error) => build_error_hash(ruby, &error),
             }
@@ -483,7 +1476,18 @@ pub fn define_s3_client(
     class.define_alloc_func::<RubyS3Client>();
     class.define_method("initialize", method!(RubyS3Client::rb_initialize, 1))?;
     class.define_method("get_object", method!(RubyS3Client::rb_get_object, 1))?;
+    class.define_method("get_objects", method!(RubyS3Client::rb_get_objects, 1))?;
     class.define_method("put_object", method!(RubyS3Client::rb_put_object, 1))?;
+    class.define_method(
+        "max_active_connections",
+        method!(RubyS3Client::rb_max_active_connections, 0),
+    )?;
+    class.define_method(
+        "sign_post_policy",
+        method!(RubyS3Client::rb_sign_post_policy, 1),
+    )?;
+    class.define_method("make_request", method!(RubyS3Client::rb_make_request, 1))?;
+    class.define_method("pipe_object", method!(RubyS3Client::rb_pipe_object, 1))?;
 
     Ok(())
 }
@@ -8,9 +8,14 @@
 //! The signing config is passed by reference to the CRT S3 client, which
 //! deep-copies what it needs. The config owns the region string to ensure
 //! the byte cursor pointing into it remains valid for the config's lifetime.
+//!
+//! Also exposes SigV4 signing-key derivation on top of aws-c-cal's
+//! HMAC-SHA256, for signing documents the CRT signer does not handle
+//! (S3 POST policies).
 
-use crate::credentials::{AwsByteCursor, AwsCredentialsProvider, CredentialsProvider};
+use crate::credentials::{AwsByteCursor, AwsCredentialsProvider, CredentialsProvider, SecretString};
 use crate::error::CrtError;
+use crate::runtime::{AwsAllocator, CrtRuntime};
 
 // ---------------------------------------------------------------------------
 // Opaque CRT type
@@ -52,6 +57,28 @@ extern "C" {
     fn aws_validate_aws_signing_config_aws(
         config: *const AwsSigningConfigAws,
     ) -> i32;
+
+    /// Compute HMAC-SHA256 of `to_hmac` keyed by `secret`, appending the
+    /// digest to `output`. Returns 0 on success.
+    fn aws_sha256_hmac_compute(
+        allocator: *mut AwsAllocator,
+        secret: *const AwsByteCursor,
+        to_hmac: *const AwsByteCursor,
+        output: *mut AwsByteBuf,
+        truncate_to: usize,
+    ) -> i32;
+}
+
+/// Mirrors `struct aws_byte_buf` from aws-c-common.
+///
+/// Used with a null allocator over a fixed stack array, so the CRT writes
+/// into our memory and never tries to grow or free it.
+#[repr(C)]
+struct AwsByteBuf {
+    len: usize,
+    buffer: *mut u8,
+    capacity: usize,
+    allocator: *mut AwsAllocator,
 }
 
 // ---------------------------------------------------------------------------
@@ -132,3 +159,65 @@ impl SigningConfig {
         self.config.as_ref() as *const AwsSigningConfigAws
     }
 }
+
+// ---------------------------------------------------------------------------
+// SigV4 signing key derivation
+// ---------------------------------------------------------------------------
+
+/// Length of a SHA-256 digest / SigV4 signing key.
+const SHA256_LEN: usize = 32;
+
+/// HMAC-SHA256 via aws-c-cal.
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<[u8; SHA256_LEN], CrtError> {
+    let mut digest = [0u8; SHA256_LEN];
+    let mut output = AwsByteBuf {
+        len: 0,
+        buffer: digest.as_mut_ptr(),
+        capacity: SHA256_LEN,
+        allocator: std::ptr::null_mut(),
+    };
+    let key_cursor = AwsByteCursor { len: key.len(), ptr: key.as_ptr() };
+    let data_cursor = AwsByteCursor { len: data.len(), ptr: data.as_ptr() };
+
+    // Going through the runtime guarantees aws-c-cal has been initialized.
+    let allocator = CrtRuntime::get().allocator();
+    let rc = unsafe {
+        aws_sha256_hmac_compute(allocator, &key_cursor, &data_cursor, &mut output, 0)
+    };
+    if rc != 0 {
+        return Err(CrtError::last_error());
+    }
+    Ok(digest)
+}
+
+/// Derive the SigV4 signing key for `date` (`YYYYMMDD`), `region`, and
+/// `service`:
+///
+/// ```text
+/// kDate    = HMAC("AWS4" + secret, date)
+/// kRegion  = HMAC(kDate, region)
+/// kService = HMAC(kRegion, service)
+/// kSigning = HMAC(kService, "aws4_request")
+/// ```
+pub fn derive_signing_key(
+    secret_access_key: &str,
+    date: &str,
+    region: &str,
+    service: &str,
+) -> Result<[u8; SHA256_LEN], CrtError> {
+    let secret = SecretString::new(format!("AWS4{}", secret_access_key));
+    let k_date = hmac_sha256(secret.as_str().as_bytes(), date.as_bytes())?;
+    let k_region = hmac_sha256(&k_date, region.as_bytes())?;
+    let k_service = hmac_sha256(&k_region, service.as_bytes())?;
+    hmac_sha256(&k_service, b"aws4_request")
+}
+
+/// Sign `string_to_sign` with a derived signing key, returning the
+/// lowercase hex signature.
+pub fn sign_with_key(
+    signing_key: &[u8; SHA256_LEN],
+    string_to_sign: &str,
+) -> Result<String, CrtError> {
+    let signature = hmac_sha256(signing_key, string_to_sign.as_bytes())?;
+    Ok(signature.iter().map(|b| format!("{:02x}", b)).collect())
+}
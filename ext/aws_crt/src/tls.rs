@@ -56,6 +56,12 @@ extern "C" {
         ca_file: *const std::ffi::c_char,
     ) -> i32;
 
+    fn aws_tls_ctx_options_set_tls_cipher_preference(
+        options: *mut TlsCtxOptionsBuffer,
+        cipher_pref: u32,
+    );
+    fn aws_tls_is_cipher_pref_supported(cipher_pref: u32) -> bool;
+
     fn aws_tls_client_ctx_new(
         allocator: *mut AwsAllocator,
         options: *const TlsCtxOptionsBuffer,
@@ -63,6 +69,73 @@ extern "C" {
     fn aws_tls_ctx_release(ctx: *mut AwsTlsCtx);
 }
 
+// ---------------------------------------------------------------------------
+// Platform TLS backend
+// ---------------------------------------------------------------------------
+
+/// Name of the TLS implementation the CRT was built against.
+///
+/// aws-c-io selects its TLS channel handler at compile time from the target
+/// OS, so this mirrors the same choice: Security.framework on Apple
+/// platforms, SChannel on Windows, and s2n-tls everywhere else.
+pub fn backend_name() -> &'static str {
+    if cfg!(target_vendor = "apple") {
+        "securetransport"
+    } else if cfg!(target_os = "windows") {
+        "schannel"
+    } else {
+        "s2n"
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Cipher preferences
+// ---------------------------------------------------------------------------
+
+/// A named cipher suite policy (`enum aws_tls_cipher_pref`). Only s2n-tls
+/// implements anything but `SystemDefault`; see `is_supported`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TlsCipherPreference {
+    /// The TLS library's default policy.
+    SystemDefault,
+    /// `AWS_IO_TLS_CIPHER_PREF_PQ_TLSv1_0_2021_05`: TLS 1.0+ with hybrid
+    /// post-quantum key exchange.
+    PqTlsV1,
+    /// `AWS_IO_TLS_CIPHER_PREF_PQ_TLSV1_2_2024_10`: TLS 1.2+ with hybrid
+    /// post-quantum key exchange and FIPS-approved ciphers.
+    PqTlsV1_2,
+    /// `AWS_IO_TLS_CIPHER_PREF_PQ_DEFAULT`: the CRT's recommended
+    /// post-quantum policy, which may change between releases.
+    PqDefault,
+}
+
+impl TlsCipherPreference {
+    /// Look up a preference by its Ruby option name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "system_default" => Some(Self::SystemDefault),
+            "pq_tls_v1" => Some(Self::PqTlsV1),
+            "pq_tls_v1_2" => Some(Self::PqTlsV1_2),
+            "pq_default" => Some(Self::PqDefault),
+            _ => None,
+        }
+    }
+
+    fn as_crt(self) -> u32 {
+        match self {
+            Self::SystemDefault => 0,
+            Self::PqTlsV1 => 6,
+            Self::PqTlsV1_2 => 7,
+            Self::PqDefault => 8,
+        }
+    }
+
+    /// Whether the TLS backend this build uses implements the policy.
+    pub fn is_supported(self) -> bool {
+        unsafe { aws_tls_is_cipher_pref_supported(self.as_crt()) }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // TlsContext — wraps aws_tls_ctx
 // ---------------------------------------------------------------------------
@@ -73,8 +146,13 @@ pub struct TlsOptions {
     pub verify_peer: bool,
     /// Path to a custom CA file for certificate verification.
     pub ca_filepath: Option<String>,
+    /// Path to a directory of CA certificates (OpenSSL hashed-name layout,
+    /// as produced by `c_rehash`), used alongside or instead of `ca_filepath`.
+    pub ca_path: Option<String>,
     /// Semicolon-delimited ALPN protocol list (e.g. "h2;http/1.1").
     pub alpn_list: Option<String>,
+    /// Cipher suite policy (default: the TLS library's own).
+    pub cipher_preference: Option<TlsCipherPreference>,
 }
 
 impl Default for TlsOptions {
@@ -82,7 +160,9 @@ impl Default for TlsOptions {
         Self {
             verify_peer: true,
             ca_filepath: None,
+            ca_path: None,
             alpn_list: None,
+            cipher_preference: None,
         }
     }
 }
@@ -138,14 +218,20 @@ impl TlsContext {
         // Peer verification
         aws_tls_ctx_options_set_verify_peer(opts_ptr, options.verify_peer);
 
-        // Custom CA bundle
-        if let Some(ref ca_path) = options.ca_filepath {
-            let ca_file_c = CString::new(ca_path.as_str())
-                .map_err(|_| CrtError::from_code(0))?;
+        // Custom CA bundle and/or CA directory
+        if options.ca_filepath.is_some() || options.ca_path.is_some() {
+            let to_c = |path: &Option<String>| {
+                path.as_deref()
+                    .map(CString::new)
+                    .transpose()
+                    .map_err(|_| CrtError::from_code(0))
+            };
+            let ca_file_c = to_c(&options.ca_filepath)?;
+            let ca_path_c = to_c(&options.ca_path)?;
             let rc = aws_tls_ctx_options_override_default_trust_store_from_path(
                 opts_ptr,
-                std::ptr::null(), // ca_path (directory) — not used
-                ca_file_c.as_ptr(),
+                ca_path_c.as_ref().map_or(std::ptr::null(), |c| c.as_ptr()),
+                ca_file_c.as_ref().map_or(std::ptr::null(), |c| c.as_ptr()),
             );
             if rc != 0 {
                 return Err(CrtError::last_error());
@@ -162,6 +248,12 @@ impl TlsContext {
             }
         }
 
+        // Cipher suite policy. aws_tls_client_ctx_new fails with
+        // AWS_IO_TLS_CIPHER_PREF_UNSUPPORTED if the backend lacks it.
+        if let Some(pref) = options.cipher_preference {
+            aws_tls_ctx_options_set_tls_cipher_preference(opts_ptr, pref.as_crt());
+        }
+
         // Create the TLS context
         let ctx = aws_tls_client_ctx_new(allocator, opts_ptr);
         if ctx.is_null() {
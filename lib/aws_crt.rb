@@ -4,6 +4,49 @@ require_relative "aws_crt/version"
 
 module AwsCrt
   class Error < StandardError; end
+
+  # Raised when a client is built with `validate_credentials: true` and no
+  # usable credentials can be resolved.
+  class MissingCredentialsError < Error; end
+
+  # Configure process-wide CRT runtime settings.
+  #
+  # Settings are applied when the shared CRT runtime (event loop group, host
+  # resolver, client bootstrap) is first initialized, which happens when the
+  # first {Http::ConnectionPool} or {S3::Client} is created. Calling this
+  # afterwards raises {AwsCrt::Error}. Keywords left nil keep their current
+  # value, so settings from separate calls accumulate.
+  #
+  # @example Re-resolve DNS every 10 seconds for DNS-based failover
+  #   AwsCrt.configure(dns_ttl_secs: 10)
+  #
+  # @example Ride out a slow resolver on cold start
+  #   AwsCrt.configure(dns_failure_retries: 3, dns_retry_backoff_ms: 200)
+  #
+  # @param dns_ttl_secs [Integer, nil] maximum time in seconds a resolved DNS
+  #   record is cached before it is re-resolved (CRT default: 30)
+  # @param dns_failure_retries [Integer, nil] how many more times a
+  #   {Http::ConnectionPool} request asks for a connection after its DNS
+  #   query fails (`AWS_IO_DNS_QUERY_FAILED`). Nothing has been sent at that
+  #   point, so this applies to every method, independently of the pool's
+  #   `:max_retries`. Default: 0
+  # @param dns_retry_backoff_ms [Integer, nil] delay before the first DNS
+  #   retry, doubled for each later one. Default: 100
+  # @return [nil]
+  def self.configure(dns_ttl_secs: nil, dns_failure_retries: nil, dns_retry_backoff_ms: nil)
+    unless dns_ttl_secs.nil? || (dns_ttl_secs.is_a?(Integer) && dns_ttl_secs.positive?)
+      raise ArgumentError, ":dns_ttl_secs must be a positive Integer"
+    end
+    unless dns_failure_retries.nil? || (dns_failure_retries.is_a?(Integer) && !dns_failure_retries.negative?)
+      raise ArgumentError, ":dns_failure_retries must be a non-negative Integer"
+    end
+    unless dns_retry_backoff_ms.nil? || (dns_retry_backoff_ms.is_a?(Integer) && dns_retry_backoff_ms.positive?)
+      raise ArgumentError, ":dns_retry_backoff_ms must be a positive Integer"
+    end
+
+    _native_configure(dns_ttl_secs, dns_failure_retries, dns_retry_backoff_ms)
+    nil
+  end
 end
 
 # Load the native extension after AwsCrt::Error is defined,
@@ -2,7 +2,9 @@
 
 require_relative "http/errors"
 require_relative "http/connection_pool"
+require_relative "http/response"
 require_relative "http/connection_pool_manager"
+require_relative "http/response_cache"
 require_relative "http/handler"
 require_relative "http/plugin"
 require_relative "http/patcher"
@@ -14,13 +14,225 @@
 #     ssl_verify_peer: true
 #   )
 #
-#   status, headers, body = pool.request("GET", "/", [["Host", "example.com"]])
+#   response = pool.request("GET", "/", [["Host", "example.com"]])
+#   response.status          # => 200
+#   response["content-type"] # case-insensitive
+#
+# `request` returns an {AwsCrt::Http::Response}, which also destructures as
+# `status, headers, body = pool.request(...)`.
+#
+# `#scheme`, `#host` and `#port` return the endpoint the pool was created
+# with, the port defaulting to 80 or 443; `#endpoint` joins them as
+# `"scheme://host:port"`, e.g. for logging or keying per-endpoint state.
+#
+# @example
+#   pool = AwsCrt::Http::ConnectionPool.new("https://example.com")
+#   pool.endpoint # => "https://example.com:443"
+#   pool.port     # => 443
+#
+# Disabling TLS peer verification requires an explicit
+# `ssl_verify_peer: false` (other falsy-looking values raise TypeError) and
+# logs a warning once per endpoint, via `:logger` if given or `Kernel#warn`.
 #
 # @example Streaming response
 #   pool.request("GET", "/large", [["Host", "example.com"]]) do |chunk|
 #     io.write(chunk)
 #   end
 #
+# @example Pace a fast server to a slow streaming consumer
+#   pool = AwsCrt::Http::ConnectionPool.new("https://example.com",
+#     enable_read_backpressure: true,
+#     initial_read_window: 256 * 1024
+#   )
+#   pool.request("GET", "/large", [["Host", "example.com"]]) do |chunk|
+#     slow_io.write(chunk) # the read window reopens after each chunk
+#   end
+#
+# With `enable_read_backpressure: true` the server may only send
+# `initial_read_window` bytes (default 64 KiB) ahead of what has been
+# consumed. Streamed chunks count as consumed once the block returns;
+# buffered responses reopen the window as data arrives.
+#
+# `read_timeout_ms:` only bounds the wait for the first byte of the
+# response. To also give up on a streamed download that stalls part-way,
+# set `inactivity_timeout_ms:`: if no body data arrives for that long, the
+# connection is closed and the request raises AwsCrt::Http::TimeoutError.
+# Time spent in the block doesn't count.
+#
+# @example Abandon a download that goes quiet for 30 seconds
+#   pool = AwsCrt::Http::ConnectionPool.new("https://example.com",
+#     read_timeout_ms: 10_000,
+#     inactivity_timeout_ms: 30_000
+#   )
+#
+# On dual-stack hosts with unreliable IPv6, pass `socket_domain: :ipv4` to
+# open IPv4 sockets only (or `:ipv6` for the reverse). By default the CRT
+# chooses.
+#
+# NAT gateways and load balancers often drop connections that sit idle in
+# the pool without telling either end, so the first request after a quiet
+# spell fails. `tcp_keepalive: true` turns on TCP keep-alive with the OS's
+# probe timings; a Hash sets them: `:idle_sec` before the first probe and
+# `:interval_sec` between probes (both or neither), and
+# `:max_failed_probes` before the connection is treated as dead. Keep
+# `:idle_sec` below the middlebox's idle timeout.
+#
+# @example Keep pooled connections alive through a 350s NAT idle timeout
+#   pool = AwsCrt::Http::ConnectionPool.new("https://example.com",
+#     tcp_keepalive: { idle_sec: 60, interval_sec: 15, max_failed_probes: 4 }
+#   )
+#
+# To trust a custom CA, pass `ssl_ca_bundle:` (a PEM file) and/or
+# `ssl_ca_path:` (a directory of certificates named by subject hash, as
+# `c_rehash` or `openssl rehash` lays them out, e.g. "/etc/ssl/certs").
+# Either replaces the system trust store. `ssl_ca_path:` needs s2n-tls
+# (Linux); on macOS the pool raises when it creates the TLS context.
+#
+# `tls_cipher_preference:` restricts the TLS cipher suites offered, for
+# compliance or post-quantum requirements: `:pq_tls_v1` (hybrid
+# post-quantum key exchange, TLS 1.0+), `:pq_tls_v1_2` (the same with TLS
+# 1.2+ and FIPS-approved ciphers), `:pq_default` (the CRT's current
+# recommended post-quantum policy) or `:system_default`. Only s2n-tls
+# (Linux) implements anything but `:system_default`; on macOS and Windows
+# the others raise ArgumentError at construction.
+#
+# Buffered responses reserve memory up front from the response's
+# `Content-Length`, but never more than `:max_prealloc_bytes` (default
+# 4 MiB), so a server cannot force a huge allocation just by advertising a
+# large body. Bodies past the cap are still buffered as they arrive.
+# For a server known to send wrong Content-Length values, pass
+# `trust_content_length: false` to skip the reservation altogether.
+#
+# @example Upload with an aws-chunked CRC32 trailer
+#   pool.request("PUT", "/bucket/key", headers, data, trailer_checksum: "CRC32")
+#
+# Pass `connection_close: true` to send `Connection: close` and discard the
+# connection once the response completes, for servers with unreliable
+# keep-alive handling.
+#
+# With `max_retries: n`, a request that fails with a connection error (DNS
+# failure, refused or reset connection, or a connection closed before the
+# response) is resent up to n times — but only for idempotent methods (GET,
+# HEAD, PUT, DELETE, OPTIONS, TRACE). POST and PATCH are never resent unless
+# the request passes `retry_non_idempotent: true`, since the server may
+# already have acted on them. Streaming requests are not retried once a
+# chunk has been yielded. `Response#attempts` is the attempt that succeeded
+# and `Response#retries` the number of retries before it.
+#
+# Retries also cover a 429 or 503 response with a `Retry-After` header: the
+# pool waits the delay it gives (seconds or an HTTP date), at most
+# `:max_retry_after_ms` (default 20,000), without holding the GVL, then
+# resends. The throttled response's body is not yielded to a streaming
+# block. When retries run out, the last 429 or 503 is returned as usual.
+#
+# @example Retry idempotent requests on connection errors
+#   pool = AwsCrt::Http::ConnectionPool.new("https://example.com", max_retries: 2)
+#   response = pool.request("GET", "/", [["Host", "example.com"]])
+#   response.retries # => 0 unless a connection error or Retry-After was retried
+#
+# `Response#timing` breaks the successful attempt's latency down into
+# connection acquisition, time to first header byte, time to first body
+# byte and total duration, in milliseconds.
+#
+# @example Log slow requests
+#   response = pool.request("GET", "/", [["Host", "example.com"]])
+#   t = response.timing
+#   warn "slow: connect=#{t[:connect_ms]} ttfb=#{t[:ttfb_ms]}" if t[:total_ms] > 500
+#
+# Pass `response_targets:` to stream the body to several sinks at once,
+# e.g. a file and a digest, without a second pass over the data. Each chunk
+# goes to every sink in order, via `<<` or else `update`, and then to the
+# block if one is given. An exception raised by a sink stops delivery and is
+# re-raised once the request completes.
+#
+# @example Write a download to disk while hashing it
+#   File.open("object.bin", "wb") do |file|
+#     digest = Digest::SHA256.new
+#     pool.request("GET", "/object.bin", [["Host", "example.com"]], nil,
+#                  response_targets: [file, digest])
+#     digest.hexdigest
+#   end
+#
+# @example Decode a CBOR response body in native code
+#   response = pool.request("POST", "/", headers, AwsCrt::Cbor.encode(input), decode: :cbor)
+#   response.data # => decoded Ruby object; raises AwsCrt::Cbor::Error if the body isn't CBOR
+#
+# With `decompress: true` the request advertises `Accept-Encoding: gzip,
+# deflate` (a caller-supplied Accept-Encoding is sent as given instead) and
+# a gzip or deflate response body is decoded before it is returned. The
+# response then has no Content-Encoding header and its Content-Length is
+# the decoded size. Bodies in any other coding are returned untouched.
+# Buffered requests only; corrupt compressed data raises
+# {AwsCrt::Http::Error}.
+#
+# @example Transparent compression
+#   response = pool.request("GET", "/report.json", [["Host", "example.com"]], nil, decompress: true)
+#   response.body # => decompressed JSON
+#
+# Pass `verify_checksum: { crc32c: expected }` (or `crc32:` / `crc64nvme:`)
+# to checksum the body as it arrives — buffered, streamed or async — and
+# raise {AwsCrt::Http::ChecksumMismatchError} at completion if it doesn't
+# match the base64 `expected`. With a nil value the expected checksum comes
+# from the response's `x-amz-checksum-*` header, and a response without one
+# is not verified. Only 2xx responses are checked; HEAD requests never are.
+# The checksum covers the body as received, before `decompress:`. A
+# streamed body has already reached the block and sinks by the time a
+# mismatch is raised.
+#
+# @example Verify a download against a known checksum
+#   pool.request("GET", "/object.bin", [["Host", "example.com"]], nil,
+#                verify_checksum: { crc32c: "yZRlqg==" })
+#
+# @example Verify against the checksum the server sends
+#   pool.request("GET", "/object.bin", [["Host", "bucket.s3.amazonaws.com"], ["x-amz-checksum-mode", "ENABLED"]],
+#                nil, verify_checksum: { crc32c: nil })
+#
+# A POST, PUT or PATCH with an empty String body is sent with
+# `Content-Length: 0` (unless the headers already carry `Content-Length` or
+# `Transfer-Encoding`), since servers may answer 411 Length Required
+# otherwise. A nil body adds nothing, so GET and HEAD are unaffected. Pass
+# `empty_body_content_length: false` to send the headers exactly as given.
+#
+# @example POST with an empty body
+#   pool.request("POST", "/queue/flush", [["Host", "example.com"]], "")
+#   # sends Content-Length: 0
+#
+# @example Work around a server with broken keep-alive
+#   pool.request("GET", "/", [["Host", "example.com"]], nil, connection_close: true)
+#
+# Under heavy contention, threads waiting for a connection are not served
+# in any particular order, so some can wait much longer than others. With
+# `fair_queue: true` requests are admitted in arrival order, at most
+# `:max_connections` at a time; a request holds its place across retries.
+# `:max_queue` bounds how many may wait: once that many are queued, further
+# requests raise {AwsCrt::Http::QueueFullError} immediately instead of
+# waiting, which lets a busy server shed load.
+#
+# @example FIFO admission with a bounded queue
+#   pool = AwsCrt::Http::ConnectionPool.new("https://example.com",
+#                                           max_connections: 16, fair_queue: true, max_queue: 256)
+#
+# `request_async` sends a buffered request and returns an
+# {AwsCrt::Http::PendingResponse} straight away, so one thread can keep many
+# requests in flight. `#done?` polls without blocking; `#value` waits (with
+# the GVL released) and returns the {AwsCrt::Http::Response}, or raises the
+# request's error — the same result on every call. It takes `request`'s
+# options, but no block or `:response_targets`, and failed requests are not
+# retried. It can't be combined with `fair_queue: true`.
+#
+# @example Fan out requests from one thread
+#   pending = paths.map { |path| pool.request_async("GET", path, [["Host", "example.com"]]) }
+#   pending.map(&:value).map(&:status)
+#
+# `close` shuts the pool down: requests still queued for a connection (or
+# in the fair queue) fail promptly with {AwsCrt::Http::ConnectionError}
+# instead of waiting, requests that already hold a connection finish
+# normally, and any later request raises ConnectionError. Call it while
+# draining on shutdown (e.g. SIGTERM).
+#
+# @example Drain on shutdown
+#   Signal.trap("TERM") { Thread.new { pool.close } }
+#
 # @see AwsCrt::Http::ConnectionPoolManager for per-endpoint pool management
 
 require "aws_crt"
@@ -10,12 +10,34 @@
 # Hierarchy:
 #   AwsCrt::Error (defined in lib/aws_crt.rb)
 #     └─ AwsCrt::Http::Error
-#          ├─ AwsCrt::Http::ConnectionError  (DNS failures, connection refused)
+#          ├─ AwsCrt::Http::ConnectionError  (DNS failures, connection refused,
+#          │                                   pool closed)
 #          ├─ AwsCrt::Http::TimeoutError     (connect/read timeouts)
 #          ├─ AwsCrt::Http::TlsError         (handshake/cert failures)
-#          └─ AwsCrt::Http::ProxyError       (proxy connection/auth failures)
+#          ├─ AwsCrt::Http::ProxyError       (proxy connection/auth failures)
+#          ├─ AwsCrt::Http::QueueFullError   (fair-queued pool already has
+#          │                                  :max_queue requests waiting)
+#          └─ AwsCrt::Http::ChecksumMismatchError (response body failed
+#                                                  :verify_checksum)
 #
 # Each exception message includes the CRT error name, human-readable
 # message, and numeric error code for debugging.
 
 require "aws_crt"
+
+module AwsCrt
+  module Http
+    # Raised by `ConnectionPool#request` with `verify_checksum:` when a
+    # response body's checksum differs from the expected one.
+    class ChecksumMismatchError
+      # @return [String] checksum algorithm, e.g. `"CRC32C"`
+      attr_reader :algorithm
+
+      # @return [String] base64 checksum the body was expected to have
+      attr_reader :expected_checksum
+
+      # @return [String] base64 checksum of the received body
+      attr_reader :computed_checksum
+    end
+  end
+end
@@ -31,7 +31,8 @@ module AwsCrt
           read_timeout_ms: (config.http_read_timeout * 1000).to_i,
           ssl_verify_peer: config.ssl_verify_peer,
           ssl_ca_bundle: config.ssl_ca_bundle,
-          proxy: config.http_proxy
+          proxy: config.http_proxy,
+          logger: config.logger
         )
       end
 
@@ -0,0 +1,61 @@
+# frozen_string_literal: true
+
+module AwsCrt
+  module Http
+    # The result of {ConnectionPool#request}.
+    #
+    # The class is defined in the Rust native extension
+    # (ext/aws_crt/src/response.rs), which provides:
+    #
+    # - `#status` — Integer status code
+    # - `#body` — String body, or nil when the body was streamed to a block
+    #   or decoded
+    # - `#data` — the decoded body when requested with `decode: :cbor`
+    # - `#header_pairs` — `[[name, value], ...]` exactly as received
+    # - `#[](name)` / `#key?(name)` — case-insensitive header lookup;
+    #   repeated headers are joined with ", "
+    # - `#cookies` — every `Set-Cookie` value as an Array, never joined (a
+    #   joined Set-Cookie can't be split again, since `Expires` dates contain
+    #   commas)
+    # - `#success?` — true for 2xx statuses
+    # - `#attempts` / `#retries` — which attempt produced this response, and
+    #   how many retries came before it: after connection errors, and after
+    #   429 or 503 responses with `Retry-After` (see the pool's
+    #   `:max_retries`)
+    # - `#timing` — milliseconds from requesting a connection to each stage
+    #   of the successful attempt: `{connect_ms:, ttfb_ms:,
+    #   first_body_byte_ms:, total_ms:, send_ms:, receive_ms:}`. `send_ms` and
+    #   `receive_ms` are the CRT's own measurements; stages that didn't
+    #   happen (e.g. no body) are nil
+    # - `#clock_skew_ms` — milliseconds the server's clock was ahead of the
+    #   local one (negative when behind) when the headers arrived, from the
+    #   `Date` header (so only accurate to about a second); nil without one
+    # - `#to_ary` — `[status, header_pairs, body]` (`[status, header_pairs]`
+    #   when streamed, `[status, header_pairs, data]` when decoded), so
+    #   existing `status, headers, body = pool.request(...)` code keeps
+    #   working
+    #
+    # @example
+    #   response = pool.request("GET", "/", [["Host", "example.com"]])
+    #   response.status                  # => 200
+    #   response["content-type"]         # => "application/json"
+    #   response.headers["Content-Type"] # => "application/json"
+    class Response
+      # Headers as a Hash keyed by lowercased name. Lookups with any casing
+      # find the header; repeated headers are joined with ", ". Use {#cookies}
+      # for Set-Cookie.
+      #
+      # @return [Hash{String => String}]
+      def headers
+        @headers ||= begin
+          hash = Hash.new { |h, name| h.fetch(name.downcase, nil) if name.is_a?(String) }
+          header_pairs.each do |(name, value)|
+            key = name.downcase
+            hash[key] = hash.key?(key) ? "#{hash[key]}, #{value}" : value
+          end
+          hash.freeze
+        end
+      end
+    end
+  end
+end
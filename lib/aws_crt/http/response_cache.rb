@@ -0,0 +1,198 @@
+# frozen_string_literal: true
+
+require_relative "connection_pool"
+
+module AwsCrt
+  module Http
+    # In-memory LRU cache for GET responses made through a ConnectionPool.
+    #
+    # Honors `Cache-Control: max-age` (plus `no-store` / `no-cache`) and the
+    # `Age` header for freshness. Stale entries that carry an `ETag` are
+    # revalidated with `If-None-Match`; a `304 Not Modified` refreshes the
+    # entry and the cached body is served. Entries are keyed by the request
+    # `Host` header and path, with one variant per combination of request
+    # header values named in the response's `Vary` header. Responses with
+    # `Vary: *` are never cached.
+    #
+    # Only 200 responses are stored. Streaming requests are not cached.
+    #
+    # @example
+    #   cache = AwsCrt::Http::ResponseCache.new(max_entries: 512)
+    #   pool = AwsCrt::Http::ConnectionPool.new("https://example.com")
+    #   status, headers, body = cache.get(pool, "/config.json", [["Host", "example.com"]])
+    class ResponseCache
+      DEFAULT_MAX_ENTRIES = 256
+
+      # A stored response variant.
+      Entry = Struct.new(:status, :headers, :body, :etag, :stored_at, :max_age, :age,
+                         :no_cache, :vary)
+
+      # @param max_entries [Integer] maximum number of cached variants;
+      #   the least recently used variant is evicted when full.
+      # @param clock [#call] monotonic time source in seconds (for testing)
+      def initialize(max_entries: DEFAULT_MAX_ENTRIES, clock: nil)
+        raise ArgumentError, "max_entries must be positive" unless max_entries.positive?
+
+        @max_entries = max_entries
+        @clock = clock || -> { Process.clock_gettime(Process::CLOCK_MONOTONIC) }
+        # { key => [Entry, ...] } — Hash insertion order doubles as LRU order.
+        @entries = {}
+        @size = 0
+        @mutex = Mutex.new
+      end
+
+      # Perform a GET through `pool`, serving from or populating the cache.
+      #
+      # @param pool [ConnectionPool]
+      # @param path [String]
+      # @param headers [Array<Array(String, String)>] request headers
+      # @return [Array(Integer, Array<Array(String, String)>, String)]
+      #   status, headers, body — the same values a {Response} destructures to
+      def get(pool, path, headers = [])
+        key = cache_key(path, headers)
+        entry = lookup(key, headers)
+
+        return entry_response(entry) if entry && fresh?(entry)
+
+        request_headers = headers
+        request_headers += [["If-None-Match", entry.etag]] if entry&.etag
+
+        status, resp_headers, body = pool.request("GET", path, request_headers)
+
+        if status == 304 && entry
+          revalidate(entry, resp_headers)
+          return entry_response(entry)
+        end
+
+        store(key, headers, status, resp_headers, body)
+        [status, resp_headers, body]
+      end
+
+      # @return [Integer] number of cached variants
+      def size
+        @mutex.synchronize { @size }
+      end
+
+      # Remove all cached entries.
+      def clear
+        @mutex.synchronize do
+          @entries.clear
+          @size = 0
+        end
+      end
+
+      private
+
+      def cache_key(path, headers)
+        host = header_value(headers, "host")
+        "#{host}#{path}"
+      end
+
+      # Find the variant matching this request's Vary'd headers and mark
+      # the key as most recently used.
+      def lookup(key, headers)
+        @mutex.synchronize do
+          variants = @entries.delete(key)
+          return nil unless variants
+
+          @entries[key] = variants
+          variants.find { |e| vary_matches?(e, headers) }
+        end
+      end
+
+      def store(key, headers, status, resp_headers, body) # rubocop:disable Metrics/MethodLength
+        return unless status == 200
+
+        directives = cache_control(resp_headers)
+        return if directives.key?("no-store")
+
+        vary = vary_names(resp_headers)
+        return if vary.include?("*")
+
+        entry = Entry.new(status, resp_headers, body, header_value(resp_headers, "etag"),
+                          @clock.call, max_age(directives), age(resp_headers),
+                          directives.key?("no-cache"),
+                          vary.to_h { |name| [name, header_value(headers, name)] })
+
+        # Nothing to serve fresh and nothing to revalidate with.
+        return if entry.max_age.nil? && entry.etag.nil?
+
+        @mutex.synchronize do
+          variants = @entries.delete(key) || []
+          @size -= variants.size
+          variants.reject! { |e| e.vary == entry.vary }
+          variants << entry
+          @entries[key] = variants
+          @size += variants.size
+          evict
+        end
+      end
+
+      # Update freshness from a 304 response.
+      def revalidate(entry, resp_headers)
+        directives = cache_control(resp_headers)
+        @mutex.synchronize do
+          entry.stored_at = @clock.call
+          entry.age = age(resp_headers)
+          entry.max_age = max_age(directives) if directives.key?("max-age")
+        end
+      end
+
+      def evict
+        while @size > @max_entries
+          key, variants = @entries.first
+          variants.shift
+          @size -= 1
+          @entries.delete(key) if variants.empty?
+        end
+      end
+
+      def fresh?(entry)
+        return false if entry.no_cache || entry.max_age.nil?
+
+        entry.age + (@clock.call - entry.stored_at) < entry.max_age
+      end
+
+      def vary_matches?(entry, headers)
+        entry.vary.all? { |name, value| header_value(headers, name) == value }
+      end
+
+      def entry_response(entry)
+        [entry.status, entry.headers, entry.body]
+      end
+
+      # Parse Cache-Control into { "directive" => "value" or nil }.
+      def cache_control(headers)
+        value = header_value(headers, "cache-control")
+        return {} unless value
+
+        value.split(",").to_h do |part|
+          name, arg = part.strip.split("=", 2)
+          [name.downcase, arg&.delete('"')]
+        end
+      end
+
+      def max_age(directives)
+        value = directives["max-age"]
+        value&.match?(/\A\d+\z/) ? value.to_i : nil
+      end
+
+      def age(headers)
+        value = header_value(headers, "age")
+        value&.match?(/\A\d+\z/) ? value.to_i : 0
+      end
+
+      def vary_names(headers)
+        value = header_value(headers, "vary")
+        return [] unless value
+
+        value.split(",").map { |name| name.strip.downcase }.reject(&:empty?)
+      end
+
+      def header_value(headers, name)
+        pair = headers.find { |(n, _)| n.casecmp?(name) }
+        pair && pair[1]
+      end
+    end
+  end
+end
@@ -1,8 +1,13 @@
 # frozen_string_literal: true
 
 require "aws_crt"
+require "digest/md5"
 require "fileutils"
+require "json"
 require "tempfile"
+require "time"
+require "uri"
+require "zlib"
 require_relative "credentials"
 require_relative "errors"
 require_relative "response"
@@ -48,10 +53,23 @@ module AwsCrt
       # Alias the Rust-defined methods so we can wrap them with Ruby logic.
       alias _native_initialize initialize
       alias _native_get_object get_object
+      alias _native_get_objects get_objects
       alias _native_put_object put_object
+      alias _native_sign_post_policy sign_post_policy
+      alias _native_max_active_connections max_active_connections
+      alias _native_make_request make_request
+      alias _native_pipe_object pipe_object
+      private :sign_post_policy, :_native_sign_post_policy, :make_request, :_native_make_request,
+              :_native_pipe_object
 
       VALID_CHECKSUM_ALGORITHMS = %w[CRC32 CRC32C SHA1 SHA256].freeze
 
+      OBJECT_LOCK_MODES = %w[GOVERNANCE COMPLIANCE].freeze
+
+      FILE_IO_KEYS = %i[should_stream disk_throughput_gbps direct_io].freeze
+
+      CREDENTIALS_KEYS = %i[access_key_id secret_access_key session_token].freeze
+
       # Chunk size for streaming tempfile data to block targets.
       STREAM_CHUNK_SIZE = 1024 * 1024 # 1 MB
 
@@ -61,8 +79,39 @@ module AwsCrt
       # via the :io_tempfile_threshold option on the client constructor.
       DEFAULT_IO_TEMPFILE_THRESHOLD = 16 * 1024 * 1024 # 16 MB
 
+      # Default number of GETs kept in flight by #get_objects.
+      DEFAULT_GET_OBJECTS_CONCURRENCY = 16
+
+      # Endpoint asked for a bucket's region by #bucket_region.
+      BUCKET_REGION_ENDPOINT = "s3.amazonaws.com"
+
+      # Highest part number S3 accepts in a multipart upload.
+      MAX_PART_NUMBER = 10_000
+
+      # Region signed for when a client with an :endpoint doesn't name one.
+      DEFAULT_ENDPOINT_REGION = "us-east-1"
+
+      # Smallest size S3 accepts for a multipart upload part other than the last.
+      MIN_PART_SIZE = 5 * 1024 * 1024 # 5 MB
+
+      # Default bytes per uploaded part in #pipe_object.
+      DEFAULT_PIPE_PART_SIZE = 8 * 1024 * 1024 # 8 MB
+
+      # SigV4 caps pre-signed expirations at 7 days.
+      MAX_PRESIGN_EXPIRES_IN = 7 * 24 * 60 * 60
+
       # @param [Hash] options
-      # @option options [String] :region (required) AWS region
+      # @option options [String] :region (required) AWS region. Optional
+      #   with `:endpoint`, defaulting to "us-east-1".
+      # @option options [String] :endpoint (nil)
+      #   `scheme://host[:port]` of an S3-compatible service (e.g. MinIO)
+      #   to send every request to, instead of AWS. Requests use path-style
+      #   addressing (`/<bucket>/<key>`), so bucket names may contain dots,
+      #   and an `http` endpoint is used without TLS.
+      # @option options [Boolean] :use_tls (true)
+      #   Set to false to connect over plain HTTP, e.g. to a local MinIO.
+      #   Requests are still signed. With an `:endpoint` it defaults to the
+      #   endpoint's scheme, and contradicting that raises ArgumentError.
       # @option options [#credentials, #access_key_id] :credentials
       #   A credential provider (responds to `credentials` returning an object
       #   with `access_key_id`, `secret_access_key`, `session_token`) or a
@@ -72,28 +121,81 @@ module AwsCrt
       # @option options [String] :secret_access_key (deprecated — use :credentials)
       # @option options [String] :session_token (deprecated — use :credentials)
       # @option options [Float] :throughput_target_gbps (10.0)
+      #   The CRT sizes its connection pool from this target, opening more
+      #   connections (spread across the IPs it resolves for the endpoint)
+      #   as the target rises. See {#max_active_connections} for the result.
       # @option options [Integer] :part_size (nil) auto-tuned by CRT
       # @option options [Integer] :multipart_upload_threshold (nil)
       # @option options [Integer] :memory_limit_in_bytes (nil)
       # @option options [Integer] :max_active_connections_override (nil)
+      #   Upper bound on the connection count derived from
+      #   `:throughput_target_gbps`. It can only lower the count; raise the
+      #   throughput target to get more connections.
+      # @option options [Boolean] :compute_content_md5 (false)
+      #   Have the CRT compute and send `Content-MD5` on every upload request
+      #   (PutObject and each UploadPart), for S3-compatible stores that
+      #   require it. When a put specifies `:checksum_algorithm`, the CRT
+      #   sends that checksum instead and no `Content-MD5` is added.
+      # @option options [Integer] :max_concurrent_transfers (nil)
+      #   Cap on operations this client runs at once across all threads.
+      #   Further calls wait (without holding the GVL) until one finishes.
+      #   Each object in a {#get_objects} batch counts as one transfer.
       # @option options [Integer] :io_tempfile_threshold (16 MB)
       #   IO bodies larger than this are spilled to a tempfile for
       #   CRT parallel file I/O instead of buffering in memory.
+      # @option options [Boolean] :validate_credentials (false)
+      #   Resolve credentials once at construction and raise
+      #   {AwsCrt::MissingCredentialsError} if none are available.
+      # @option options [Boolean] :follow_region_redirect (false)
+      #   When S3 reports that a bucket lives in another region, retry the
+      #   operation once on a client for that region (created on first use
+      #   and kept) instead of raising {WrongRegionError}.
+      # @option options [Integer] :max_retry_after_ms (nil)
+      #   The CRT retries throttled requests with its own backoff. With this
+      #   set, an operation that still fails with a 429 or 503 carrying a
+      #   `Retry-After` header (delay-seconds or an HTTP date) is sent once
+      #   more after the wait it asks for, capped at this many milliseconds.
+      #   Off by default.
+      # @option options [Array<String>, String] :alpn (nil)
+      #   Protocols to offer via TLS ALPN, most preferred first, e.g.
+      #   `["h2", "http/1.1"]`, so the client can negotiate HTTP/2 with
+      #   endpoints that support it. None are offered by default. Raises
+      #   ArgumentError on a plain HTTP client.
+      # @raise [AwsCrt::MissingCredentialsError] if `:validate_credentials`
+      #   is set and the provider yields no usable credentials.
       def initialize(options = {}) # rubocop:disable Metrics/MethodLength
+        options = options.merge(region: DEFAULT_ENDPOINT_REGION) if options[:endpoint] && options[:region].nil?
         validate_required_option!(options, :region)
+        @options = options
+        @region = options[:region]
+        @follow_region_redirect = options[:follow_region_redirect]
+        @max_retry_after_ms = options[:max_retry_after_ms]
+        @region_clients = {}
+        @region_clients_mutex = Mutex.new
+        @bucket_region_pool = nil
         @credential_provider = resolve_credential_provider(options)
         @io_tempfile_threshold = options.fetch(:io_tempfile_threshold, DEFAULT_IO_TEMPFILE_THRESHOLD)
 
+        if options[:validate_credentials] && !credentials_valid?
+          raise MissingCredentialsError,
+                "unable to resolve credentials from #{@credential_provider.class}"
+        end
+
         # Resolve initial credentials for CRT client creation.
         initial_creds = @credential_provider.credentials
         native_options = options.slice(
           :region,
+          :endpoint,
+          :use_tls,
           :throughput_target_gbps,
           :part_size,
           :multipart_upload_threshold,
           :memory_limit_in_bytes,
-          :max_active_connections_override
+          :max_active_connections_override,
+          :compute_content_md5,
+          :max_concurrent_transfers
         ).merge(
+          alpn_list: alpn_list(options[:alpn]),
           access_key_id: initial_creds.access_key_id,
           secret_access_key: initial_creds.secret_access_key,
           session_token: initial_creds.session_token
@@ -109,15 +211,51 @@ module AwsCrt
       # @option params [String] :key (required)
       # @option params [String, File, IO] :response_target (nil) file path, File, or IO object
       # @option params [String] :checksum_mode (nil) 'ENABLED' to validate
+      # @option params [String] :range (nil) e.g. `"bytes=1048576-"`
+      # @option params [String, Time] :if_range (nil) an ETag, or a Time /
+      #   HTTP date matching the object's Last-Modified. Requires `:range`.
+      #   If the object is unchanged the range is returned with status 206
+      #   ({Response#partial?}); if it changed, the whole object comes back
+      #   with status 200, so resume logic must check which it got.
+      # @option params [Hash] :file_io (nil) CRT file I/O tuning for
+      #   downloads written to disk; see {#put_object} for the keys. The size
+      #   of each write follows the client's `:part_size`.
+      # @option params [Hash, #access_key_id] :credentials (nil) credentials
+      #   for this request only, as `{ access_key_id:, secret_access_key:,
+      #   session_token: }` or a credentials object. The client's provider
+      #   is not consulted and later requests are unaffected.
+      # @option params [String] :expected_bucket_owner (nil) account ID that
+      #   must own the bucket, sent as `x-amz-expected-bucket-owner`; S3
+      #   answers 403 if another account owns it
+      # @option params [String, Symbol] :request_payer (nil) `"requester"` to
+      #   accept the charges of a Requester Pays bucket, sent as
+      #   `x-amz-request-payer`
+      # @option params [Boolean] :telemetry (false) collect the CRT's metrics
+      #   for every HTTP request of the transfer and return a summary as
+      #   {Response#telemetry}
       # @option params [Proc] :on_progress (nil)
+      # @option params [Boolean] :verify_etag (false) once the download
+      #   completes, compute the MD5 of what was received — reading a
+      #   `:response_target` file back from disk — and compare it with the
+      #   object's ETag. Only ETags that are an MD5 can be checked: objects
+      #   uploaded in multiple parts (ETag ending in `-<parts>`), objects
+      #   encrypted with SSE-KMS or SSE-C, and partial (206) responses are
+      #   returned without the check.
       # @yield [chunk] Each body chunk as it arrives
       # @return [AwsCrt::S3::Response]
+      # @raise [WrongRegionError] if the bucket is in another region and
+      #   `:follow_region_redirect` is not set
+      # @raise [EtagMismatchError] if `:verify_etag` is set and the body does
+      #   not match the ETag
       def get_object(params = {}, &block) # rubocop:disable Metrics/MethodLength
+        original_params = params
+        validate_file_io!(params[:file_io]) if params[:file_io]
+        params = params.merge(if_range: params[:if_range].httpdate) if params[:if_range].is_a?(Time)
         stream_target, params = resolve_response_target(params, &block)
 
         begin
-          result = _native_get_object(inject_credentials(params), &block)
-          raise_if_error!(result)
+          result = with_retry_after(params) { _native_get_object(inject_credentials(params), &block) }
+          verify_etag!(result, params[:response_target]) if params[:verify_etag]
 
           body = result[:body]
 
@@ -134,6 +272,58 @@ module AwsCrt
           # Clean up the tempfile if we created one for an IO/block target.
           FileUtils.rm_f(params[:response_target]) if stream_target
         end
+      rescue WrongRegionError => e
+        redirect_to_region(e, :get_object, original_params, &block)
+      end
+
+      # Download many objects concurrently into memory.
+      #
+      # All GETs go through this client's CRT connection pool, with up to
+      # `max_concurrency` in flight at once. The calling thread waits for
+      # the whole batch in one place rather than once per object, so this
+      # is much faster than calling {#get_object} in a loop for many small
+      # objects. Failures do not stop the batch: each failed key maps to
+      # the error that {#get_object} would have raised.
+      #
+      # @example Warm a cache
+      #   results = client.get_objects(bucket: "assets", keys: %w[a.json b.json])
+      #   results.each do |key, result|
+      #     cache[key] = result.body unless result.is_a?(AwsCrt::S3::Error)
+      #   end
+      #
+      # @param bucket [String]
+      # @param keys [Array<String>] duplicate keys are fetched once
+      # @param max_concurrency [Integer] maximum GETs in flight at once
+      # @param checksum_mode [String, nil] 'ENABLED' to validate checksums
+      # @param credentials [Hash, #access_key_id, nil] credentials for this
+      #   batch only; see {#get_object}
+      # @param expected_bucket_owner [String, nil] see {#get_object}
+      # @param request_payer [String, Symbol, nil] see {#get_object}
+      # @return [Hash{String => Response, Error}] keyed by object key, in
+      #   the order of `keys`
+      def get_objects(bucket:, keys:, max_concurrency: DEFAULT_GET_OBJECTS_CONCURRENCY, checksum_mode: nil, # rubocop:disable Metrics/MethodLength,Metrics/ParameterLists
+                      credentials: nil, expected_bucket_owner: nil, request_payer: nil)
+        raise ArgumentError, ":keys must be an Array of Strings" unless keys.is_a?(Array) && keys.all?(String)
+        unless max_concurrency.is_a?(Integer) && max_concurrency.positive?
+          raise ArgumentError, ":max_concurrency must be a positive Integer"
+        end
+
+        params = { bucket: bucket, keys: keys.uniq, max_concurrency: max_concurrency, checksum_mode: checksum_mode,
+                   expected_bucket_owner: expected_bucket_owner, request_payer: request_payer,
+                   credentials: credentials }.compact
+        results = _native_get_objects(inject_credentials(params))
+        results = results.transform_values do |result|
+          result[:error] ? error_for(result) : build_response(result, result[:body])
+        end
+        return results unless @follow_region_redirect
+
+        # Refetch keys S3 redirected, on a client for the bucket's region.
+        redirected = results.select { |_, result| result.is_a?(WrongRegionError) }
+        redirected.group_by { |_, error| error.region }.each do |region, pairs|
+          results.merge!(region_client(region).get_objects(**params.except(:keys, :credentials),
+                                                           keys: pairs.map(&:first), credentials: credentials))
+        end
+        results
       end
 
       # Upload an S3 object.
@@ -142,28 +332,459 @@ module AwsCrt
       # @option params [String] :bucket (required)
       # @option params [String] :key (required)
       # @option params [String, File, IO] :body (required)
-      # @option params [Integer] :content_length (nil)
+      # @option params [Integer] :content_length (nil) defaults to the size of
+      #   a File body, or the bytes read from an IO body. Required for an IO
+      #   that doesn't respond to `size` (e.g. a pipe), of which exactly this
+      #   many bytes are read; ArgumentError if it ends sooner.
+      # @option params [Integer] :max_parts (10_000) most parts a multipart
+      #   upload may use (S3 allows 10,000). When the size is known up front
+      #   (`:content_length`, a String or File body) and the client's
+      #   `:part_size` would need more parts, this upload uses the smallest
+      #   larger part size that fits. Raises ArgumentError if even S3's 5 GiB
+      #   maximum part size can't fit, before anything is sent.
       # @option params [String] :content_type (nil)
+      # @option params [String] :cache_control (nil) e.g. `"max-age=31536000, immutable"`
+      # @option params [String] :content_disposition (nil) e.g. `'attachment; filename="report.pdf"'`
+      # @option params [String] :content_encoding (nil) e.g. `"gzip"` for a
+      #   pre-compressed body
+      # @option params [Symbol] :compress (nil) `:gzip` to compress the body
+      #   before upload and add `gzip` to `Content-Encoding`. The signature
+      #   and any checksum cover the compressed bytes. File and IO bodies are
+      #   compressed into a tempfile; `:content_length` is ignored since it
+      #   would describe the uncompressed body.
+      # @option params [Time, String] :expires (nil) a Time, or an RFC 1123
+      #   date String such as `"Wed, 21 Oct 2015 07:28:00 GMT"`
+      #
+      #   These are stored with the object and returned on GET.
       # @option params [String] :checksum_algorithm (nil) CRC32, CRC32C, SHA1, SHA256
+      # @option params [String] :verify_checksum (nil) CRC32, CRC32C or
+      #   CRC64NVME: checksum the body locally instead of sending a checksum
+      #   trailer, and compare it with the `x-amz-checksum-*` header of the
+      #   response. File bodies are read on a separate thread while they
+      #   upload. A mismatch raises {ChecksumMismatchError}; a match sets
+      #   `checksum_validated`. Stores that return no such header (or only a
+      #   composite multipart checksum) leave the upload unverified. S3
+      #   reports a CRC64NVME for every object, so use that with S3. Cannot
+      #   be combined with `:checksum_algorithm` (or `:object_lock`, which
+      #   implies one).
+      # @option params [Hash] :tagging (nil) object tags, e.g. `{ "env" => "prod" }`.
+      #   Sent URL-encoded in the `x-amz-tagging` header.
+      # @option params [Hash] :object_lock (nil) Object Lock settings, sent as
+      #   the `x-amz-object-lock-*` headers:
+      #   * `:mode` — `"GOVERNANCE"` or `"COMPLIANCE"`
+      #   * `:retain_until` — a Time or ISO 8601 String; required with `:mode`
+      #   * `:legal_hold` — true/false (or `"ON"`/`"OFF"`)
+      #
+      #   The bucket must have Object Lock enabled, otherwise S3 rejects the
+      #   upload with an `InvalidRequest` {ServiceError}. S3 also requires an
+      #   integrity checksum on these uploads, so `:checksum_algorithm`
+      #   defaults to CRC32 when `:object_lock` is given.
+      # @option params [Hash] :file_io (nil) CRT file I/O tuning for File
+      #   bodies (experimental in the CRT; omit to keep its defaults):
+      #   * `:should_stream` — read parts straight from disk into the request
+      #     instead of buffering each part in memory first
+      #   * `:disk_throughput_gbps` — expected disk throughput, used to pace
+      #     streamed parts; set it with `:should_stream` so slow storage
+      #     doesn't starve connections
+      #   * `:direct_io` — bypass the page cache with O_DIRECT (Linux only)
+      # @option params [#call] :on_upload_review (nil) called before a
+      #   multipart upload is completed with a Hash
+      #   `{ checksum_algorithm: String or nil, parts: [{ size:, checksum: }] }`
+      #   (checksums are base64). Return a truthy value to complete the
+      #   upload; a falsy value aborts it and raises {UploadRejectedError}.
+      #   Not called for uploads small enough to be sent in a single
+      #   PutObject. The upload is paused while the hook runs, so it should
+      #   return promptly.
+      # @option params [Hash, #access_key_id] :credentials (nil) credentials
+      #   for this upload only; see {#get_object}
+      # @option params [String] :expected_bucket_owner (nil) see {#get_object}
+      # @option params [String, Symbol] :request_payer (nil) see {#get_object}
+      # @option params [Boolean] :telemetry (false) see {#get_object}
       # @option params [Proc] :on_progress (nil)
-      # @return [AwsCrt::S3::Response]
-      def put_object(params = {})
+      # @return [AwsCrt::S3::Response] for a multipart upload with a
+      #   `:checksum_algorithm`, {Response#parts} lists each part's checksum
+      # @raise [UploadRejectedError] if `:on_upload_review` rejects the upload
+      # @raise [ChecksumMismatchError] if `:verify_checksum` finds the stored
+      #   object differs from the body
+      # @raise [WrongRegionError] if the bucket is in another region and
+      #   `:follow_region_redirect` is not set
+      def put_object(params = {}) # rubocop:disable Metrics/MethodLength
+        original_params = params
         validate_checksum_algorithm!(params[:checksum_algorithm]) if params[:checksum_algorithm]
+        validate_file_io!(params[:file_io]) if params[:file_io]
+        params = normalize_tagging(params) if params[:tagging]
+        params = normalize_object_lock(params) if params[:object_lock]
+        params = params.merge(expires: params[:expires].httpdate) if params[:expires].is_a?(Time)
+        params, compressed_path = compress_put_body(params) if params.key?(:compress)
+        if params[:on_upload_review] && !params[:on_upload_review].respond_to?(:call)
+          raise ArgumentError, ":on_upload_review must respond to #call"
+        end
 
         params, tempfile_path = resolve_put_body(params)
 
         begin
-          result = _native_put_object(inject_credentials(params))
-          raise_if_error!(result)
+          result = with_retry_after(params) { _native_put_object(inject_credentials(params)) }
 
           build_response(result, result[:body])
         ensure
-          cleanup_put_tempfile(params, tempfile_path)
+          cleanup_put_tempfile(params, tempfile_path || compressed_path)
+        end
+      rescue WrongRegionError => e
+        redirect_to_region(e, :put_object, original_params)
+      end
+
+      # Start a multipart upload whose parts are sent with {#upload_part}.
+      #
+      # {#put_object} splits large bodies into parts itself; these methods
+      # are for uploads it can't express, such as parts produced by
+      # separate processes or an upload resumed later. Each part is one
+      # request, sent and retried by the CRT but never split further.
+      # Finish with {#complete_multipart_upload}, or
+      # {#abort_multipart_upload} to discard the parts (S3 keeps, and bills
+      # for, the parts of an upload that is never finished).
+      #
+      # @example
+      #   upload_id = client.create_multipart_upload(bucket: "b", key: "big.bin")
+      #   parts = chunks.each_with_index.map do |chunk, i|
+      #     etag = client.upload_part(bucket: "b", key: "big.bin", upload_id: upload_id,
+      #                               part_number: i + 1, body: chunk)
+      #     { part_number: i + 1, etag: etag }
+      #   end
+      #   client.complete_multipart_upload(bucket: "b", key: "big.bin", upload_id: upload_id, parts: parts)
+      #
+      # @param [Hash] params
+      # @option params [String] :bucket (required)
+      # @option params [String] :key (required)
+      # @option params [String] :content_type (nil) stored with the object
+      # @option params [Hash, #access_key_id] :credentials (nil) see {#get_object}
+      # @option params [String] :expected_bucket_owner (nil) see {#get_object}
+      # @option params [String, Symbol] :request_payer (nil) see {#get_object}
+      # @return [String] the upload ID
+      def create_multipart_upload(params = {})
+        headers = { "Content-Type" => params[:content_type] }.compact
+        result = multipart_request(params, "POST", "CreateMultipartUpload", "uploads", headers: headers)
+        upload_id = result[:body][%r{<UploadId>([^<]+)</UploadId>}, 1]
+        return upload_id if upload_id
+
+        raise ServiceError.new(
+          "S3 did not return an UploadId for CreateMultipartUpload (HTTP #{result[:status_code]})",
+          status_code: result[:status_code], headers: result[:headers], error_body: result[:body]
+        )
+      end
+
+      # Upload one part of a multipart upload started with
+      # {#create_multipart_upload}.
+      #
+      # Every part but the last must be at least 5 MiB. Uploading a part
+      # number again replaces that part.
+      #
+      # @param [Hash] params
+      # @option params [String] :bucket (required)
+      # @option params [String] :key (required)
+      # @option params [String] :upload_id (required)
+      # @option params [Integer] :part_number (required) 1 to 10,000
+      # @option params [String, IO] :body (required) IO bodies are read into
+      #   memory
+      # @option params [Hash, #access_key_id] :credentials (nil) see {#get_object}
+      # @option params [String] :expected_bucket_owner (nil) see {#get_object}
+      # @option params [String, Symbol] :request_payer (nil) see {#get_object}
+      # @return [String] the part's ETag, needed by {#complete_multipart_upload}
+      def upload_part(params = {})
+        part_number = params[:part_number]
+        unless part_number.is_a?(Integer) && part_number.between?(1, MAX_PART_NUMBER)
+          raise ArgumentError, ":part_number must be an Integer between 1 and #{MAX_PART_NUMBER}"
+        end
+
+        body = params[:body]
+        body = body.read if body.respond_to?(:read)
+        raise ArgumentError, ":body must be a String or IO" unless body.is_a?(String)
+
+        query = "partNumber=#{part_number}&uploadId=#{query_escape(upload_id_param(params))}"
+        result = multipart_request(params, "PUT", "UploadPart", query, body: body)
+        result[:headers].find { |name, _| name.casecmp?("etag") }&.last
+      end
+
+      # Finish a multipart upload, assembling the parts into the object.
+      #
+      # S3 can report a failure after answering 200, while it assembles
+      # the object; that is raised as a {ServiceError} like any other.
+      #
+      # @param [Hash] params
+      # @option params [String] :bucket (required)
+      # @option params [String] :key (required)
+      # @option params [String] :upload_id (required)
+      # @option params [Array<Hash>] :parts (required) `{ part_number:, etag: }`
+      #   for each part to include, in any order
+      # @option params [Hash, #access_key_id] :credentials (nil) see {#get_object}
+      # @option params [String] :expected_bucket_owner (nil) see {#get_object}
+      # @option params [String, Symbol] :request_payer (nil) see {#get_object}
+      # @return [AwsCrt::S3::Response] whose body is S3's
+      #   CompleteMultipartUploadResult XML, with the object's ETag
+      def complete_multipart_upload(params = {})
+        query = "uploadId=#{query_escape(upload_id_param(params))}"
+        body = complete_multipart_upload_xml(params[:parts])
+        result = multipart_request(params, "POST", "CompleteMultipartUpload", query,
+                                   headers: { "Content-Type" => "application/xml" }, body: body)
+        raise_if_error_document!(result)
+
+        build_response(result, result[:body])
+      end
+
+      # Abort a multipart upload and delete the parts uploaded so far.
+      #
+      # @param [Hash] params
+      # @option params [String] :bucket (required)
+      # @option params [String] :key (required)
+      # @option params [String] :upload_id (required)
+      # @option params [Hash, #access_key_id] :credentials (nil) see {#get_object}
+      # @option params [String] :expected_bucket_owner (nil) see {#get_object}
+      # @option params [String, Symbol] :request_payer (nil) see {#get_object}
+      # @return [AwsCrt::S3::Response] status 204 on success
+      def abort_multipart_upload(params = {})
+        query = "uploadId=#{query_escape(upload_id_param(params))}"
+        result = multipart_request(params, "DELETE", "AbortMultipartUpload", query)
+        build_response(result, result[:body])
+      end
+
+      # Copy an object through this process, optionally transforming it on
+      # the way, without holding it in memory.
+      #
+      # The CRT downloads the source and uploads the destination at the
+      # same time, each with its usual parallel part requests, and the body
+      # is streamed from one to the other as it arrives. The download is
+      # paced by the upload: it only reads a couple of parts ahead of what
+      # the upload has taken, so memory stays bounded whatever the object's
+      # size. After the first, every ranged GET of the source carries
+      # `If-Match` with the first response's ETag, so a source that changes
+      # mid-copy fails the copy instead of mixing versions.
+      #
+      # If anything fails, including the block raising, both transfers are
+      # stopped, the multipart upload is aborted, and the error raised.
+      #
+      # @example Re-encrypt while copying
+      #   cipher = OpenSSL::Cipher.new("aes-256-ctr").encrypt
+      #   cipher.key = key
+      #   cipher.iv = iv
+      #   client.pipe_object(source: { bucket: "in", key: "a.bin" },
+      #                      destination: { bucket: "out", key: "a.bin.enc" }) do |chunk|
+      #     chunk ? cipher.update(chunk) : cipher.final
+      #   end
+      #
+      # @param source [Hash] `:bucket` and `:key` of the object to read, plus
+      #   `:checksum_mode`, `:credentials`, `:expected_bucket_owner` and
+      #   `:request_payer` as for {#get_object}
+      # @param destination [Hash] `:bucket` and `:key` to write, plus
+      #   `:content_type`, `:credentials`, `:expected_bucket_owner` and
+      #   `:request_payer` as for {#put_object}
+      # @param part_size [Integer] bytes per uploaded part, at least 5 MB;
+      #   the destination may have at most 10,000 parts
+      # @yield [chunk] each piece of the source in order, as it arrives, then
+      #   nil once it is exhausted (e.g. to flush a cipher); return the bytes
+      #   to write in its place, or nil for none. Without a block the object
+      #   is copied as is.
+      # @return [AwsCrt::S3::Response] the response of the upload
+      def pipe_object(source:, destination:, part_size: DEFAULT_PIPE_PART_SIZE, &transform) # rubocop:disable Metrics/MethodLength
+        unless part_size.is_a?(Integer) && part_size >= MIN_PART_SIZE
+          raise ArgumentError, ":part_size must be an Integer of at least #{MIN_PART_SIZE}"
         end
+
+        source = source.slice(:bucket, :key, :checksum_mode, :credentials, :expected_bucket_owner, :request_payer)
+        destination = destination.slice(:bucket, :key, :content_type, :credentials, :expected_bucket_owner,
+                                        :request_payer)
+        result = _native_pipe_object(
+          source: inject_credentials(source),
+          destination: inject_credentials(destination),
+          part_size: part_size,
+          transform: transform
+        )
+        raise_if_error!(result)
+        build_response(result, nil)
+      end
+
+      # Build the form fields for a browser-based upload (pre-signed POST).
+      #
+      # Returns the fields to include in a `multipart/form-data` POST to the
+      # bucket endpoint, alongside the `file` field. The policy document
+      # restricts the upload to `bucket` and `key` plus any extra
+      # `conditions`, and expires after `expires_in` seconds. A key ending
+      # in `${filename}` is matched by prefix, so the browser can substitute
+      # the uploaded file's name.
+      #
+      # @example
+      #   fields = client.presign_post(
+      #     bucket: "uploads",
+      #     key: "user/42/${filename}",
+      #     conditions: [["content-length-range", 0, 10 * 1024 * 1024]],
+      #     expires_in: 900
+      #   )
+      #
+      # @param bucket [String]
+      # @param key [String]
+      # @param conditions [Array<Hash, Array>] additional POST policy
+      #   conditions, e.g. `{ "acl" => "private" }` or
+      #   `["starts-with", "$Content-Type", "image/"]`
+      # @param expires_in [Integer] seconds until the policy expires (max 7 days)
+      # @return [Hash{String => String}] form fields: `key`, `policy`,
+      #   `x-amz-algorithm`, `x-amz-credential`, `x-amz-date`,
+      #   `x-amz-signature`, and `x-amz-security-token` for temporary credentials
+      def presign_post(bucket:, key:, conditions: [], expires_in: 3600) # rubocop:disable Metrics/MethodLength
+        unless expires_in.is_a?(Integer) && expires_in.between?(1, MAX_PRESIGN_EXPIRES_IN)
+          raise ArgumentError, ":expires_in must be an Integer between 1 and #{MAX_PRESIGN_EXPIRES_IN}"
+        end
+
+        creds = @credential_provider.credentials
+        now = Time.now.utc
+        date = now.strftime("%Y%m%d")
+
+        fields = {
+          "key" => key,
+          "x-amz-algorithm" => "AWS4-HMAC-SHA256",
+          "x-amz-credential" => "#{creds.access_key_id}/#{date}/#{@region}/s3/aws4_request",
+          "x-amz-date" => now.strftime("%Y%m%dT%H%M%SZ")
+        }
+        fields["x-amz-security-token"] = creds.session_token unless blank?(creds.session_token)
+
+        policy = {
+          "expiration" => (now + expires_in).strftime("%Y-%m-%dT%H:%M:%S.000Z"),
+          "conditions" => [{ "bucket" => bucket }, key_condition(key)] + conditions +
+                          fields.except("key").map { |name, value| { name => value } }
+        }
+        encoded_policy = [JSON.generate(policy)].pack("m0")
+
+        fields["policy"] = encoded_policy
+        fields["x-amz-signature"] = _native_sign_post_policy(
+          policy: encoded_policy,
+          date: date,
+          _secret_access_key: creds.secret_access_key
+        )
+        fields
+      end
+
+      # Look up the region a bucket lives in.
+      #
+      # Sends an unsigned HEAD for the bucket to `s3.amazonaws.com`. S3
+      # names the bucket's region in `x-amz-bucket-region` whether it
+      # answers 200, redirects (301) or refuses the anonymous request (403),
+      # so no access to the bucket is needed. Use it to build a client for
+      # the right region.
+      #
+      # @example
+      #   region = client.bucket_region(bucket: "my-bucket")
+      #   regional = AwsCrt::S3::Client.new(region: region, credentials: provider)
+      #
+      # @param bucket [String]
+      # @return [String] e.g. `"eu-west-1"`
+      # @raise [ServiceError] if S3 does not report a region, e.g. a 404 for
+      #   a bucket that does not exist
+      # @raise [AwsCrt::Http::Error] on connection failures
+      def bucket_region(bucket:)
+        raise ArgumentError, ":bucket must be a non-empty String" unless bucket.is_a?(String) && !bucket.empty?
+
+        response = bucket_region_pool.request("HEAD", "/#{bucket}", [["Host", BUCKET_REGION_ENDPOINT]])
+        region = response["x-amz-bucket-region"]
+        return region if region && !region.empty?
+
+        raise ServiceError.new(
+          "S3 did not report a region for bucket #{bucket.inspect} (HTTP #{response.status})",
+          status_code: response.status,
+          headers: response.header_pairs.to_h,
+          error_body: response.body.to_s
+        )
+      end
+
+      # Resolve credentials from the configured provider once and check
+      # that they are usable.
+      #
+      # Returns false if the provider raises, returns nil, or returns
+      # credentials with an empty access key or secret key.
+      #
+      # @return [Boolean]
+      def credentials_valid?
+        creds = begin
+          @credential_provider.credentials
+        rescue StandardError
+          return false
+        end
+        return false unless creds
+
+        !blank?(creds.access_key_id) && !blank?(creds.secret_access_key)
+      end
+
+      # The number of connections the CRT keeps active at once for this
+      # client: the count it derived from `:throughput_target_gbps`, capped
+      # by `:max_active_connections_override`.
+      #
+      # @return [Integer]
+      def max_active_connections
+        _native_max_active_connections
       end
 
       private
 
+      def blank?(value)
+        value.nil? || (value.is_a?(String) && value.empty?)
+      end
+
+      # POST policy condition for the object key. `${filename}` is filled in
+      # by S3 from the uploaded file, so such keys are matched by prefix.
+      def key_condition(key)
+        if key.end_with?("${filename}")
+          ["starts-with", "$key", key.delete_suffix("${filename}")]
+        else
+          { "key" => key }
+        end
+      end
+
+      # Send one multipart upload operation as a DEFAULT meta-request and
+      # raise if it failed. Returns the result hash, with the body buffered.
+      def multipart_request(params, method, operation_name, query, headers: {}, body: nil) # rubocop:disable Metrics/ParameterLists
+        native_params = params.slice(:bucket, :key, :credentials, :expected_bucket_owner, :request_payer).merge(
+          method: method, operation_name: operation_name, query: query, headers: headers, body: body
+        ).compact
+        with_retry_after(native_params) { _native_make_request(inject_credentials(native_params)) }
+      end
+
+      # CompleteMultipartUpload can fail after S3 has answered 200, in which
+      # case the body is an <Error> document rather than the result.
+      def raise_if_error_document!(result)
+        return unless result[:body].include?("<Error>")
+
+        raise ServiceError.new(
+          "S3 service error: HTTP #{result[:status_code]}#{error_summary(result[:body])}",
+          status_code: result[:status_code], headers: result[:headers], error_body: result[:body]
+        )
+      end
+
+      def upload_id_param(params)
+        upload_id = params[:upload_id]
+        raise ArgumentError, ":upload_id must be a non-empty String" unless upload_id.is_a?(String) && !upload_id.empty?
+
+        upload_id
+      end
+
+      # Percent-encode a query parameter value (spaces as %20, not +).
+      def query_escape(value)
+        URI.encode_www_form_component(value).gsub("+", "%20")
+      end
+
+      # The CompleteMultipartUpload request body, listing the parts in
+      # part number order as S3 requires.
+      def complete_multipart_upload_xml(parts) # rubocop:disable Metrics/MethodLength
+        unless parts.is_a?(Array) && !parts.empty? && parts.all? { |part| part.is_a?(Hash) }
+          raise ArgumentError, ":parts must be a non-empty Array of { part_number:, etag: } Hashes"
+        end
+
+        entries = parts.sort_by { |part| part[:part_number].to_i }.map do |part|
+          unless part[:part_number].is_a?(Integer) && part[:etag].is_a?(String)
+            raise ArgumentError, "each part needs an Integer :part_number and a String :etag"
+          end
+
+          "<Part><PartNumber>#{part[:part_number]}</PartNumber>" \
+            "<ETag>#{part[:etag].encode(xml: :text)}</ETag></Part>"
+        end
+        "<CompleteMultipartUpload>#{entries.join}</CompleteMultipartUpload>"
+      end
+
       # Resolve a credential provider from the options hash.
       #
       # Accepts three forms:
@@ -210,11 +831,16 @@ module AwsCrt
         StaticCredentialProvider.new(creds)
       end
 
-      # Resolve fresh credentials and inject them into the params hash
-      # for the Rust native method.
+      # Resolve fresh credentials (or take the per-request `:credentials`
+      # override) and inject them into the params hash for the Rust native
+      # method, which builds a signing config for this request alone.
       def inject_credentials(params)
-        creds = @credential_provider.credentials
-        params.merge(
+        creds = if params.key?(:credentials)
+                  request_credentials(params[:credentials])
+                else
+                  @credential_provider.credentials
+                end
+        params.except(:credentials).merge(
           _access_key_id: creds.access_key_id,
           _secret_access_key: creds.secret_access_key,
           _session_token: creds.session_token
@@ -282,6 +908,28 @@ module AwsCrt
         [params.merge(body: file), tempfile.path]
       end
 
+      # Gzip the PUT body for `:compress`, so the native layer signs and
+      # checksums the compressed bytes like any other body. String bodies are
+      # compressed in memory; File and IO bodies into a tempfile that is
+      # reopened as a File for send_filepath.
+      #
+      # Returns [params, tempfile_path] like resolve_put_body.
+      def compress_put_body(params) # rubocop:disable Metrics/MethodLength
+        raise ArgumentError, ":compress must be :gzip" unless params[:compress] == :gzip
+
+        encoding = [params[:content_encoding], "gzip"].compact.join(", ")
+        params = params.except(:compress, :content_length).merge(content_encoding: encoding)
+        body = params[:body]
+        return [params, nil] unless body
+        return [params.merge(body: Zlib.gzip(body)), nil] if body.is_a?(String)
+
+        tempfile = Tempfile.new("aws-crt-s3-gzip", binmode: true)
+        gz = Zlib::GzipWriter.new(tempfile)
+        IO.copy_stream(body, gz)
+        gz.close # also closes the tempfile
+        [params.merge(body: File.open(tempfile.path, "rb")), tempfile.path]
+      end
+
       # Create a tempfile and return params with its path as response_target.
       def create_tempfile_params(params)
         tempfile = Tempfile.new("aws-crt-s3-download")
@@ -317,13 +965,52 @@ module AwsCrt
         end
       end
 
+      # Compare the MD5 of a downloaded body, or of the file it was written
+      # to, with the object's ETag (`:verify_etag`).
+      def verify_etag!(result, path)
+        _, etag = result[:headers].find { |name, _| name.casecmp?("etag") }
+        etag = etag.to_s.delete('"').downcase
+        return unless md5_etag?(result, etag)
+
+        computed = path ? Digest::MD5.file(path).hexdigest : Digest::MD5.hexdigest(result[:body].to_s)
+        return if computed == etag
+
+        raise EtagMismatchError.new(
+          "downloaded object does not match its ETag: expected #{etag}, computed MD5 #{computed}",
+          expected_etag: etag,
+          computed_md5: computed
+        )
+      end
+
+      # Whether `etag` is the MD5 of the whole body in `result`: a complete
+      # (200) response for an object uploaded in one part and not encrypted
+      # with SSE-KMS or SSE-C, whose ETags are something else.
+      def md5_etag?(result, etag)
+        return false unless result[:status_code] == 200 && etag.match?(/\A\h{32}\z/)
+
+        result[:headers].none? do |name, value|
+          name.casecmp?("x-amz-server-side-encryption-customer-algorithm") ||
+            (name.casecmp?("x-amz-server-side-encryption") && value.start_with?("aws:kms"))
+        end
+      end
+
       # Build a Response from a successful Rust result hash.
       def build_response(result, body)
         Response.new(
           status_code: result[:status_code],
           headers: result[:headers],
           body: body,
-          checksum_validated: result[:checksum_validated]
+          checksum_validated: result[:checksum_validated],
+          last_modified: result[:last_modified],
+          content_length: result[:content_length],
+          storage_class: result[:storage_class],
+          range_start: result[:range_start],
+          range_end: result[:range_end],
+          total_size: result[:total_size],
+          metadata: result[:metadata] || {},
+          clock_skew_ms: result[:clock_skew_ms],
+          telemetry: result[:telemetry],
+          parts: result[:parts]
         )
       end
 
@@ -342,6 +1029,19 @@ module AwsCrt
         raise ArgumentError, "missing required option :#{key}"
       end
 
+      # The `:alpn` option as the CRT's semicolon-delimited list, or nil.
+      def alpn_list(protocols)
+        protocols = Array(protocols)
+        return nil if protocols.empty?
+
+        protocols.each do |protocol|
+          next if protocol.is_a?(String) && !protocol.empty? && !protocol.include?(";")
+
+          raise ArgumentError, "invalid ALPN protocol #{protocol.inspect}"
+        end
+        protocols.join(";")
+      end
+
       # Validate that a checksum algorithm is one of the supported values.
       def validate_checksum_algorithm!(algorithm)
         return if VALID_CHECKSUM_ALGORITHMS.include?(algorithm)
@@ -351,27 +1051,210 @@ module AwsCrt
               "must be CRC32, CRC32C, SHA1, or SHA256"
       end
 
+      # Convert tag keys and values to Strings so the native layer can
+      # encode them (symbols and numbers are common in tag hashes).
+      def normalize_tagging(params)
+        tagging = params[:tagging]
+        raise ArgumentError, ":tagging must be a Hash" unless tagging.is_a?(Hash)
+
+        params.merge(tagging: tagging.to_h { |k, v| [k.to_s, v.to_s] })
+      end
+
+      # Turn a per-request `:credentials` override into a credentials object.
+      def request_credentials(override)
+        return override if override.respond_to?(:access_key_id) && !override.is_a?(Hash)
+        raise ArgumentError, ":credentials must be a Hash or credentials object" unless override.is_a?(Hash)
+
+        unknown = override.keys - CREDENTIALS_KEYS
+        raise ArgumentError, "unknown :credentials keys: #{unknown.join(", ")}" unless unknown.empty?
+
+        %i[access_key_id secret_access_key].each do |key|
+          raise ArgumentError, ":credentials is missing :#{key}" if blank?(override[key])
+        end
+        Credentials.new(**override)
+      end
+
+      def validate_file_io!(file_io)
+        raise ArgumentError, ":file_io must be a Hash" unless file_io.is_a?(Hash)
+
+        unknown = file_io.keys - FILE_IO_KEYS
+        raise ArgumentError, "unknown :file_io keys: #{unknown.join(", ")}" unless unknown.empty?
+      end
+
+      # Validate `:object_lock` and convert it to the header values the
+      # native layer sends: upcased mode, ISO 8601 retain-until, ON/OFF.
+      def normalize_object_lock(params) # rubocop:disable Metrics/AbcSize,Metrics/CyclomaticComplexity,Metrics/MethodLength,Metrics/PerceivedComplexity
+        lock = params[:object_lock]
+        raise ArgumentError, ":object_lock must be a Hash" unless lock.is_a?(Hash)
+
+        unknown = lock.keys - %i[mode retain_until legal_hold]
+        raise ArgumentError, "unknown :object_lock keys: #{unknown.join(", ")}" unless unknown.empty?
+
+        mode = lock[:mode]&.to_s&.upcase
+        if mode && !OBJECT_LOCK_MODES.include?(mode)
+          raise ArgumentError, ":object_lock :mode must be GOVERNANCE or COMPLIANCE"
+        end
+        if mode.nil? != lock[:retain_until].nil?
+          raise ArgumentError, ":object_lock :mode and :retain_until must be given together"
+        end
+
+        retain_until = lock[:retain_until]
+        retain_until = retain_until.getutc.iso8601(3) if retain_until.is_a?(Time)
+
+        legal_hold = case lock[:legal_hold]
+                     when nil then nil
+                     when true, "ON" then "ON"
+                     when false, "OFF" then "OFF"
+                     else raise ArgumentError, ":object_lock :legal_hold must be true, false, \"ON\", or \"OFF\""
+                     end
+
+        params.merge(
+          object_lock: { mode: mode, retain_until: retain_until, legal_hold: legal_hold }.compact,
+          checksum_algorithm: params[:checksum_algorithm] || "CRC32"
+        )
+      end
+
+      # Retry an operation that S3 redirected on the client for the bucket's
+      # region, or re-raise when `:follow_region_redirect` is off. A body the
+      # first attempt read from is rewound; one that can't be is not retried.
+      def redirect_to_region(error, operation, params, &block)
+        raise error unless @follow_region_redirect && rewind_body(params[:body])
+
+        region_client(error.region).public_send(operation, params, &block)
+      end
+
+      # Rewind a body for another attempt. False if it can't be rewound.
+      def rewind_body(body)
+        body.rewind if body.respond_to?(:rewind)
+        true
+      rescue IOError, SystemCallError
+        false
+      end
+
+      # Call the block, which returns a native result hash, and raise if the
+      # result is an error. With `:max_retry_after_ms`, a throttled response
+      # carrying `Retry-After` is retried once after the wait it asks for.
+      def with_retry_after(params) # rubocop:disable Metrics/MethodLength
+        retried = false
+        begin
+          result = yield
+          raise_if_error!(result)
+          result
+        rescue ServiceError => e
+          delay = retry_after_delay(e)
+          raise if retried || delay.nil? || !rewind_body(params[:body])
+
+          retried = true
+          sleep(delay)
+          retry
+        end
+      end
+
+      # Seconds to wait before retrying a throttled (429 or 503) response,
+      # from its `Retry-After` header and capped at `:max_retry_after_ms`;
+      # nil if it shouldn't be retried.
+      def retry_after_delay(error)
+        return nil unless @max_retry_after_ms && [429, 503].include?(error.status_code)
+
+        _, value = error.headers.find { |name, _| name.casecmp?("retry-after") }
+        seconds = parse_retry_after(value.to_s.strip)
+        seconds && [seconds, @max_retry_after_ms / 1000.0].min
+      end
+
+      # A `Retry-After` value, delay-seconds or an HTTP date, as seconds
+      # from now (zero for a date that has passed). Nil if it is neither.
+      def parse_retry_after(value)
+        return value.to_i if value.match?(/\A\d+\z/)
+
+        [Time.httpdate(value) - Time.now, 0].max
+      rescue ArgumentError
+        nil
+      end
+
+      # Connection pool for #bucket_region, created on first use.
+      def bucket_region_pool
+        @region_clients_mutex.synchronize do
+          @bucket_region_pool ||= AwsCrt::Http::ConnectionPool.new("https://#{BUCKET_REGION_ENDPOINT}")
+        end
+      end
+
+      # A client for another region, built from this client's options.
+      def region_client(region)
+        @region_clients_mutex.synchronize do
+          @region_clients[region] ||= self.class.new(
+            @options.merge(region: region, follow_region_redirect: false, validate_credentials: false)
+          )
+        end
+      end
+
+      # The bucket's region when a failed response is S3 pointing at another
+      # region, else nil.
+      def redirect_region(status_code, headers)
+        return nil unless [301, 307, 400].include?(status_code)
+
+        _, region = headers.find { |name, _| name.casecmp?("x-amz-bucket-region") }
+        region unless region.nil? || region.empty? || region == @region
+      end
+
       # Inspect a result hash from the Rust layer and raise the appropriate
       # error if it represents a failure.
-      def raise_if_error!(result) # rubocop:disable Metrics/MethodLength
+      def raise_if_error!(result)
         return unless result[:error]
 
+        raise error_for(result)
+      end
+
+      # Build the error for a failed result hash from the Rust layer.
+      def error_for(result) # rubocop:disable Metrics/MethodLength
+        return UploadRejectedError.new("multipart upload rejected by :on_upload_review") if result[:upload_rejected]
+
         error_code = result[:error_code]
         status_code = result[:status_code]
         headers = result[:headers] || {}
-        body = result[:body] || ""
+        body = result[:body] || "".b
+
+        if (region = redirect_region(status_code, headers))
+          return WrongRegionError.new(
+            "S3 bucket is in region #{region} but the client is configured for #{@region} " \
+            "(HTTP #{status_code}); use region: #{region.inspect} or follow_region_redirect: true",
+            region: region, status_code: status_code, headers: headers, error_body: body
+          )
+        end
+
+        return checksum_mismatch_error(result) if result[:checksum_algorithm]
 
         unless error_code.zero? && status_code >= 400
-          raise NetworkError, "S3 network error (CRT error code: #{error_code}): #{body}"
+          return NetworkError.new("S3 network error (CRT error code: #{error_code}): #{body}")
         end
 
-        raise ServiceError.new(
-          "S3 service error: HTTP #{status_code}",
+        ServiceError.new(
+          "S3 service error: HTTP #{status_code}#{error_summary(body)}",
           status_code: status_code,
           headers: headers,
           error_body: body
         )
       end
+
+      def checksum_mismatch_error(result)
+        computed = result[:computed_checksum]
+        ChecksumMismatchError.new(
+          "S3 response checksum mismatch (#{result[:checksum_algorithm]}): " \
+          "expected #{result[:expected_checksum]}, computed #{computed || "unavailable"}",
+          algorithm: result[:checksum_algorithm],
+          expected_checksum: result[:expected_checksum],
+          computed_checksum: computed
+        )
+      end
+
+      # " (Code: Message)" from an S3 XML error body, so errors such as a
+      # missing Object Lock configuration say what went wrong.
+      def error_summary(body)
+        code = body[%r{<Code>([^<]*)</Code>}, 1]
+        return "" unless code
+
+        message = body[%r{<Message>([^<]*)</Message>}, 1]
+        message ? " (#{code}: #{message})" : " (#{code})"
+      end
     end
   end
 end
@@ -15,7 +15,8 @@ module AwsCrt
       # @return [Hash<String, String>] response headers
       attr_reader :headers
 
-      # @return [String] error response body (XML)
+      # @return [String] error response body (usually XML), as received,
+      #   ASCII-8BIT encoded
       attr_reader :error_body
 
       def initialize(message, status_code:, headers:, error_body:)
@@ -26,7 +27,67 @@ module AwsCrt
       end
     end
 
+    # Raised when the bucket lives in a different region than the client's.
+    # S3 answers such requests with a 301/307 redirect (or a 400 for some
+    # signing errors) carrying the `x-amz-bucket-region` header.
+    class WrongRegionError < ServiceError
+      # @return [String] the bucket's region, from `x-amz-bucket-region`
+      attr_reader :region
+
+      def initialize(message, region:, status_code:, headers:, error_body:)
+        super(message, status_code: status_code, headers: headers, error_body: error_body)
+        @region = region
+      end
+    end
+
     # Raised for network/transport-level failures.
     class NetworkError < Error; end
+
+    # Raised when a downloaded body does not match the checksum S3 sent
+    # for it (`checksum_mode: "ENABLED"`), or when S3 reports a different
+    # checksum for an uploaded object than the body had (`put_object`
+    # `verify_checksum:`). A NetworkError, as corruption in transit is the
+    # usual cause; compare the checksums to tell that apart from an object
+    # stored with a wrong checksum.
+    class ChecksumMismatchError < NetworkError
+      # @return [String] checksum algorithm, e.g. `"CRC32"`
+      attr_reader :algorithm
+
+      # @return [String] base64 checksum from the response headers
+      attr_reader :expected_checksum
+
+      # @return [String, nil] base64 checksum of the received (or uploaded)
+      #   body; nil when a download was written to a file or the algorithm
+      #   is SHA1/SHA256
+      attr_reader :computed_checksum
+
+      def initialize(message, algorithm:, expected_checksum:, computed_checksum: nil)
+        super(message)
+        @algorithm = algorithm
+        @expected_checksum = expected_checksum
+        @computed_checksum = computed_checksum
+      end
+    end
+
+    # Raised by get_object with `:verify_etag` when the MD5 of the downloaded
+    # body, or of the file it was written to, differs from the object's
+    # ETag — a truncated or corrupted download.
+    class EtagMismatchError < Error
+      # @return [String] the object's ETag, without quotes
+      attr_reader :expected_etag
+
+      # @return [String] hex MD5 of the received bytes
+      attr_reader :computed_md5
+
+      def initialize(message, expected_etag:, computed_md5:)
+        super(message)
+        @expected_etag = expected_etag
+        @computed_md5 = computed_md5
+      end
+    end
+
+    # Raised when a put_object `:on_upload_review` hook rejects a multipart
+    # upload. The upload is aborted and the object is not created.
+    class UploadRejectedError < Error; end
   end
 end
@@ -16,21 +16,110 @@ module AwsCrt
       # @return [String, nil] checksum algorithm used for validation
       attr_reader :checksum_validated
 
+      # @return [Time, nil] parsed `Last-Modified` header (UTC)
+      attr_reader :last_modified
+
+      # @return [Integer, nil] parsed `Content-Length` header
+      attr_reader :content_length
+
+      # @return [String, nil] `x-amz-storage-class` header (S3 omits it
+      #   for STANDARD objects)
+      attr_reader :storage_class
+
+      # @return [Integer, nil] first byte offset from `Content-Range`
+      attr_reader :range_start
+
+      # @return [Integer, nil] last byte offset (inclusive) from `Content-Range`
+      attr_reader :range_end
+
+      # @return [Integer, nil] full object size from `Content-Range`; nil when
+      #   the server sent `*` (unknown) or the header is absent
+      attr_reader :total_size
+
+      # @return [Hash<String, String>] user metadata from the `x-amz-meta-*`
+      #   headers, keyed by lowercase name without the prefix
+      attr_reader :metadata
+
+      # @return [Integer, nil] milliseconds S3's clock was ahead of the local
+      #   clock (negative when behind) when the response headers arrived,
+      #   from the `Date` header; accurate to about a second. SigV4 requests
+      #   fail with `RequestTimeTooSkewed` once this passes 15 minutes
+      attr_reader :clock_skew_ms
+
+      # @return [Hash, nil] per-transfer metrics when the request was made
+      #   with `telemetry: true`:
+      #   * `:parts` — successful GetObject / UploadPart / PutObject requests
+      #   * `:retries` — requests that failed and were retried
+      #   * `:connections` — distinct connections used
+      #   * `:bytes` — payload bytes moved by the successful parts
+      #   * `:duration_ms` — from the first request's start to the last one's end
+      #   * `:requests` — one Hash per HTTP request with `:operation`,
+      #     `:status_code`, `:error_code`, `:bytes`, `:start_ms` (relative to
+      #     the first request) and `:duration_ms`
+      attr_reader :telemetry
+
+      # @return [Array<Hash>, nil] after a multipart `put_object` with a
+      #   checksum algorithm, one Hash per part in order, with
+      #   `:part_number`, `:size` and `:checksum` (base64). See
+      #   {#composite_checksum} for the checksum S3 computed from them.
+      attr_reader :parts
+
       # @param status_code [Integer] HTTP status code
       # @param headers [Hash<String, String>] response headers
       # @param body [String, nil] response body
       # @param checksum_validated [String, nil] checksum algorithm validated
-      def initialize(status_code:, headers:, body: nil, checksum_validated: nil)
+      # @param last_modified [Time, nil] parsed Last-Modified time
+      # @param content_length [Integer, nil] parsed Content-Length
+      # @param storage_class [String, nil] object storage class
+      # @param range_start [Integer, nil] parsed Content-Range start
+      # @param range_end [Integer, nil] parsed Content-Range end
+      # @param total_size [Integer, nil] parsed Content-Range total
+      # @param metadata [Hash<String, String>] user metadata
+      # @param clock_skew_ms [Integer, nil] server clock skew
+      # @param telemetry [Hash, nil] transfer metrics
+      # @param parts [Array<Hash>, nil] per-part checksums of an upload
+      def initialize(status_code:, headers:, body: nil, checksum_validated: nil, # rubocop:disable Metrics/MethodLength,Metrics/ParameterLists
+                     last_modified: nil, content_length: nil, storage_class: nil,
+                     range_start: nil, range_end: nil, total_size: nil, metadata: {}, telemetry: nil,
+                     parts: nil, clock_skew_ms: nil)
         @status_code = status_code
         @headers = headers
         @body = body
         @checksum_validated = checksum_validated
+        @last_modified = last_modified
+        @content_length = content_length
+        @storage_class = storage_class
+        @range_start = range_start
+        @range_end = range_end
+        @total_size = total_size
+        @metadata = metadata
+        @clock_skew_ms = clock_skew_ms
+        @telemetry = telemetry
+        @parts = parts
       end
 
       # @return [Boolean] true if the response status code is 2xx
       def successful?
         status_code >= 200 && status_code < 300
       end
+
+      # @return [Boolean] true for a 206 Partial Content response to a
+      #   `:range` request
+      def partial?
+        status_code == 206
+      end
+
+      # @return [String, nil] the composite checksum of a multipart object:
+      #   the base64 checksum of its part checksums, suffixed with the part
+      #   count (e.g. `"Hd0tpQ==-12"`), from the `x-amz-checksum-*` header.
+      #   nil for full-object checksums and when no checksum was returned
+      def composite_checksum
+        headers.each do |name, value|
+          next unless name.downcase.start_with?("x-amz-checksum-")
+          return value if value.to_s.match?(/\A[A-Za-z0-9+\/=]+-\d+\z/)
+        end
+        nil
+      end
     end
   end
 end
@@ -1,7 +1,133 @@
 # frozen_string_literal: true
 
+require "English"
+
 RSpec.describe AwsCrt do
   it "has a version number" do
     expect(AwsCrt::VERSION).not_to be nil
   end
 end
+
+RSpec.describe AwsCrt, ".configure" do
+  it "rejects a non-positive dns_ttl_secs" do
+    expect { AwsCrt.configure(dns_ttl_secs: 0) }.to raise_error(ArgumentError, /dns_ttl_secs/)
+  end
+
+  it "rejects a non-Integer dns_ttl_secs" do
+    expect { AwsCrt.configure(dns_ttl_secs: "30") }.to raise_error(ArgumentError, /dns_ttl_secs/)
+  end
+
+  it "rejects a negative dns_failure_retries" do
+    expect { AwsCrt.configure(dns_failure_retries: -1) }.to raise_error(ArgumentError, /dns_failure_retries/)
+  end
+
+  it "rejects a non-positive dns_retry_backoff_ms" do
+    expect { AwsCrt.configure(dns_retry_backoff_ms: 0) }.to raise_error(ArgumentError, /dns_retry_backoff_ms/)
+  end
+
+  it "raises once the runtime has been initialized" do
+    AwsCrt::Http::ConnectionPool.new("http://127.0.0.1:1")
+
+    expect { AwsCrt.configure(dns_ttl_secs: 10) }
+      .to raise_error(AwsCrt::Error, /before the first connection pool/)
+  end
+end
+
+RSpec.describe AwsCrt, "DNS failure retries" do
+  # The runtime reads its configuration once per process, so each example
+  # configures a fresh Ruby. `.invalid` never resolves; only a failed query
+  # (AWS_IO_DNS_QUERY_FAILED) is retried, so a resolver that answers with
+  # NXDOMAIN instead leaves nothing to test. RES_OPTIONS caps each query at
+  # a second so the timings below stay bounded.
+  def run_isolated(*configs, body: timed_request)
+    script = <<~RUBY
+      require "aws_crt"
+      #{configs.map { |config| "AwsCrt.configure(**#{config.inspect})" }.join("\n")}
+      pool = AwsCrt::Http::ConnectionPool.new("http://aws-crt-dns-retry.invalid")
+      headers = [["Host", "aws-crt-dns-retry.invalid"]]
+      #{body}
+    RUBY
+    output = IO.popen(
+      { "RES_OPTIONS" => "timeout:1 attempts:1" },
+      [RbConfig.ruby, "-I", File.expand_path("../lib", __dir__), "-e", script],
+      err: %i[child out], &:read
+    )
+    expect($CHILD_STATUS.success?).to be(true), "Subprocess failed:\n#{output}"
+    skip "resolver reports an invalid name, not a failed query" if output.include?("DNS_INVALID_NAME")
+    output
+  end
+
+  def timed_request
+    <<~RUBY
+      started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+      begin
+        pool.request("GET", "/", headers)
+      rescue AwsCrt::Http::ConnectionError => e
+        puts e.message
+      end
+      puts "elapsed=\#{Process.clock_gettime(Process::CLOCK_MONOTONIC) - started}"
+    RUBY
+  end
+
+  def elapsed(output)
+    output[/elapsed=([\d.]+)/, 1].to_f
+  end
+
+  it "asks for a connection again dns_failure_retries times" do
+    output = run_isolated({ dns_failure_retries: 1, dns_retry_backoff_ms: 1_500 })
+
+    expect(output).to match(/DNS_QUERY_FAILED/)
+    # One 1.5s backoff; a second retry would add another 3s.
+    expect(elapsed(output)).to be_between(1.5, 4.0)
+  end
+
+  it "doubles the backoff for each later retry" do
+    output = run_isolated({ dns_failure_retries: 3, dns_retry_backoff_ms: 200 })
+
+    expect(output).to match(/DNS_QUERY_FAILED/)
+    # 200ms + 400ms + 800ms.
+    expect(elapsed(output)).to be >= 1.4
+  end
+
+  it "does not retry by default" do
+    output = run_isolated({ dns_retry_backoff_ms: 5_000 })
+
+    expect(output).to match(/DNS_QUERY_FAILED/)
+    expect(elapsed(output)).to be < 5.0
+  end
+
+  it "keeps settings from earlier configure calls" do
+    output = run_isolated({ dns_failure_retries: 2 }, { dns_retry_backoff_ms: 300 })
+
+    # Both retries ran, backing off 300ms then 600ms.
+    expect(elapsed(output)).to be >= 0.9
+  end
+
+  it "fails with SHUTTING_DOWN when the pool is closed during the backoff" do
+    output = run_isolated({ dns_failure_retries: 1, dns_retry_backoff_ms: 2_000 }, body: <<~RUBY)
+      request = Thread.new { pool.request("GET", "/", headers) }
+      sleep 0.5
+      pool.close
+      begin
+        request.value
+      rescue AwsCrt::Http::ConnectionError => e
+        puts e.message
+      end
+    RUBY
+
+    expect(output).to match(/SHUTTING_DOWN/)
+  end
+end
+
+RSpec.describe AwsCrt, ".tls_backend" do
+  it "reports the platform TLS implementation" do
+    expected =
+      case RbConfig::CONFIG["host_os"]
+      when /darwin/ then :securetransport
+      when /mswin|mingw/ then :schannel
+      else :s2n
+      end
+
+    expect(AwsCrt.tls_backend).to eq(expected)
+  end
+end
@@ -14,12 +14,19 @@ RSpec.describe AwsCrt::Cbor::Decoder do
   end
 
   describe "#decode" do
-    it "raises when there are extra bytes" do
+    it "raises when there are extra bytes and strict: true is given" do
       expect do
-        cbor64_decode("AAA==")
+        described_class.new(Base64.decode64("AAA=="), strict: true).decode
       end.to raise_error(AwsCrt::Cbor::ExtraBytesError)
     end
 
+    it "leaves extra bytes for the next call by default" do
+      bytes = AwsCrt::Cbor::Encoder.new.add(1).add("two").add([3]).bytes
+      decoder = described_class.new(bytes)
+      expect([decoder.decode, decoder.decode, decoder.decode]).to eq([1, "two", [3]])
+      expect { decoder.decode }.to raise_error(AwsCrt::Cbor::OutOfBytesError)
+    end
+
     it "raises for undefined reserved" do
       buffer = String.new
       buffer << 0xf8 # 111_11000 - Major type 7 (Float) + value: 24
@@ -53,6 +60,95 @@ RSpec.describe AwsCrt::Cbor::Decoder do
       end
     end
 
+    it "decodes negative zero" do
+      value = cbor64_decode("+oAAAAA=")
+      expect(value).to eq(0.0)
+      expect(1 / value).to eq(-Float::INFINITY)
+    end
+
+    it "preserves NaN payload bits" do
+      value = cbor64_decode("+3/4AAAAAAAB")
+      expect([value].pack("G").unpack1("Q>")).to eq(0x7ff8000000000001)
+    end
+
+    context "with exact_floats: true" do
+      def exact_decode(bytes)
+        described_class.new(bytes, exact_floats: true).decode
+      end
+
+      it "returns Floats that re-encode to the same bytes" do
+        [1.1, -0.0, Float::INFINITY, 1.5].each do |value|
+          bytes = AwsCrt::Cbor::Encoder.new.add(value).bytes
+          expect(exact_decode(bytes)).to be_a(Float)
+        end
+      end
+
+      it "returns ExactFloat for half precision values" do
+        value = exact_decode("\xf9\x3c\x00".b)
+        expect(value).to eq(AwsCrt::Cbor::ExactFloat.new(16, 0x3c00))
+        expect(value.to_f).to eq(1.0)
+      end
+
+      it "returns ExactFloat for doubles that would narrow to a single" do
+        value = exact_decode("\xfb\x3f\xf0\x00\x00\x00\x00\x00\x00".b)
+        expect(value.width).to eq(64)
+        expect(value.bits).to eq(0x3ff0000000000000)
+      end
+
+      it "round-trips special values byte-for-byte" do
+        [
+          "\xf9\x7c\x01".b,
+          "\xf9\x80\x00".b,
+          "\xfa\x7f\x80\x00\x01".b,
+          "\xfb\x7f\xf0\x00\x00\x00\x00\x00\x01".b,
+          "\xfb\x80\x00\x00\x00\x00\x00\x00\x00".b
+        ].each do |bytes|
+          expect(AwsCrt::Cbor.encode(exact_decode(bytes))).to eq(bytes)
+        end
+      end
+
+      it "applies to floats nested in collections" do
+        value = exact_decode("\x81\xf9\x3c\x00".b)
+        expect(value.first).to be_a(AwsCrt::Cbor::ExactFloat)
+      end
+    end
+
+    context "with floats_as_decimal: true" do
+      def decimal_decode(bytes)
+        described_class.new(bytes, floats_as_decimal: true).decode
+      end
+
+      it "returns the shortest decimal for a double" do
+        value = decimal_decode(AwsCrt::Cbor::Encoder.new.add(0.1).bytes)
+        expect(value).to be_a(BigDecimal)
+        expect(value).to eq(BigDecimal("0.1"))
+      end
+
+      it "uses the single-precision decimal for singles" do
+        expect(decimal_decode("\xfa\x3d\xcc\xcc\xcd".b)).to eq(BigDecimal("0.1"))
+      end
+
+      it "decodes half precision" do
+        expect(decimal_decode("\xf9\x3e\x00".b)).to eq(BigDecimal("1.5"))
+      end
+
+      it "maps NaN and the infinities to BigDecimal's special values" do
+        expect(decimal_decode("\xf9\x7e\x00".b)).to be_nan
+        expect(decimal_decode("\xf9\x7c\x00".b)).to eq(BigDecimal("Infinity"))
+        expect(decimal_decode("\xf9\xfc\x00".b)).to eq(BigDecimal("-Infinity"))
+      end
+
+      it "leaves integers alone and applies inside collections" do
+        bytes = AwsCrt::Cbor::Encoder.new.add({ "price" => 19.99, "qty" => 3 }).bytes
+        expect(decimal_decode(bytes)).to eq({ "price" => BigDecimal("19.99"), "qty" => 3 })
+      end
+
+      it "cannot be combined with exact_floats" do
+        expect { described_class.new("\xf9\x3c\x00".b, exact_floats: true, floats_as_decimal: true) }
+          .to raise_error(ArgumentError, /cannot be combined/)
+      end
+    end
+
     it "decodes undefined" do
       undefined_bytes = 0xf7 # 111_10111 - Major type 7 (Float) + value: 23
       buffer = String.new
@@ -75,9 +171,359 @@ RSpec.describe AwsCrt::Cbor::Decoder do
       expect(encode_decode(value)).to eq(value)
     end
 
+    it "decodes large BigNums of either sign" do
+      value = (2**511) + 12_345
+      expect(encode_decode(value)).to eq(value)
+      expect(encode_decode(-value)).to eq(-value)
+    end
+
+    it "decodes tag 3 when adding one carries into a new byte" do
+      # -1 - 0xffff_ffff_ffff_ffff_ff
+      expect(described_class.new("\xc3\x49#{"\xff" * 9}".b).decode).to eq(-(2**72))
+    end
+
+    it "decodes bignum payloads that fit in 64 bits or have leading zeros" do
+      expect(described_class.new("\xc2\x40".b).decode).to eq(0)
+      expect(described_class.new("\xc3\x40".b).decode).to eq(-1)
+      expect(described_class.new("\xc2\x48#{"\xff" * 8}".b).decode).to eq((2**64) - 1)
+      expect(described_class.new("\xc3\x48#{"\xff" * 8}".b).decode).to eq(-(2**64))
+      expect(described_class.new("\xc3\x48\x7f#{"\xff" * 7}".b).decode).to eq(-(2**63))
+      expect(described_class.new("\xc2\x4a\x00\x00\x01#{"\x00" * 7}".b).decode).to eq(2**56)
+    end
+
     it "decodes BigDecimals" do
       value = BigDecimal("273.15")
       expect(cbor64_decode("xIIhGWqz")).to eq(value)
     end
+
+    context "arrays of small integers" do
+      it "decodes a homogeneous array of one-byte integers" do
+        value = Array.new(1000) { |i| (i % 48) - 24 }
+        expect(encode_decode(value)).to eq(value)
+      end
+
+      it "falls back to the general path when a larger element follows the run" do
+        value = Array.new(20) { |i| i % 24 } + [1_000, "x", nil, -500] + [3, 2, 1]
+        expect(encode_decode(value)).to eq(value)
+      end
+
+      it "decodes short arrays below the probe length" do
+        expect(encode_decode([1, -2, 3])).to eq([1, -2, 3])
+      end
+
+      it "raises OutOfBytesError when the run is truncated" do
+        bytes = AwsCrt::Cbor::Encoder.new.add(Array.new(16, 5)).bytes
+        expect do
+          described_class.new(bytes[0...-4]).decode
+        end.to raise_error(AwsCrt::Cbor::OutOfBytesError)
+      end
+
+      it "raises OutOfBytesError for a hostile 8-byte count ahead of a run" do
+        bytes = "\x9b\xff\xff\xff\xff\xff\xff\xff\xfe".b + ("\x05".b * 16)
+        expect do
+          described_class.new(bytes).decode
+        end.to raise_error(AwsCrt::Cbor::OutOfBytesError)
+      end
+
+      it "raises OutOfBytesError for a hostile 8-byte map count" do
+        bytes = "\xbb\x7f\xff\xff\xff\xff\xff\xff\xff".b + ("\x05".b * 16)
+        expect do
+          described_class.new(bytes).decode
+        end.to raise_error(AwsCrt::Cbor::OutOfBytesError)
+      end
+    end
+
+    context "indefinite-length strings" do
+      def indefinite(major, *chunks)
+        head = major == :text ? 0x60 : 0x40
+        ([head | 31].pack("C") + chunks.map { |c| [head | c.bytesize].pack("C") + c.b }.join + "\xff".b).b
+      end
+
+      it "joins text chunks into a UTF-8 String" do
+        value = described_class.new(indefinite(:text, "ab", "c\u00e9")).decode
+        expect(value).to eq("abc\u00e9")
+        expect(value.encoding).to eq(Encoding::UTF_8)
+      end
+
+      it "joins byte string chunks into a binary String" do
+        value = described_class.new(indefinite(:bytes, "\x00\x01", "\x02")).decode
+        expect(value).to eq("\x00\x01\x02".b)
+        expect(value.encoding).to eq(Encoding::BINARY)
+      end
+
+      it "keeps nested indefinite strings separate" do
+        bytes = "\x82".b + indefinite(:text, "one") + indefinite(:bytes, "two")
+        expect(described_class.new(bytes).decode).to eq(["one", "two".b])
+      end
+
+      it "decodes correctly after a truncated string and after a large one" do
+        expect { described_class.new(indefinite(:text, "abc")[0...-1]).decode }
+          .to raise_error(AwsCrt::Cbor::OutOfBytesError)
+        large = Array.new(100) { "x" * 20 }
+        expect(described_class.new(indefinite(:text, *large)).decode).to eq("x" * 2000)
+        expect(described_class.new(indefinite(:text, "z")).decode).to eq("z")
+      end
+    end
+
+    context "with max_string_length:" do
+      def decode_limited(bytes, max)
+        described_class.new(bytes, max_string_length: max).decode
+      end
+
+      it "decodes strings up to the limit" do
+        bytes = AwsCrt::Cbor::Encoder.new.add({ "key" => "x" * 100, "bin" => ("y" * 100).b }).bytes
+        expect(decode_limited(bytes, 100)).to eq({ "key" => "x" * 100, "bin" => ("y" * 100).b })
+      end
+
+      it "raises Cbor::Error for a text string over the limit" do
+        bytes = AwsCrt::Cbor::Encoder.new.add("x" * 101).bytes
+        expect { decode_limited(bytes, 100) }
+          .to raise_error(AwsCrt::Cbor::Error, /String length 101 exceeds max_string_length 100/)
+      end
+
+      it "checks the declared length before reading the bytes" do
+        # Byte string header claiming 4 GiB, with no payload behind it.
+        bytes = "[       ".b
+        expect { decode_limited(bytes, 1024) }
+          .to raise_error(AwsCrt::Cbor::Error, /exceeds max_string_length/)
+        expect { described_class.new(bytes).decode }.to raise_error(AwsCrt::Cbor::OutOfBytesError)
+      end
+
+      it "applies to short strings and map keys" do
+        expect { decode_limited(AwsCrt::Cbor::Encoder.new.add("abcd").bytes, 3) }
+          .to raise_error(AwsCrt::Cbor::Error)
+        expect { decode_limited(AwsCrt::Cbor::Encoder.new.add({ "abcd" => 1 }).bytes, 3) }
+          .to raise_error(AwsCrt::Cbor::Error)
+      end
+
+      it "limits the total length of an indefinite-length string" do
+        bytes = "cabccdefÿ".b
+        expect(decode_limited(bytes, 6)).to eq("abcdef")
+        expect { decode_limited(bytes, 5) }.to raise_error(AwsCrt::Cbor::Error)
+      end
+
+      it "is also accepted by Cbor.decode" do
+        bytes = AwsCrt::Cbor::Encoder.new.add("x" * 10).bytes
+        expect { AwsCrt::Cbor.decode(bytes, max_string_length: 5) }.to raise_error(AwsCrt::Cbor::Error)
+      end
+    end
+
+    context "with max_depth:" do
+      def nested_arrays(depth)
+        ("\x81" * depth).b + "\x01".b
+      end
+
+      it "decodes up to 1000 nested containers by default" do
+        value = described_class.new(nested_arrays(1000)).decode
+        1000.times { value = value.first }
+        expect(value).to eq(1)
+      end
+
+      it "raises MaxDepthError past the default limit instead of overflowing the stack" do
+        expect { described_class.new(nested_arrays(100_000)).decode }
+          .to raise_error(AwsCrt::Cbor::MaxDepthError, /Maximum nesting depth of 1000 exceeded/)
+      end
+
+      it "counts arrays, maps, indefinite-length containers and tags" do
+        expect(described_class.new("\x81\x81\x01".b, max_depth: 2).decode).to eq([[1]])
+        ["\x81\x81\x81\x01", "\xa1\x61a\x81\x81\x01", "\x9f\x9f\x9f\xff\xff\xff", "\xc6\xc6\xc6\x01"].each do |bytes|
+          expect { described_class.new(bytes.b, max_depth: 2).decode }.to raise_error(AwsCrt::Cbor::MaxDepthError)
+        end
+      end
+
+      it "is a Cbor::Error" do
+        expect(AwsCrt::Cbor::MaxDepthError.ancestors).to include(AwsCrt::Cbor::Error)
+      end
+
+      it "is also accepted by Cbor.decode and Cbor.parse" do
+        expect { AwsCrt::Cbor.decode(nested_arrays(3), max_depth: 2) }.to raise_error(AwsCrt::Cbor::MaxDepthError)
+        expect { AwsCrt::Cbor.parse(nested_arrays(3), max_depth: 2) { |_event| nil } }
+          .to raise_error(AwsCrt::Cbor::MaxDepthError)
+      end
+    end
+
+    context "with symbolize_keys:" do
+      it "returns map keys as Symbols, including nested maps" do
+        bytes = AwsCrt::Cbor::Encoder.new.add({ "name" => "x", "nested" => { "id" => 1 } }).bytes
+        expect(described_class.new(bytes, symbolize_keys: true).decode)
+          .to eq({ name: "x", nested: { id: 1 } })
+      end
+
+      it "symbolizes keys of indefinite-length maps" do
+        expect(described_class.new("\xbf\x61a\x01\xff".b, symbolize_keys: true).decode).to eq({ a: 1 })
+      end
+
+      it "symbolizes keys too long for the inline path" do
+        key = "k" * 40
+        bytes = AwsCrt::Cbor::Encoder.new.add({ key => true }).bytes
+        expect(described_class.new(bytes, symbolize_keys: true).decode).to eq({ key.to_sym => true })
+      end
+
+      it "leaves String values alone" do
+        bytes = AwsCrt::Cbor::Encoder.new.add({ "list" => %w[a b] }).bytes
+        expect(described_class.new(bytes, symbolize_keys: true).decode).to eq({ list: %w[a b] })
+      end
+
+      it "is also accepted by Cbor.decode" do
+        bytes = AwsCrt::Cbor::Encoder.new.add({ "a" => 1 }).bytes
+        expect(AwsCrt::Cbor.decode(bytes, symbolize_keys: true)).to eq({ a: 1 })
+        expect(AwsCrt::Cbor.decode(bytes)).to eq({ "a" => 1 })
+      end
+    end
+
+    it "reports the byte offset a decode error was raised at" do
+      # {"a" => [1, <float32 cut short>]}
+      bytes = "\xa1\x61a\x82\x01\xfa\x00".b
+      expect { described_class.new(bytes).decode }
+        .to raise_error(AwsCrt::Cbor::OutOfBytesError, /\(at byte offset 5\)/) { |e| expect(e.offset).to eq(5) }
+    end
+
+    it "reports offsets from the start of the buffer across calls" do
+      decoder = described_class.new("\x01\xfb\x00".b)
+      expect(decoder.decode).to eq(1)
+      expect { decoder.decode }
+        .to raise_error(AwsCrt::Cbor::OutOfBytesError, /offset 1\)/) { |e| expect(e.offset).to eq(1) }
+    end
+  end
+
+  describe "#decode_next" do
+    it "decodes consecutive items from one buffer" do
+      decoder = described_class.new("\x01\x62hi\xf6".b)
+      expect(decoder.decode_next).to eq(1)
+      expect(decoder.decode_next).to eq("hi")
+      expect(decoder.decode_next).to be_nil
+      expect(decoder.decode_next).to equal(AwsCrt::Cbor::NEED_MORE)
+    end
+
+    it "returns BREAK for a break code and advances past it" do
+      decoder = described_class.new("\xff\x07".b)
+      expect(decoder.decode_next).to equal(AwsCrt::Cbor::BREAK)
+      expect(decoder.decode_next).to eq(7)
+    end
+
+    it "lets the caller walk an indefinite-length array" do
+      decoder = described_class.new("\x9f\x01\x02\xff".b)
+      expect(decoder.decode_next).to eq([1, 2])
+
+      decoder = described_class.new("\x01\x02\xff\x03".b)
+      items = []
+      while (item = decoder.decode_next) != AwsCrt::Cbor::BREAK
+        items << item
+      end
+      expect(items).to eq([1, 2])
+      expect(decoder.decode_next).to eq(3)
+    end
+
+    it "returns NEED_MORE without consuming anything once the buffer is exhausted" do
+      decoder = described_class.new("\x01".b)
+      decoder.decode_next
+      expect(decoder.decode_next).to equal(AwsCrt::Cbor::NEED_MORE)
+      expect(decoder.position).to eq(1)
+    end
+
+    it "returns NEED_MORE without consuming anything for an incomplete item" do
+      decoder = described_class.new("\x01\x82\x01".b)
+      expect(decoder.decode_next).to eq(1)
+      expect(decoder.decode_next).to equal(AwsCrt::Cbor::NEED_MORE)
+      expect(decoder.position).to eq(1)
+      expect(decoder.rest).to eq("\x82\x01".b)
+    end
+
+    it "still raises for malformed input" do
+      expect { described_class.new("\x1c".b).decode_next }.to raise_error(AwsCrt::Cbor::Error)
+    end
+
+    it "leaves #decode strict about break codes" do
+      expect { described_class.new("\xff".b).decode }
+        .to raise_error(AwsCrt::Cbor::UnexpectedBreakCodeError)
+    end
+
+    it "exposes frozen BREAK and NEED_MORE sentinels" do
+      expect(AwsCrt::Cbor::BREAK).to be_frozen
+      expect(AwsCrt::Cbor::BREAK.inspect).to eq("AwsCrt::Cbor::BREAK")
+      expect(AwsCrt::Cbor::NEED_MORE).to be_frozen
+      expect(AwsCrt::Cbor::NEED_MORE.inspect).to eq("AwsCrt::Cbor::NEED_MORE")
+    end
+  end
+
+  describe "#feed" do
+    it "decodes items as the bytes that complete them arrive" do
+      bytes = AwsCrt::Cbor::Encoder.new.add({ "id" => 1 }).add("two").bytes
+      decoder = described_class.new("".b)
+      items = []
+      bytes.each_char do |byte|
+        decoder.feed(byte)
+        until (item = decoder.decode_next).equal?(AwsCrt::Cbor::NEED_MORE)
+          items << item
+        end
+      end
+
+      expect(items).to eq([{ "id" => 1 }, "two"])
+      expect(decoder.remaining).to eq(0)
+    end
+
+    it "tells a fed null apart from an incomplete item" do
+      decoder = described_class.new("".b)
+      expect(decoder.decode_next).to equal(AwsCrt::Cbor::NEED_MORE)
+      decoder.feed("\xf6".b)
+      expect(decoder.decode_next).to be_nil
+      expect(decoder.position).to eq(1)
+      expect(decoder.decode_next).to equal(AwsCrt::Cbor::NEED_MORE)
+    end
+
+    it "keeps position counting from the first byte fed" do
+      decoder = described_class.new("\x01\x02".b)
+      decoder.decode_next
+      expect(decoder.feed("\x03".b)).to be(decoder)
+      expect(decoder.position).to eq(1)
+      expect(decoder.rest).to eq("\x02\x03".b)
+      decoder.decode_next
+      expect(decoder.position).to eq(2)
+    end
+
+    it "reports error offsets from the first byte fed" do
+      unfed_offset = begin
+        described_class.new("\x1c".b).decode_next
+      rescue AwsCrt::Cbor::Error => e
+        e.offset
+      end
+      decoder = described_class.new("\x01\x02".b)
+      2.times { decoder.decode_next }
+      decoder.feed("\x1c".b)
+      expect { decoder.decode_next }
+        .to raise_error(AwsCrt::Cbor::Error) { |e| expect(e.offset).to eq(unfed_offset + 2) }
+    end
+  end
+
+  describe "#position, #remaining and #rest" do
+    let(:bytes) { AwsCrt::Cbor::Encoder.new.add(1).add("two").bytes }
+
+    it "start at the beginning of the buffer" do
+      decoder = described_class.new(bytes)
+      expect(decoder.position).to eq(0)
+      expect(decoder.remaining).to eq(bytes.bytesize)
+      expect(decoder.rest).to eq(bytes)
+    end
+
+    it "track the bytes each decode consumes" do
+      decoder = described_class.new(bytes)
+      decoder.decode
+      expect(decoder.position).to eq(1)
+      expect(decoder.remaining).to eq(bytes.bytesize - 1)
+      expect(decoder.rest).to eq(AwsCrt::Cbor.encode("two"))
+      expect(decoder.rest.encoding).to eq(Encoding::BINARY)
+
+      decoder.decode_next
+      expect(decoder.position).to eq(bytes.bytesize)
+      expect(decoder.remaining).to eq(0)
+      expect(decoder.rest).to eq("".b)
+    end
+
+    it "are unchanged by a decode that runs out of bytes" do
+      decoder = described_class.new(bytes.byteslice(0, bytes.bytesize - 1))
+      decoder.decode
+      expect { decoder.decode }.to raise_error(AwsCrt::Cbor::OutOfBytesError)
+      expect(decoder.position).to eq(1)
+    end
   end
 end
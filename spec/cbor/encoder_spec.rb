@@ -2,6 +2,8 @@
 
 require "base64"
 require "bigdecimal"
+require "stringio"
+require "set"
 require "time"
 
 RSpec.describe AwsCrt::Cbor::Encoder do
@@ -37,6 +39,67 @@ RSpec.describe AwsCrt::Cbor::Encoder do
       expect(cbor64_encode(Float::INFINITY)).to eq("+n+AAAA=")
     end
 
+    it "takes float_format: in new" do
+      encoder = described_class.new(float_format: :double)
+      expect(encoder.add(0.5).bytes).to eq("\xfb".b + [0.5].pack("G"))
+    end
+
+    it "takes special_float_width: in new" do
+      encoder = described_class.new(special_float_width: :half)
+      expect(encoder.add(Float::INFINITY).bytes).to eq("\xf9\x7c\x00".b)
+    end
+
+    it "encodes negative zero with its sign bit" do
+      expect(cbor64_encode(-0.0)).to eq("+oAAAAA=")
+    end
+
+    it "keeps NaN payloads that do not fit in a single" do
+      nan = [0x7ff8000000000001].pack("Q>").unpack1("G")
+      expect(cbor64_encode(nan)).to eq("+3/4AAAAAAAB")
+    end
+
+    it "encodes ExactFloats with their original width and bits" do
+      expect(cbor64_encode(AwsCrt::Cbor::ExactFloat.new(16, 0x3c00))).to eq("+TwA")
+      expect(cbor64_encode(AwsCrt::Cbor::ExactFloat.new(64, 0x3ff0000000000000)))
+        .to eq("+z/wAAAAAAAA")
+    end
+
+    context "with exact_floats: true" do
+      let(:payload_nan) { [0x7ff8000000000001].pack("Q>").unpack1("G") }
+
+      it "raises when the options would drop a NaN payload" do
+        encoder = described_class.new(special_float_width: :half, exact_floats: true)
+        expect { encoder.add(payload_nan) }.to raise_error(AwsCrt::Cbor::Error, /exactly/)
+        expect(encoder.bytes).to be_empty
+      end
+
+      it "writes floats whose bits survive" do
+        encoder = described_class.new(canonical: true, exact_floats: true)
+        expect(encoder.add(1.5).add(Float::NAN).bytes).to eq("\xf9\x3e\x00\xf9\x7e\x00".b)
+      end
+
+      it "raises for an ExactFloat canonical CBOR would narrow" do
+        single_one = AwsCrt::Cbor::ExactFloat.new(32, 0x3f800000)
+        expect { described_class.new(canonical: true, exact_floats: true).add(single_one) }
+          .to raise_error(AwsCrt::Cbor::Error, /canonical/)
+      end
+
+      it "writes a Rational with a terminating decimal as a decimal fraction" do
+        bytes = described_class.new(exact_floats: true).add(Rational(3, 8)).bytes
+        expect(bytes).to eq("\xc4\x82\x22\x19\x01\x77".b)
+        expect(AwsCrt::Cbor.decode(bytes)).to eq(BigDecimal("0.375"))
+      end
+
+      it "raises for a Rational with no exact decimal form" do
+        expect { described_class.new(exact_floats: true).add(Rational(1, 3)) }
+          .to raise_error(AwsCrt::Cbor::Error, %r{\(1/3\) has no exact decimal form})
+      end
+
+      it "still rejects a Rational without the option" do
+        expect { described_class.new.add(Rational(3, 8)) }.to raise_error(AwsCrt::Cbor::UnknownTypeError)
+      end
+    end
+
     it "encodes booleans and nil" do
       expect(cbor64_encode(true)).to eq("9Q==")
       expect(cbor64_encode(false)).to eq("9A==")
@@ -60,6 +123,22 @@ RSpec.describe AwsCrt::Cbor::Encoder do
       expect(cbor64_encode(:a)).to eq("YWE=")
     end
 
+    it "encodes non-ASCII UTF-8 Symbols as text" do
+      bytes = AwsCrt::Cbor::Encoder.new.add(:"caf\u00e9").bytes
+      expect(bytes).to eq("\x65caf\xc3\xa9".b)
+    end
+
+    it "encodes binary Symbols as byte strings" do
+      bytes = AwsCrt::Cbor::Encoder.new.add("\xff\xfe".b.to_sym).bytes
+      expect(bytes).to eq("\x42\xff\xfe".b)
+    end
+
+    it "raises Cbor::Error for Symbols that are not valid UTF-8" do
+      sym = "caf\xe9".dup.force_encoding(Encoding::ISO_8859_1).to_sym
+      expect { AwsCrt::Cbor::Encoder.new.add(sym) }
+        .to raise_error(AwsCrt::Cbor::Error, /Symbol :caf.* is not valid UTF-8 \(invalid byte at offset 3\)/)
+    end
+
     it "encodes Tagged items" do
       expect(cbor64_encode(AwsCrt::Cbor::Tagged.new(0, 0))).to eq("wAA=")
     end
@@ -86,10 +165,134 @@ RSpec.describe AwsCrt::Cbor::Encoder do
       expect(cbor64_encode(BigDecimal("NaN"))).to eq("+n/AAAA=")
     end
 
+    it "encodes Sets as arrays" do
+      expect(described_class.new.add(Set[1, 2]).bytes).to eq("\x82\x01\x02".b)
+    end
+
+    it "wraps Sets in tag 258 with tag_sets: true" do
+      encoder = described_class.new(tag_sets: true)
+      expect(encoder.add(Set["a"]).bytes).to eq("\xd9\x01\x02\x81\x61a".b)
+    end
+
+    it "encodes Ranges as [begin, end]" do
+      expect(described_class.new.add(1..5).bytes).to eq("\x82\x01\x05".b)
+      expect(described_class.new.add(1...5).bytes).to eq("\x82\x01\x05".b)
+      expect(described_class.new.add(1..).bytes).to eq("\x82\x01\xf6".b)
+      expect(described_class.new.add("a".."c").bytes).to eq("\x82\x61a\x61c".b)
+    end
+
     it "raises on unknown items" do
       expect do
         cbor64_encode(described_class.new)
       end.to raise_error(AwsCrt::Cbor::UnknownTypeError)
     end
   end
+
+  describe "#reset" do
+    it "discards the encoded bytes so the encoder can be reused" do
+      encoder = described_class.new.add("first")
+      expect(encoder.reset).to be(encoder)
+      expect(encoder.bytes).to eq("".b)
+      expect(encoder.add(1).bytes).to eq("\x01".b)
+    end
+  end
+
+  describe "#size" do
+    it "returns the number of bytes buffered" do
+      encoder = described_class.new
+      expect(encoder.size).to eq(0)
+      expect(encoder.add("abc").size).to eq(4)
+      expect(encoder.reset.size).to eq(0)
+    end
+  end
+
+  describe ".to_io" do
+    # Records each write so tests can see where flushes happened.
+    let(:io) do
+      Class.new(StringIO) do
+        def writes
+          @writes ||= []
+        end
+
+        def write(chunk)
+          writes << chunk.dup
+          super
+        end
+      end.new("".b)
+    end
+
+    it "writes buffered items to the IO once the threshold is reached" do
+      encoder = described_class.to_io(io, 8)
+      encoder.add("abc") # 4 bytes buffered
+      expect(io.writes).to be_empty
+
+      encoder.add("defg") # 9 bytes buffered
+      expect(io.writes.size).to eq(1)
+      expect(encoder.bytes).to be_empty
+    end
+
+    it "never splits an item across writes" do
+      encoder = described_class.to_io(io, 4)
+      records = Array.new(20) { |i| { "id" => i, "name" => "record-#{i}" } }
+      records.each { |r| encoder.add(r) }
+      encoder.flush
+
+      # Each write must be exactly a run of whole, consecutive items.
+      encoded = records.map { |r| AwsCrt::Cbor.encode(r) }
+      io.writes.each do |chunk|
+        run = +"".b
+        run << encoded.shift while run.bytesize < chunk.bytesize && encoded.any?
+        expect(chunk).to eq(run)
+      end
+      expect(encoded).to be_empty
+    end
+
+    it "produces the same bytes as an in-memory encoder" do
+      values = [1, "two", [3.5], { "four" => nil }, "x" * 1000]
+      encoder = described_class.to_io(io, 16)
+      values.each { |v| encoder.add(v) }
+      encoder.flush
+
+      expected = values.each_with_object(described_class.new) { |v, e| e.add(v) }.bytes
+      expect(io.string).to eq(expected)
+      expect(io.string.encoding).to eq(Encoding::BINARY)
+    end
+
+    it "keeps memory flat by flushing every 64 KiB by default" do
+      encoder = described_class.to_io(io)
+      2_000.times { |i| encoder.add("x" * 100 + i.to_s) }
+
+      expect(io.writes).not_to be_empty
+      expect(encoder.bytes.bytesize).to be < 64 * 1024
+    end
+
+    it "accepts the same options as Encoder.new" do
+      encoder = described_class.to_io(io, 4, float_format: :double)
+      encoder.add(1.5).flush
+
+      expect(io.string).to eq("\xfb".b + [1.5].pack("G"))
+    end
+
+    it "accepts options in place of the threshold" do
+      encoder = described_class.to_io(io, canonical: true)
+      encoder.add({ "bb" => 1, "a" => 2 }).flush
+
+      expect(io.string).to eq(AwsCrt::Cbor.encode({ "bb" => 1, "a" => 2 }, canonical: true))
+      expect(io.writes.size).to eq(1)
+    end
+
+    it "raises ArgumentError for invalid options" do
+      expect { described_class.to_io(io, float_format: :quad) }.to raise_error(ArgumentError)
+    end
+
+    it "flush is a no-op for an in-memory encoder" do
+      encoder = described_class.new.add(1)
+      expect(encoder.flush).to be(encoder)
+      expect(encoder.bytes).to eq("\x01".b)
+    end
+
+    it "raises TypeError for a non-IO" do
+      expect { described_class.to_io(Object.new) }.to raise_error(TypeError)
+    end
+  end
 end
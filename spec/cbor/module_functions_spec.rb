@@ -2,6 +2,7 @@
 
 require "base64"
 require "bigdecimal"
+require "stringio"
 require "time"
 
 RSpec.describe AwsCrt::Cbor do
@@ -23,6 +24,16 @@ RSpec.describe AwsCrt::Cbor do
       expect(described_class.decode(described_class.encode(Float::INFINITY))).to eq(Float::INFINITY)
     end
 
+    it "accepts decode options" do
+      value = described_class.decode("\xf9\x3c\x00".b, exact_floats: true)
+      expect(value).to be_a(AwsCrt::Cbor::ExactFloat)
+    end
+
+    it "decodes floats as BigDecimal with floats_as_decimal" do
+      value = described_class.decode(described_class.encode(1.1), floats_as_decimal: true)
+      expect(value).to eq(BigDecimal("1.1"))
+    end
+
     it "round-trips strings" do
       expect(described_class.decode(described_class.encode("hello"))).to eq("hello")
     end
@@ -75,5 +86,414 @@ RSpec.describe AwsCrt::Cbor do
     it "raises TypeError on non-string decode input" do
       expect { described_class.decode(123) }.to raise_error(TypeError)
     end
+
+    it "reports the byte offset and buffer length of a decode error" do
+      # [1, "abc..." truncated after 2 of its 5 bytes]
+      bytes = "\x82\x01\x65ab".b
+      expect { described_class.decode(bytes) }
+        .to raise_error(AwsCrt::Cbor::OutOfBytesError, /\(at byte offset 3 of 5\)/) { |e| expect(e.offset).to eq(3) }
+    end
+
+    it "reports where trailing bytes start" do
+      encoded = described_class.encode(1) + described_class.encode(2)
+      expect { described_class.decode(encoded) }
+        .to raise_error(AwsCrt::Cbor::ExtraBytesError, /at byte offset 1 of 2/) { |e| expect(e.offset).to eq(1) }
+    end
+
+    it "leaves #offset nil on encode errors" do
+      expect { described_class.encode(Object.new) }
+        .to raise_error(AwsCrt::Cbor::UnknownTypeError) { |e| expect(e.offset).to be_nil }
+    end
+  end
+
+  describe ".encode with float_format:" do
+    it "narrows lossless floats by default" do
+      expect(described_class.encode(1.5, float_format: :auto)).to eq("\xf9\x3e\x00".b)
+    end
+
+    it "writes every float as a double with :double" do
+      expect(described_class.encode(1.5, float_format: :double)).to eq("\xfb".b + [1.5].pack("G"))
+      expect(described_class.encode([0.0, 100_000.0], float_format: :double))
+        .to eq("\x82\xfb".b + [0.0].pack("G") + "\xfb".b + [100_000.0].pack("G"))
+      expect(described_class.encode(Float::INFINITY, float_format: :double))
+        .to eq("\xfb".b + [Float::INFINITY].pack("G"))
+    end
+
+    it "still honours special_float_width for NaN and infinities" do
+      expect(described_class.encode(Float::NAN, float_format: :double, special_float_width: :half))
+        .to eq("\xf9\x7e\x00".b)
+    end
+
+    it "applies to encode_base64" do
+      expect(described_class.encode_base64(1.0, float_format: :double)).to eq("+z/wAAAAAAAA")
+    end
+
+    it "rejects unknown formats" do
+      expect { described_class.encode(1.0, float_format: :single) }
+        .to raise_error(ArgumentError, /invalid float_format :single/)
+    end
+
+    it "cannot be combined with canonical" do
+      expect { described_class.encode(1.0, float_format: :double, canonical: true) }
+        .to raise_error(ArgumentError, /cannot be combined/)
+    end
+  end
+
+  describe ".encode with canonical: true" do
+    def canonical(value)
+      described_class.encode(value, canonical: true)
+    end
+
+    it "sorts map keys by their encoded bytes, not their Ruby values" do
+      # "b" encodes shorter than "aa", so it sorts first; 10 (0x0a) sorts
+      # before any text key (0x6x).
+      expect(canonical({ "aa" => 1, "b" => 2, 10 => 3 })).to eq("\xa3\x0a\x03\x61b\x02\x62aa\x01".b)
+    end
+
+    it "gives the same bytes whatever the insertion order" do
+      a = { "z" => 1, "a" => { "y" => [1.5, nil], "b" => true } }
+      b = { "a" => { "b" => true, "y" => [1.5, nil] }, "z" => 1 }
+      expect(canonical(a)).to eq(canonical(b))
+      expect(described_class.encode(a)).not_to eq(described_class.encode(b))
+    end
+
+    it "writes floats in the shortest form that keeps their value" do
+      expect(canonical(1.5)).to eq("\xf9\x3e\x00".b)
+      expect(canonical(100_000.0)).to eq("\xfa\x47\xc3\x50\x00".b)
+      expect(canonical(1.1)).to eq("\xfb".b + [1.1].pack("G"))
+      expect(canonical(Float::NAN)).to eq("\xf9\x7e\x00".b)
+      expect(canonical(-Float::INFINITY)).to eq("\xf9\xfc\x00".b)
+    end
+
+    it "uses the shortest integer head" do
+      expect(canonical([23, 24, 256, -25])).to eq("\x84\x17\x18\x18\x19\x01\x00\x38\x18".b)
+    end
+
+    it "raises for keys that encode the same" do
+      expect { canonical({ "a" => 1, a: 2 }) }.to raise_error(AwsCrt::Cbor::Error, /Duplicate map key/)
+    end
+
+    it "cannot be combined with special_float_width" do
+      expect { described_class.encode(1.0, canonical: true, special_float_width: :half) }
+        .to raise_error(ArgumentError, /cannot be combined/)
+    end
+
+    it "is accepted by Encoder.new and encode_base64" do
+      expect(AwsCrt::Cbor::Encoder.new(canonical: true).add({ "b" => 1, "a" => 2 }).bytes)
+        .to eq("\xa2\x61a\x02\x61b\x01".b)
+      expect(described_class.encode_base64(1.0, canonical: true)).to eq("+TwA")
+    end
+  end
+
+  describe ".parse" do
+    def events(bytes, **opts)
+      [].tap { |out| described_class.parse(bytes, opts) { |event| out << event } }
+    end
+
+    it "yields container and value events" do
+      bytes = described_class.encode({ "a" => [1, "x"], "b" => nil })
+      expect(events(bytes)).to eq(
+        [
+          [:map_start, 2],
+          [:key, "a"], [:array_start, 2], [:value, 1], [:value, "x"], [:array_end],
+          [:key, "b"], [:value, nil],
+          [:map_end]
+        ]
+      )
+    end
+
+    it "reports nil sizes for indefinite-length containers" do
+      bytes = "\x9f\x01\xbf\x61a\x02\xff\xff".b
+      expect(events(bytes)).to eq(
+        [
+          [:array_start, nil], [:value, 1],
+          [:map_start, nil], [:key, "a"], [:value, 2], [:map_end],
+          [:array_end]
+        ]
+      )
+    end
+
+    it "yields a tag event before the tagged item" do
+      bytes = described_class.encode(AwsCrt::Cbor::Tagged.new(42, [1]))
+      expect(events(bytes)).to eq([[:tag, 42], [:array_start, 1], [:value, 1], [:array_end]])
+    end
+
+    it "yields times and bignums as values" do
+      time = Time.at(1_600_000_000)
+      bytes = described_class.encode([time, 2**70])
+      expect(events(bytes)[1..2]).to eq([[:value, time], [:value, 2**70]])
+    end
+
+    it "applies decode options to values" do
+      expect(events("\xf9\x3c\x00".b, exact_floats: true).first.last)
+        .to be_a(AwsCrt::Cbor::ExactFloat)
+    end
+
+    it "returns an Enumerator without a block" do
+      enum = described_class.parse(described_class.encode([1]))
+      expect(enum.to_a).to eq([[:array_start, 1], [:value, 1], [:array_end]])
+    end
+
+    it "raises ExtraBytesError on trailing data" do
+      bytes = described_class.encode(1) + described_class.encode(2)
+      expect { described_class.parse(bytes) { nil } }
+        .to raise_error(AwsCrt::Cbor::ExtraBytesError)
+    end
+
+    it "raises OutOfBytesError on truncated input after yielding earlier events" do
+      seen = []
+      expect { described_class.parse("\x82\x01".b) { |e| seen << e } }
+        .to raise_error(AwsCrt::Cbor::OutOfBytesError)
+      expect(seen).to eq([[:array_start, 2], [:value, 1]])
+    end
+  end
+
+  describe ".decode_seq" do
+    it "decodes every top-level item into an array" do
+      bytes = described_class.encode(1) + described_class.encode("two") + described_class.encode({ "k" => [3] })
+      expect(described_class.decode_seq(bytes)).to eq([1, "two", { "k" => [3] }])
+    end
+
+    it "returns an empty array for empty input" do
+      expect(described_class.decode_seq("".b)).to eq([])
+    end
+
+    it "accepts decode options" do
+      bytes = "\xf9\x3c\x00".b * 2
+      expect(described_class.decode_seq(bytes, exact_floats: true)).to all(be_a(AwsCrt::Cbor::ExactFloat))
+    end
+
+    it "raises OutOfBytesError with the offset of a truncated final item" do
+      bytes = described_class.encode("ok") + "\x82\x01".b
+      expect { described_class.decode_seq(bytes) }
+        .to raise_error(AwsCrt::Cbor::OutOfBytesError, /offset 3/) { |e| expect(e.offset).to eq(3) }
+    end
+
+    it "raises TypeError for non-String input" do
+      expect { described_class.decode_seq(123) }.to raise_error(TypeError)
+    end
+  end
+
+  describe ".encode_stream" do
+    it "writes the items as an indefinite-length array" do
+      io = StringIO.new("".b)
+      written = described_class.encode_stream([1, "two"].each, io)
+
+      expect(io.string).to eq("\x9f\x01\x63two\xff".b)
+      expect(written).to eq(io.string.bytesize)
+      expect(described_class.decode(io.string)).to eq([1, "two"])
+    end
+
+    it "accepts a lazy enumerator" do
+      io = StringIO.new("".b)
+      described_class.encode_stream((1..).lazy.map { |i| i * 2 }.take(100), io)
+
+      expect(described_class.decode(io.string)).to eq((1..100).map { |i| i * 2 })
+    end
+
+    it "writes in pieces as the buffer fills" do
+      writes = []
+      io = Object.new
+      io.define_singleton_method(:write) { |chunk| writes << chunk.bytesize }
+      described_class.encode_stream(Enumerator.new { |y| 100.times { y << ("x" * 4096) } }, io)
+
+      expect(writes.size).to be > 1
+    end
+
+    it "encodes a Hash's pairs as two-element arrays" do
+      io = StringIO.new("".b)
+      described_class.encode_stream({ "a" => 1 }, io)
+
+      expect(described_class.decode(io.string)).to eq([["a", 1]])
+    end
+
+    it "encodes nil items" do
+      io = StringIO.new("".b)
+      described_class.encode_stream([nil, 1], io)
+
+      expect(io.string).to eq("\x9f\xf6\x01\xff".b)
+    end
+
+    it "accepts encode options" do
+      io = StringIO.new("".b)
+      described_class.encode_stream([1.5], io, float_format: :double)
+      expect(io.string).to eq("\x9f\xfb".b + [1.5].pack("G") + "\xff".b)
+    end
+
+    it "raises ArgumentError with canonical: true" do
+      expect { described_class.encode_stream([], StringIO.new, canonical: true) }
+        .to raise_error(ArgumentError, /canonical/)
+    end
+
+    it "raises TypeError without an IO" do
+      expect { described_class.encode_stream([], Object.new) }.to raise_error(TypeError)
+    end
+  end
+
+  describe ".encode_base64 / .decode_base64" do
+    it "base64-encodes the encoded bytes" do
+      value = { "id" => 7, "tags" => %w[a b] }
+      expect(described_class.encode_base64(value)).to eq([described_class.encode(value)].pack("m0"))
+    end
+
+    it "round-trips through decode_base64" do
+      value = { "n" => -1, "f" => 1.5, "s" => "text" }
+      expect(described_class.decode_base64(described_class.encode_base64(value))).to eq(value)
+    end
+
+    it "uses the URL-safe alphabet with url_safe: true" do
+      bytes = "\xfb\xff".b
+      expect(described_class.encode_base64(bytes)).to eq("Qvv/")
+      expect(described_class.encode_base64(bytes, url_safe: true)).to eq("Qvv_")
+      expect(described_class.decode_base64("Qvv_", url_safe: true)).to eq(bytes)
+    end
+
+    it "accepts unpadded URL-safe input" do
+      expect(described_class.decode_base64("YQ", url_safe: true)).to eq(-2)
+    end
+
+    it "rejects characters from the other alphabet" do
+      expect { described_class.decode_base64("Qvv_") }.to raise_error(ArgumentError, /invalid base64/)
+    end
+
+    it "rejects unpadded standard input" do
+      expect { described_class.decode_base64("YQ") }.to raise_error(ArgumentError, /invalid base64/)
+    end
+
+    it "accepts decode options" do
+      expect(described_class.decode_base64("+TwA", exact_floats: true)).to be_a(AwsCrt::Cbor::ExactFloat)
+    end
+
+    it "accepts encode options" do
+      expect(described_class.encode_base64(Float::NAN, special_float_width: :half)).to eq("+X4A")
+    end
+  end
+
+  describe ".encode with special_float_width:" do
+    {
+      half: ["\xf9\x7e\x00", "\xf9\x7c\x00", "\xf9\xfc\x00"],
+      single: ["\xfa\x7f\xc0\x00\x00", "\xfa\x7f\x80\x00\x00", "\xfa\xff\x80\x00\x00"],
+      double: ["\xfb\x7f\xf8\x00\x00\x00\x00\x00\x00", "\xfb\x7f\xf0\x00\x00\x00\x00\x00\x00",
+               "\xfb\xff\xf0\x00\x00\x00\x00\x00\x00"]
+    }.each do |width, (nan, infinity, negative_infinity)|
+      it "writes NaN and ±Infinity as #{width}s" do
+        expect(described_class.encode(Float::NAN, special_float_width: width)).to eq(nan.b)
+        expect(described_class.encode(Float::INFINITY, special_float_width: width)).to eq(infinity.b)
+        expect(described_class.encode(-Float::INFINITY, special_float_width: width)).to eq(negative_infinity.b)
+      end
+    end
+
+    it "writes NaN payloads as the canonical quiet NaN" do
+      nan = [0x7ff8000000000001].pack("Q>").unpack1("G")
+      expect(described_class.encode(nan, special_float_width: :double)).to eq("\xfb\x7f\xf8\x00\x00\x00\x00\x00\x00".b)
+    end
+
+    it "applies inside containers and to BigDecimal specials" do
+      require "bigdecimal"
+      bytes = described_class.encode({ "x" => [Float::NAN, BigDecimal("Infinity")] }, special_float_width: :half)
+      expect(bytes).to eq("\xa1\x61x\x82\xf9\x7e\x00\xf9\x7c\x00".b)
+    end
+
+    it "leaves finite floats alone" do
+      expect(described_class.encode(1.5, special_float_width: :double)).to eq(described_class.encode(1.5))
+    end
+
+    it "keeps the default behavior when nil" do
+      expect(described_class.encode(Float::NAN, special_float_width: nil)).to eq("\xfa\x7f\xc0\x00\x00".b)
+    end
+
+    it "rejects other widths" do
+      expect { described_class.encode(1.0, special_float_width: :quad) }
+        .to raise_error(ArgumentError, /special_float_width/)
+    end
+  end
+
+  describe ".decode_each" do
+    let(:bytes) { described_class.encode(1) + described_class.encode("two") + described_class.encode([3]) }
+
+    it "returns every item as an Array without a block" do
+      expect(described_class.decode_each(bytes)).to eq([1, "two", [3]])
+    end
+
+    it "yields each item to a block and returns nil" do
+      items = []
+      expect(described_class.decode_each(bytes) { |item| items << item }).to be_nil
+      expect(items).to eq([1, "two", [3]])
+    end
+
+    it "yields the items before a truncated one, then raises OutOfBytesError" do
+      items = []
+      truncated = bytes + described_class.encode("four")[0, 2]
+      expect { described_class.decode_each(truncated) { |item| items << item } }
+        .to raise_error(AwsCrt::Cbor::OutOfBytesError, /offset #{bytes.bytesize}/)
+      expect(items).to eq([1, "two", [3]])
+    end
+
+    it "is unaffected by the block mutating the String" do
+      buffer = bytes.dup
+      items = []
+      described_class.decode_each(buffer) do |item|
+        items << item
+        buffer.clear
+      end
+      expect(items).to eq([1, "two", [3]])
+    end
+
+    it "accepts decode options" do
+      expect(described_class.decode_each(described_class.encode(1.5), floats_as_decimal: true))
+        .to eq([BigDecimal("1.5")])
+    end
+  end
+
+  describe ".decode_as" do
+    let(:point) { Struct.new(:x, :y, :count) }
+
+    it "fills the struct's members from the map's text keys" do
+      bytes = described_class.encode({ "x" => 1, "y" => 2, "count" => 3 })
+      expect(described_class.decode_as(bytes, point)).to eq(point.new(1, 2, 3))
+    end
+
+    it "uses defaults: for members missing from the map" do
+      bytes = described_class.encode({ "x" => 1, "y" => 2 })
+      expect(described_class.decode_as(bytes, point, defaults: { count: 0 })).to eq(point.new(1, 2, 0))
+    end
+
+    it "prefers the map's value over the default" do
+      bytes = described_class.encode({ "x" => 1, "y" => 2, "count" => 3 })
+      expect(described_class.decode_as(bytes, point, defaults: { count: 0 }).count).to eq(3)
+    end
+
+    it "raises Cbor::Error for a missing member without a default" do
+      bytes = described_class.encode({ "x" => 1 })
+      expect { described_class.decode_as(bytes, point, defaults: { count: 0 }) }
+        .to raise_error(AwsCrt::Cbor::Error, /missing member :y/)
+    end
+
+    it "ignores keys that aren't members" do
+      bytes = described_class.encode({ "x" => 1, "y" => 2, "count" => 3, "added_later" => true })
+      expect(described_class.decode_as(bytes, point)).to eq(point.new(1, 2, 3))
+    end
+
+    it "works with keyword_init structs" do
+      klass = Struct.new(:x, :y, :count, keyword_init: true)
+      bytes = described_class.encode({ "x" => 1, "y" => 2 })
+      expect(described_class.decode_as(bytes, klass, defaults: { count: 0 })).to eq(klass.new(x: 1, y: 2, count: 0))
+    end
+
+    it "accepts decode options alongside defaults:" do
+      bytes = described_class.encode({ "x" => 1.5, "y" => 2, "count" => 3 })
+      result = described_class.decode_as(bytes, point, floats_as_decimal: true)
+      expect(result.x).to eq(BigDecimal("1.5"))
+    end
+
+    it "raises Cbor::Error when the item isn't a map" do
+      expect { described_class.decode_as(described_class.encode([1, 2]), point) }
+        .to raise_error(AwsCrt::Cbor::Error, /expected a CBOR map/)
+    end
+
+    it "raises TypeError for a class that isn't a Struct" do
+      expect { described_class.decode_as(described_class.encode({}), Hash) }
+        .to raise_error(TypeError, /expected a Struct subclass/)
+    end
   end
 end
@@ -1,6 +1,8 @@
 # frozen_string_literal: true
 
 require "base64"
+require "pathname"
+require "tempfile"
 
 ZERO_CHAR = [0].pack("C*")
 INT_MAX = (2**32) - 1
@@ -158,4 +160,289 @@ RSpec.describe AwsCrt::Checksums do
       skip "Unable to allocate memory for crc64nvme huge buffer test"
     end
   end
+
+  %i[crc32 crc32c crc64nvme].each do |function|
+    describe ".#{function}_combine" do
+      def checksum(function, data)
+        described_class.public_send(function, data)
+      end
+
+      it "combines the checksums of two adjacent parts" do
+        a = checksum(function, "Hello ")
+        b = checksum(function, "world")
+        expect(described_class.public_send(:"#{function}_combine", a, b, 5))
+          .to eq(checksum(function, "Hello world"))
+      end
+
+      it "combines parts of different sizes in order" do
+        data = (0..255).to_a.pack("C*") * 1000
+        parts = [data[0, 1], data[1, 70_000], data[70_001..]]
+        combine = :"#{function}_combine"
+        combined = checksum(function, parts.first)
+        parts.drop(1).each do |part|
+          combined = described_class.public_send(combine, combined, checksum(function, part), part.bytesize)
+        end
+        expect(combined).to eq(checksum(function, data))
+      end
+
+      it "returns the first checksum for an empty second part" do
+        a = checksum(function, "abc")
+        expect(described_class.public_send(:"#{function}_combine", a, checksum(function, ""), 0)).to eq(a)
+      end
+
+      it "raises for a negative length" do
+        expect { described_class.public_send(:"#{function}_combine", 0, 0, -1) }.to raise_error(RangeError)
+      end
+    end
+  end
+
+  { crc32: "N", crc32c: "N", crc64nvme: "Q>" }.each do |function, pack|
+    describe ".#{function}_base64" do
+      it "encodes the big-endian checksum as base64" do
+        ["", "abc", "Hello world"].each do |str|
+          expected = Base64.strict_encode64([described_class.public_send(function, str)].pack(pack))
+          expect(described_class.public_send(:"#{function}_base64", str)).to eq(expected)
+        end
+      end
+
+      it "raises TypeError for a non-String" do
+        expect { described_class.public_send(:"#{function}_base64", nil) }.to raise_error(TypeError)
+      end
+    end
+  end
+
+  %i[crc32 crc32c crc64nvme].each do |function|
+    describe ".#{function}_file" do
+      def with_file(content)
+        file = Tempfile.new("checksum", binmode: true)
+        file.write(content)
+        file.close
+        yield file.path
+      ensure
+        file&.unlink
+      end
+
+      it "matches #{function} of the file's contents" do
+        content = (0..255).to_a.pack("C*") * 5000 # spans several 1 MiB reads
+        with_file(content) do |path|
+          expect(described_class.public_send(:"#{function}_file", path))
+            .to eq(described_class.public_send(function, content))
+        end
+      end
+
+      it "returns 0 for an empty file" do
+        with_file("") { |path| expect(described_class.public_send(:"#{function}_file", path)).to eq(0) }
+      end
+
+      it "accepts a Pathname" do
+        with_file("abc") do |path|
+          expect(described_class.public_send(:"#{function}_file", Pathname.new(path)))
+            .to eq(described_class.public_send(function, "abc"))
+        end
+      end
+
+      it "raises Errno::ENOENT for a missing file" do
+        expect { described_class.public_send(:"#{function}_file", "/nonexistent/aws-crt-checksum") }
+          .to raise_error(Errno::ENOENT, %r{/nonexistent/aws-crt-checksum})
+      end
+    end
+  end
+
+  it "matches the S3 header format for known values" do
+    expect(described_class.crc32_base64("Hello world")).to eq("i9aeUg==")
+    expect(described_class.crc64nvme_base64("").bytesize).to eq(12)
+  end
+
+  [
+    [AwsCrt::Checksums::Crc32, :crc32, 4],
+    [AwsCrt::Checksums::Crc32c, :crc32c, 4],
+    [AwsCrt::Checksums::Crc64Nvme, :crc64nvme, 8]
+  ].each do |klass, function, size|
+    describe klass do
+      it "starts at 0" do
+        expect(klass.new.value).to eq(0)
+      end
+
+      it "matches the one-shot #{function} after several updates" do
+        crc = klass.new
+        expect(crc.update("Hello ")).to equal(crc)
+        crc.update("world")
+        expect(crc.value).to eq(AwsCrt::Checksums.public_send(function, "Hello world"))
+      end
+
+      it "accepts binary strings" do
+        data = (0..255).to_a.pack("C*")
+        expect(klass.new.update(data).value).to eq(AwsCrt::Checksums.public_send(function, data))
+      end
+
+      it "returns the value as #{size} big-endian bytes from #digest" do
+        crc = klass.new.update("abc")
+        digest = crc.digest
+        expect(digest.bytesize).to eq(size)
+        expect(digest.encoding).to eq(Encoding::BINARY)
+        expect(digest.unpack1("H*").to_i(16)).to eq(crc.value)
+      end
+
+      it "can keep updating after #digest" do
+        crc = klass.new.update("Hello ")
+        crc.digest
+        crc.update("world")
+        expect(crc.value).to eq(AwsCrt::Checksums.public_send(function, "Hello world"))
+      end
+
+      it "raises TypeError for a non-String" do
+        expect { klass.new.update(1) }.to raise_error(TypeError)
+      end
+    end
+  end
+
+  describe ".sha1" do
+    [
+      { str: "", expected: "da39a3ee5e6b4b0d3255bfef95601890afd80709" },
+      { str: "abc", expected: "a9993e364706816aba3e25717850c26c9cd0d89d" },
+      { str: "Hello world", expected: "7b502c3a1f48c8609ae212cdfb639dee39673f5e" }
+    ].each do |test_case|
+      it "produces the correct digest for '#{test_case[:str]}'" do
+        expect(described_class.sha1(test_case[:str]).unpack1("H*")).to eq(test_case[:expected])
+      end
+    end
+
+    it "returns a 20-byte binary string" do
+      digest = described_class.sha1("abc")
+      expect(digest.bytesize).to eq(20)
+      expect(digest.encoding).to eq(Encoding::BINARY)
+    end
+
+    it "accumulates calls into a context" do
+      ctx = AwsCrt::Checksums::Sha1.new
+      expect(described_class.sha1("Hello ", ctx)).to equal(ctx)
+      described_class.sha1("world", ctx)
+      expect(ctx.digest).to eq(described_class.sha1("Hello world"))
+    end
+
+    it "raises TypeError for a non-String argument" do
+      expect { described_class.sha1(nil) }.to raise_error(TypeError)
+      expect { described_class.sha1(123) }.to raise_error(TypeError)
+    end
+
+    it "raises TypeError for a SHA-256 context" do
+      expect { described_class.sha1("abc", AwsCrt::Checksums::Sha256.new) }.to raise_error(TypeError, /Sha1 context/)
+    end
+  end
+
+  describe ".sha256" do
+    [
+      { str: "", expected: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855" },
+      { str: "abc", expected: "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad" },
+      { str: "Hello world", expected: "64ec88ca00b268e5ba1a35678a1b5316d212f4f366b2477232534a8aeca37f3c" }
+    ].each do |test_case|
+      it "produces the correct digest for '#{test_case[:str]}'" do
+        expect(described_class.sha256(test_case[:str]).unpack1("H*")).to eq(test_case[:expected])
+      end
+    end
+
+    it "returns a 32-byte binary string" do
+      digest = described_class.sha256("abc")
+      expect(digest.bytesize).to eq(32)
+      expect(digest.encoding).to eq(Encoding::BINARY)
+    end
+
+    it "treats a nil previous like no previous" do
+      expect(described_class.sha256("abc", nil)).to eq(described_class.sha256("abc"))
+    end
+
+    it "accumulates calls into a context" do
+      ctx = AwsCrt::Checksums::Sha256.new
+      expect(described_class.sha256("Hello ", ctx)).to equal(ctx)
+      described_class.sha256("world", ctx)
+      expect(ctx.digest).to eq(described_class.sha256("Hello world"))
+    end
+
+    it "works with zeros iterated" do
+      ctx = AwsCrt::Checksums::Sha256.new
+      32.times { described_class.sha256(ZERO_CHAR, ctx) }
+      expect(ctx.digest).to eq(described_class.sha256(ZERO_CHAR * 32))
+    end
+
+    it "returns the empty digest for an unused context" do
+      expect(AwsCrt::Checksums::Sha256.new.digest).to eq(described_class.sha256(""))
+    end
+
+    it "returns the same digest when #digest is called again" do
+      ctx = AwsCrt::Checksums::Sha256.new.update("abc")
+      expect(ctx.digest).to eq(ctx.digest)
+    end
+
+    it "raises when updating a finished context" do
+      ctx = AwsCrt::Checksums::Sha256.new
+      ctx.digest
+      expect { described_class.sha256("abc", ctx) }.to raise_error(RuntimeError, /already finished/)
+    end
+
+    it "raises TypeError for a non-String argument" do
+      expect { described_class.sha256(123) }.to raise_error(TypeError)
+    end
+
+    it "raises TypeError for a previous value that is not a context" do
+      expect { described_class.sha256("abc", 0) }.to raise_error(TypeError, /Sha256 context/)
+    end
+  end
+
+  describe ".compute" do
+    it "dispatches on a Symbol" do
+      expect(described_class.compute(:crc32, "abc")).to eq(described_class.crc32("abc"))
+      expect(described_class.compute(:crc32c, "abc")).to eq(described_class.crc32c("abc"))
+      expect(described_class.compute(:crc64nvme, "abc")).to eq(described_class.crc64nvme("abc"))
+      expect(described_class.compute(:sha1, "abc")).to eq(described_class.sha1("abc"))
+      expect(described_class.compute(:sha256, "abc")).to eq(described_class.sha256("abc"))
+    end
+
+    it "accepts a String in any case" do
+      expect(described_class.compute("CRC32C", "abc")).to eq(described_class.crc32c("abc"))
+      expect(described_class.compute("Sha256", "abc")).to eq(described_class.sha256("abc"))
+    end
+
+    it "continues from a previous CRC" do
+      part1 = described_class.compute(:crc32, "Hello ")
+      expect(described_class.compute(:crc32, "world", part1)).to eq(described_class.crc32("Hello world"))
+    end
+
+    it "adds to a SHA context" do
+      ctx = AwsCrt::Checksums::Sha256.new
+      described_class.compute(:sha256, "Hello ", ctx)
+      described_class.compute(:sha256, "world", ctx)
+      expect(ctx.digest).to eq(described_class.sha256("Hello world"))
+    end
+
+    it "raises ArgumentError listing the valid names for an unknown algorithm" do
+      expect { described_class.compute(:md5, "abc") }
+        .to raise_error(ArgumentError, /unknown checksum algorithm "md5".*crc32, crc32c, crc64nvme, sha1, sha256/)
+    end
+
+    it "raises TypeError for an algorithm that is not a Symbol or String" do
+      expect { described_class.compute(1, "abc") }.to raise_error(TypeError)
+    end
+  end
+
+  describe ".hardware_accelerated?" do
+    it "returns a boolean for each CRC algorithm" do
+      %i[crc32 crc32c crc64nvme].each do |algorithm|
+        expect(described_class.hardware_accelerated?(algorithm)).to be(true).or be(false)
+      end
+    end
+
+    it "accepts a String in any case" do
+      expect(described_class.hardware_accelerated?("CRC32C")).to eq(described_class.hardware_accelerated?(:crc32c))
+    end
+
+    it "returns false for the SHA algorithms" do
+      expect(described_class.hardware_accelerated?(:sha1)).to be(false)
+      expect(described_class.hardware_accelerated?(:sha256)).to be(false)
+    end
+
+    it "raises ArgumentError for an unknown algorithm" do
+      expect { described_class.hardware_accelerated?(:md5) }
+        .to raise_error(ArgumentError, /unknown checksum algorithm "md5"/)
+    end
+  end
 end
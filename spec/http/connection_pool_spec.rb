@@ -9,6 +9,8 @@
 #   10.2 — error hierarchy
 
 require "socket"
+require "time"
+require "zlib"
 
 RSpec.describe AwsCrt::Http::ConnectionPool do
   # A minimal HTTP/1.1 server using raw TCP sockets.
@@ -55,6 +57,24 @@ RSpec.describe AwsCrt::Http::ConnectionPool do
     server&.close
   end
 
+  # Serves one canned raw response, ignoring the request.
+  def with_raw_response_server(response)
+    server = TCPServer.new("127.0.0.1", 0)
+    thread = Thread.new do
+      client = server.accept
+      while (line = client.gets) && line.strip != ""; end
+      client.write(response)
+      client.close
+    rescue IOError, Errno::EPIPE
+      nil
+    end
+
+    yield server.addr[1]
+  ensure
+    thread&.kill
+    server&.close
+  end
+
   describe "#initialize" do
     it "creates a pool for an HTTP endpoint" do
       with_echo_server do |port|
@@ -63,72 +83,1051 @@ RSpec.describe AwsCrt::Http::ConnectionPool do
       end
     end
 
-    it "raises ArgumentError for invalid endpoints" do
-      expect { described_class.new("not-a-url") }
-        .to raise_error(ArgumentError, /Invalid endpoint/)
+    it "raises ArgumentError for invalid endpoints" do
+      expect { described_class.new("not-a-url") }
+        .to raise_error(ArgumentError, /Invalid endpoint/)
+    end
+
+    it "raises ArgumentError for unsupported schemes" do
+      expect { described_class.new("ftp://example.com") }
+        .to raise_error(ArgumentError, /Unsupported scheme/)
+    end
+
+    it "raises ArgumentError for empty host" do
+      expect { described_class.new("http://") }
+        .to raise_error(ArgumentError, /Empty host/)
+    end
+  end
+
+  describe "socket_domain" do
+    it "connects over IPv4 when forced" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}", socket_domain: :ipv4)
+        status, = pool.request("GET", "/", [["Host", "127.0.0.1"]])
+        expect(status).to eq(200)
+      end
+    end
+
+    it "accepts :ipv6 and nil" do
+      [:ipv6, nil].each do |value|
+        expect(described_class.new("http://example.com", socket_domain: value)).to be_a(described_class)
+      end
+    end
+
+    it "raises ArgumentError for other values" do
+      [:local, "ipv4", 4].each do |value|
+        expect { described_class.new("http://example.com", socket_domain: value) }
+          .to raise_error(ArgumentError, /socket_domain must be :ipv4 or :ipv6/)
+      end
+    end
+  end
+
+  describe "tcp_keepalive" do
+    it "makes requests over keep-alive sockets" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}",
+                                   tcp_keepalive: { idle_sec: 30, interval_sec: 10, max_failed_probes: 3 })
+        status, = pool.request("GET", "/", [["Host", "127.0.0.1"]])
+        expect(status).to eq(200)
+      end
+    end
+
+    it "accepts true, false and nil" do
+      [true, false, nil].each do |value|
+        expect(described_class.new("http://example.com", tcp_keepalive: value)).to be_a(described_class)
+      end
+    end
+
+    it "requires :idle_sec and :interval_sec together" do
+      expect { described_class.new("http://example.com", tcp_keepalive: { idle_sec: 30 }) }
+        .to raise_error(ArgumentError, /both :idle_sec and :interval_sec/)
+    end
+
+    it "raises TypeError for other values" do
+      expect { described_class.new("http://example.com", tcp_keepalive: 30) }
+        .to raise_error(TypeError, /tcp_keepalive must be true or a Hash/)
+    end
+
+    it "rejects timings that do not fit the socket option" do
+      expect { described_class.new("http://example.com", tcp_keepalive: { idle_sec: 70_000, interval_sec: 10 }) }
+        .to raise_error(RangeError)
+    end
+  end
+
+  describe "tls_cipher_preference" do
+    it "accepts :system_default on every TLS backend" do
+      pool = described_class.new("https://example.com", tls_cipher_preference: :system_default)
+      expect(pool).to be_a(described_class)
+    end
+
+    it "accepts the post-quantum policies with s2n-tls", if: AwsCrt.tls_backend == :s2n do
+      %i[pq_tls_v1 pq_tls_v1_2 pq_default].each do |pref|
+        expect(described_class.new("https://example.com", tls_cipher_preference: pref)).to be_a(described_class)
+      end
+    end
+
+    it "rejects the post-quantum policies on other backends", unless: AwsCrt.tls_backend == :s2n do
+      expect { described_class.new("https://example.com", tls_cipher_preference: :pq_tls_v1) }
+        .to raise_error(ArgumentError, /:pq_tls_v1 is not supported by the #{AwsCrt.tls_backend} TLS backend/)
+    end
+
+    it "rejects unknown policies" do
+      expect { described_class.new("https://example.com", tls_cipher_preference: :fips) }
+        .to raise_error(ArgumentError, /:tls_cipher_preference must be/)
+    end
+  end
+
+  describe "ssl_verify_peer" do
+    it "accepts true, false, and nil" do
+      [true, false, nil].each_with_index do |value, i|
+        pool = described_class.new("https://verify-#{i}.example.com", ssl_verify_peer: value)
+        expect(pool).to be_a(described_class)
+      end
+    end
+
+    it "raises TypeError for non-boolean values" do
+      [0, "false", :false].each do |value|
+        expect { described_class.new("https://example.com", ssl_verify_peer: value) }
+          .to raise_error(TypeError, /ssl_verify_peer must be true or false/)
+      end
+    end
+
+    it "logs a warning through the given logger when verification is disabled" do
+      logger = double("logger")
+      expect(logger).to receive(:warn).with(/TLS peer verification is disabled for insecure-a\.example\.com:443/).once
+
+      described_class.new("https://insecure-a.example.com", ssl_verify_peer: false, logger: logger)
+    end
+
+    it "warns only once per endpoint" do
+      logger = double("logger")
+      expect(logger).to receive(:warn).once
+
+      2.times do
+        described_class.new("https://insecure-b.example.com", ssl_verify_peer: false, logger: logger)
+      end
+    end
+
+    it "does not warn for plain HTTP endpoints" do
+      logger = double("logger")
+      expect(logger).not_to receive(:warn)
+
+      described_class.new("http://127.0.0.1:9", ssl_verify_peer: false, logger: logger)
+    end
+
+    it "does not warn when verification is enabled" do
+      logger = double("logger")
+      expect(logger).not_to receive(:warn)
+
+      described_class.new("https://secure.example.com", ssl_verify_peer: true, logger: logger)
+    end
+  end
+
+  describe "#request" do
+    it "sends a GET request and returns status, headers, and body" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        headers = [["Host", "127.0.0.1:#{port}"], ["Accept", "*/*"]]
+
+        status, resp_headers, body = pool.request("GET", "/hello", headers)
+
+        expect(status).to eq(200)
+        expect(body).to include("GET /hello")
+
+        # resp_headers is an array of [name, value] pairs
+        header_hash = resp_headers.to_h { |name, value| [name.downcase, value] }
+        expect(header_hash["x-custom"]).to eq("test-value")
+      end
+    end
+
+    it "sends a POST request with a body" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        headers = [
+          ["Host", "127.0.0.1:#{port}"],
+          ["Content-Length", "11"]
+        ]
+
+        status, _resp_headers, body = pool.request(
+          "POST", "/submit", headers, "hello world"
+        )
+
+        expect(status).to eq(200)
+        expect(body).to include("POST /submit")
+        expect(body).to include("hello world")
+      end
+    end
+
+    it "streams the response body when a block is given" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        headers = [["Host", "127.0.0.1:#{port}"]]
+
+        chunks = []
+        status, resp_headers = pool.request("GET", "/stream", headers) do |chunk|
+          chunks << chunk
+        end
+
+        expect(status).to eq(200)
+        expect(chunks.join).to include("GET /stream")
+        expect(resp_headers).to be_an(Array)
+      end
+    end
+  end
+
+  describe "response object" do
+    it "returns an AwsCrt::Http::Response" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/hello", [["Host", "127.0.0.1:#{port}"]])
+
+        expect(response).to be_a(AwsCrt::Http::Response)
+        expect(response.status).to eq(200)
+        expect(response).to be_success
+        expect(response.body).to eq("GET /hello ")
+        expect(response.header_pairs).to include(["X-Custom", "test-value"])
+      end
+    end
+
+    it "looks up headers case-insensitively" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1:#{port}"]])
+
+        expect(response["x-custom"]).to eq("test-value")
+        expect(response["X-CUSTOM"]).to eq("test-value")
+        expect(response.key?("x-Custom")).to be(true)
+        expect(response["x-missing"]).to be_nil
+        expect(response.headers["X-Custom"]).to eq("test-value")
+        expect(response.headers).to include("x-custom" => "test-value")
+      end
+    end
+
+    it "joins repeated headers and keeps each pair" do
+      raw = "HTTP/1.1 404 Not Found\r\nX-Tag: a\r\nx-tag: b\r\nContent-Length: 0\r\n\r\n"
+      with_raw_response_server(raw) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1"]])
+
+        expect(response).not_to be_success
+        expect(response["X-Tag"]).to eq("a, b")
+        expect(response.headers["x-tag"]).to eq("a, b")
+        expect(response.header_pairs).to include(%w[X-Tag a], %w[x-tag b])
+      end
+    end
+
+    it "returns each Set-Cookie header separately from #cookies" do
+      raw = "HTTP/1.1 200 OK\r\n" \
+            "Set-Cookie: a=1; Expires=Wed, 21 Oct 2026 07:28:00 GMT\r\n" \
+            "X-Other: x\r\n" \
+            "set-cookie: b=2; Path=/\r\n" \
+            "Content-Length: 0\r\n\r\n"
+      with_raw_response_server(raw) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1"]])
+
+        expect(response.cookies).to eq(["a=1; Expires=Wed, 21 Oct 2026 07:28:00 GMT", "b=2; Path=/"])
+        expect(response.header_pairs.count { |(name, _)| name.casecmp?("set-cookie") }).to eq(2)
+      end
+    end
+
+    it "returns no cookies when there are no Set-Cookie headers" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        expect(pool.request("GET", "/", [["Host", "127.0.0.1:#{port}"]]).cookies).to eq([])
+      end
+    end
+
+    it "has a nil body when the body was streamed" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1:#{port}"]]) { |_chunk| }
+
+        expect(response.status).to eq(200)
+        expect(response.body).to be_nil
+        expect(response.to_ary.size).to eq(2)
+      end
+    end
+
+    it "destructures like the status/headers/body array" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/a", [["Host", "127.0.0.1:#{port}"]])
+
+        status, headers, body = response
+        expect([status, headers, body]).to eq([response.status, response.header_pairs, response.body])
+      end
+    end
+
+    it "reports request timing" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        timing = pool.request("GET", "/", [["Host", "127.0.0.1:#{port}"]]).timing
+
+        expect(timing.keys).to eq(%i[connect_ms ttfb_ms first_body_byte_ms total_ms send_ms receive_ms])
+        expect(timing[:connect_ms]).to be >= 0
+        expect(timing[:ttfb_ms]).to be >= timing[:connect_ms]
+        expect(timing[:first_body_byte_ms]).to be >= timing[:ttfb_ms]
+        expect(timing[:total_ms]).to be >= timing[:first_body_byte_ms]
+      end
+    end
+
+    it "reports the clock skew from the Date header" do
+      date = (Time.now + 3600).httpdate
+      with_raw_response_server("HTTP/1.1 200 OK\r\nDate: #{date}\r\nContent-Length: 0\r\n\r\n") do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        skew = pool.request("GET", "/", [["Host", "127.0.0.1"]]).clock_skew_ms
+
+        expect(skew).to be_within(2_000).of(3_600_000)
+      end
+    end
+
+    it "has no clock skew without a Date header" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        expect(pool.request("GET", "/", [["Host", "127.0.0.1:#{port}"]]).clock_skew_ms).to be_nil
+      end
+    end
+
+    it "reports request timing for streamed responses" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1:#{port}"]]) { |_chunk| }
+
+        expect(response.timing[:ttfb_ms]).to be_a(Float)
+        expect(response.timing[:total_ms]).to be >= response.timing[:ttfb_ms]
+      end
+    end
+  end
+
+  describe "#request with decode: :cbor" do
+    def cbor_response(status, body, content_type: "application/cbor")
+      "HTTP/1.1 #{status}\r\nContent-Type: #{content_type}\r\n" \
+        "Content-Length: #{body.bytesize}\r\n\r\n".b + body
+    end
+
+    it "decodes the body into #data" do
+      payload = { "Items" => [1, "two", 3.5], "Count" => 3 }
+      with_raw_response_server(cbor_response("200 OK", AwsCrt::Cbor.encode(payload))) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("POST", "/", [["Host", "127.0.0.1"]], nil, decode: :cbor)
+
+        expect(response.status).to eq(200)
+        expect(response.data).to eq(payload)
+        expect(response.body).to be_nil
+        expect(response["content-type"]).to eq("application/cbor")
+      end
+    end
+
+    it "destructures to status, headers and the decoded data" do
+      with_raw_response_server(cbor_response("200 OK", AwsCrt::Cbor.encode([1, 2]))) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        status, _headers, data = pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, decode: :cbor)
+
+        expect(status).to eq(200)
+        expect(data).to eq([1, 2])
+      end
+    end
+
+    it "decodes an empty body to nil" do
+      with_raw_response_server(cbor_response("200 OK", "")) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, decode: :cbor)
+
+        expect(response.data).to be_nil
+      end
+    end
+
+    it "raises AwsCrt::Cbor::Error naming the status and content type for non-CBOR bodies" do
+      html = "<html>Bad Gateway</html>"
+      with_raw_response_server(cbor_response("502 Bad Gateway", html, content_type: "text/html")) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+
+        expect { pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, decode: :cbor) }
+          .to raise_error(AwsCrt::Cbor::Error, %r{HTTP 502 response body \(Content-Type: text/html\) is not valid CBOR})
+      end
+    end
+
+    it "rejects unsupported formats and streaming blocks" do
+      pool = described_class.new("http://127.0.0.1:9")
+
+      expect { pool.request("GET", "/", [], nil, decode: :json) }
+        .to raise_error(ArgumentError, /invalid decode :json/)
+      expect { pool.request("GET", "/", [], nil, decode: :cbor) { |_chunk| } }
+        .to raise_error(ArgumentError, /cannot be combined with a streaming block/)
+    end
+  end
+
+  describe "#request with response_targets" do
+    let(:payload) { "x" * 100_000 }
+    let(:raw_response) do
+      "HTTP/1.1 200 OK\r\nContent-Length: #{payload.bytesize}\r\n\r\n#{payload}"
+    end
+
+    it "delivers every chunk to each sink" do
+      require "digest"
+      require "stringio"
+      with_raw_response_server(raw_response) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        io = StringIO.new
+        digest = Digest::SHA256.new
+
+        response = pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, response_targets: [io, digest])
+
+        expect(response.status).to eq(200)
+        expect(response.body).to be_nil
+        expect(io.string).to eq(payload)
+        expect(digest.hexdigest).to eq(Digest::SHA256.hexdigest(payload))
+      end
+    end
+
+    it "calls update on sinks that do not respond to <<" do
+      sink = Class.new do
+        attr_reader :data
+
+        def initialize
+          @data = +""
+        end
+
+        def update(chunk)
+          @data << chunk
+        end
+      end.new
+
+      with_raw_response_server(raw_response) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, response_targets: [sink])
+
+        expect(sink.data).to eq(payload)
+      end
+    end
+
+    it "also yields chunks to a block after the sinks" do
+      with_raw_response_server(raw_response) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        sink = []
+        chunks = []
+        pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, response_targets: [sink]) { |c| chunks << c }
+
+        expect(sink.join).to eq(payload)
+        expect(chunks.join).to eq(payload)
+      end
+    end
+
+    it "re-raises an exception raised by a sink" do
+      sink = Object.new
+      def sink.<<(_chunk)
+        raise IOError, "disk full"
+      end
+
+      with_raw_response_server(raw_response) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+
+        expect { pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, response_targets: [sink]) }
+          .to raise_error(IOError, "disk full")
+      end
+    end
+
+    it "rejects invalid sinks and decode: :cbor" do
+      pool = described_class.new("http://127.0.0.1:9")
+
+      expect { pool.request("GET", "/", [], nil, response_targets: "io") }
+        .to raise_error(ArgumentError, /must be an Array/)
+      expect { pool.request("GET", "/", [], nil, response_targets: [Object.new]) }
+        .to raise_error(ArgumentError, /must respond to << or update/)
+      expect { pool.request("GET", "/", [], nil, response_targets: [[]], decode: :cbor) }
+        .to raise_error(ArgumentError, /cannot be combined with :response_targets/)
+    end
+  end
+
+  describe "read backpressure" do
+    # Serves a single large body, written in one go so that only the
+    # client's read window limits how fast it is delivered.
+    def with_large_body_server(body)
+      server = TCPServer.new("127.0.0.1", 0)
+      thread = Thread.new do
+        client = server.accept
+        while (line = client.gets) && line.strip != ""; end
+        client.write("HTTP/1.1 200 OK\r\nContent-Length: #{body.bytesize}\r\nConnection: close\r\n\r\n")
+        client.write(body)
+        client.close
+      rescue IOError, Errno::EPIPE
+        nil
+      end
+
+      yield server.addr[1]
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    let(:body) { "x" * (1024 * 1024) }
+
+    it "delivers a streamed body larger than the initial window" do
+      with_large_body_server(body) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}",
+                                   enable_read_backpressure: true, initial_read_window: 16 * 1024)
+        received = +""
+        status, = pool.request("GET", "/", [["Host", "127.0.0.1"]]) do |chunk|
+          received << chunk
+        end
+
+        expect(status).to eq(200)
+        expect(received.bytesize).to eq(body.bytesize)
+      end
+    end
+
+    it "delivers a buffered body larger than the initial window" do
+      with_large_body_server(body) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}",
+                                   enable_read_backpressure: true, initial_read_window: 16 * 1024)
+        status, _headers, received = pool.request("GET", "/", [["Host", "127.0.0.1"]])
+
+        expect(status).to eq(200)
+        expect(received.bytesize).to eq(body.bytesize)
+      end
+    end
+
+    it "raises ArgumentError for a zero initial window" do
+      expect do
+        described_class.new("http://127.0.0.1:9", enable_read_backpressure: true, initial_read_window: 0)
+      end.to raise_error(ArgumentError, /initial_read_window/)
+    end
+  end
+
+  describe "max_prealloc_bytes" do
+    # Advertises a huge Content-Length, sends a few bytes, then hangs up.
+    def with_lying_content_length_server
+      server = TCPServer.new("127.0.0.1", 0)
+      thread = Thread.new do
+        client = server.accept
+        while (line = client.gets) && line.strip != ""; end
+        client.write("HTTP/1.1 200 OK\r\nContent-Length: 4000000000000\r\n\r\nshort")
+        client.close
+      rescue IOError, Errno::EPIPE
+        nil
+      end
+
+      yield server.addr[1]
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    it "does not reserve the advertised Content-Length up front" do
+      with_lying_content_length_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_prealloc_bytes: 1024)
+
+        expect do
+          pool.request("GET", "/", [["Host", "127.0.0.1"]])
+        end.to raise_error(AwsCrt::Http::Error)
+      end
+    end
+
+    it "skips the reservation with trust_content_length: false" do
+      with_lying_content_length_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}", trust_content_length: false)
+
+        expect do
+          pool.request("GET", "/", [["Host", "127.0.0.1"]])
+        end.to raise_error(AwsCrt::Http::Error)
+      end
+    end
+
+    it "still buffers bodies larger than the cap" do
+      body = "x" * 10_000
+      server = TCPServer.new("127.0.0.1", 0)
+      thread = Thread.new do
+        client = server.accept
+        while (line = client.gets) && line.strip != ""; end
+        client.write("HTTP/1.1 200 OK\r\nContent-Length: #{body.bytesize}\r\nConnection: close\r\n\r\n#{body}")
+        client.close
+      end
+
+      pool = described_class.new("http://127.0.0.1:#{server.addr[1]}", max_prealloc_bytes: 16)
+      status, _headers, received = pool.request("GET", "/", [["Host", "127.0.0.1"]])
+
+      expect(status).to eq(200)
+      expect(received).to eq(body)
+    ensure
+      thread&.kill
+      server&.close
+    end
+  end
+
+  describe "inactivity_timeout_ms" do
+    # Sends the headers and part of the body, then goes quiet.
+    def with_stalling_server
+      server = TCPServer.new("127.0.0.1", 0)
+      thread = Thread.new do
+        client = server.accept
+        while (line = client.gets) && line.strip != ""; end
+        client.write("HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\nfirst")
+        sleep 10
+        client.close
+      rescue IOError, Errno::EPIPE
+        nil
+      end
+
+      yield server.addr[1]
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    it "raises TimeoutError when a streamed body stalls" do
+      with_stalling_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}", inactivity_timeout_ms: 200)
+        received = +""
+        started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+
+        expect do
+          pool.request("GET", "/", [["Host", "127.0.0.1"]]) { |chunk| received << chunk }
+        end.to raise_error(AwsCrt::Http::TimeoutError)
+        expect(received).to eq("first")
+        expect(Process.clock_gettime(Process::CLOCK_MONOTONIC) - started).to be < 5
+      end
+    end
+  end
+
+  describe "#request with connection_close" do
+    # A keep-alive server that serves any number of requests per connection,
+    # recording each connection's Connection request headers.
+    def with_keep_alive_server
+      server = TCPServer.new("127.0.0.1", 0)
+      connections = Queue.new
+
+      thread = Thread.new do
+        loop do
+          client = server.accept
+          seen = []
+          connections << seen
+          Thread.new do
+            while client.gets
+              while (line = client.gets) && line.strip != ""
+                key, value = line.split(":", 2)
+                seen << value.strip if key.strip.casecmp?("connection")
+              end
+              client.write("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+            end
+          rescue IOError, Errno::ECONNRESET
+            nil
+          ensure
+            client.close
+          end
+        end
+      rescue IOError
+        nil
+      end
+
+      yield server.addr[1], connections
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    it "reuses the connection by default" do
+      with_keep_alive_server do |port, connections|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        2.times { pool.request("GET", "/", [["Host", "127.0.0.1"]]) }
+
+        expect(connections.size).to eq(1)
+      end
+    end
+
+    it "sends Connection: close and does not reuse the connection" do
+      with_keep_alive_server do |port, connections|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        2.times do
+          status, _headers, body = pool.request("GET", "/", [["Host", "127.0.0.1"]], nil,
+                                                connection_close: true)
+          expect([status, body]).to eq([200, "ok"])
+        end
+
+        expect(connections.size).to eq(2)
+        expect(connections.pop).to eq(["close"])
+      end
+    end
+
+    it "replaces a caller-supplied Connection header" do
+      with_keep_alive_server do |port, connections|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        pool.request("GET", "/", [["Host", "127.0.0.1"], ["Connection", "keep-alive"]], nil,
+                     connection_close: true)
+
+        expect(connections.pop).to eq(["close"])
+      end
+    end
+  end
+
+  describe "#close" do
+    # Answers each request only once `release` is pushed, so requests can be
+    # held on their connection.
+    def with_held_response_server
+      server = TCPServer.new("127.0.0.1", 0)
+      release = Queue.new
+
+      thread = Thread.new do
+        loop do
+          client = server.accept
+          Thread.new do
+            while (line = client.gets) && line.strip != ""; end
+            release.pop
+            client.write("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+          rescue IOError, Errno::EPIPE, Errno::ECONNRESET
+            nil
+          ensure
+            client.close
+          end
+        end
+      rescue IOError
+        nil
+      end
+
+      yield server.addr[1], release
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    it "fails requests waiting for a connection and lets in-flight ones finish" do
+      with_held_response_server do |port, release|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_connections: 1)
+        headers = [["Host", "127.0.0.1"]]
+
+        in_flight = Thread.new { pool.request("GET", "/held", headers) }
+        sleep 0.2 # let it take the only connection
+        waiting = Thread.new { pool.request("GET", "/queued", headers) }
+        sleep 0.2
+
+        pool.close
+
+        expect { waiting.join(5) }.to raise_error(AwsCrt::Http::ConnectionError, /SHUTTING_DOWN/)
+        release << true
+        expect(in_flight.value.status).to eq(200)
+      end
+    end
+
+    it "makes later requests raise ConnectionError" do
+      pool = described_class.new("http://127.0.0.1:9")
+      pool.close
+
+      expect { pool.request("GET", "/", [["Host", "127.0.0.1"]]) }
+        .to raise_error(AwsCrt::Http::ConnectionError)
+      expect { pool.request("GET", "/", [["Host", "127.0.0.1"]]) { |_chunk| nil } }
+        .to raise_error(AwsCrt::Http::ConnectionError)
     end
 
-    it "raises ArgumentError for unsupported schemes" do
-      expect { described_class.new("ftp://example.com") }
-        .to raise_error(ArgumentError, /Unsupported scheme/)
+    it "is idempotent and reported by #closed?" do
+      pool = described_class.new("http://127.0.0.1:9")
+      expect(pool.closed?).to be false
+
+      2.times { pool.close }
+      expect(pool.closed?).to be true
     end
+  end
 
-    it "raises ArgumentError for empty host" do
-      expect { described_class.new("http://") }
-        .to raise_error(ArgumentError, /Empty host/)
+  describe "fair queueing" do
+    # Holds each response until `release` is pushed and records the request
+    # paths in the order they reached the server.
+    def with_recording_server
+      server = TCPServer.new("127.0.0.1", 0)
+      release = Queue.new
+      paths = Queue.new
+
+      thread = Thread.new do
+        loop do
+          client = server.accept
+          Thread.new do
+            paths << client.gets.to_s.split[1]
+            while (line = client.gets) && line.strip != ""; end
+            release.pop
+            client.write("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+          rescue IOError, Errno::EPIPE, Errno::ECONNRESET
+            nil
+          ensure
+            client.close
+          end
+        end
+      rescue IOError
+        nil
+      end
+
+      yield server.addr[1], release, paths
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    let(:headers) { [["Host", "127.0.0.1"]] }
+
+    it "serves queued requests in arrival order" do
+      with_recording_server do |port, release, paths|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_connections: 1, fair_queue: true)
+
+        threads = %w[/first /second /third /fourth].map do |path|
+          thread = Thread.new { pool.request("GET", path, headers) }
+          sleep 0.1 # queue them in a known order
+          thread
+        end
+        4.times { release << true }
+
+        expect(threads.map { |t| t.value.status }).to all(eq(200))
+        expect(Array.new(4) { paths.pop }).to eq(%w[/first /second /third /fourth])
+      end
+    end
+
+    it "raises QueueFullError once max_queue requests are waiting" do
+      with_recording_server do |port, release, _paths|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_connections: 1, fair_queue: true,
+                                                               max_queue: 1)
+
+        in_flight = Thread.new { pool.request("GET", "/held", headers) }
+        sleep 0.2
+        queued = Thread.new { pool.request("GET", "/queued", headers) }
+        sleep 0.2
+
+        expect { pool.request("GET", "/rejected", headers) }.to raise_error(AwsCrt::Http::QueueFullError)
+        2.times { release << true }
+        expect([in_flight.value.status, queued.value.status]).to eq([200, 200])
+      end
+    end
+
+    it "fails waiting requests when the pool is closed" do
+      with_recording_server do |port, release, _paths|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_connections: 1, fair_queue: true)
+
+        in_flight = Thread.new { pool.request("GET", "/held", headers) }
+        sleep 0.2
+        waiting = Thread.new { pool.request("GET", "/queued", headers) }
+        sleep 0.2
+
+        pool.close
+        expect { waiting.join(5) }.to raise_error(AwsCrt::Http::ConnectionError, /closed/)
+        release << true
+        expect(in_flight.value.status).to eq(200)
+      end
+    end
+
+    it "requires fair_queue for max_queue" do
+      expect { described_class.new("http://127.0.0.1:9", max_queue: 10) }
+        .to raise_error(ArgumentError, /fair_queue/)
     end
   end
 
-  describe "#request" do
-    it "sends a GET request and returns status, headers, and body" do
-      with_echo_server do |port|
+  describe "retries" do
+    # Reads each request and hangs up without answering on the first
+    # `drops` connections, then answers normally. Yields the port and a
+    # Queue of the request lines received.
+    def with_flaky_server(drops)
+      server = TCPServer.new("127.0.0.1", 0)
+      requests = Queue.new
+
+      thread = Thread.new do
+        loop do
+          client = server.accept
+          requests << client.gets.to_s.strip
+          while (line = client.gets) && line.strip != ""; end
+          if drops.positive?
+            drops -= 1
+          else
+            client.write("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+          end
+        rescue IOError, Errno::EPIPE, Errno::ECONNRESET
+          nil
+        ensure
+          client&.close
+        end
+      end
+
+      yield server.addr[1], requests
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    let(:headers) { [["Host", "127.0.0.1"]] }
+
+    it "does not retry by default" do
+      with_flaky_server(1) do |port, _requests|
         pool = described_class.new("http://127.0.0.1:#{port}")
-        headers = [["Host", "127.0.0.1:#{port}"], ["Accept", "*/*"]]
+        expect { pool.request("GET", "/", headers) }.to raise_error(AwsCrt::Http::Error)
+      end
+    end
 
-        status, resp_headers, body = pool.request("GET", "/hello", headers)
+    it "retries an idempotent request and reports the attempt that succeeded" do
+      with_flaky_server(2) do |port, requests|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 2)
+        response = pool.request("GET", "/", headers)
 
-        expect(status).to eq(200)
-        expect(body).to include("GET /hello")
+        expect(response.body).to eq("ok")
+        expect(response.attempts).to eq(3)
+        expect(response.retries).to eq(2)
+        expect(requests.size).to eq(3)
+      end
+    end
 
-        # resp_headers is an array of [name, value] pairs
-        header_hash = resp_headers.to_h { |name, value| [name.downcase, value] }
-        expect(header_hash["x-custom"]).to eq("test-value")
+    it "reports a single attempt when the first one succeeds" do
+      with_flaky_server(0) do |port, _requests|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 2)
+        response = pool.request("PUT", "/", headers, "data")
+        expect(response.attempts).to eq(1)
+        expect(response.retries).to eq(0)
       end
     end
 
-    it "sends a POST request with a body" do
+    it "retries streaming requests that fail before any chunk" do
+      with_flaky_server(1) do |port, _requests|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 1)
+        chunks = []
+        response = pool.request("GET", "/", headers) { |chunk| chunks << chunk }
+        expect(chunks.join).to eq("ok")
+        expect(response.attempts).to eq(2)
+      end
+    end
+
+    it "never retries POST unless asked to" do
+      with_flaky_server(1) do |port, requests|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 2)
+        expect { pool.request("POST", "/", headers, "data") }.to raise_error(AwsCrt::Http::Error)
+        expect(requests.size).to eq(1)
+      end
+    end
+
+    it "retries POST with retry_non_idempotent: true" do
+      with_flaky_server(1) do |port, _requests|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 1)
+        response = pool.request("POST", "/", headers, "data", retry_non_idempotent: true)
+        expect(response.attempts).to eq(2)
+      end
+    end
+
+    it "gives up after max_retries" do
+      with_flaky_server(3) do |port, requests|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 2)
+        expect { pool.request("GET", "/", headers) }.to raise_error(AwsCrt::Http::Error)
+        expect(requests.size).to eq(3)
+      end
+    end
+
+    context "with a Retry-After response" do
+      # Answers the first `throttles` requests with 503 and the given
+      # Retry-After value, then 200. Yields the port and a Queue of the
+      # times the requests arrived.
+      def with_throttling_server(throttles, retry_after)
+        server = TCPServer.new("127.0.0.1", 0)
+        arrivals = Queue.new
+
+        thread = Thread.new do
+          loop do
+            client = server.accept
+            while (line = client.gets) && line.strip != ""; end
+            arrivals << Process.clock_gettime(Process::CLOCK_MONOTONIC)
+            if throttles.positive?
+              throttles -= 1
+              client.write("HTTP/1.1 503 Service Unavailable\r\nRetry-After: #{retry_after}\r\n" \
+                           "Content-Length: 4\r\nConnection: close\r\n\r\nbusy")
+            else
+              client.write("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+            end
+          rescue IOError, Errno::EPIPE, Errno::ECONNRESET
+            nil
+          ensure
+            client&.close
+          end
+        end
+
+        yield server.addr[1], arrivals
+      ensure
+        thread&.kill
+        server&.close
+      end
+
+      it "waits the delay-seconds before retrying" do
+        with_throttling_server(1, "1") do |port, arrivals|
+          pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 1)
+          response = pool.request("GET", "/", headers)
+
+          expect(response.status).to eq(200)
+          expect(response.attempts).to eq(2)
+          first = arrivals.pop
+          expect(arrivals.pop - first).to be >= 0.9
+        end
+      end
+
+      it "accepts an HTTP date" do
+        with_throttling_server(1, (Time.now - 60).httpdate) do |port, _arrivals|
+          pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 1)
+          expect(pool.request("GET", "/", headers).attempts).to eq(2)
+        end
+      end
+
+      it "caps the wait at max_retry_after_ms" do
+        with_throttling_server(1, "3600") do |port, _arrivals|
+          pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 1, max_retry_after_ms: 50)
+          started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+          expect(pool.request("GET", "/", headers).attempts).to eq(2)
+          expect(Process.clock_gettime(Process::CLOCK_MONOTONIC) - started).to be < 5
+        end
+      end
+
+      it "does not yield the throttled response's body to a streaming block" do
+        with_throttling_server(1, "0") do |port, _arrivals|
+          pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 1)
+          chunks = []
+          response = pool.request("GET", "/", headers) { |chunk| chunks << chunk }
+          expect(chunks.join).to eq("ok")
+          expect(response.attempts).to eq(2)
+        end
+      end
+
+      it "returns the throttled response when retries are off or used up" do
+        with_throttling_server(1, "0") do |port, _arrivals|
+          pool = described_class.new("http://127.0.0.1:#{port}")
+          expect(pool.request("GET", "/", headers).status).to eq(503)
+        end
+        with_throttling_server(2, "0") do |port, _arrivals|
+          pool = described_class.new("http://127.0.0.1:#{port}", max_retries: 1)
+          response = pool.request("GET", "/", headers)
+          expect(response.status).to eq(503)
+          expect(response.attempts).to eq(2)
+        end
+      end
+    end
+  end
+
+  describe "#request with trailer_checksum" do
+    it "sends the body aws-chunked encoded with a CRC32 trailer" do
       with_echo_server do |port|
         pool = described_class.new("http://127.0.0.1:#{port}")
-        headers = [
-          ["Host", "127.0.0.1:#{port}"],
-          ["Content-Length", "11"]
-        ]
+        headers = [["Host", "127.0.0.1:#{port}"], ["Content-Length", "5"]]
 
-        status, _resp_headers, body = pool.request(
-          "POST", "/submit", headers, "hello world"
-        )
+        _status, _headers, body = pool.request("PUT", "/obj", headers, "hello",
+                                               trailer_checksum: "CRC32")
 
-        expect(status).to eq(200)
-        expect(body).to include("POST /submit")
-        expect(body).to include("hello world")
+        crc = [[AwsCrt::Checksums.crc32("hello")].pack("N")].pack("m0")
+        expect(body).to eq("PUT /obj 5\r\nhello\r\n0\r\nx-amz-checksum-crc32:#{crc}\r\n\r\n")
       end
     end
 
-    it "streams the response body when a block is given" do
+    it "encodes an empty body as just the terminating chunk and trailer" do
       with_echo_server do |port|
         pool = described_class.new("http://127.0.0.1:#{port}")
         headers = [["Host", "127.0.0.1:#{port}"]]
 
-        chunks = []
-        status, resp_headers = pool.request("GET", "/stream", headers) do |chunk|
-          chunks << chunk
-        end
+        _status, _headers, body = pool.request("PUT", "/obj", headers, nil,
+                                               trailer_checksum: "crc32c")
 
-        expect(status).to eq(200)
-        expect(chunks.join).to include("GET /stream")
-        expect(resp_headers).to be_an(Array)
+        crc = [[AwsCrt::Checksums.crc32c("")].pack("N")].pack("m0")
+        expect(body).to eq("PUT /obj 0\r\nx-amz-checksum-crc32c:#{crc}\r\n\r\n")
       end
     end
+
+    it "raises ArgumentError for unsupported algorithms" do
+      pool = described_class.new("http://127.0.0.1:9")
+      expect { pool.request("PUT", "/", [], "x", trailer_checksum: "MD5") }
+        .to raise_error(ArgumentError, /invalid trailer_checksum 'MD5'/)
+    end
   end
 
   describe "default configuration" do
@@ -215,5 +1214,346 @@ RSpec.describe AwsCrt::Http::ConnectionPool do
       pool = described_class.new("HTTP://127.0.0.1:9999")
       expect(pool).to be_a(described_class)
     end
+
+    it "exposes the parsed endpoint" do
+      pool = described_class.new("HTTPS://example.com:8443/")
+      expect(pool.scheme).to eq("https")
+      expect(pool.host).to eq("example.com")
+      expect(pool.port).to eq(8443)
+      expect(pool.endpoint).to eq("https://example.com:8443")
+    end
+
+    it "fills in the scheme's default port" do
+      expect(described_class.new("http://127.0.0.1").port).to eq(80)
+      expect(described_class.new("https://example.com").endpoint).to eq("https://example.com:443")
+    end
+
+    it "keeps the endpoint after #close" do
+      pool = described_class.new("http://127.0.0.1:9999")
+      pool.close
+      expect(pool.endpoint).to eq("http://127.0.0.1:9999")
+    end
+  end
+
+  describe "#request with decompress: true" do
+    # Serves `body` with the given Content-Encoding and records the
+    # request's Accept-Encoding header.
+    def with_encoding_server(body, encoding)
+      server = TCPServer.new("127.0.0.1", 0)
+      accepted = Queue.new
+      thread = Thread.new do
+        client = server.accept
+        accept = nil
+        while (line = client.gets) && line.strip != ""
+          name, value = line.split(":", 2)
+          accept = value.strip if name.casecmp?("accept-encoding")
+        end
+        accepted << accept
+        head = "HTTP/1.1 200 OK\r\nContent-Length: #{body.bytesize}\r\n"
+        head += "Content-Encoding: #{encoding}\r\n" if encoding
+        client.write("#{head}\r\n".b + body)
+        client.close
+      end
+
+      yield server.addr[1], accepted
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    let(:text) { "hello " * 100 }
+
+    it "advertises gzip and deflate and decodes a gzip body" do
+      with_encoding_server(Zlib.gzip(text), "gzip") do |port, accepted|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, decompress: true)
+
+        expect(accepted.pop).to eq("gzip, deflate")
+        expect(response.body).to eq(text)
+        expect(response["content-encoding"]).to be_nil
+        expect(response["content-length"]).to eq(text.bytesize.to_s)
+      end
+    end
+
+    it "decodes a deflate body" do
+      with_encoding_server(Zlib::Deflate.deflate(text), "deflate") do |port, _accepted|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, decompress: true)
+
+        expect(response.body).to eq(text)
+      end
+    end
+
+    it "decodes a raw deflate body sent without the zlib header" do
+      deflater = Zlib::Deflate.new(Zlib::DEFAULT_COMPRESSION, -Zlib::MAX_WBITS)
+      raw = deflater.deflate(text, Zlib::FINISH)
+      deflater.close
+
+      with_encoding_server(raw, "deflate") do |port, _accepted|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, decompress: true)
+
+        expect(response.body).to eq(text)
+        expect(response["content-length"]).to eq(text.bytesize.to_s)
+      end
+    end
+
+    it "sends a caller-supplied Accept-Encoding as given" do
+      with_encoding_server(text, nil) do |port, accepted|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        headers = [["Host", "127.0.0.1"], ["accept-encoding", "identity"]]
+        response = pool.request("GET", "/", headers, nil, decompress: true)
+
+        expect(accepted.pop).to eq("identity")
+        expect(response.body).to eq(text)
+      end
+    end
+
+    it "leaves bodies in other codings untouched" do
+      with_encoding_server("compressed", "br") do |port, _accepted|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, decompress: true)
+
+        expect(response.body).to eq("compressed")
+        expect(response["content-encoding"]).to eq("br")
+      end
+    end
+
+    it "sends no Accept-Encoding and keeps the body encoded without the option" do
+      body = Zlib.gzip(text)
+      with_encoding_server(body, "gzip") do |port, accepted|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", [["Host", "127.0.0.1"]])
+
+        expect(accepted.pop).to be_nil
+        expect(response.body).to eq(body)
+      end
+    end
+
+    it "raises Http::Error for a corrupt body" do
+      with_encoding_server("not gzip", "gzip") do |port, _accepted|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        expect { pool.request("GET", "/", [["Host", "127.0.0.1"]], nil, decompress: true) }
+          .to raise_error(AwsCrt::Http::Error, /could not decompress gzip/)
+      end
+    end
+
+    it "cannot be combined with a streaming block" do
+      pool = described_class.new("http://127.0.0.1:9999")
+      expect { pool.request("GET", "/", [], nil, decompress: true) { |_chunk| } }
+        .to raise_error(ArgumentError, /decompress/)
+    end
+  end
+
+  describe "#request with verify_checksum:" do
+    # Serves `body` with a 200 (or `status`) and any extra headers.
+    def with_body_server(body, headers = {}, status: "200 OK")
+      server = TCPServer.new("127.0.0.1", 0)
+      thread = Thread.new do
+        client = server.accept
+        while (line = client.gets) && line.strip != ""; end
+        head = "HTTP/1.1 #{status}\r\nContent-Length: #{body.bytesize}\r\n"
+        headers.each { |name, value| head += "#{name}: #{value}\r\n" }
+        client.write("#{head}\r\n".b + body)
+        client.close
+      end
+
+      yield server.addr[1]
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    def crc32c_base64(data)
+      [[AwsCrt::Checksums.crc32c(data)].pack("N")].pack("m0")
+    end
+
+    let(:body) { "checksummed body " * 100 }
+    let(:headers) { [["Host", "127.0.0.1"]] }
+
+    it "returns the response when the body matches the expected checksum" do
+      with_body_server(body) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", headers, nil, verify_checksum: { crc32c: crc32c_base64(body) })
+
+        expect(response.body).to eq(body)
+      end
+    end
+
+    it "raises ChecksumMismatchError with both checksums when the body differs" do
+      with_body_server(body) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        expected = crc32c_base64("other")
+        expect do
+          pool.request("GET", "/", headers, nil, verify_checksum: { crc32c: expected })
+        end.to raise_error(AwsCrt::Http::ChecksumMismatchError) { |e|
+          expect(e).to be_a(AwsCrt::Http::Error)
+          expect(e.algorithm).to eq("CRC32C")
+          expect(e.expected_checksum).to eq(expected)
+          expect(e.computed_checksum).to eq(crc32c_base64(body))
+        }
+      end
+    end
+
+    it "verifies a streamed body after the chunks are yielded" do
+      with_body_server(body) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        received = +""
+        expect do
+          pool.request("GET", "/", headers, nil, verify_checksum: { crc32c: crc32c_base64("other") }) do |chunk|
+            received << chunk
+          end
+        end.to raise_error(AwsCrt::Http::ChecksumMismatchError)
+        expect(received).to eq(body)
+      end
+    end
+
+    it "uses the response's x-amz-checksum header for a nil expected value" do
+      with_body_server(body, "x-amz-checksum-crc32c" => crc32c_base64("other")) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        expect { pool.request("GET", "/", headers, nil, verify_checksum: { "CRC32C" => nil }) }
+          .to raise_error(AwsCrt::Http::ChecksumMismatchError, /expected #{Regexp.escape(crc32c_base64("other"))}/)
+      end
+    end
+
+    it "leaves a response without the checksum header unverified" do
+      with_body_server(body) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", headers, nil, verify_checksum: { crc32c: nil })
+
+        expect(response.body).to eq(body)
+      end
+    end
+
+    it "does not verify error responses" do
+      with_body_server("not found", {}, status: "404 Not Found") do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", headers, nil, verify_checksum: { crc32c: crc32c_base64(body) })
+
+        expect(response.status).to eq(404)
+      end
+    end
+
+    it "verifies request_async responses" do
+      with_body_server(body) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        pending = pool.request_async("GET", "/", headers, nil, verify_checksum: { crc32c: crc32c_base64("other") })
+
+        expect { pending.value }.to raise_error(AwsCrt::Http::ChecksumMismatchError)
+      end
+    end
+
+    it "checksums the body as received, before decompress:" do
+      compressed = Zlib.gzip(body)
+      with_body_server(compressed, "Content-Encoding" => "gzip") do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request("GET", "/", headers, nil,
+                                decompress: true, verify_checksum: { crc32c: crc32c_base64(compressed) })
+
+        expect(response.body).to eq(body)
+      end
+    end
+
+    it "rejects an unknown algorithm" do
+      pool = described_class.new("http://127.0.0.1:9999")
+      expect { pool.request("GET", "/", [], nil, verify_checksum: { md5: "x" }) }
+        .to raise_error(ArgumentError, /invalid verify_checksum algorithm 'md5'/)
+    end
+
+    it "rejects a value that is not a one-entry Hash" do
+      pool = described_class.new("http://127.0.0.1:9999")
+      expect { pool.request("GET", "/", [], nil, verify_checksum: "CRC32C") }
+        .to raise_error(ArgumentError, /invalid verify_checksum/)
+    end
+  end
+
+  describe "#request_async" do
+    it "returns a PendingResponse whose value is the Response" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        pending = pool.request_async("POST", "/async", [["Host", "127.0.0.1:#{port}"]], "data")
+
+        expect(pending).to be_a(AwsCrt::Http::PendingResponse)
+        response = pending.value
+        expect(pending).to be_done
+        expect(response).to be_a(AwsCrt::Http::Response)
+        expect(response.status).to eq(200)
+        expect(response.body).to eq("POST /async data")
+        expect(pending.value).to equal(response)
+      end
+    end
+
+    it "does not block the calling thread while the server is slow" do
+      release = Queue.new
+      raw = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok"
+      server = TCPServer.new("127.0.0.1", 0)
+      thread = Thread.new do
+        client = server.accept
+        while (line = client.gets) && line.strip != ""; end
+        release.pop
+        client.write(raw)
+        client.close
+      end
+
+      pool = described_class.new("http://127.0.0.1:#{server.addr[1]}")
+      pending = pool.request_async("GET", "/", [["Host", "127.0.0.1"]])
+      sleep 0.1
+      expect(pending).not_to be_done
+      expect(pending.inspect).to include("pending")
+
+      release << true
+      expect(pending.value.body).to eq("ok")
+    ensure
+      thread&.kill
+      server&.close
+    end
+
+    it "keeps many requests in flight from one thread" do
+      with_echo_server do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}", max_connections: 4)
+        pending = (1..8).map { |i| pool.request_async("GET", "/#{i}", [["Host", "127.0.0.1:#{port}"]]) }
+
+        expect(pending.map { |p| p.value.body.strip }).to eq((1..8).map { |i| "GET /#{i}" })
+      end
+    end
+
+    it "raises the request's error from #value, every time" do
+      server = TCPServer.new("127.0.0.1", 0)
+      port = server.addr[1]
+      server.close
+
+      pool = described_class.new("http://127.0.0.1:#{port}")
+      pending = pool.request_async("GET", "/", [["Host", "127.0.0.1"]])
+      expect { pending.value }.to raise_error(AwsCrt::Http::ConnectionError)
+      expect { pending.value }.to raise_error(AwsCrt::Http::ConnectionError)
+      expect(pending).to be_done
+    end
+
+    it "decodes CBOR bodies with decode: :cbor" do
+      body = AwsCrt::Cbor.encode({ "a" => 1 })
+      raw = "HTTP/1.1 200 OK\r\nContent-Length: #{body.bytesize}\r\n\r\n".b + body
+      with_raw_response_server(raw) do |port|
+        pool = described_class.new("http://127.0.0.1:#{port}")
+        response = pool.request_async("GET", "/", [["Host", "127.0.0.1"]], nil, decode: :cbor).value
+
+        expect(response.data).to eq({ "a" => 1 })
+      end
+    end
+
+    it "rejects a block, :response_targets and fair_queue pools" do
+      pool = described_class.new("http://127.0.0.1:9999")
+      expect { pool.request_async("GET", "/", []) { |_chunk| } }.to raise_error(ArgumentError)
+      expect { pool.request_async("GET", "/", [], nil, response_targets: [+""]) }
+        .to raise_error(ArgumentError, /response_targets/)
+
+      fair = described_class.new("http://127.0.0.1:9999", fair_queue: true)
+      expect { fair.request_async("GET", "/", []) }.to raise_error(ArgumentError, /fair_queue/)
+    end
+
+    it "raises ConnectionError once the pool is closed" do
+      pool = described_class.new("http://127.0.0.1:9999")
+      pool.close
+      expect { pool.request_async("GET", "/", []) }.to raise_error(AwsCrt::Http::ConnectionError)
+    end
   end
 end
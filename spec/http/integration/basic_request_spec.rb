@@ -144,6 +144,37 @@ RSpec.describe "Basic HTTP request integration" do
     end
   end
 
+  describe "requests with empty bodies" do
+    def echoed_length(method, headers, body, **options)
+      _status, _headers, response_body = @pool.request(method, "/empty", headers, body, options)
+      headers_hash(parse_echo(response_body)["headers"])["content-length"]
+    end
+
+    %w[POST PUT PATCH].each do |method|
+      it "#{method} with an empty body sends Content-Length: 0" do
+        expect(echoed_length(method, [host_header], "")).to eq("0")
+      end
+    end
+
+    it "does not add Content-Length when the body is nil" do
+      expect(echoed_length("POST", [host_header], nil)).to be_nil
+    end
+
+    it "does not add Content-Length to a GET with an empty body" do
+      expect(echoed_length("GET", [host_header], "")).to be_nil
+    end
+
+    it "keeps a caller-supplied Content-Length" do
+      headers = [host_header, %w[content-length 0]]
+      status, = @pool.request("POST", "/empty", headers, "")
+      expect(status).to eq(200)
+    end
+
+    it "can be turned off with empty_body_content_length: false" do
+      expect(echoed_length("POST", [host_header], "", empty_body_content_length: false)).to be_nil
+    end
+  end
+
   describe "requests with bodies" do
     %w[POST PUT PATCH DELETE].each do |method|
       it "#{method} with a body round-trips the content" do
@@ -11,7 +11,10 @@
 #
 # Requirements: 5.1, 5.2, 5.3, 5.5, 12.2
 
+require "fileutils"
 require "json"
+require "openssl"
+require "tmpdir"
 require "support/test_server"
 
 RSpec.describe "TLS integration" do
@@ -84,4 +87,36 @@ RSpec.describe "TLS integration" do
       }.to raise_error(AwsCrt::Http::Error)
     end
   end
+
+  describe "custom CA directory (ssl_ca_path)" do
+    before do
+      skip "only s2n-tls (Linux) honors a CA directory" unless RUBY_PLATFORM.include?("linux")
+    end
+
+    def hashed_ca_dir(cert_path)
+      dir = Dir.mktmpdir("aws_crt_ca")
+      cert = OpenSSL::X509::Certificate.new(File.read(cert_path))
+      File.write(File.join(dir, format("%08x.0", cert.subject.hash)), cert.to_pem)
+      dir
+    end
+
+    it "verifies the server against a CA found in the directory" do
+      dir = hashed_ca_dir(@server.ca_cert_path)
+      pool = AwsCrt::Http::ConnectionPool.new(@server.endpoint, ssl_ca_path: dir)
+
+      status, _headers, _body = pool.request("GET", "/ca-dir", [host_header])
+      expect(status).to eq(200)
+    ensure
+      FileUtils.rm_rf(dir) if dir
+    end
+
+    it "fails verification when the directory lacks the server's CA" do
+      dir = Dir.mktmpdir("aws_crt_ca")
+      pool = AwsCrt::Http::ConnectionPool.new(@server.endpoint, ssl_ca_path: dir)
+
+      expect { pool.request("GET", "/ca-dir", [host_header]) }.to raise_error(AwsCrt::Http::Error)
+    ensure
+      FileUtils.rm_rf(dir) if dir
+    end
+  end
 end
@@ -0,0 +1,166 @@
+# frozen_string_literal: true
+
+require "aws_crt/http/response_cache"
+
+# Unit tests for AwsCrt::Http::ResponseCache.
+#
+# The pool is a test double that records requests and returns canned
+# responses, and the cache clock is driven manually so freshness can be
+# tested deterministically.
+
+RSpec.describe AwsCrt::Http::ResponseCache do
+  let(:now) { [1000.0] }
+  let(:cache) { described_class.new(max_entries: 4, clock: -> { now[0] }) }
+  let(:pool) { double("pool") }
+  let(:host) { [["Host", "example.com"]] }
+
+  def respond_with(*responses)
+    allow(pool).to receive(:request).and_return(*responses)
+  end
+
+  describe "freshness" do
+    it "serves a fresh response from cache without a request" do
+      respond_with([200, [["Cache-Control", "max-age=60"]], "v1"])
+
+      expect(cache.get(pool, "/a", host)).to eq([200, [["Cache-Control", "max-age=60"]], "v1"])
+      now[0] += 30
+      expect(cache.get(pool, "/a", host)[2]).to eq("v1")
+      expect(pool).to have_received(:request).once
+    end
+
+    it "refetches once max-age has elapsed" do
+      respond_with([200, [["Cache-Control", "max-age=60"]], "v1"],
+                   [200, [["Cache-Control", "max-age=60"]], "v2"])
+
+      cache.get(pool, "/a", host)
+      now[0] += 61
+      expect(cache.get(pool, "/a", host)[2]).to eq("v2")
+      expect(pool).to have_received(:request).twice
+    end
+
+    it "counts the Age header against max-age" do
+      respond_with([200, [["Cache-Control", "max-age=60"], ["Age", "50"]], "v1"],
+                   [200, [["Cache-Control", "max-age=60"]], "v2"])
+
+      cache.get(pool, "/a", host)
+      now[0] += 11
+      expect(cache.get(pool, "/a", host)[2]).to eq("v2")
+    end
+
+    it "does not store no-store responses" do
+      respond_with([200, [["Cache-Control", "no-store, max-age=60"]], "v1"])
+
+      cache.get(pool, "/a", host)
+      expect(cache.size).to eq(0)
+    end
+
+    it "does not store non-200 responses" do
+      respond_with([404, [["Cache-Control", "max-age=60"]], "missing"])
+
+      cache.get(pool, "/a", host)
+      expect(cache.size).to eq(0)
+    end
+
+    it "keys entries by Host and path" do
+      respond_with([200, [["Cache-Control", "max-age=60"]], "a"],
+                   [200, [["Cache-Control", "max-age=60"]], "b"],
+                   [200, [["Cache-Control", "max-age=60"]], "c"])
+
+      expect(cache.get(pool, "/a", host)[2]).to eq("a")
+      expect(cache.get(pool, "/b", host)[2]).to eq("b")
+      expect(cache.get(pool, "/a", [["Host", "other.com"]])[2]).to eq("c")
+    end
+  end
+
+  describe "conditional revalidation" do
+    it "sends If-None-Match for stale entries and serves the cached body on 304" do
+      allow(pool).to receive(:request)
+        .with("GET", "/a", host)
+        .and_return([200, [["ETag", '"abc"'], ["Cache-Control", "max-age=10"]], "body"])
+      expect(pool).to receive(:request)
+        .with("GET", "/a", host + [["If-None-Match", '"abc"']])
+        .and_return([304, [["Cache-Control", "max-age=10"]], ""])
+
+      cache.get(pool, "/a", host)
+      now[0] += 20
+      status, _headers, body = cache.get(pool, "/a", host)
+      expect(status).to eq(200)
+      expect(body).to eq("body")
+    end
+
+    it "treats a 304 as renewing freshness" do
+      allow(pool).to receive(:request).and_return(
+        [200, [["ETag", '"abc"'], ["Cache-Control", "max-age=10"]], "body"],
+        [304, [], ""]
+      )
+
+      cache.get(pool, "/a", host)
+      now[0] += 20
+      cache.get(pool, "/a", host)
+      now[0] += 5
+      cache.get(pool, "/a", host)
+      expect(pool).to have_received(:request).twice
+    end
+
+    it "always revalidates no-cache entries" do
+      allow(pool).to receive(:request).and_return(
+        [200, [["ETag", '"abc"'], ["Cache-Control", "no-cache"]], "body"],
+        [304, [], ""]
+      )
+
+      cache.get(pool, "/a", host)
+      expect(cache.get(pool, "/a", host)[2]).to eq("body")
+      expect(pool).to have_received(:request).twice
+    end
+
+    it "replaces the entry when revalidation returns a new 200" do
+      respond_with([200, [["ETag", '"v1"']], "one"],
+                   [200, [["ETag", '"v2"']], "two"])
+
+      cache.get(pool, "/a", host)
+      expect(cache.get(pool, "/a", host)[2]).to eq("two")
+      expect(cache.size).to eq(1)
+    end
+  end
+
+  describe "Vary" do
+    it "stores separate variants per Vary'd request header" do
+      respond_with([200, [["Vary", "Accept-Encoding"], ["Cache-Control", "max-age=60"]], "gzip"],
+                   [200, [["Vary", "Accept-Encoding"], ["Cache-Control", "max-age=60"]], "plain"])
+
+      gzip = host + [["Accept-Encoding", "gzip"]]
+      expect(cache.get(pool, "/a", gzip)[2]).to eq("gzip")
+      expect(cache.get(pool, "/a", host)[2]).to eq("plain")
+      expect(cache.get(pool, "/a", gzip)[2]).to eq("gzip")
+      expect(cache.get(pool, "/a", host)[2]).to eq("plain")
+      expect(pool).to have_received(:request).twice
+    end
+
+    it "never caches Vary: *" do
+      respond_with([200, [["Vary", "*"], ["Cache-Control", "max-age=60"]], "x"])
+
+      cache.get(pool, "/a", host)
+      expect(cache.size).to eq(0)
+    end
+  end
+
+  describe "LRU eviction" do
+    it "evicts the least recently used entry when full" do
+      allow(pool).to receive(:request) { |_m, path, _h| [200, [["Cache-Control", "max-age=60"]], path] }
+
+      %w[/1 /2 /3 /4].each { |p| cache.get(pool, p, host) }
+      cache.get(pool, "/1", host) # touch /1 so /2 is now oldest
+      cache.get(pool, "/5", host)
+
+      expect(cache.size).to eq(4)
+      cache.get(pool, "/1", host)
+      expect(pool).to have_received(:request).with("GET", "/1", host).once
+      cache.get(pool, "/2", host)
+      expect(pool).to have_received(:request).with("GET", "/2", host).twice
+    end
+  end
+
+  it "rejects a non-positive max_entries" do
+    expect { described_class.new(max_entries: 0) }.to raise_error(ArgumentError)
+  end
+end
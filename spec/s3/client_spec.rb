@@ -1,7 +1,10 @@
 # frozen_string_literal: true
 
 require "aws_crt/s3/client"
+require "openssl"
+require "socket"
 require "stringio"
+require "tmpdir"
 
 # Unit tests for AwsCrt::S3::Client.
 #
@@ -261,6 +264,588 @@ RSpec.describe AwsCrt::S3::Client do
     end
   end
 
+  describe "per-request :credentials" do
+    let(:provider) { double("provider") }
+    let(:client) { described_class.new(region: "us-east-1", credentials: provider) }
+    let(:success_result) { { status_code: 200, headers: {}, body: "ok", checksum_validated: nil } }
+
+    before do
+      allow_any_instance_of(described_class).to receive(:_native_initialize)
+      allow(provider).to receive(:credentials).and_return(
+        AwsCrt::S3::Credentials.new(access_key_id: "CLIENT_AKID", secret_access_key: "CLIENT_SECRET")
+      )
+    end
+
+    it "signs get_object with the override instead of the provider" do
+      expect(client).to receive(:_native_get_object) do |params|
+        expect(params[:_access_key_id]).to eq("ROLE_AKID")
+        expect(params[:_secret_access_key]).to eq("ROLE_SECRET")
+        expect(params[:_session_token]).to eq("ROLE_TOKEN")
+        expect(params).not_to have_key(:credentials)
+        success_result
+      end
+
+      client.get_object(
+        bucket: "b", key: "k",
+        credentials: { access_key_id: "ROLE_AKID", secret_access_key: "ROLE_SECRET", session_token: "ROLE_TOKEN" }
+      )
+    end
+
+    it "does not carry the override into later requests" do
+      keys = []
+      allow(client).to receive(:_native_put_object) do |params|
+        keys << [params[:_access_key_id], params[:_session_token]]
+        success_result
+      end
+
+      client.put_object(bucket: "b", key: "k", body: "x",
+                        credentials: { access_key_id: "ROLE_AKID", secret_access_key: "ROLE_SECRET",
+                                       session_token: "ROLE_TOKEN" })
+      client.put_object(bucket: "b", key: "k", body: "x")
+
+      expect(keys).to eq([%w[ROLE_AKID ROLE_TOKEN], ["CLIENT_AKID", nil]])
+    end
+
+    it "accepts a credentials object" do
+      creds = AwsCrt::S3::Credentials.new(access_key_id: "OBJ_AKID", secret_access_key: "OBJ_SECRET")
+      expect(client).to receive(:_native_get_objects) do |params|
+        expect(params[:_access_key_id]).to eq("OBJ_AKID")
+        {}
+      end
+
+      client.get_objects(bucket: "b", keys: ["k"], credentials: creds)
+    end
+
+    it "rejects an override without a secret key" do
+      expect do
+        client.get_object(bucket: "b", key: "k", credentials: { access_key_id: "AKID" })
+      end.to raise_error(ArgumentError, /missing :secret_access_key/)
+    end
+
+    it "rejects unknown keys" do
+      expect do
+        client.get_object(bucket: "b", key: "k",
+                          credentials: { access_key_id: "A", secret_access_key: "S", region: "eu-west-1" })
+      end.to raise_error(ArgumentError, /unknown :credentials keys: region/)
+    end
+  end
+
+  describe "#initialize — validate_credentials" do
+    before do
+      allow_any_instance_of(described_class).to receive(:_native_initialize)
+    end
+
+    let(:creds) do
+      AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+    end
+
+    it "constructs the client when credentials resolve" do
+      expect do
+        described_class.new(region: "us-east-1", credentials: creds, validate_credentials: true)
+      end.not_to raise_error
+    end
+
+    it "raises MissingCredentialsError when the provider returns nil" do
+      provider = Object.new
+      provider.define_singleton_method(:credentials) { nil }
+
+      expect do
+        described_class.new(region: "us-east-1", credentials: provider, validate_credentials: true)
+      end.to raise_error(AwsCrt::MissingCredentialsError, /unable to resolve credentials/)
+    end
+
+    it "raises MissingCredentialsError when the provider returns an empty secret" do
+      empty = AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "")
+
+      expect do
+        described_class.new(region: "us-east-1", credentials: empty, validate_credentials: true)
+      end.to raise_error(AwsCrt::MissingCredentialsError)
+    end
+
+    it "raises MissingCredentialsError when the provider raises" do
+      provider = Object.new
+      provider.define_singleton_method(:credentials) { raise IOError, "metadata service unreachable" }
+
+      expect do
+        described_class.new(region: "us-east-1", credentials: provider, validate_credentials: true)
+      end.to raise_error(AwsCrt::MissingCredentialsError, /unable to resolve credentials/)
+    end
+
+    it "does not create the native client when validation fails" do
+      empty = AwsCrt::S3::Credentials.new(access_key_id: "", secret_access_key: "secret")
+      expect_any_instance_of(described_class).not_to receive(:_native_initialize)
+
+      expect do
+        described_class.new(region: "us-east-1", credentials: empty, validate_credentials: true)
+      end.to raise_error(AwsCrt::MissingCredentialsError)
+    end
+
+    it "is an AwsCrt::Error" do
+      expect(AwsCrt::MissingCredentialsError.superclass).to eq(AwsCrt::Error)
+    end
+  end
+
+  describe "#initialize — endpoint" do
+    let(:creds) do
+      AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+    end
+
+    it "passes :endpoint through to the native client" do
+      expect_any_instance_of(described_class).to receive(:_native_initialize) do |_instance, opts|
+        expect(opts[:endpoint]).to eq("https://minio.example.com:9000")
+      end
+
+      described_class.new(region: "eu-west-1", endpoint: "https://minio.example.com:9000", credentials: creds)
+    end
+
+    it "defaults :region to us-east-1 when an endpoint is given" do
+      expect_any_instance_of(described_class).to receive(:_native_initialize) do |_instance, opts|
+        expect(opts[:region]).to eq("us-east-1")
+      end
+
+      described_class.new(endpoint: "http://localhost:9000", credentials: creds)
+    end
+
+    it "still requires :region without an endpoint" do
+      expect do
+        described_class.new(credentials: creds)
+      end.to raise_error(ArgumentError, /missing required option :region/)
+    end
+
+    it "constructs native clients for http and https endpoints" do
+      expect do
+        described_class.new(endpoint: "http://localhost:9000", credentials: creds)
+        described_class.new(endpoint: "https://minio.example.com", credentials: creds)
+      end.not_to raise_error
+    end
+
+    it "rejects an endpoint without a scheme" do
+      expect do
+        described_class.new(endpoint: "minio.example.com:9000", credentials: creds)
+      end.to raise_error(ArgumentError, /expected scheme:\/\/host\[:port\]/)
+    end
+
+    it "rejects an unsupported scheme" do
+      expect do
+        described_class.new(endpoint: "ftp://minio.example.com", credentials: creds)
+      end.to raise_error(ArgumentError, /Unsupported scheme 'ftp'/)
+    end
+
+    it "passes :use_tls through to the native client" do
+      expect_any_instance_of(described_class).to receive(:_native_initialize) do |_instance, opts|
+        expect(opts[:use_tls]).to be(false)
+      end
+
+      described_class.new(region: "us-east-1", use_tls: false, credentials: creds)
+    end
+
+    it "constructs a plaintext native client with use_tls: false" do
+      expect do
+        described_class.new(region: "us-east-1", use_tls: false, credentials: creds)
+      end.not_to raise_error
+    end
+
+    it "accepts use_tls: false with an http endpoint" do
+      expect do
+        described_class.new(endpoint: "http://localhost:9000", use_tls: false, credentials: creds)
+      end.not_to raise_error
+    end
+
+    it "rejects use_tls that contradicts the endpoint's scheme" do
+      expect do
+        described_class.new(endpoint: "https://minio.example.com", use_tls: false, credentials: creds)
+      end.to raise_error(ArgumentError, /contradicts the endpoint's https scheme/)
+      expect do
+        described_class.new(endpoint: "http://localhost:9000", use_tls: true, credentials: creds)
+      end.to raise_error(ArgumentError, /contradicts the endpoint's http scheme/)
+    end
+
+    it "passes :alpn to the native client as a semicolon-delimited list" do
+      expect_any_instance_of(described_class).to receive(:_native_initialize) do |_instance, opts|
+        expect(opts[:alpn_list]).to eq("h2;http/1.1")
+      end
+
+      described_class.new(region: "us-east-1", alpn: %w[h2 http/1.1], credentials: creds)
+    end
+
+    it "constructs a native client that offers ALPN" do
+      expect do
+        described_class.new(region: "us-east-1", alpn: "h2", credentials: creds)
+      end.not_to raise_error
+    end
+
+    it "rejects invalid ALPN protocols" do
+      expect do
+        described_class.new(region: "us-east-1", alpn: ["h2;http/1.1"], credentials: creds)
+      end.to raise_error(ArgumentError, /invalid ALPN protocol/)
+      expect do
+        described_class.new(region: "us-east-1", alpn: [""], credentials: creds)
+      end.to raise_error(ArgumentError, /invalid ALPN protocol/)
+    end
+
+    it "rejects :alpn on a plaintext client" do
+      expect do
+        described_class.new(endpoint: "http://localhost:9000", alpn: ["h2"], credentials: creds)
+      end.to raise_error(ArgumentError, /:alpn needs TLS/)
+    end
+
+    it "allows bucket names with dots" do
+      client = described_class.new(endpoint: "http://127.0.0.1:1", credentials: creds)
+      # Nothing listens on the endpoint; only the name check matters here.
+      error = begin
+        client.get_object(bucket: "backups.2024", key: "k")
+        nil
+      rescue StandardError => e
+        e
+      end
+      expect(error).not_to be_a(ArgumentError)
+    end
+  end
+
+  describe "#initialize — compute_content_md5" do
+    let(:creds) do
+      AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+    end
+
+    it "passes :compute_content_md5 through to the native client" do
+      expect_any_instance_of(described_class).to receive(:_native_initialize) do |_instance, opts|
+        expect(opts[:compute_content_md5]).to be(true)
+      end
+
+      described_class.new(region: "us-east-1", credentials: creds, compute_content_md5: true)
+    end
+
+    it "omits :compute_content_md5 by default" do
+      expect_any_instance_of(described_class).to receive(:_native_initialize) do |_instance, opts|
+        expect(opts).not_to have_key(:compute_content_md5)
+      end
+
+      described_class.new(region: "us-east-1", credentials: creds)
+    end
+
+    it "constructs a native client with Content-MD5 enabled" do
+      expect do
+        described_class.new(region: "us-east-1", credentials: creds, compute_content_md5: true)
+      end.not_to raise_error
+    end
+  end
+
+  describe "#initialize — max_concurrent_transfers" do
+    let(:creds) do
+      AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+    end
+
+    it "passes :max_concurrent_transfers through to the native client" do
+      expect_any_instance_of(described_class).to receive(:_native_initialize) do |_instance, opts|
+        expect(opts[:max_concurrent_transfers]).to eq(4)
+      end
+
+      described_class.new(region: "us-east-1", credentials: creds, max_concurrent_transfers: 4)
+    end
+
+    it "constructs a native client with a transfer limit" do
+      expect do
+        described_class.new(region: "us-east-1", credentials: creds, max_concurrent_transfers: 4)
+      end.not_to raise_error
+    end
+  end
+
+  describe "#max_active_connections" do
+    let(:creds) do
+      AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+    end
+
+    it "reports the connection count the CRT chose" do
+      client = described_class.new(region: "us-east-1", credentials: creds)
+      expect(client.max_active_connections).to be_a(Integer).and be_positive
+    end
+
+    it "scales with throughput_target_gbps" do
+      low = described_class.new(region: "us-east-1", credentials: creds, throughput_target_gbps: 1.0)
+      high = described_class.new(region: "us-east-1", credentials: creds, throughput_target_gbps: 100.0)
+      expect(high.max_active_connections).to be > low.max_active_connections
+    end
+
+    it "is capped by max_active_connections_override" do
+      client = described_class.new(region: "us-east-1", credentials: creds,
+                                   throughput_target_gbps: 100.0, max_active_connections_override: 7)
+      expect(client.max_active_connections).to eq(7)
+    end
+  end
+
+  describe "bucket name validation" do
+    let(:client) do
+      creds = AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+      described_class.new(region: "us-east-1", credentials: creds)
+    end
+
+    {
+      "ab" => /between 3 and 63 characters/,
+      "a" * 64 => /between 3 and 63 characters/,
+      "My-Bucket" => /lowercase letters, digits, hyphens and dots/,
+      "my_bucket" => /lowercase letters, digits, hyphens and dots/,
+      "-bucket" => /begin and end with a letter or digit/,
+      "xn--bucket" => /reserved prefixes/,
+      "192.168.5.4" => /IP address/,
+      "my.bucket" => /dots can't use virtual-hosted-style requests over TLS/
+    }.each do |name, message|
+      it "rejects #{name[0, 20].inspect} before sending a request" do
+        expect { client.get_object(bucket: name, key: "k") }
+          .to raise_error(ArgumentError, message)
+        expect { client.put_object(bucket: name, key: "k", body: "x") }
+          .to raise_error(ArgumentError, message)
+      end
+    end
+
+    it "names the bucket in the error" do
+      expect { client.get_objects(bucket: "Bad", keys: ["k"]) }
+        .to raise_error(ArgumentError, /invalid bucket name "Bad"/)
+    end
+
+    it "allows bucket names with dots on a plaintext client" do
+      creds = AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+      plaintext = described_class.new(region: "us-east-1", credentials: creds, use_tls: false)
+      # max_parts is checked after the bucket, so reaching it means the
+      # name passed, without sending anything.
+      expect { plaintext.put_object(bucket: "my.bucket", key: "k", body: "x", max_parts: 0) }
+        .to raise_error(ArgumentError, /max_parts/)
+    end
+  end
+
+  describe "expected_bucket_owner and request_payer" do
+    let(:client) do
+      creds = AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+      described_class.new(region: "us-east-1", credentials: creds)
+    end
+
+    it "rejects request_payer values other than requester on every operation" do
+      expect { client.get_object(bucket: "bkt", key: "k", request_payer: "owner") }
+        .to raise_error(ArgumentError, /invalid :request_payer "owner"/)
+      expect { client.put_object(bucket: "bkt", key: "k", body: "x", request_payer: :bucket_owner) }
+        .to raise_error(ArgumentError, /invalid :request_payer "bucket_owner"/)
+      expect { client.get_objects(bucket: "bkt", keys: ["k"], request_payer: "nobody") }
+        .to raise_error(ArgumentError, /invalid :request_payer/)
+    end
+
+    it "rejects an expected_bucket_owner that would break the header" do
+      expect { client.get_object(bucket: "bkt", key: "k", expected_bucket_owner: "1111\r\nX-Evil: 1") }
+        .to raise_error(ArgumentError, /:expected_bucket_owner must not contain CR, LF, or NUL/)
+    end
+  end
+
+  describe "#put_object — IO bodies without a size" do
+    let(:client) do
+      creds = AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+      described_class.new(region: "us-east-1", credentials: creds)
+    end
+
+    it "requires :content_length" do
+      reader, writer = IO.pipe
+      writer.write("data")
+      writer.close
+      expect { client.put_object(bucket: "bkt", key: "k", body: reader) }
+        .to raise_error(ArgumentError, /:content_length is required/)
+    ensure
+      reader&.close
+    end
+
+    it "rejects an IO that ends before :content_length bytes" do
+      reader, writer = IO.pipe
+      writer.write("data")
+      writer.close
+      expect { client.put_object(bucket: "bkt", key: "k", body: reader, content_length: 10) }
+        .to raise_error(ArgumentError, /ended after 4 of the 10 bytes/)
+    ensure
+      reader&.close
+    end
+  end
+
+  describe "#presign_post" do
+    let(:secret) { "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY" }
+    let(:creds) do
+      AwsCrt::S3::Credentials.new(access_key_id: "AKIDEXAMPLE", secret_access_key: secret)
+    end
+    let(:client) { described_class.new(region: "us-west-2", credentials: creds) }
+    let(:now) { Time.utc(2026, 1, 2, 3, 4, 5) }
+
+    before { allow(Time).to receive(:now).and_return(now) }
+
+    def decode_policy(fields)
+      JSON.parse(fields["policy"].unpack1("m0"))
+    end
+
+    def expected_signature(policy, date: "20260102", region: "us-west-2")
+      key = ["AWS4#{secret}", date, region, "s3", "aws4_request"].reduce do |k, data|
+        OpenSSL::HMAC.digest("SHA256", k, data)
+      end
+      OpenSSL::HMAC.hexdigest("SHA256", key, policy)
+    end
+
+    it "returns the SigV4 form fields" do
+      fields = client.presign_post(bucket: "b", key: "uploads/a.txt")
+
+      expect(fields).to include(
+        "key" => "uploads/a.txt",
+        "x-amz-algorithm" => "AWS4-HMAC-SHA256",
+        "x-amz-credential" => "AKIDEXAMPLE/20260102/us-west-2/s3/aws4_request",
+        "x-amz-date" => "20260102T030405Z"
+      )
+      expect(fields).not_to have_key("x-amz-security-token")
+    end
+
+    it "signs the base64 policy with the derived SigV4 signing key" do
+      fields = client.presign_post(bucket: "b", key: "k")
+
+      expect(fields["x-amz-signature"]).to eq(expected_signature(fields["policy"]))
+    end
+
+    it "builds a policy covering the bucket, key, and x-amz fields" do
+      fields = client.presign_post(bucket: "b", key: "k", expires_in: 600)
+      policy = decode_policy(fields)
+
+      expect(policy["expiration"]).to eq("2026-01-02T03:14:05.000Z")
+      expect(policy["conditions"]).to include(
+        { "bucket" => "b" },
+        { "key" => "k" },
+        { "x-amz-algorithm" => "AWS4-HMAC-SHA256" },
+        { "x-amz-credential" => fields["x-amz-credential"] },
+        { "x-amz-date" => fields["x-amz-date"] }
+      )
+    end
+
+    it "appends caller conditions to the policy" do
+      range = ["content-length-range", 0, 1024]
+      fields = client.presign_post(bucket: "b", key: "k", conditions: [range, { "acl" => "private" }])
+
+      expect(decode_policy(fields)["conditions"]).to include(range, { "acl" => "private" })
+    end
+
+    it "matches ${filename} keys by prefix" do
+      fields = client.presign_post(bucket: "b", key: "user/42/${filename}")
+
+      expect(decode_policy(fields)["conditions"]).to include(["starts-with", "$key", "user/42/"])
+    end
+
+    it "includes the session token for temporary credentials" do
+      temp = AwsCrt::S3::Credentials.new(
+        access_key_id: "ASIA", secret_access_key: secret, session_token: "TOKEN"
+      )
+      temp_client = described_class.new(region: "us-west-2", credentials: temp)
+
+      fields = temp_client.presign_post(bucket: "b", key: "k")
+
+      expect(fields["x-amz-security-token"]).to eq("TOKEN")
+      expect(decode_policy(fields)["conditions"]).to include({ "x-amz-security-token" => "TOKEN" })
+    end
+
+    it "rejects expirations beyond seven days" do
+      expect { client.presign_post(bucket: "b", key: "k", expires_in: 8 * 24 * 3600) }
+        .to raise_error(ArgumentError, /expires_in/)
+    end
+
+    it "does not expose the native signing primitive publicly" do
+      expect(client).not_to respond_to(:sign_post_policy)
+    end
+  end
+
+  describe "#bucket_region" do
+    let(:client) do
+      allow_any_instance_of(described_class).to receive(:_native_initialize)
+      described_class.new(region: "us-east-1", access_key_id: "AKID", secret_access_key: "secret")
+    end
+    let(:pool) { instance_double(AwsCrt::Http::ConnectionPool) }
+
+    def http_response(status, headers)
+      instance_double(AwsCrt::Http::Response, status: status, header_pairs: headers, body: "",
+                                              "[]": nil).tap do |response|
+        headers.each { |name, value| allow(response).to receive(:[]).with(name).and_return(value) }
+      end
+    end
+
+    before do
+      allow(AwsCrt::Http::ConnectionPool).to receive(:new).with("https://s3.amazonaws.com").and_return(pool)
+    end
+
+    it "reads x-amz-bucket-region from an unsigned HEAD bucket" do
+      expect(pool).to receive(:request)
+        .with("HEAD", "/my-bucket", [["Host", "s3.amazonaws.com"]])
+        .and_return(http_response(200, [["x-amz-bucket-region", "us-east-1"]]))
+
+      expect(client.bucket_region(bucket: "my-bucket")).to eq("us-east-1")
+    end
+
+    it "reads the region off a redirect or an access-denied response" do
+      allow(pool).to receive(:request).and_return(
+        http_response(301, [["x-amz-bucket-region", "eu-west-1"]]),
+        http_response(403, [["x-amz-bucket-region", "ap-southeast-2"]])
+      )
+
+      expect(client.bucket_region(bucket: "a")).to eq("eu-west-1")
+      expect(client.bucket_region(bucket: "b")).to eq("ap-southeast-2")
+    end
+
+    it "reuses one connection pool" do
+      allow(pool).to receive(:request).and_return(http_response(200, [["x-amz-bucket-region", "us-east-1"]]))
+
+      2.times { client.bucket_region(bucket: "b") }
+      expect(AwsCrt::Http::ConnectionPool).to have_received(:new).once
+    end
+
+    it "raises ServiceError when no region is reported" do
+      allow(pool).to receive(:request).and_return(http_response(404, []))
+
+      expect { client.bucket_region(bucket: "missing") }
+        .to raise_error(AwsCrt::S3::ServiceError, /did not report a region.*HTTP 404/) { |e|
+          expect(e.status_code).to eq(404)
+        }
+    end
+
+    it "requires a bucket name" do
+      expect { client.bucket_region(bucket: "") }.to raise_error(ArgumentError, /:bucket/)
+    end
+  end
+
+  describe "#credentials_valid?" do
+    before do
+      allow_any_instance_of(described_class).to receive(:_native_initialize)
+    end
+
+    it "returns true for credentials with both keys set" do
+      creds = AwsCrt::S3::Credentials.new(
+        access_key_id: "AKID",
+        secret_access_key: "secret",
+        session_token: "token"
+      )
+      client = described_class.new(region: "us-east-1", credentials: creds)
+
+      expect(client.credentials_valid?).to be(true)
+    end
+
+    it "returns false when the provider stops returning credentials" do
+      current = AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+      provider = Object.new
+      provider.define_singleton_method(:credentials) { current }
+      client = described_class.new(region: "us-east-1", credentials: provider)
+
+      current = nil
+      expect(client.credentials_valid?).to be(false)
+    end
+
+    it "returns false when the provider raises" do
+      calls = 0
+      provider = Object.new
+      provider.define_singleton_method(:credentials) do
+        calls += 1
+        raise "expired" if calls > 1
+
+        AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+      end
+      client = described_class.new(region: "us-east-1", credentials: provider)
+
+      expect(client.credentials_valid?).to be(false)
+    end
+  end
+
   # For tests that exercise post-validation logic (checksum validation,
   # response handling, error translation), we stub the native Rust methods
   # so we can test the Ruby layer in isolation.
@@ -359,6 +944,312 @@ RSpec.describe AwsCrt::S3::Client do
       end
     end
 
+    describe "#put_object — tagging" do
+      let(:success_result) { { status_code: 200, headers: {}, body: nil, checksum_validated: nil } }
+
+      it "passes String tags through to the native method" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:tagging]).to eq({ "env" => "prod", "team" => "data" })
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data", tagging: { "env" => "prod", "team" => "data" })
+      end
+
+      it "stringifies Symbol keys and non-String values" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:tagging]).to eq({ "env" => "prod", "version" => "3" })
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data", tagging: { env: :prod, version: 3 })
+      end
+
+      it "omits tagging when not provided" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params).not_to have_key(:tagging)
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data")
+      end
+
+      it "raises ArgumentError when tagging is not a Hash" do
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data", tagging: "env=prod")
+        end.to raise_error(ArgumentError, /:tagging must be a Hash/)
+      end
+    end
+
+    describe "#put_object — object lock" do
+      let(:success_result) { { status_code: 200, headers: {}, body: nil, checksum_validated: nil } }
+
+      it "normalizes the settings into header values" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:object_lock]).to eq(
+            mode: "COMPLIANCE", retain_until: "2030-01-02T03:04:05.000Z", legal_hold: "ON"
+          )
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data",
+                          object_lock: { mode: :compliance,
+                                         retain_until: Time.utc(2030, 1, 2, 3, 4, 5),
+                                         legal_hold: true })
+      end
+
+      it "passes a String retain_until through unchanged" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:object_lock][:retain_until]).to eq("2030-01-01T00:00:00Z")
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data",
+                          object_lock: { mode: "GOVERNANCE", retain_until: "2030-01-01T00:00:00Z" })
+      end
+
+      it "allows a legal hold on its own" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:object_lock]).to eq(legal_hold: "OFF")
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data", object_lock: { legal_hold: false })
+      end
+
+      it "defaults checksum_algorithm to CRC32" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:checksum_algorithm]).to eq("CRC32")
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data", object_lock: { legal_hold: true })
+      end
+
+      it "keeps an explicit checksum_algorithm" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:checksum_algorithm]).to eq("SHA256")
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data", checksum_algorithm: "SHA256",
+                          object_lock: { legal_hold: true })
+      end
+
+      it "rejects an unknown mode" do
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data",
+                            object_lock: { mode: "FOREVER", retain_until: Time.now })
+        end.to raise_error(ArgumentError, /GOVERNANCE or COMPLIANCE/)
+      end
+
+      it "requires mode and retain_until together" do
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data", object_lock: { mode: "GOVERNANCE" })
+        end.to raise_error(ArgumentError, /must be given together/)
+      end
+
+      it "rejects unknown keys" do
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data", object_lock: { hold: true })
+        end.to raise_error(ArgumentError, /unknown :object_lock keys: hold/)
+      end
+
+      it "surfaces the S3 error when the bucket lacks Object Lock" do
+        allow(client).to receive(:_native_put_object).and_return(
+          error: true, error_code: 0, status_code: 400, headers: {},
+          body: "<Error><Code>InvalidRequest</Code>" \
+                "<Message>Bucket is missing Object Lock Configuration</Message></Error>"
+        )
+
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data", object_lock: { legal_hold: true })
+        end.to raise_error(AwsCrt::S3::ServiceError, /Object Lock Configuration/)
+      end
+    end
+
+    describe "file_io" do
+      let(:success_result) { { status_code: 200, headers: {}, body: nil, checksum_validated: nil } }
+      let(:file_io) { { should_stream: true, disk_throughput_gbps: 5.0, direct_io: false } }
+
+      it "passes :file_io through on put_object" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:file_io]).to eq(file_io)
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data", file_io: file_io)
+      end
+
+      it "passes :file_io through on get_object" do
+        expect(client).to receive(:_native_get_object) do |params|
+          expect(params[:file_io]).to eq(file_io)
+          success_result.merge(body: "data")
+        end
+
+        client.get_object(bucket: "b", key: "k", file_io: file_io)
+      end
+
+      it "rejects unknown keys" do
+        expect { client.get_object(bucket: "b", key: "k", file_io: { buffer_size: 1 }) }
+          .to raise_error(ArgumentError, /unknown :file_io keys: buffer_size/)
+      end
+
+      it "rejects a non-Hash" do
+        expect { client.put_object(bucket: "b", key: "k", body: "data", file_io: true) }
+          .to raise_error(ArgumentError, /:file_io must be a Hash/)
+      end
+    end
+
+    describe "#put_object — compress: :gzip" do
+      let(:success_result) do
+        { status_code: 200, headers: {}, body: nil, checksum_validated: nil }
+      end
+
+      it "gzips a String body and sets Content-Encoding" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:content_encoding]).to eq("gzip")
+          expect(Zlib.gunzip(params[:body])).to eq("hello " * 100)
+          expect(params).not_to have_key(:compress)
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "hello " * 100, compress: :gzip)
+      end
+
+      it "drops a :content_length that describes the uncompressed body" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params).not_to have_key(:content_length)
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data", content_length: 4, compress: :gzip)
+      end
+
+      it "appends gzip to an existing Content-Encoding" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:content_encoding]).to eq("br, gzip")
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data", content_encoding: "br", compress: :gzip)
+      end
+
+      it "compresses an IO body into a tempfile and removes it afterwards" do
+        seen_path = nil
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:body]).to be_a(File)
+          seen_path = params[:body].path
+          expect(Zlib.gunzip(File.binread(seen_path))).to eq("streamed data")
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: StringIO.new("streamed data"), compress: :gzip)
+        expect(File.exist?(seen_path)).to be(false)
+      end
+
+      it "rejects other compression formats" do
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data", compress: :zstd)
+        end.to raise_error(ArgumentError, /:compress must be :gzip/)
+      end
+    end
+
+    describe "#put_object — object headers" do
+      let(:success_result) do
+        { status_code: 200, headers: {}, body: nil, checksum_validated: nil }
+      end
+
+      it "passes header options through to the native method" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:cache_control]).to eq("max-age=60")
+          expect(params[:content_disposition]).to eq('attachment; filename="a.txt"')
+          expect(params[:content_encoding]).to eq("gzip")
+          expect(params[:expires]).to eq("Wed, 21 Oct 2015 07:28:00 GMT")
+          success_result
+        end
+
+        client.put_object(
+          bucket: "b", key: "k", body: "data",
+          cache_control: "max-age=60",
+          content_disposition: 'attachment; filename="a.txt"',
+          content_encoding: "gzip",
+          expires: "Wed, 21 Oct 2015 07:28:00 GMT"
+        )
+      end
+
+      it "formats a Time :expires as an HTTP date" do
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:expires]).to eq("Wed, 21 Oct 2015 07:28:00 GMT")
+          success_result
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data",
+                          expires: Time.utc(2015, 10, 21, 7, 28, 0))
+      end
+
+      [
+        "Wed, 21 Oct 2015 24:00:00 GMT",
+        "Wed, 21 Oct 2015 07:60:00 GMT",
+        "Wed, 21 Oct 2015 07:28:61 GMT",
+        "Wed, 21 Oct 2015 -7:28:00 GMT",
+        "Wed, 21 Oct 2015 07:28:+0 GMT",
+        "Wed, 00 Oct 2015 07:28:00 GMT",
+        "Thu, 31 Apr 2015 07:28:00 GMT",
+        "Sun, 29 Feb 2015 07:28:00 GMT",
+        "Tue, 29 Feb 2100 07:28:00 GMT",
+        "Wed, 21 Oct 2015 07:28:00:00 GMT",
+        "Wed, 21 Oct 2015 07:28:00 GMT trailing"
+      ].each do |date|
+        it "rejects the malformed :expires #{date.inspect}" do
+          expect { client.put_object(bucket: "b", key: "k", body: "data", expires: date) }
+            .to raise_error(ArgumentError, /invalid :expires/)
+        end
+      end
+    end
+
+    describe "#get_object — range requests" do
+      let(:partial_result) do
+        { status_code: 206, headers: { "content-range" => "bytes 5-9/10" }, body: "56789",
+          checksum_validated: nil }
+      end
+
+      it "passes :range and :if_range through to the native method" do
+        expect(client).to receive(:_native_get_object) do |params|
+          expect(params[:range]).to eq("bytes=5-")
+          expect(params[:if_range]).to eq('"etag-1"')
+          partial_result
+        end
+
+        response = client.get_object(bucket: "b", key: "k", range: "bytes=5-", if_range: '"etag-1"')
+        expect(response).to be_partial
+      end
+
+      it "formats a Time :if_range as an HTTP date" do
+        expect(client).to receive(:_native_get_object) do |params|
+          expect(params[:if_range]).to eq("Wed, 21 Oct 2015 07:28:00 GMT")
+          partial_result
+        end
+
+        client.get_object(bucket: "b", key: "k", range: "bytes=5-",
+                          if_range: Time.utc(2015, 10, 21, 7, 28, 0))
+      end
+
+      it "surfaces a 200 when the object changed and was returned in full" do
+        allow(client).to receive(:_native_get_object)
+          .and_return({ status_code: 200, headers: {}, body: "0123456789", checksum_validated: nil })
+
+        response = client.get_object(bucket: "b", key: "k", range: "bytes=5-", if_range: '"stale"')
+        expect(response.status_code).to eq(200)
+        expect(response).not_to be_partial
+      end
+
+      it "rejects :if_range without :range" do
+        expect { client.get_object(bucket: "b", key: "k", if_range: '"etag-1"') }
+          .to raise_error(ArgumentError, /:if_range requires :range/)
+      end
+    end
+
     describe "#put_object — response building" do
       it "returns a Response with status_code and headers" do
         result = {
@@ -412,6 +1303,39 @@ RSpec.describe AwsCrt::S3::Client do
       end
     end
 
+    describe "#put_object — upload review" do
+      it "passes the hook through to the native method" do
+        hook = ->(_review) { true }
+        expect(client).to receive(:_native_put_object) do |params|
+          expect(params[:on_upload_review]).to be(hook)
+          { status_code: 200, headers: {}, body: nil, checksum_validated: nil }
+        end
+
+        client.put_object(bucket: "b", key: "k", body: "data", on_upload_review: hook)
+      end
+
+      it "raises UploadRejectedError when the hook rejects the upload" do
+        allow(client).to receive(:_native_put_object).and_return(
+          error: true, error_code: 14_347, status_code: 0, headers: {}, body: "",
+          upload_rejected: true
+        )
+
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data", on_upload_review: ->(_) { false })
+        end.to raise_error(AwsCrt::S3::UploadRejectedError)
+      end
+
+      it "raises ArgumentError when the hook is not callable" do
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data", on_upload_review: "yes")
+        end.to raise_error(ArgumentError, /:on_upload_review must respond to #call/)
+      end
+
+      it "is an AwsCrt::S3::Error" do
+        expect(AwsCrt::S3::UploadRejectedError.superclass).to eq(AwsCrt::S3::Error)
+      end
+    end
+
     describe "#put_object — IO tempfile spilling" do
       let(:success_result) do
         { status_code: 200, headers: {}, body: nil, checksum_validated: nil }
@@ -540,14 +1464,118 @@ RSpec.describe AwsCrt::S3::Client do
           io_tempfile_threshold: 100
         )
 
-        body = StringIO.new("x" * 99)
+        body = StringIO.new("x" * 99)
+
+        allow(custom_client).to receive(:_native_put_object) do |params|
+          expect(params[:body]).to be(body)
+          success_result
+        end
+
+        custom_client.put_object(bucket: "b", key: "k", body: body)
+      end
+    end
+
+    describe "#get_objects" do
+      let(:success_result) do
+        { status_code: 200, headers: {}, body: "data", checksum_validated: nil }
+      end
+
+      it "passes unique keys and the concurrency cap to the native method" do
+        expect(client).to receive(:_native_get_objects) do |params|
+          expect(params[:bucket]).to eq("b")
+          expect(params[:keys]).to eq(%w[a b])
+          expect(params[:max_concurrency]).to eq(4)
+          expect(params[:_access_key_id]).to eq("AKID")
+          { "a" => success_result, "b" => success_result }
+        end
+
+        client.get_objects(bucket: "b", keys: %w[a b a], max_concurrency: 4)
+      end
+
+      it "fetches a repeated key once when called natively" do
+        server = TCPServer.new("127.0.0.1", 0)
+        paths = Queue.new
+        server_thread = Thread.new do
+          loop do
+            Thread.new(server.accept) do |socket|
+              while (request_line = socket.gets)
+                paths << request_line.split[1]
+                nil until socket.gets == "\r\n"
+                socket.write("HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\ndata")
+              end
+            ensure
+              socket.close
+            end
+          end
+        end
+        creds = AwsCrt::S3::Credentials.new(access_key_id: "AKID", secret_access_key: "secret")
+        native = described_class.new(endpoint: "http://127.0.0.1:#{server.addr[1]}", credentials: creds)
+
+        results = native._native_get_objects(bucket: "b", keys: %w[a b a], _access_key_id: "AKID",
+                                             _secret_access_key: "secret")
+
+        expect(results.keys).to eq(%w[a b])
+        expect(Array.new(paths.size) { paths.pop }.sort).to eq(%w[/b/a /b/b])
+      ensure
+        server_thread&.kill
+        server&.close
+      end
+
+      it "passes the bucket owner and request payer to the native method" do
+        expect(client).to receive(:_native_get_objects) do |params|
+          expect(params[:expected_bucket_owner]).to eq("111122223333")
+          expect(params[:request_payer]).to eq("requester")
+          {}
+        end
+
+        client.get_objects(bucket: "b", keys: [], expected_bucket_owner: "111122223333", request_payer: "requester")
+      end
+
+      it "defaults max_concurrency" do
+        expect(client).to receive(:_native_get_objects) do |params|
+          expect(params[:max_concurrency]).to eq(described_class::DEFAULT_GET_OBJECTS_CONCURRENCY)
+          {}
+        end
+
+        client.get_objects(bucket: "b", keys: [])
+      end
+
+      it "returns a Response for each successful key" do
+        allow(client).to receive(:_native_get_objects).and_return(
+          "a" => success_result, "b" => success_result.merge(body: "other")
+        )
+
+        results = client.get_objects(bucket: "b", keys: %w[a b])
+        expect(results.keys).to eq(%w[a b])
+        expect(results["a"]).to be_a(AwsCrt::S3::Response)
+        expect(results["b"].body).to eq("other")
+      end
 
-        allow(custom_client).to receive(:_native_put_object) do |params|
-          expect(params[:body]).to be(body)
-          success_result
-        end
+      it "maps failed keys to errors without raising" do
+        allow(client).to receive(:_native_get_objects).and_return(
+          "a" => success_result,
+          "missing" => { error: true, error_code: 0, status_code: 404, headers: {},
+                         body: "<Error><Code>NoSuchKey</Code></Error>" },
+          "down" => { error: true, error_code: 1029, status_code: 0, headers: {}, body: "" }
+        )
 
-        custom_client.put_object(bucket: "b", key: "k", body: body)
+        results = client.get_objects(bucket: "b", keys: %w[a missing down])
+        expect(results["a"]).to be_a(AwsCrt::S3::Response)
+        expect(results["missing"]).to be_a(AwsCrt::S3::ServiceError)
+        expect(results["missing"].status_code).to eq(404)
+        expect(results["down"]).to be_a(AwsCrt::S3::NetworkError)
+      end
+
+      it "rejects non-String keys" do
+        expect do
+          client.get_objects(bucket: "b", keys: [:a])
+        end.to raise_error(ArgumentError, /:keys must be an Array of Strings/)
+      end
+
+      it "rejects a non-positive max_concurrency" do
+        expect do
+          client.get_objects(bucket: "b", keys: ["a"], max_concurrency: 0)
+        end.to raise_error(ArgumentError, /:max_concurrency must be a positive Integer/)
       end
     end
 
@@ -569,6 +1597,14 @@ RSpec.describe AwsCrt::S3::Client do
         expect(response.status_code).to eq(200)
       end
 
+      it "keeps an empty buffered body as an empty String" do
+        allow(client).to receive(:_native_get_object)
+          .and_return(success_result.merge(body: ""))
+
+        response = client.get_object(bucket: "b", key: "k")
+        expect(response.body).to eq("")
+      end
+
       it "streams tempfile to IO response_target without buffering entire body" do
         # When response_target is an IO, the client creates a tempfile,
         # passes its path to the CRT (recv_filepath), then streams the
@@ -668,6 +1704,72 @@ RSpec.describe AwsCrt::S3::Client do
       end
     end
 
+    describe "#get_object — parsed metadata" do
+      it "copies last_modified, content_length, and storage_class onto the Response" do
+        time = Time.utc(2015, 10, 21, 7, 28, 0)
+        result = {
+          status_code: 200,
+          headers: { "last-modified" => "Wed, 21 Oct 2015 07:28:00 GMT", "content-length" => "5" },
+          body: "hello",
+          checksum_validated: nil,
+          last_modified: time,
+          content_length: 5,
+          storage_class: "STANDARD_IA"
+        }
+        allow(client).to receive(:_native_get_object).and_return(result)
+
+        response = client.get_object(bucket: "b", key: "k")
+        expect(response.last_modified).to eq(time)
+        expect(response.content_length).to eq(5)
+        expect(response.storage_class).to eq("STANDARD_IA")
+      end
+
+      it "copies clock_skew_ms onto the Response" do
+        result = { status_code: 200, headers: {}, body: "", checksum_validated: nil, clock_skew_ms: -1_250 }
+        allow(client).to receive(:_native_get_object).and_return(result)
+
+        expect(client.get_object(bucket: "b", key: "k").clock_skew_ms).to eq(-1_250)
+      end
+
+      it "copies the parsed Content-Range fields onto the Response" do
+        result = {
+          status_code: 206,
+          headers: { "content-range" => "bytes 0-1023/4096" },
+          body: "x" * 1024,
+          checksum_validated: nil,
+          range_start: 0,
+          range_end: 1023,
+          total_size: 4096
+        }
+        allow(client).to receive(:_native_get_object).and_return(result)
+
+        response = client.get_object(bucket: "b", key: "k", range: "bytes=0-1023")
+        expect(response.range_start).to eq(0)
+        expect(response.range_end).to eq(1023)
+        expect(response.total_size).to eq(4096)
+      end
+
+      it "copies the user metadata onto the Response" do
+        result = {
+          status_code: 200,
+          headers: { "x-amz-meta-author" => "jane" },
+          body: "hello",
+          checksum_validated: nil,
+          metadata: { "author" => "jane" }
+        }
+        allow(client).to receive(:_native_get_object).and_return(result)
+
+        expect(client.get_object(bucket: "b", key: "k").metadata).to eq({ "author" => "jane" })
+      end
+
+      it "defaults metadata to an empty Hash when the native result omits it" do
+        allow(client).to receive(:_native_get_object)
+          .and_return(status_code: 200, headers: {}, body: "", checksum_validated: nil)
+
+        expect(client.get_object(bucket: "b", key: "k").metadata).to eq({})
+      end
+    end
+
     describe "#get_object — error translation" do
       it "raises ServiceError for HTTP error responses" do
         error_result = {
@@ -702,5 +1804,545 @@ RSpec.describe AwsCrt::S3::Client do
         end.to raise_error(AwsCrt::S3::NetworkError)
       end
     end
+
+    describe "#get_object — checksum mismatch" do
+      let(:mismatch_result) do
+        {
+          error: true,
+          error_code: 14_350,
+          status_code: 0,
+          headers: {},
+          body: "",
+          checksum_algorithm: "CRC32",
+          expected_checksum: "AAAAAA==",
+          computed_checksum: "NbKjSA=="
+        }
+      end
+
+      it "raises ChecksumMismatchError with both checksums" do
+        allow(client).to receive(:_native_get_object).and_return(mismatch_result)
+
+        expect do
+          client.get_object(bucket: "b", key: "k", checksum_mode: "ENABLED")
+        end.to raise_error(AwsCrt::S3::ChecksumMismatchError, /expected AAAAAA==, computed NbKjSA==/) { |e|
+          expect(e.algorithm).to eq("CRC32")
+          expect(e.expected_checksum).to eq("AAAAAA==")
+          expect(e.computed_checksum).to eq("NbKjSA==")
+          expect(e).to be_a(AwsCrt::S3::NetworkError)
+        }
+      end
+
+      it "says when the computed checksum is unavailable" do
+        mismatch_result[:computed_checksum] = nil
+        allow(client).to receive(:_native_get_object).and_return(mismatch_result)
+
+        expect do
+          client.get_object(bucket: "b", key: "k", checksum_mode: "ENABLED")
+        end.to raise_error(AwsCrt::S3::ChecksumMismatchError, /computed unavailable/)
+      end
+    end
+
+    describe "#get_object — verify_etag" do
+      let(:md5) { Digest::MD5.hexdigest("hello world") }
+
+      def etag_result(etag, headers = {})
+        { status_code: 200, headers: headers.merge("ETag" => %("#{etag}")), body: "hello world",
+          checksum_validated: nil }
+      end
+
+      it "accepts a buffered body whose MD5 matches the ETag" do
+        allow(client).to receive(:_native_get_object).and_return(etag_result(md5.upcase))
+
+        expect(client.get_object(bucket: "b", key: "k", verify_etag: true).body).to eq("hello world")
+      end
+
+      it "raises EtagMismatchError when the body does not match" do
+        allow(client).to receive(:_native_get_object).and_return(etag_result("0" * 32))
+
+        expect do
+          client.get_object(bucket: "b", key: "k", verify_etag: true)
+        end.to raise_error(AwsCrt::S3::EtagMismatchError, /expected 0{32}, computed MD5 #{md5}/) { |e|
+          expect(e.expected_etag).to eq("0" * 32)
+          expect(e.computed_md5).to eq(md5)
+        }
+      end
+
+      it "reads a response_target file back from disk" do
+        Dir.mktmpdir do |dir|
+          path = File.join(dir, "out")
+          allow(client).to receive(:_native_get_object) do |params|
+            File.write(params[:response_target], "hello wor") # truncated write
+            etag_result(md5).merge(body: nil)
+          end
+
+          expect do
+            client.get_object(bucket: "b", key: "k", response_target: path, verify_etag: true)
+          end.to raise_error(AwsCrt::S3::EtagMismatchError)
+        end
+      end
+
+      it "checks an IO target before copying to it" do
+        allow(client).to receive(:_native_get_object) do |params|
+          File.write(params[:response_target], "corrupt")
+          etag_result(md5).merge(body: nil)
+        end
+        io = StringIO.new
+
+        expect do
+          client.get_object(bucket: "b", key: "k", response_target: io, verify_etag: true)
+        end.to raise_error(AwsCrt::S3::EtagMismatchError)
+        expect(io.string).to eq("")
+      end
+
+      it "skips ETags that are not an MD5 of the body" do
+        [
+          etag_result("#{"0" * 32}-3"),
+          etag_result("0" * 32, "x-amz-server-side-encryption" => "aws:kms"),
+          etag_result("0" * 32, "x-amz-server-side-encryption-customer-algorithm" => "AES256"),
+          etag_result("0" * 32).merge(status_code: 206)
+        ].each do |result|
+          allow(client).to receive(:_native_get_object).and_return(result)
+
+          expect(client.get_object(bucket: "b", key: "k", verify_etag: true).body).to eq("hello world")
+        end
+      end
+
+      it "does nothing without verify_etag" do
+        allow(client).to receive(:_native_get_object).and_return(etag_result("0" * 32))
+
+        expect(client.get_object(bucket: "b", key: "k").body).to eq("hello world")
+      end
+    end
+
+    describe "#put_object — verify_checksum" do
+      it "raises ChecksumMismatchError when the stored checksum differs" do
+        allow(client).to receive(:_native_put_object).and_return(
+          error: true, error_code: 0, status_code: 200, headers: { "x-amz-checksum-crc32" => "AAAAAA==" },
+          body: "".b, checksum_algorithm: "CRC32", expected_checksum: "AAAAAA==", computed_checksum: "NbKjSA=="
+        )
+
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data", verify_checksum: "CRC32")
+        end.to raise_error(AwsCrt::S3::ChecksumMismatchError) { |e|
+          expect(e.expected_checksum).to eq("AAAAAA==")
+          expect(e.computed_checksum).to eq("NbKjSA==")
+        }
+      end
+
+      it "reports the verified algorithm" do
+        allow(client).to receive(:_native_put_object).and_return(
+          status_code: 200, headers: {}, body: nil, checksum_validated: "CRC32"
+        )
+
+        response = client.put_object(bucket: "b", key: "k", body: "data", verify_checksum: "CRC32")
+        expect(response.checksum_validated).to eq("CRC32")
+      end
+
+      it "rejects verify_checksum combined with checksum_algorithm" do
+        expect do
+          client.put_object(bucket: "bucket", key: "k", body: "x", verify_checksum: "CRC32",
+                            checksum_algorithm: "CRC32")
+        end.to raise_error(ArgumentError, /cannot be combined/)
+      end
+
+      it "rejects an unknown verify_checksum algorithm" do
+        expect { client.put_object(bucket: "bucket", key: "k", body: "x", verify_checksum: "MD5") }
+          .to raise_error(ArgumentError, /invalid verify_checksum/)
+      end
+    end
+
+    describe "#put_object — max_parts" do
+      it "rejects max_parts outside 1..10,000" do
+        expect { client.put_object(bucket: "bucket", key: "k", body: "x", max_parts: 0) }
+          .to raise_error(ArgumentError, /max_parts must be between 1 and 10000/)
+        expect { client.put_object(bucket: "bucket", key: "k", body: "x", max_parts: 10_001) }
+          .to raise_error(ArgumentError, /max_parts/)
+      end
+
+      it "raises before uploading when no part size S3 accepts would fit" do
+        expect do
+          client.put_object(bucket: "bucket", key: "k", body: "x", content_length: 6 * 1024**3, max_parts: 1)
+        end.to raise_error(ArgumentError, /cannot be split into 1 parts.*raise :max_parts/)
+      end
+    end
+
+    describe "manual multipart upload" do
+      let(:created) do
+        { status_code: 200, headers: {},
+          body: "<InitiateMultipartUploadResult><UploadId>abc+def/1</UploadId></InitiateMultipartUploadResult>".b }
+      end
+
+      it "creates an upload with POST ?uploads and returns the upload ID" do
+        expect(client).to receive(:_native_make_request) do |params|
+          expect(params).to include(bucket: "bucket", key: "k", method: "POST",
+                                     operation_name: "CreateMultipartUpload", query: "uploads",
+                                     headers: { "Content-Type" => "text/plain" })
+          created
+        end
+
+        expect(client.create_multipart_upload(bucket: "bucket", key: "k", content_type: "text/plain"))
+          .to eq("abc+def/1")
+      end
+
+      it "uploads a part with PUT and returns its ETag" do
+        expect(client).to receive(:_native_make_request) do |params|
+          expect(params).to include(method: "PUT", operation_name: "UploadPart", body: "part data",
+                                     query: "partNumber=3&uploadId=abc%2Bdef%2F1")
+          { status_code: 200, headers: { "ETag" => '"etag-3"' }, body: "".b }
+        end
+
+        etag = client.upload_part(bucket: "bucket", key: "k", upload_id: "abc+def/1", part_number: 3,
+                                  body: StringIO.new("part data"))
+        expect(etag).to eq('"etag-3"')
+      end
+
+      it "rejects part numbers outside 1..10,000 and a missing upload ID" do
+        expect { client.upload_part(bucket: "bucket", key: "k", upload_id: "u", part_number: 0, body: "x") }
+          .to raise_error(ArgumentError, /part_number/)
+        expect { client.upload_part(bucket: "bucket", key: "k", part_number: 1, body: "x") }
+          .to raise_error(ArgumentError, /upload_id/)
+      end
+
+      it "completes the upload with the parts in order" do
+        expect(client).to receive(:_native_make_request) do |params|
+          expect(params).to include(method: "POST", operation_name: "CompleteMultipartUpload", query: "uploadId=u1")
+          expect(params[:body]).to eq(
+            "<CompleteMultipartUpload>" \
+            "<Part><PartNumber>1</PartNumber><ETag>\"a\"</ETag></Part>" \
+            "<Part><PartNumber>2</PartNumber><ETag>\"b&amp;\"</ETag></Part>" \
+            "</CompleteMultipartUpload>"
+          )
+          { status_code: 200, headers: {}, body: "<CompleteMultipartUploadResult/>".b }
+        end
+
+        response = client.complete_multipart_upload(
+          bucket: "bucket", key: "k", upload_id: "u1",
+          parts: [{ part_number: 2, etag: '"b&"' }, { part_number: 1, etag: '"a"' }]
+        )
+        expect(response.status_code).to eq(200)
+      end
+
+      it "raises for an error document returned with a 200" do
+        allow(client).to receive(:_native_make_request).and_return(
+          status_code: 200, headers: {},
+          body: "<Error><Code>InvalidPart</Code><Message>One or more parts could not be found</Message></Error>".b
+        )
+
+        expect do
+          client.complete_multipart_upload(bucket: "bucket", key: "k", upload_id: "u1",
+                                           parts: [{ part_number: 1, etag: "a" }])
+        end.to raise_error(AwsCrt::S3::ServiceError, /InvalidPart/)
+      end
+
+      it "rejects an empty part list" do
+        expect { client.complete_multipart_upload(bucket: "bucket", key: "k", upload_id: "u1", parts: []) }
+          .to raise_error(ArgumentError, /:parts/)
+      end
+
+      it "aborts the upload with DELETE" do
+        expect(client).to receive(:_native_make_request)
+          .with(hash_including(method: "DELETE", operation_name: "AbortMultipartUpload", query: "uploadId=u1"))
+          .and_return(status_code: 204, headers: {}, body: "".b)
+
+        expect(client.abort_multipart_upload(bucket: "bucket", key: "k", upload_id: "u1").status_code).to eq(204)
+      end
+
+      it "raises a ServiceError for a failed request" do
+        allow(client).to receive(:_native_make_request).and_return(
+          error: true, error_code: 0, status_code: 404, headers: {},
+          body: "<Error><Code>NoSuchUpload</Code></Error>".b
+        )
+
+        expect { client.abort_multipart_upload(bucket: "bucket", key: "k", upload_id: "u1") }
+          .to raise_error(AwsCrt::S3::ServiceError, /NoSuchUpload/)
+      end
+    end
+
+    describe "#pipe_object" do
+      let(:part_size) { AwsCrt::S3::Client::MIN_PART_SIZE }
+      let(:source) { { bucket: "in", key: "src", range: "bytes=0-9" } }
+      let(:destination) { { bucket: "out", key: "dst", content_type: "application/octet-stream", tagging: {} } }
+      let(:success) { { status_code: 200, headers: { "ETag" => '"d1"' } } }
+
+      it "streams through one native call with credentials injected on each side" do
+        transform = proc { |chunk| chunk&.upcase }
+        expect(client).to receive(:_native_pipe_object) do |params|
+          expect(params[:source]).to include(bucket: "in", key: "src", _access_key_id: "AKID")
+          expect(params[:source]).not_to have_key(:range)
+          expect(params[:destination]).to include(bucket: "out", key: "dst",
+                                                  content_type: "application/octet-stream")
+          expect(params[:destination]).not_to have_key(:tagging)
+          expect(params[:part_size]).to eq(part_size)
+          expect(params[:transform]).to be(transform)
+          success
+        end
+
+        response = client.pipe_object(source: source, destination: destination, part_size: part_size, &transform)
+
+        expect(response.status_code).to eq(200)
+        expect(response.body).to be_nil
+      end
+
+      it "passes no transform without a block" do
+        expect(client).to receive(:_native_pipe_object).with(hash_including(transform: nil)).and_return(success)
+
+        client.pipe_object(source: source, destination: destination)
+      end
+
+      it "raises the error of whichever side failed" do
+        allow(client).to receive(:_native_pipe_object).and_return(
+          error: true, error_code: 0, status_code: 404, headers: {},
+          body: "<Error><Code>NoSuchKey</Code></Error>".b
+        )
+
+        expect { client.pipe_object(source: source, destination: destination) }
+          .to raise_error(AwsCrt::S3::ServiceError, /NoSuchKey/)
+      end
+
+      it "rejects a part_size below S3's minimum" do
+        expect(client).not_to receive(:_native_pipe_object)
+
+        expect { client.pipe_object(source: source, destination: destination, part_size: 1024) }
+          .to raise_error(ArgumentError, /part_size/)
+      end
+    end
+
+    describe "telemetry" do
+      let(:telemetry) do
+        {
+          parts: 2, retries: 1, connections: 2, bytes: 16, duration_ms: 12.5,
+          requests: [
+            { operation: "GetObject", status_code: 200, error_code: 0, bytes: 8, start_ms: 0.0, duration_ms: 4.0 },
+            { operation: "GetObject", status_code: 503, error_code: 14_364, bytes: nil, start_ms: 0.5,
+              duration_ms: 2.0 },
+            { operation: "GetObject", status_code: 200, error_code: 0, bytes: 8, start_ms: 3.0, duration_ms: 9.5 }
+          ]
+        }
+      end
+
+      it "passes the option through and exposes the summary" do
+        expect(client).to receive(:_native_get_object)
+          .with(hash_including(telemetry: true))
+          .and_return(status_code: 200, headers: {}, body: "data", telemetry: telemetry)
+
+        response = client.get_object(bucket: "b", key: "k", telemetry: true)
+        expect(response.telemetry[:parts]).to eq(2)
+        expect(response.telemetry[:retries]).to eq(1)
+        expect(response.telemetry[:requests].length).to eq(3)
+      end
+
+      it "is nil when not requested" do
+        allow(client).to receive(:_native_put_object).and_return(status_code: 200, headers: {}, body: nil)
+
+        expect(client.put_object(bucket: "b", key: "k", body: "data").telemetry).to be_nil
+      end
+    end
+
+    describe "per-part checksums" do
+      it "exposes the parts reported for a multipart upload" do
+        parts = [
+          { part_number: 1, size: 8_388_608, checksum: "2Q/Mfw==" },
+          { part_number: 2, size: 1024, checksum: "AAAAAA==" }
+        ]
+        expect(client).to receive(:_native_put_object)
+          .with(hash_including(checksum_algorithm: "CRC32"))
+          .and_return(status_code: 200, headers: { "x-amz-checksum-crc32" => "Hd0tpQ==-2" }, body: nil, parts: parts)
+
+        response = client.put_object(bucket: "b", key: "k", body: "data", checksum_algorithm: "CRC32")
+        expect(response.parts).to eq(parts)
+        expect(response.composite_checksum).to eq("Hd0tpQ==-2")
+      end
+
+      it "has no composite checksum for a full-object checksum" do
+        allow(client).to receive(:_native_put_object)
+          .and_return(status_code: 200, headers: { "x-amz-checksum-crc32" => "Hd0tpQ==" }, body: nil)
+
+        expect(client.put_object(bucket: "b", key: "k", body: "data").composite_checksum).to be_nil
+      end
+
+      it "is nil when the native result has no parts" do
+        allow(client).to receive(:_native_put_object).and_return(status_code: 200, headers: {}, body: nil)
+
+        expect(client.put_object(bucket: "b", key: "k", body: "data").parts).to be_nil
+      end
+    end
+
+    describe "region redirects" do
+      let(:redirect_result) do
+        {
+          error: true,
+          error_code: 14_343,
+          status_code: 301,
+          headers: { "x-amz-bucket-region" => "eu-west-1" },
+          body: "<Error><Code>PermanentRedirect</Code></Error>"
+        }
+      end
+      let(:success_result) { { status_code: 200, headers: {}, body: "ok", checksum_validated: nil } }
+
+      it "raises WrongRegionError naming the bucket's region" do
+        allow(client).to receive(:_native_get_object).and_return(redirect_result)
+
+        expect do
+          client.get_object(bucket: "b", key: "k")
+        end.to raise_error(AwsCrt::S3::WrongRegionError, /region eu-west-1.*us-east-1/) { |e|
+          expect(e.region).to eq("eu-west-1")
+          expect(e.status_code).to eq(301)
+          expect(e).to be_a(AwsCrt::S3::ServiceError)
+        }
+      end
+
+      it "matches the header name case-insensitively" do
+        redirect_result[:headers] = { "X-Amz-Bucket-Region" => "ap-south-1" }
+        redirect_result[:status_code] = 307
+        allow(client).to receive(:_native_put_object).and_return(redirect_result)
+
+        expect do
+          client.put_object(bucket: "b", key: "k", body: "data")
+        end.to raise_error(AwsCrt::S3::WrongRegionError) { |e| expect(e.region).to eq("ap-south-1") }
+      end
+
+      it "keeps a plain ServiceError when the header names the client's own region" do
+        redirect_result.merge!(error_code: 0, status_code: 400, headers: { "x-amz-bucket-region" => "us-east-1" })
+        allow(client).to receive(:_native_get_object).and_return(redirect_result)
+
+        expect do
+          client.get_object(bucket: "b", key: "k")
+        end.to raise_error(AwsCrt::S3::ServiceError) { |e| expect(e).not_to be_a(AwsCrt::S3::WrongRegionError) }
+      end
+
+      context "with follow_region_redirect: true" do
+        let(:client) do
+          allow_any_instance_of(described_class).to receive(:_native_initialize)
+          described_class.new(region: "us-east-1", access_key_id: "AKID", secret_access_key: "secret",
+                              follow_region_redirect: true)
+        end
+        let(:regional) { instance_double(described_class) }
+
+        before do
+          client
+          allow(described_class).to receive(:new)
+            .with(hash_including(region: "eu-west-1", follow_region_redirect: false))
+            .and_return(regional)
+        end
+
+        it "retries get_object once on a client for the bucket's region" do
+          allow(client).to receive(:_native_get_object).and_return(redirect_result)
+          response = AwsCrt::S3::Response.new(status_code: 200, headers: {}, body: "ok")
+          expect(regional).to receive(:get_object).with({ bucket: "b", key: "k" }).twice.and_return(response)
+
+          expect(client.get_object(bucket: "b", key: "k")).to equal(response)
+          client.get_object(bucket: "b", key: "k")
+          expect(described_class).to have_received(:new).once
+        end
+
+        it "rewinds an IO body before retrying put_object" do
+          body = StringIO.new("data")
+          allow(client).to receive(:_native_put_object) do |params|
+            params[:body].read
+            redirect_result
+          end
+          expect(regional).to receive(:put_object) do |params|
+            expect(params[:body].read).to eq("data")
+          end
+
+          client.put_object(bucket: "b", key: "k", body: body)
+        end
+
+        it "refetches only the redirected keys of a get_objects batch" do
+          allow(client).to receive(:_native_get_objects).and_return(
+            "a" => success_result, "b" => redirect_result
+          )
+          moved = AwsCrt::S3::Response.new(status_code: 200, headers: {}, body: "moved")
+          expect(regional).to receive(:get_objects)
+            .with(hash_including(bucket: "bkt", keys: ["b"])).and_return("b" => moved)
+
+          results = client.get_objects(bucket: "bkt", keys: %w[a b])
+          expect(results.keys).to eq(%w[a b])
+          expect(results["b"]).to equal(moved)
+        end
+      end
+    end
+
+    describe "Retry-After" do
+      let(:throttled_result) do
+        {
+          error: true,
+          error_code: 0,
+          status_code: 503,
+          headers: { "Retry-After" => "2" },
+          body: "<Error><Code>SlowDown</Code></Error>"
+        }
+      end
+      let(:success_result) { { status_code: 200, headers: {}, body: "ok", checksum_validated: nil } }
+
+      it "raises without retrying by default" do
+        expect(client).to receive(:_native_get_object).once.and_return(throttled_result)
+
+        expect { client.get_object(bucket: "b", key: "k") }
+          .to raise_error(AwsCrt::S3::ServiceError) { |e| expect(e.status_code).to eq(503) }
+      end
+
+      context "with max_retry_after_ms:" do
+        let(:client) do
+          allow_any_instance_of(described_class).to receive(:_native_initialize)
+          described_class.new(region: "us-east-1", access_key_id: "AKID", secret_access_key: "secret",
+                              max_retry_after_ms: 5_000)
+        end
+
+        before { allow(client).to receive(:sleep) }
+
+        it "waits the delay-seconds and retries once" do
+          allow(client).to receive(:_native_get_object).and_return(throttled_result, success_result)
+
+          expect(client.get_object(bucket: "b", key: "k").body).to eq("ok")
+          expect(client).to have_received(:sleep).with(2)
+        end
+
+        it "waits until an HTTP date" do
+          throttled_result[:status_code] = 429
+          throttled_result[:headers] = { "retry-after" => (Time.now + 3).httpdate }
+          allow(client).to receive(:_native_put_object).and_return(throttled_result, success_result)
+
+          client.put_object(bucket: "b", key: "k", body: "data")
+          expect(client).to have_received(:sleep).with(be_between(1, 3))
+        end
+
+        it "caps the wait at max_retry_after_ms" do
+          throttled_result[:headers] = { "Retry-After" => "3600" }
+          allow(client).to receive(:_native_get_object).and_return(throttled_result, success_result)
+
+          client.get_object(bucket: "b", key: "k")
+          expect(client).to have_received(:sleep).with(5.0)
+        end
+
+        it "retries only once" do
+          expect(client).to receive(:_native_get_object).twice.and_return(throttled_result)
+
+          expect { client.get_object(bucket: "b", key: "k") }.to raise_error(AwsCrt::S3::ServiceError)
+        end
+
+        it "does not retry without a Retry-After header or on other statuses" do
+          throttled_result[:headers] = {}
+          expect(client).to receive(:_native_get_object).once.and_return(throttled_result)
+          expect { client.get_object(bucket: "b", key: "k") }.to raise_error(AwsCrt::S3::ServiceError)
+
+          throttled_result.merge!(status_code: 500, headers: { "Retry-After" => "1" })
+          expect(client).to receive(:_native_put_object).once.and_return(throttled_result)
+          expect { client.put_object(bucket: "b", key: "k", body: "data") }.to raise_error(AwsCrt::S3::ServiceError)
+          expect(client).not_to have_received(:sleep)
+        end
+
+        it "rewinds an IO body before retrying put_object" do
+          bodies = []
+          allow(client).to receive(:_native_put_object) do |params|
+            bodies << params[:body].read
+            bodies.size == 1 ? throttled_result : success_result
+          end
+
+          client.put_object(bucket: "b", key: "k", body: StringIO.new("data"))
+          expect(bodies).to eq(%w[data data])
+        end
+      end
+    end
   end
 end
@@ -106,6 +106,24 @@ RSpec.describe "AwsCrt::S3 error hierarchy" do
     end
   end
 
+  describe "AwsCrt::S3::WrongRegionError" do
+    it "inherits from AwsCrt::S3::ServiceError" do
+      expect(AwsCrt::S3::WrongRegionError.superclass).to eq(AwsCrt::S3::ServiceError)
+    end
+
+    it "exposes the bucket's region alongside the response details" do
+      error = AwsCrt::S3::WrongRegionError.new(
+        "wrong region",
+        region: "eu-west-1",
+        status_code: 301,
+        headers: { "x-amz-bucket-region" => "eu-west-1" },
+        error_body: ""
+      )
+      expect(error.region).to eq("eu-west-1")
+      expect(error.status_code).to eq(301)
+    end
+  end
+
   describe "AwsCrt::S3::NetworkError" do
     it "inherits from AwsCrt::S3::Error" do
       expect(AwsCrt::S3::NetworkError.superclass).to eq(AwsCrt::S3::Error)
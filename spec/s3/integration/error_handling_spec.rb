@@ -98,6 +98,7 @@ RSpec.describe "S3 Error Handling integration", :integration do
         # error_body should contain S3's XML error response
         expect(e.error_body).to be_a(String)
         expect(e.error_body).not_to be_empty
+        expect(e.error_body.encoding).to eq(Encoding::BINARY)
       end
     end
   end
@@ -73,6 +73,27 @@ RSpec.describe "S3 GetObject integration", :integration do
       expect(response.headers).to be_a(Hash)
       expect(response.headers).not_to be_empty
     end
+
+    it "returns an empty String, not nil, for a zero-byte object" do
+      empty_key = "#{@test_key}_empty"
+      @client.put_object(bucket: @bucket, key: empty_key, body: "")
+
+      response = @client.get_object(bucket: @bucket, key: empty_key)
+
+      expect(response.status_code).to eq(200)
+      expect(response.body).to eq("")
+    end
+
+    it "round-trips arbitrary bytes as an ASCII-8BIT String" do
+      binary_key = "#{@test_key}_binary"
+      bytes = ((0..255).to_a.pack("C*") * 4) + "\xff\xfe\xc3\x28".b
+      @client.put_object(bucket: @bucket, key: binary_key, body: bytes)
+
+      response = @client.get_object(bucket: @bucket, key: binary_key)
+
+      expect(response.body.encoding).to eq(Encoding::BINARY)
+      expect(response.body.bytes).to eq(bytes.bytes)
+    end
   end
 
   describe "file path mode" do
@@ -127,6 +148,23 @@ RSpec.describe "S3 GetObject integration", :integration do
     end
   end
 
+  describe "batched mode" do
+    it "fetches several objects concurrently and reports missing keys" do
+      keys = Array.new(3) { |i| "#{@test_key}_batch_#{i}" }
+      keys.each { |key| @client.put_object(bucket: @bucket, key: key, body: "body of #{key}") }
+      missing = "#{@test_key}_batch_missing"
+
+      results = @client.get_objects(bucket: @bucket, keys: keys + [missing], max_concurrency: 2)
+
+      keys.each do |key|
+        expect(results[key]).to be_a(AwsCrt::S3::Response)
+        expect(results[key].body).to eq("body of #{key}")
+      end
+      expect(results[missing]).to be_a(AwsCrt::S3::ServiceError)
+      expect(results[missing].status_code).to eq(404)
+    end
+  end
+
   describe "response metadata" do
     it "includes response headers from S3" do
       response = @client.get_object(bucket: @bucket, key: @test_key)
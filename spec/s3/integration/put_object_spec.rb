@@ -8,6 +8,7 @@
 # - File body (send_filepath path)
 # - IO body (StringIO, read into buffer)
 # - content_length and content_type headers
+# - Cache-Control, Content-Disposition, Content-Encoding, Expires
 #
 # Requirements: 5.1, 5.2, 5.3, 5.7, 5.8
 #
@@ -160,6 +161,29 @@ RSpec.describe "S3 PutObject integration", :integration do
     end
   end
 
+  describe "object headers" do
+    it "stores Cache-Control, Content-Disposition, Content-Encoding, and Expires" do
+      key = new_test_key("object_headers")
+
+      @client.put_object(
+        bucket: @bucket,
+        key: key,
+        body: "asset",
+        cache_control: "public, max-age=31536000",
+        content_disposition: 'inline; filename="asset.txt"',
+        content_encoding: "identity",
+        expires: Time.utc(2030, 1, 1)
+      )
+
+      headers = @client.get_object(bucket: @bucket, key: key).headers
+                       .transform_keys(&:downcase)
+      expect(headers["cache-control"]).to eq("public, max-age=31536000")
+      expect(headers["content-disposition"]).to eq('inline; filename="asset.txt"')
+      expect(headers["content-encoding"]).to eq("identity")
+      expect(headers["expires"]).to eq("Tue, 01 Jan 2030 00:00:00 GMT")
+    end
+  end
+
   describe "response metadata" do
     it "includes response headers from S3" do
       key = new_test_key("metadata")
@@ -169,4 +193,57 @@ RSpec.describe "S3 PutObject integration", :integration do
       expect(response.headers).not_to be_empty
     end
   end
+
+  describe "upload review" do
+    let(:multipart_client) do
+      AwsCrt::S3::Client.new(
+        region: ENV.fetch("S3_REGION"),
+        credentials: AwsCrt::S3::Credentials.new(
+          access_key_id: ENV.fetch("AWS_ACCESS_KEY_ID"),
+          secret_access_key: ENV.fetch("AWS_SECRET_ACCESS_KEY"),
+          session_token: ENV.fetch("AWS_SESSION_TOKEN", nil)
+        ),
+        part_size: 5 * 1024 * 1024,
+        multipart_upload_threshold: 5 * 1024 * 1024
+      )
+    end
+    let(:body) { SecureRandom.random_bytes(11 * 1024 * 1024) }
+
+    it "presents the parts and checksums before completing a multipart upload" do
+      key = new_test_key("review_approved")
+      reviews = []
+
+      multipart_client.put_object(
+        bucket: @bucket, key: key, body: body, checksum_algorithm: "CRC32",
+        on_upload_review: ->(review) { reviews << review }
+      )
+
+      expect(reviews.size).to eq(1)
+      expect(reviews.first[:checksum_algorithm]).to eq("CRC32")
+      expect(reviews.first[:parts].sum { |part| part[:size] }).to eq(body.bytesize)
+      expect(reviews.first[:parts]).to all(include(checksum: a_kind_of(String)))
+      expect(get_body(key).bytesize).to eq(body.bytesize)
+    end
+
+    it "aborts the upload when the hook returns false" do
+      key = new_test_key("review_rejected")
+
+      expect do
+        multipart_client.put_object(bucket: @bucket, key: key, body: body, on_upload_review: ->(_) { false })
+      end.to raise_error(AwsCrt::S3::UploadRejectedError)
+
+      expect { multipart_client.get_object(bucket: @bucket, key: key) }
+        .to raise_error(AwsCrt::S3::ServiceError) { |e| expect(e.status_code).to eq(404) }
+    end
+
+    it "re-raises an exception from the hook after aborting the upload" do
+      key = new_test_key("review_raised")
+
+      expect do
+        multipart_client.put_object(
+          bucket: @bucket, key: key, body: body, on_upload_review: ->(_) { raise "denied" }
+        )
+      end.to raise_error(RuntimeError, "denied")
+    end
+  end
 end
@@ -35,6 +35,35 @@ RSpec.describe AwsCrt::S3::Response do
       response = described_class.new(status_code: 200, headers: {}, checksum_validated: "CRC32")
       expect(response.checksum_validated).to eq("CRC32")
     end
+
+    it "exposes last_modified" do
+      time = Time.utc(2015, 10, 21, 7, 28, 0)
+      response = described_class.new(status_code: 200, headers: {}, last_modified: time)
+      expect(response.last_modified).to eq(time)
+    end
+
+    it "exposes content_length" do
+      response = described_class.new(status_code: 200, headers: {}, content_length: 1024)
+      expect(response.content_length).to eq(1024)
+    end
+
+    it "exposes storage_class" do
+      response = described_class.new(status_code: 200, headers: {}, storage_class: "GLACIER")
+      expect(response.storage_class).to eq("GLACIER")
+    end
+
+    it "exposes the parsed Content-Range fields" do
+      response = described_class.new(status_code: 206, headers: {},
+                                      range_start: 0, range_end: 1023, total_size: 4096)
+      expect(response.range_start).to eq(0)
+      expect(response.range_end).to eq(1023)
+      expect(response.total_size).to eq(4096)
+    end
+
+    it "exposes metadata" do
+      response = described_class.new(status_code: 200, headers: {}, metadata: { "author" => "jane" })
+      expect(response.metadata).to eq({ "author" => "jane" })
+    end
   end
 
   describe "default values" do
@@ -47,6 +76,26 @@ RSpec.describe AwsCrt::S3::Response do
       response = described_class.new(status_code: 200, headers: {})
       expect(response.checksum_validated).to be_nil
     end
+
+    it "defaults the parsed metadata fields to nil" do
+      response = described_class.new(status_code: 200, headers: {})
+      expect(response.last_modified).to be_nil
+      expect(response.content_length).to be_nil
+      expect(response.storage_class).to be_nil
+      expect(response.range_start).to be_nil
+      expect(response.range_end).to be_nil
+      expect(response.total_size).to be_nil
+    end
+
+    it "defaults clock_skew_ms to nil" do
+      response = described_class.new(status_code: 200, headers: {})
+      expect(response.clock_skew_ms).to be_nil
+    end
+
+    it "defaults metadata to an empty Hash" do
+      response = described_class.new(status_code: 200, headers: {})
+      expect(response.metadata).to eq({})
+    end
   end
 
   describe "#successful?" do
@@ -85,4 +134,24 @@ RSpec.describe AwsCrt::S3::Response do
       expect(response.successful?).to be false
     end
   end
+
+  describe "#composite_checksum" do
+    it "returns a checksum header with a part-count suffix" do
+      response = described_class.new(
+        status_code: 200,
+        headers: { "x-amz-checksum-type" => "COMPOSITE", "x-amz-checksum-crc32c" => "yZRlqg==-3" }
+      )
+      expect(response.composite_checksum).to eq("yZRlqg==-3")
+    end
+
+    it "returns nil for a full-object checksum" do
+      response = described_class.new(status_code: 200, headers: { "x-amz-checksum-crc32" => "Hd0tpQ==" })
+      expect(response.composite_checksum).to be_nil
+    end
+
+    it "returns nil without a checksum header" do
+      response = described_class.new(status_code: 200, headers: {})
+      expect(response.composite_checksum).to be_nil
+    end
+  end
 end